@@ -0,0 +1,320 @@
+//! Algorithms written once for every tree type, over the [TreeLike] trait. Walks start at the root and visit
+//! every node at most once, so they end even on trees with cycles or shared children; nodes unreachable from the
+//! root are skipped.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Write;
+
+use crate::TreeLike;
+
+/// Node ids in depth-first pre-order, children in their order.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::sized::BTree;
+///         let tree = BTree::new([1, 3, -1, -1], [2, -1, -1, -1], ['a', 'b', 'c', 'd']);
+///
+///         assert_eq!(algo::dfs(&tree).collect::<Vec<_>>(), vec![0, 1, 3, 2]);
+///         assert_eq!(algo::bfs(&tree).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+/// ```
+pub fn dfs(tree: &impl TreeLike) -> impl Iterator<Item = usize> + '_ {
+    let mut visited = vec![false; tree.len()];
+    let mut stack: Vec<usize> = tree.root().into_iter().collect();
+    std::iter::from_fn(move || loop {
+        let node = stack.pop()?;
+        if !std::mem::replace(&mut visited[node], true) {
+            stack.extend(tree.children(node).into_iter().rev());
+            return Some(node);
+        }
+    })
+}
+
+/// Node ids in breadth-first order, level by level, children in their order.
+pub fn bfs(tree: &impl TreeLike) -> impl Iterator<Item = usize> + '_ {
+    levels(tree).map(|(node, _)| node)
+}
+
+/// Nodes in breadth-first order with their depth, the root being at depth zero.
+fn levels(tree: &impl TreeLike) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut visited = vec![false; tree.len()];
+    let mut queue: VecDeque<(usize, usize)> =
+        tree.root().map(|root| (root, 0)).into_iter().collect();
+    std::iter::from_fn(move || loop {
+        let (node, depth) = queue.pop_front()?;
+        if !std::mem::replace(&mut visited[node], true) {
+            queue.extend(
+                tree.children(node)
+                    .into_iter()
+                    .map(|child| (child, depth + 1)),
+            );
+            return Some((node, depth));
+        }
+    })
+}
+
+/// Number of levels, zero for an empty tree.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::implicit::ImplicitBTree;
+///
+///         assert_eq!(algo::height(&ImplicitBTree::from(vec![1, 2, 3, 4])), 3);
+///         assert_eq!(algo::height(&ImplicitBTree::<u8>::new()), 0);
+/// ```
+pub fn height(tree: &impl TreeLike) -> usize {
+    levels(tree).last().map_or(0, |(_, depth)| depth + 1)
+}
+
+/// Number of nodes without children. Counts all nodes, reachable from the root or not.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::sized::BTree;
+///         let tree = BTree::new([1, 3, -1, -1], [2, -1, -1, -1], [1, 2, 3, 4]);
+///
+///         assert_eq!(algo::leaf_count(&tree), 2);
+///         assert_eq!(algo::max_degree(&tree), 2);
+///         assert_eq!(algo::degree_histogram(&tree), vec![2, 1, 1]);
+/// ```
+pub fn leaf_count(tree: &impl TreeLike) -> usize {
+    (0..tree.len()).filter(|&node| tree.is_leaf(node)).count()
+}
+
+/// Highest number of children of a node, zero for an empty tree. Counts all nodes, reachable from the root or not.
+pub fn max_degree(tree: &impl TreeLike) -> usize {
+    (0..tree.len())
+        .map(|node| tree.degree(node))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Number of nodes by their number of children, the last entry being for [max_degree] children. Empty for an
+/// empty tree. Counts all nodes, reachable from the root or not.
+pub fn degree_histogram(tree: &impl TreeLike) -> Vec<usize> {
+    let mut histogram = Vec::new();
+    for degree in (0..tree.len()).map(|node| tree.degree(node)) {
+        if histogram.len() <= degree {
+            histogram.resize(degree + 1, 0);
+        }
+        histogram[degree] += 1;
+    }
+    histogram
+}
+
+/// Lowest common ancestor of `a` and `b`, a node being its own ancestor, found by walking parents up to the root.
+/// Makes a [TreeLike::parent] lookup per ancestor of both nodes: O(depth) for trees finding parents in constant
+/// time, such as [ImplicitBTree](crate::implicit::ImplicitBTree) or the arenas, but O(N·depth) for the dense and
+/// array-backed trees, which scan the links for a parent. Returns `None` if a node doesn't exist or the nodes have
+/// no common ancestor.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::implicit::ImplicitBTree;
+///         let tree = ImplicitBTree::from(vec![0, 1, 2, 3, 4, 5]);
+///
+///         assert_eq!(algo::lca_naive(&tree, 3, 4), Some(1));
+///         assert_eq!(algo::lca_naive(&tree, 4, 5), Some(0));
+///         assert_eq!(algo::lca_naive(&tree, 1, 3), Some(1));
+///         assert_eq!(algo::lca_naive(&tree, 1, 6), None);
+/// ```
+pub fn lca_naive(tree: &impl TreeLike, a: usize, b: usize) -> Option<usize> {
+    if a >= tree.len() || b >= tree.len() {
+        return None;
+    }
+    let ancestors =
+        |node: usize| std::iter::successors(Some(node), |&node| tree.parent(node)).take(tree.len());
+    let of_a: HashSet<usize> = ancestors(a).collect();
+    ancestors(b).find(|node| of_a.contains(node))
+}
+
+/// Ids of nodes on the path from the root to `node_id`, both ends included, in a tree of `len` nodes.
+/// `children` returns valid child ids of a node, the path is found by descending from the root.
+pub(crate) fn path_to<I>(
+    len: usize,
+    node_id: usize,
+    children: impl Fn(usize) -> I,
+) -> Option<Vec<usize>>
+where
+    I: IntoIterator<Item = usize>,
+{
+    if node_id >= len {
+        return None;
+    }
+
+    let mut visited = vec![false; len];
+    let mut path: Vec<usize> = Vec::new();
+    let mut stack = vec![(0_usize, 0_usize)];
+    while let Some((node, depth)) = stack.pop() {
+        if visited[node] {
+            continue;
+        }
+        visited[node] = true;
+        path.truncate(depth);
+        path.push(node);
+        if node == node_id {
+            return Some(path);
+        }
+
+        let children: Vec<usize> = children(node).into_iter().collect();
+        stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+    }
+
+    None
+}
+
+/// First node in depth-first pre-order whose value matches `predicate`.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::tree::Tree;
+///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec![1, 4, 9]).expect("Tree has a valid structure");
+///
+///         assert_eq!(algo::find(&tree, |value| value % 2 == 0), Some(1));
+///         assert_eq!(algo::find(&tree, |value| *value > 10), None);
+/// ```
+pub fn find<T: TreeLike>(tree: &T, predicate: impl Fn(&T::Value) -> bool) -> Option<usize> {
+    dfs(tree).find(|&node| predicate(tree.value(node)))
+}
+
+/// Folds the tree bottom-up, `f` being given a node's value and results of its children in order. Returns `None`
+/// for an empty tree.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::sized::BTree;
+///         let tree = BTree::new([1, 3, -1, -1], [2, -1, -1, -1], [1, 2, 3, 4]);
+///
+///         let sum = algo::fold(&tree, |value, children: Vec<i32>| value + children.iter().sum::<i32>());
+///         assert_eq!(sum, Some(10));
+/// ```
+pub fn fold<T: TreeLike, A>(tree: &T, mut f: impl FnMut(&T::Value, Vec<A>) -> A) -> Option<A> {
+    // Nodes in pre-order, each listing the children it was the first to reach
+    let mut order = Vec::new();
+    let mut subtrees: Vec<Vec<usize>> = vec![Vec::new(); tree.len()];
+    let mut visited = vec![false; tree.len()];
+    let mut stack: Vec<(usize, Option<usize>)> = vec![(tree.root()?, None)];
+    while let Some((node, parent)) = stack.pop() {
+        if std::mem::replace(&mut visited[node], true) {
+            continue;
+        }
+        if let Some(parent) = parent {
+            subtrees[parent].push(node);
+        }
+        order.push(node);
+        stack.extend(
+            tree.children(node)
+                .into_iter()
+                .rev()
+                .map(|child| (child, Some(node))),
+        );
+    }
+
+    let mut results: Vec<Option<A>> = (0..tree.len()).map(|_| None).collect();
+    for &node in order.iter().rev() {
+        let children = subtrees[node]
+            .iter()
+            .filter_map(|&child| results[child].take())
+            .collect();
+        results[node] = Some(f(tree.value(node), children));
+    }
+    results[order[0]].take()
+}
+
+/// Graphviz DOT description of all nodes and edges of the tree, nodes labeled by `label`.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::algo;
+///         use treesome::implicit::ImplicitBTree;
+///         let tree = ImplicitBTree::from(vec!["root", "child"]);
+///
+///         let dot = algo::to_dot(&tree, |_, value| value.to_string());
+///         assert_eq!(dot, "digraph {\n    0 [label=\"root\"];\n    1 [label=\"child\"];\n    0 -> 1;\n}\n");
+/// ```
+pub fn to_dot<T: TreeLike>(tree: &T, label: impl Fn(usize, &T::Value) -> String) -> String {
+    let mut dot = String::from("digraph {\n");
+    for node in 0..tree.len() {
+        let text = label(node, tree.value(node))
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        let _ = writeln!(dot, "    {node} [label=\"{text}\"];");
+    }
+    for node in 0..tree.len() {
+        for child in tree.children(node) {
+            let _ = writeln!(dot, "    {node} -> {child};");
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algo;
+    use crate::sparse::Node;
+    use crate::tree::Tree;
+
+    #[test]
+    fn generic_algorithms() {
+        let root = Node::root("a");
+        let b = root.create_child("b");
+        b.create_child("c");
+        b.create_child("d").create_child("e");
+        root.create_child("f");
+        let indexed = root.indexed();
+
+        assert_eq!(
+            algo::dfs(&indexed).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            algo::bfs(&indexed).collect::<Vec<_>>(),
+            vec![0, 1, 5, 2, 3, 4]
+        );
+        assert_eq!(algo::height(&indexed), 4);
+        assert_eq!(algo::lca_naive(&indexed, 2, 4), Some(1));
+        assert_eq!(algo::lca_naive(&indexed, 4, 5), Some(0));
+        assert_eq!(algo::find(&indexed, |value| *value == "e"), Some(4));
+        let depths = algo::fold(&indexed, |_, children: Vec<usize>| {
+            children.into_iter().max().map_or(1, |depth| depth + 1)
+        });
+        assert_eq!(depths, Some(algo::height(&indexed)));
+
+        // Node 2 is linked from both 0 and 1, node 3 is unreachable
+        let shared = Tree::new(
+            vec![vec![1, 2, -1, -1], vec![2, -1, -1, -1]],
+            vec![1, 10, 100, 1000],
+        )
+        .expect("Tree has a valid structure");
+        assert_eq!(algo::dfs(&shared).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(
+            algo::fold(&shared, |value, children: Vec<i32>| value
+                + children.iter().sum::<i32>()),
+            Some(111)
+        );
+        assert_eq!(algo::find(&shared, |value| *value == 1000), None);
+        let later = Tree::new(vec![vec![1, -1, 1], vec![2, -1, -1]], vec![1, 10, 100])
+            .expect("Tree has a valid structure");
+        let subtrees = algo::fold(&later, |value, children: Vec<String>| {
+            format!("{value}{children:?}")
+        });
+        assert_eq!(subtrees.as_deref(), Some(r#"1["10[]", "100[]"]"#));
+
+        let dot = algo::to_dot(&shared, |node, value| format!("\"{node}\": {value}"));
+        assert!(dot.contains("    2 [label=\"\\\"2\\\": 100\"];\n"));
+        assert_eq!(dot.matches("->").count(), 3);
+    }
+}
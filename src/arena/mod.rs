@@ -0,0 +1,467 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use crate::footprint::MemoryFootprint;
+use crate::tree::TreeError;
+
+/// Mutable n-ary tree with nodes stored in a single vector. Nodes link their parent, first child and siblings by
+/// index, so subtrees can be detached, moved and removed without reference counting.
+///
+/// Nodes are addressed by generational [ArenaId]s. Slots of removed nodes are reused, but with a new generation,
+/// so ids of removed nodes never alias new ones: accessors return `None` and mutations fail with
+/// [TreeError::StaleNode] instead. Slots whose generation would overflow are never reused.
+///
+/// Nodes may exist without a parent, see [ArenaTree::insert]. Such detached subtrees aren't reachable from the
+/// root until attached with [ArenaTree::attach].
+///
+/// # Examples
+///
+/// ```
+///         use treesome::arena::ArenaTree;
+///         let mut tree = ArenaTree::new("root");
+///         let a = tree.append(tree.root(), "a").expect("Root exists");
+///         let b = tree.append(tree.root(), "b").expect("Root exists");
+///         let a0 = tree.append(a, "a0").expect("Node a exists");
+///
+///         // Move a0 under b, then remove a
+///         tree.detach(a0).expect("Node a0 exists");
+///         tree.attach(b, a0).expect("Node a0 is detached");
+///         assert_eq!(tree.remove(a), Ok("a"));
+///
+///         assert_eq!(tree.get(a), None);
+///         assert_eq!(tree.children(tree.root()).collect::<Vec<_>>(), vec![b]);
+///         assert_eq!(tree.parent(a0), Some(b));
+///         assert_eq!(tree.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArenaTree<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    root: ArenaId,
+    len: usize,
+}
+
+/// Id of a node of an [ArenaTree]: the slot holding the node and the generation of the slot at the time the node
+/// was created.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ArenaId {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    node: Option<Node<T>>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    parent: Option<u32>,
+    first_child: Option<u32>,
+    last_child: Option<u32>,
+    prev_sibling: Option<u32>,
+    next_sibling: Option<u32>,
+}
+
+impl ArenaId {
+    /// Position of the node's slot in the arena.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl Display for ArenaId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}v{}", self.index, self.generation)
+    }
+}
+
+impl<T> ArenaTree<T> {
+    /// Creates a tree consisting of a root node only.
+    pub fn new(root: T) -> Self {
+        let mut tree = Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            root: ArenaId {
+                index: 0,
+                generation: 0,
+            },
+            len: 0,
+        };
+        tree.root = tree.insert(root);
+        tree
+    }
+
+    pub fn root(&self) -> ArenaId {
+        self.root
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Vacant slots and the list of them are overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::slots(&self.slots, self.len, size_of::<T>(), &self.free)
+    }
+
+    /// Number of nodes, detached ones included.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always false, the root can't be removed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// True if `node` hasn't been removed.
+    pub fn contains(&self, node: ArenaId) -> bool {
+        self.node(node).is_ok()
+    }
+
+    pub fn get(&self, node: ArenaId) -> Option<&T> {
+        self.node(node).ok().map(|node| &node.value)
+    }
+
+    pub fn get_mut(&mut self, node: ArenaId) -> Option<&mut T> {
+        self.node_mut(node).ok().map(|node| &mut node.value)
+    }
+
+    /// Replaces the value of `node`, returning the previous one.
+    pub fn replace(&mut self, node: ArenaId, value: T) -> Result<T, TreeError> {
+        Ok(std::mem::replace(&mut self.node_mut(node)?.value, value))
+    }
+
+    /// Returns node's parent, `None` for the root, detached nodes and removed nodes.
+    pub fn parent(&self, node: ArenaId) -> Option<ArenaId> {
+        self.node(node).ok()?.parent.map(|parent| self.id(parent))
+    }
+
+    /// Children of `node` in order, none for removed nodes.
+    pub fn children(&self, node: ArenaId) -> impl Iterator<Item = ArenaId> + '_ {
+        let first = self.node(node).ok().and_then(|node| node.first_child);
+        std::iter::successors(first, |&child| self.linked(child).next_sibling)
+            .map(|child| self.id(child))
+    }
+
+    /// True if `node` has no children, including removed nodes.
+    pub fn is_leaf(&self, node: ArenaId) -> bool {
+        self.children(node).next().is_none()
+    }
+
+    /// First child of `node`, `None` for leaves and removed nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::arena::ArenaTree;
+    ///         let mut tree = ArenaTree::new("root");
+    ///         let a = tree.append(tree.root(), "a").expect("Root exists");
+    ///         let b = tree.append(tree.root(), "b").expect("Root exists");
+    ///
+    ///         assert_eq!(tree.first_child(tree.root()), Some(a));
+    ///         assert_eq!(tree.last_child(tree.root()), Some(b));
+    ///         assert_eq!(tree.nth_child(tree.root(), 2), None);
+    /// ```
+    pub fn first_child(&self, node: ArenaId) -> Option<ArenaId> {
+        self.children(node).next()
+    }
+
+    /// Last child of `node`, `None` for leaves and removed nodes. Walks the list of siblings.
+    pub fn last_child(&self, node: ArenaId) -> Option<ArenaId> {
+        self.children(node).last()
+    }
+
+    /// Child of `node` at position `k`, counting from zero. `None` if the node has at most `k` children or has
+    /// been removed.
+    pub fn nth_child(&self, node: ArenaId, k: usize) -> Option<ArenaId> {
+        self.children(node).nth(k)
+    }
+
+    /// Creates a detached node without a parent, to be attached with [ArenaTree::attach].
+    ///
+    /// Panics if the arena already holds `u32::MAX` slots.
+    pub fn insert(&mut self, value: T) -> ArenaId {
+        let node = Node {
+            value,
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+        };
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.node = Some(node);
+            return ArenaId {
+                index,
+                generation: slot.generation,
+            };
+        }
+        let index = u32::try_from(self.slots.len())
+            .ok()
+            .filter(|&index| index != u32::MAX)
+            .expect("Arena holds fewer than u32::MAX nodes");
+        self.slots.push(Slot {
+            generation: 0,
+            node: Some(node),
+        });
+        ArenaId {
+            index,
+            generation: 0,
+        }
+    }
+
+    /// Creates a new node as the last child of `parent`.
+    pub fn append(&mut self, parent: ArenaId, value: T) -> Result<ArenaId, TreeError> {
+        self.node(parent)?;
+        let child = self.insert(value);
+        self.link(parent.index, child.index);
+        Ok(child)
+    }
+
+    /// Makes detached `child` the last child of `parent`. Fails with [TreeError::MultipleParents] if `child`
+    /// already has a parent or is the root, and with [TreeError::CycleDetected] if `parent` lies in the subtree
+    /// of `child`.
+    pub fn attach(&mut self, parent: ArenaId, child: ArenaId) -> Result<(), TreeError> {
+        self.node(parent)?;
+        if self.node(child)?.parent.is_some() || child == self.root {
+            return Err(TreeError::MultipleParents {
+                node: child.index(),
+            });
+        }
+        let mut ancestor = Some(parent.index);
+        while let Some(current) = ancestor {
+            if current == child.index {
+                return Err(TreeError::CycleDetected {
+                    node: child.index(),
+                });
+            }
+            ancestor = self.linked(current).parent;
+        }
+        self.link(parent.index, child.index);
+        Ok(())
+    }
+
+    /// Unlinks `node` from its parent, keeping its subtree. Does nothing for the root and detached nodes.
+    pub fn detach(&mut self, node: ArenaId) -> Result<(), TreeError> {
+        let Node {
+            parent,
+            prev_sibling,
+            next_sibling,
+            ..
+        } = *self.node(node)?;
+        let Some(parent) = parent else {
+            return Ok(());
+        };
+        match prev_sibling {
+            Some(prev) => self.linked_mut(prev).next_sibling = next_sibling,
+            None => self.linked_mut(parent).first_child = next_sibling,
+        }
+        match next_sibling {
+            Some(next) => self.linked_mut(next).prev_sibling = prev_sibling,
+            None => self.linked_mut(parent).last_child = prev_sibling,
+        }
+        let node = self.linked_mut(node.index);
+        node.parent = None;
+        node.prev_sibling = None;
+        node.next_sibling = None;
+        Ok(())
+    }
+
+    /// Removes `node` with its whole subtree and returns its value. Ids of all removed nodes become stale.
+    /// The root can't be removed, [TreeError::RootRemoval] is returned instead.
+    pub fn remove(&mut self, node: ArenaId) -> Result<T, TreeError> {
+        if node == self.root {
+            return Err(TreeError::RootRemoval);
+        }
+        self.detach(node)?;
+        let mut stack = vec![node.index];
+        let mut value = None;
+        while let Some(index) = stack.pop() {
+            let removed = self.free_slot(index);
+            stack.extend(std::iter::successors(removed.first_child, |&child| {
+                self.linked(child).next_sibling
+            }));
+            value.get_or_insert(removed.value);
+        }
+        Ok(value.expect("Node itself is removed first"))
+    }
+
+    fn free_slot(&mut self, index: u32) -> Node<T> {
+        let slot = &mut self.slots[index as usize];
+        let node = slot.node.take().expect("Linked nodes are occupied");
+        // A slot out of generations is retired instead of wrapping around, so stale ids never match a new node
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(index);
+        }
+        self.len -= 1;
+        node
+    }
+
+    fn link(&mut self, parent: u32, child: u32) {
+        let last = self.linked(parent).last_child;
+        match last {
+            Some(last) => self.linked_mut(last).next_sibling = Some(child),
+            None => self.linked_mut(parent).first_child = Some(child),
+        }
+        let parent_node = self.linked_mut(parent);
+        parent_node.last_child = Some(child);
+        let child_node = self.linked_mut(child);
+        child_node.parent = Some(parent);
+        child_node.prev_sibling = last;
+    }
+
+    fn node(&self, id: ArenaId) -> Result<&Node<T>, TreeError> {
+        let slot = self
+            .slots
+            .get(id.index())
+            .ok_or(TreeError::NodeOutOfBounds { node: id.index() })?;
+        slot.node
+            .as_ref()
+            .filter(|_| slot.generation == id.generation)
+            .ok_or(TreeError::StaleNode { node: id.index() })
+    }
+
+    fn node_mut(&mut self, id: ArenaId) -> Result<&mut Node<T>, TreeError> {
+        let slot = self
+            .slots
+            .get_mut(id.index())
+            .ok_or(TreeError::NodeOutOfBounds { node: id.index() })?;
+        slot.node
+            .as_mut()
+            .filter(|_| slot.generation == id.generation)
+            .ok_or(TreeError::StaleNode { node: id.index() })
+    }
+
+    /// Node at a slot some live node links to.
+    fn linked(&self, index: u32) -> &Node<T> {
+        self.slots[index as usize]
+            .node
+            .as_ref()
+            .expect("Linked nodes are occupied")
+    }
+
+    fn linked_mut(&mut self, index: u32) -> &mut Node<T> {
+        self.slots[index as usize]
+            .node
+            .as_mut()
+            .expect("Linked nodes are occupied")
+    }
+
+    fn id(&self, index: u32) -> ArenaId {
+        ArenaId {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+}
+
+impl<T> Index<ArenaId> for ArenaTree<T> {
+    type Output = T;
+
+    fn index(&self, node: ArenaId) -> &Self::Output {
+        self.get(node)
+            .unwrap_or_else(|| panic!("Node {node} doesn't exist"))
+    }
+}
+
+impl<T> IndexMut<ArenaId> for ArenaTree<T> {
+    fn index_mut(&mut self, node: ArenaId) -> &mut Self::Output {
+        self.get_mut(node)
+            .unwrap_or_else(|| panic!("Node {node} doesn't exist"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::ArenaTree;
+    use crate::tree::TreeError;
+
+    #[test]
+    fn detach_and_remove() {
+        let mut tree = ArenaTree::new(0);
+        let root = tree.root();
+        let a = tree.append(root, 1).unwrap();
+        let b = tree.append(root, 2).unwrap();
+        let c = tree.append(root, 3).unwrap();
+        let a0 = tree.append(a, 10).unwrap();
+        let a00 = tree.append(a0, 100).unwrap();
+
+        // Detaching the middle child relinks its siblings
+        tree.detach(b).unwrap();
+        assert_eq!(tree.children(root).collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(tree.parent(b), None);
+        tree.attach(a00, b).unwrap();
+        assert_eq!(tree.parent(b), Some(a00));
+
+        assert_eq!(
+            tree.attach(a00, a),
+            Err(TreeError::MultipleParents { node: a.index() })
+        );
+        tree.detach(a).unwrap();
+        assert_eq!(
+            tree.attach(b, a),
+            Err(TreeError::CycleDetected { node: a.index() })
+        );
+        assert_eq!(
+            tree.attach(c, root),
+            Err(TreeError::MultipleParents { node: root.index() })
+        );
+        tree.attach(c, a).unwrap();
+
+        // Removing a removes its whole subtree, slots are reused with a new generation
+        assert_eq!(tree.remove(a), Ok(1));
+        assert_eq!(tree.len(), 2);
+        for removed in [a, a0, a00, b] {
+            assert!(!tree.contains(removed));
+            assert_eq!(
+                tree.append(removed, 0),
+                Err(TreeError::StaleNode {
+                    node: removed.index()
+                })
+            );
+        }
+        assert!(tree.is_leaf(c));
+        let d = tree.append(c, 4).unwrap();
+        assert!([a, a0, a00, b].iter().any(|id| id.index() == d.index()));
+        assert!(!tree.contains(b) && tree.contains(d));
+        assert_eq!(tree[d], 4);
+        assert_eq!(tree.remove(root), Err(TreeError::RootRemoval));
+
+        // Detached nodes live on their own
+        let orphan = tree.insert(5);
+        tree[orphan] += 1;
+        assert_eq!(tree.get(orphan), Some(&6));
+        assert_eq!(tree.parent(orphan), None);
+        assert_eq!(tree.detach(orphan), Ok(()));
+        assert_eq!(tree.len(), 4);
+    }
+
+    #[test]
+    fn exhausted_generation() {
+        let mut tree = ArenaTree::new(0);
+        let first = tree.append(tree.root(), 1).unwrap();
+        tree.slots[first.index()].generation = u32::MAX - 1;
+        let first = tree.id(first.index);
+
+        tree.remove(first).unwrap();
+        let last = tree.append(tree.root(), 2).unwrap();
+        assert_eq!((last.index(), last.generation()), (first.index(), u32::MAX));
+
+        // The slot is retired, the next node takes a fresh one
+        tree.remove(last).unwrap();
+        let next = tree.append(tree.root(), 3).unwrap();
+        assert_ne!(next.index(), last.index());
+        assert!(!tree.contains(first) && !tree.contains(last));
+        assert_eq!(tree.len(), 2);
+    }
+}
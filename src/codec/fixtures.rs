@@ -0,0 +1,94 @@
+use crate::codec::{decode, encode, Value};
+use crate::tree::{Tree, TreeError};
+
+/// Golden file of the binary format, see [verify_fixtures].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Fixture {
+    pub name: &'static str,
+    /// Tree the file encodes, in the notation of [Tree::new].
+    pub description: &'static str,
+    pub bytes: &'static [u8],
+}
+
+macro_rules! fixture {
+    ($name:literal, $description:literal) => {
+        Fixture {
+            name: $name,
+            description: $description,
+            bytes: include_bytes!(concat!("fixtures/", $name, ".bin")),
+        }
+    };
+}
+
+/// Test vectors of the binary format, also found in `src/codec/fixtures` of the crate sources.
+pub const FIXTURES: [Fixture; 6] = [
+    fixture!("empty_u8", "u8 values, links [[]], values []"),
+    fixture!(
+        "binary_i32",
+        "i32 values, links [[1, -1, -1], [2, -1, -1]], values [10, -20, 30]"
+    ),
+    fixture!(
+        "ternary_string",
+        r#"String values, links [[1, -1, 4, -1, -1], [2, -1, -1, -1, -1], [3, -1, -1, -1, -1]], values ["root", "α", "", "c\"", "leaf"]"#
+    ),
+    fixture!(
+        "chain_bool",
+        "bool values, links [[1, 2, -1]], values [true, false, true]"
+    ),
+    fixture!("single_f64", "f64 values, links [[-1]], values [-0.5]"),
+    fixture!(
+        "bytes",
+        "Vec<u8> values, links [[1, -1], [-1, -1]], values [[0x00, 0xff], []]"
+    ),
+];
+
+/// Checks every one of [FIXTURES] decodes to the tree it describes and encoding the tree gives the same bytes.
+///
+/// # Examples
+///
+/// ```
+///         assert_eq!(treesome::codec::verify_fixtures(), Ok(()));
+/// ```
+pub fn verify_fixtures() -> Result<(), TreeError> {
+    let [empty, binary, ternary, chain, single, bytes] = FIXTURES;
+    verify(empty, vec![vec![]], Vec::<u8>::new())?;
+    verify(
+        binary,
+        vec![vec![1, -1, -1], vec![2, -1, -1]],
+        vec![10_i32, -20, 30],
+    )?;
+    let strings = ["root", "α", "", "c\"", "leaf"].map(String::from).to_vec();
+    let links = vec![
+        vec![1, -1, 4, -1, -1],
+        vec![2, -1, -1, -1, -1],
+        vec![3, -1, -1, -1, -1],
+    ];
+    verify(ternary, links, strings)?;
+    verify(chain, vec![vec![1, 2, -1]], vec![true, false, true])?;
+    verify(single, vec![vec![-1]], vec![-0.5_f64])?;
+    verify(
+        bytes,
+        vec![vec![1, -1], vec![-1, -1]],
+        vec![vec![0x00_u8, 0xff], vec![]],
+    )
+}
+
+fn verify<T>(fixture: Fixture, links: Vec<Vec<isize>>, values: Vec<T>) -> Result<(), TreeError>
+where
+    T: Value + PartialEq,
+{
+    let expected = Tree::new(links, values)?;
+    if decode::<T>(fixture.bytes)? != expected {
+        return Err(TreeError::CorruptedTree(format!(
+            "Fixture {} doesn't decode to {}",
+            fixture.name, fixture.description
+        )));
+    }
+    if encode(&expected) != fixture.bytes {
+        return Err(TreeError::CorruptedTree(format!(
+            "Encoding {} doesn't match fixture {}",
+            fixture.description, fixture.name
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,278 @@
+//! Binary encoding of [Tree]s, specified to be read and written from other languages.
+//!
+//! ## Layout
+//!
+//! All integers are little-endian, there's no padding between fields.
+//!
+//! | Offset | Size  | Field                                                        |
+//! |--------|-------|--------------------------------------------------------------|
+//! | 0      | 4     | Magic bytes `TRSM`                                           |
+//! | 4      | 1     | Format version, [VERSION]                                    |
+//! | 5      | 1     | Value type code, see [Value::TYPE]                           |
+//! | 6      | 2     | Reserved, zero                                               |
+//! | 8      | 4     | Number of dimensions `M`, unsigned, at most [MAX_DIMENSIONS] |
+//! | 12     | 4     | Reserved, zero                                               |
+//! | 16     | 8     | Number of nodes `N`, unsigned                                |
+//! | 24     | 8·M·N | Links as signed 64-bit integers, `N` per dimension in turn   |
+//! | 24+8MN | rest  | `N` values in node order                                     |
+//!
+//! Link `i` of dimension `d`, at offset `24 + 8 * (d * N + i)`, is the id of the `d`-th child of node `i`, or `-1`
+//! if there's none. Node `0` is the root. Decoded trees are checked by [Tree::validate].
+//!
+//! ## Values
+//!
+//! | Code | Type          | Encoding                                          |
+//! |------|---------------|---------------------------------------------------|
+//! | 1-4  | `u8` - `u64`  | 1, 2, 4 and 8 bytes                               |
+//! | 5-8  | `i8` - `i64`  | 1, 2, 4 and 8 bytes, two's complement             |
+//! | 9    | `f32`         | IEEE 754 binary32                                 |
+//! | 10   | `f64`         | IEEE 754 binary64                                 |
+//! | 11   | `bool`        | 1 byte, `0` or `1`                                |
+//! | 12   | `String`      | Byte length as `u32`, followed by UTF-8 bytes     |
+//! | 13   | `Vec<u8>`     | Byte length as `u32`, followed by the bytes       |
+//!
+//! ## Conformance
+//!
+//! Golden files shipped with the crate, checked by [verify_fixtures], serve as test vectors for other
+//! implementations. Each is listed with the tree it encodes in [FIXTURES].
+
+use crate::consts::LEAF_NODE;
+use crate::tree::{Tree, TreeError};
+
+mod fixtures;
+
+pub use fixtures::*;
+
+pub const MAGIC: [u8; 4] = *b"TRSM";
+pub const VERSION: u8 = 1;
+pub const MAX_DIMENSIONS: u32 = 1 << 16;
+const HEADER_SIZE: usize = 24;
+
+/// Value of a tree encoded by [encode].
+pub trait Value: Sized {
+    /// Type code written to the header, checked when decoding.
+    const TYPE: u8;
+
+    fn encode(&self, bytes: &mut Vec<u8>);
+
+    /// Reads a value from the start of `bytes`, advancing past it. `None` if `bytes` don't hold a valid value.
+    fn decode(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+macro_rules! number_values {
+    ($($number:ty => $code:literal),+) => {
+        $(
+            impl Value for $number {
+                const TYPE: u8 = $code;
+
+                fn encode(&self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &mut &[u8]) -> Option<Self> {
+                    take(bytes, size_of::<Self>())
+                        .map(|value| Self::from_le_bytes(value.try_into().unwrap_or_else(|_| unreachable!("Exactly size_of::<Self>() bytes are taken"))))
+                }
+            }
+        )+
+    };
+}
+
+number_values!(
+    u8 => 1, u16 => 2, u32 => 3, u64 => 4,
+    i8 => 5, i16 => 6, i32 => 7, i64 => 8,
+    f32 => 9, f64 => 10
+);
+
+impl Value for bool {
+    const TYPE: u8 = 11;
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(u8::from(*self));
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        match u8::decode(bytes)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Value for String {
+    const TYPE: u8 = 12;
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        self.as_bytes().to_vec().encode(bytes);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        String::from_utf8(Vec::decode(bytes)?).ok()
+    }
+}
+
+impl Value for Vec<u8> {
+    const TYPE: u8 = 13;
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        let len = u32::try_from(self.len()).expect("Values are at most 4 GiB long");
+        len.encode(bytes);
+        bytes.extend_from_slice(self);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(bytes)?;
+        take(bytes, len as usize).map(<[u8]>::to_vec)
+    }
+}
+
+/// Splits `len` bytes off the start of `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let (head, tail) = bytes.split_at_checked(len)?;
+    *bytes = tail;
+    Some(head)
+}
+
+/// Encodes `tree` as described in the [module docs](self).
+///
+/// Panics if the tree has more than [MAX_DIMENSIONS] dimensions.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::codec;
+///         use treesome::tree::Tree;
+///         let tree = Tree::new(vec![vec![1, -1], vec![-1, -1]], vec![7_u16, 9]).expect("Tree has a valid structure");
+///
+///         let bytes = codec::encode(&tree);
+///         assert_eq!(&bytes[..4], b"TRSM");
+///         assert_eq!(bytes.len(), 24 + 8 * 2 * 2 + 2 * 2);
+///         assert_eq!(codec::decode(&bytes), Ok(tree));
+/// ```
+pub fn encode<T: Value>(tree: &Tree<T>) -> Vec<u8> {
+    let values = tree.values();
+    let dimensions = tree.dimensions();
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + 8 * dimensions.len() * values.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&[VERSION, T::TYPE, 0, 0]);
+    let dimension_count = u32::try_from(dimensions.len())
+        .ok()
+        .filter(|&count| count <= MAX_DIMENSIONS)
+        .expect("Tree has at most MAX_DIMENSIONS dimensions");
+    dimension_count.encode(&mut bytes);
+    0_u32.encode(&mut bytes);
+    (values.len() as u64).encode(&mut bytes);
+    for &link in dimensions.iter().flatten() {
+        (link as i64).encode(&mut bytes);
+    }
+    for value in values.iter() {
+        value.encode(&mut bytes);
+    }
+    bytes
+}
+
+/// Decodes a tree written by [encode], or by another implementation of the format. Malformed input is reported as
+/// [TreeError::CorruptedTree], structural errors of the tree as found by [Tree::validate].
+pub fn decode<T: Value>(mut bytes: &[u8]) -> Result<Tree<T>, TreeError> {
+    let corrupted = |reason: &str| TreeError::CorruptedTree(reason.to_string());
+    let header = take(&mut bytes, HEADER_SIZE).ok_or_else(|| corrupted("Header is truncated"))?;
+    if header[..4] != MAGIC {
+        return Err(corrupted("Magic bytes don't match"));
+    }
+    if header[4] != VERSION {
+        return Err(TreeError::CorruptedTree(format!(
+            "Version {} isn't supported",
+            header[4]
+        )));
+    }
+    if header[5] != T::TYPE {
+        return Err(TreeError::CorruptedTree(format!(
+            "Values have type code {}, expected {}",
+            header[5],
+            T::TYPE
+        )));
+    }
+    let mut counts = &header[8..];
+    let dimensions = u32::decode(&mut counts)
+        .filter(|&dimensions| dimensions <= MAX_DIMENSIONS)
+        .ok_or_else(|| corrupted("Too many dimensions"))? as usize;
+    let _reserved = u32::decode(&mut counts);
+    let len = u64::decode(&mut counts)
+        .and_then(|len| usize::try_from(len).ok())
+        .filter(|&len| {
+            len.checked_mul(dimensions)
+                .and_then(|links| links.checked_mul(8))
+                .is_some_and(|size| size <= bytes.len())
+        })
+        .ok_or_else(|| corrupted("Links are truncated"))?;
+
+    let nodes = (0..dimensions)
+        .map(|_| {
+            (0..len)
+                .map(|_| {
+                    let link = i64::decode(&mut bytes)
+                        .unwrap_or_else(|| unreachable!("Length is checked"));
+                    isize::try_from(link).unwrap_or(LEAF_NODE - 1)
+                })
+                .collect()
+        })
+        .collect();
+    let values = (0..len)
+        .map(|_| T::decode(&mut bytes))
+        .collect::<Option<Vec<T>>>()
+        .ok_or_else(|| corrupted("Values are truncated or invalid"))?;
+    if !bytes.is_empty() {
+        return Err(corrupted("Trailing bytes after values"));
+    }
+    Tree::new_validated(nodes, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::{decode, encode, verify_fixtures, FIXTURES};
+    use crate::tree::{Tree, TreeError};
+
+    #[test]
+    fn fixtures() {
+        assert_eq!(verify_fixtures(), Ok(()));
+        assert!(FIXTURES
+            .iter()
+            .all(|fixture| fixture.bytes.starts_with(b"TRSM\x01")));
+    }
+
+    #[test]
+    fn malformed() {
+        let tree = Tree::new(
+            vec![vec![1, -1]],
+            vec![String::from("a"), String::from("ü")],
+        )
+        .unwrap();
+        let bytes = encode(&tree);
+        assert_eq!(decode(&bytes), Ok(tree));
+
+        let corrupted =
+            |bytes: &[u8]| matches!(decode::<String>(bytes), Err(TreeError::CorruptedTree(_)));
+        assert!(corrupted(&bytes[..bytes.len() - 1]));
+        assert!(corrupted(&bytes[..20]));
+        assert!(corrupted(&[bytes.as_slice(), &[0]].concat()));
+        assert!(matches!(
+            decode::<u8>(&bytes),
+            Err(TreeError::CorruptedTree(_))
+        ));
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert!(corrupted(&wrong_magic));
+        let mut huge = bytes.clone();
+        huge[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(corrupted(&huge));
+
+        // Structurally invalid trees are rejected like by Tree::validate
+        let mut cycle = bytes;
+        cycle[24..32].copy_from_slice(&0_i64.to_le_bytes());
+        assert_eq!(
+            decode::<String>(&cycle),
+            Err(TreeError::CycleDetected { node: 0 })
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Raw link values shared by all array trees ([sized::Tree](crate::sized::Tree), [BTree](crate::sized::BTree)
+//! and the default `isize` links of [tree::Tree](crate::tree::Tree)).
+//!
+//! Most APIs have a checked variant taking and returning [NodeId](crate::tree::NodeId)s, where a missing child is
+//! `None` and these constants aren't needed at all.
+
+/// Link marking a missing child.
+pub const LEAF_NODE: isize = -1;
+
+/// Id of the root node. No node links to it, so it also stands for "no parent".
+pub const ROOT_NODE: isize = 0;
@@ -0,0 +1,293 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::consts::LEAF_NODE;
+use crate::sized::BTree;
+use crate::tree::TreeError;
+use crate::tree::TreeError::CorruptedTree;
+
+/// Number of inputs walked through the tree together by [DecisionTree::predict_many].
+const BATCH_SIZE: usize = 64;
+
+/// Node of a decision tree. Inputs with `features[feature] <= threshold` continue to the left child,
+/// all the others (including `NaN` features) to the right child.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecisionNode {
+    Split { feature: usize, threshold: f32 },
+    Leaf(f32),
+}
+
+/// Binary decision tree for inference, backed by a [BTree] of [DecisionNode]s.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::decision::{DecisionNode, DecisionTree};
+///         use treesome::sized::BTree;
+///
+///         let left = [1, -1, -1];
+///         let right = [2, -1, -1];
+///         let values = [
+///             DecisionNode::Split { feature: 1, threshold: 0.5 },
+///             DecisionNode::Leaf(-1.0),
+///             DecisionNode::Leaf(1.0),
+///         ];
+///         let tree = DecisionTree::new(BTree::new(left, right, values)).unwrap();
+///
+///         assert_eq!(tree.predict(&[0.0, 0.2]), -1.0);
+///         assert_eq!(tree.predict_many(&[[0.0, 0.7], [0.0, 0.5]]), vec![1.0, -1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "DecisionTreeData<N>"))]
+pub struct DecisionTree<const N: usize> {
+    tree: BTree<DecisionNode, N>,
+}
+
+impl<const N: usize> DecisionTree<N> {
+    /// Wraps `tree`, checking every split reachable from the root has both children, every path ends in a leaf
+    /// and no node is reachable twice, so predictions always terminate.
+    pub fn new(tree: BTree<DecisionNode, N>) -> Result<Self, TreeError> {
+        if N == 0 {
+            return Err(TreeError::EmptyTree);
+        }
+
+        let mut visited = [false; N];
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut visited[node], true) {
+                return Err(match node {
+                    0 => TreeError::CycleDetected { node },
+                    _ => TreeError::MultipleParents { node },
+                });
+            }
+            let children = tree.children(node);
+            match tree[node] {
+                DecisionNode::Leaf(_) => {
+                    if !tree.is_leaf_node(node) {
+                        return Err(CorruptedTree(format!("Leaf {node} has children")));
+                    }
+                }
+                DecisionNode::Split { .. } => {
+                    for child in [children.left, children.right] {
+                        if child == LEAF_NODE {
+                            return Err(CorruptedTree(format!("Split {node} is missing a child")));
+                        }
+                        if child < 0 || child as usize >= N {
+                            return Err(TreeError::IndexOutOfBounds { node, index: child });
+                        }
+                        stack.push(child as usize);
+                    }
+                }
+            }
+        }
+
+        Ok(Self { tree })
+    }
+
+    pub fn tree(&self) -> &BTree<DecisionNode, N> {
+        &self.tree
+    }
+
+    /// Predicts the value of a single input. Panics if a split refers to a feature index out of `features` bounds.
+    pub fn predict(&self, features: &[f32]) -> f32 {
+        let mut node = 0;
+        loop {
+            match self.step(node, features) {
+                Ok(next) => node = next,
+                Err(value) => return value,
+            }
+        }
+    }
+
+    /// Predicts values of many inputs, in order. Inputs are walked through the tree in batches, level by level,
+    /// so nodes near the root stay in cache while the whole batch passes through them.
+    pub fn predict_many<F: AsRef<[f32]>>(&self, inputs: &[F]) -> Vec<f32> {
+        let mut predictions = vec![0.0; inputs.len()];
+        for (batch, batch_predictions) in inputs
+            .chunks(BATCH_SIZE)
+            .zip(predictions.chunks_mut(BATCH_SIZE))
+        {
+            self.predict_chunk(batch, batch_predictions);
+        }
+        predictions
+    }
+
+    /// Predicts values of many inputs, in order, walking batches of inputs through the tree on multiple threads.
+    /// Batches are walked the same way as in [DecisionTree::predict_many].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::decision::{DecisionNode, DecisionTree};
+    ///         use treesome::sized::BTree;
+    ///
+    ///         let values = [
+    ///             DecisionNode::Split { feature: 0, threshold: 0.0 },
+    ///             DecisionNode::Leaf(-1.0),
+    ///             DecisionNode::Leaf(1.0),
+    ///         ];
+    ///         let tree = DecisionTree::new(BTree::new([1, -1, -1], [2, -1, -1], values)).unwrap();
+    ///
+    ///         let inputs: Vec<Vec<f32>> = (-500..500).map(|x| vec![x as f32]).collect();
+    ///         let predictions = tree.predict_batch(&inputs);
+    ///         assert_eq!(predictions.iter().filter(|&&prediction| prediction > 0.0).count(), 499);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn predict_batch<F: AsRef<[f32]> + Sync>(&self, inputs: &[F]) -> Vec<f32> {
+        use rayon::prelude::*;
+
+        let mut predictions = vec![0.0; inputs.len()];
+        inputs
+            .par_chunks(BATCH_SIZE)
+            .zip(predictions.par_chunks_mut(BATCH_SIZE))
+            .for_each(|(batch, batch_predictions)| self.predict_chunk(batch, batch_predictions));
+        predictions
+    }
+
+    /// Walks at most [BATCH_SIZE] inputs through the tree level by level.
+    fn predict_chunk<F: AsRef<[f32]>>(&self, batch: &[F], predictions: &mut [f32]) {
+        let mut nodes = [0usize; BATCH_SIZE];
+        let mut pending: Vec<usize> = (0..batch.len()).collect();
+        while !pending.is_empty() {
+            pending.retain(
+                |&input| match self.step(nodes[input], batch[input].as_ref()) {
+                    Ok(next) => {
+                        nodes[input] = next;
+                        true
+                    }
+                    Err(value) => {
+                        predictions[input] = value;
+                        false
+                    }
+                },
+            );
+        }
+    }
+
+    /// Moves an input from `node` to the next node, or returns the leaf value as an error once there.
+    #[inline]
+    fn step(&self, node: usize, features: &[f32]) -> Result<usize, f32> {
+        match self.tree[node] {
+            DecisionNode::Leaf(value) => Err(value),
+            DecisionNode::Split { feature, threshold } => {
+                let children = self.tree.children(node);
+                let next = if features[feature] <= threshold {
+                    children.left
+                } else {
+                    children.right
+                };
+                Ok(next as usize)
+            }
+        }
+    }
+}
+
+/// Serialized form of a [DecisionTree], checked by [DecisionTree::new] before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct DecisionTreeData<const N: usize> {
+    tree: BTree<DecisionNode, N>,
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> TryFrom<DecisionTreeData<N>> for DecisionTree<N> {
+    type Error = TreeError;
+
+    fn try_from(data: DecisionTreeData<N>) -> Result<Self, Self::Error> {
+        Self::new(data.tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decision::DecisionNode::{Leaf, Split};
+    use crate::decision::DecisionTree;
+    use crate::sized::BTree;
+
+    fn tree() -> DecisionTree<7> {
+        // x0 <= 1 ? (x1 <= 0 ? 10 : 20) : (x1 <= 5 ? 30 : 40)
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let values = [
+            Split {
+                feature: 0,
+                threshold: 1.0,
+            },
+            Split {
+                feature: 1,
+                threshold: 0.0,
+            },
+            Split {
+                feature: 1,
+                threshold: 5.0,
+            },
+            Leaf(10.0),
+            Leaf(20.0),
+            Leaf(30.0),
+            Leaf(40.0),
+        ];
+        DecisionTree::new(BTree::new(left, right, values)).unwrap()
+    }
+
+    #[test]
+    fn predict() {
+        let tree = tree();
+        assert_eq!(tree.predict(&[1.0, -1.0]), 10.0);
+        assert_eq!(tree.predict(&[0.0, 3.0]), 20.0);
+        assert_eq!(tree.predict(&[2.0, 5.0]), 30.0);
+        assert_eq!(tree.predict(&[2.0, f32::NAN]), 40.0);
+    }
+
+    #[test]
+    fn predict_many() {
+        let tree = tree();
+        let inputs: Vec<Vec<f32>> = (0..200)
+            .map(|i| vec![(i % 3) as f32, (i % 7) as f32 - 1.0])
+            .collect();
+        let expected: Vec<f32> = inputs.iter().map(|input| tree.predict(input)).collect();
+        assert_eq!(tree.predict_many(&inputs), expected);
+        assert!(tree.predict_many::<Vec<f32>>(&[]).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn predict_batch() {
+        let tree = tree();
+        let inputs: Vec<Vec<f32>> = (0..10_000)
+            .map(|i| vec![(i % 3) as f32, (i % 7) as f32 - 1.0])
+            .collect();
+        assert_eq!(tree.predict_batch(&inputs), tree.predict_many(&inputs));
+    }
+
+    #[test]
+    fn invalid_trees() {
+        let split = Split {
+            feature: 0,
+            threshold: 0.0,
+        };
+        let missing_child = BTree::new([1, -1], [-1, -1], [split, Leaf(1.0)]);
+        assert!(DecisionTree::new(missing_child).is_err());
+
+        let cycle = BTree::new([1, 0], [1, 0], [split, split]);
+        assert!(DecisionTree::new(cycle).is_err());
+
+        let leaf_with_children = BTree::new([1, -1], [-1, -1], [Leaf(0.0), Leaf(1.0)]);
+        assert!(DecisionTree::new(leaf_with_children).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let tree = tree();
+        let string_repr = serde_json::to_string(&tree).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DecisionTree<7>>(&string_repr).unwrap(),
+            tree
+        );
+
+        let missing_child = string_repr.replacen("[1,3,5,", "[-1,3,5,", 1);
+        assert!(serde_json::from_str::<DecisionTree<7>>(&missing_child).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::consts::LEAF_NODE;
+use crate::tree::Tree;
+
+/// Disjoint-set forest over elements `0..len`, with union by rank and path compression, so [DisjointForest::find]
+/// and [DisjointForest::union] run in amortized O(α(n)). The forest of parent links can be exported with
+/// [DisjointForest::trees], e.g. to inspect cluster hierarchies.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::disjoint::DisjointForest;
+///         let mut forest = DisjointForest::new(5);
+///         assert!(forest.union(0, 1));
+///         assert!(forest.union(3, 4));
+///         assert!(!forest.union(1, 0));
+///
+///         assert!(forest.connected(0, 1));
+///         assert!(!forest.connected(1, 3));
+///         assert_eq!(forest.set_count(), 3);
+///
+///         let trees = forest.trees();
+///         assert_eq!(trees.len(), 3);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisjointForest {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sets: usize,
+}
+
+impl DisjointForest {
+    /// Creates a forest of `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sets: len,
+        }
+    }
+
+    /// Number of elements in the forest.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// Adds a new singleton set, returning its element.
+    pub fn push(&mut self) -> usize {
+        let element = self.parents.len();
+        self.parents.push(element);
+        self.ranks.push(0);
+        self.sets += 1;
+        element
+    }
+
+    /// Parent of `element` in the forest, `None` for representatives of their sets.
+    pub fn parent(&self, element: usize) -> Option<usize> {
+        let parent = self.parents[element];
+        (parent != element).then_some(parent)
+    }
+
+    /// Representative of the set containing `element`. Compresses the path to the representative.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = element;
+        while current != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    /// Merges sets containing `a` and `b`. Returns `false` if they already were in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (parent, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = parent;
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    /// True if `a` and `b` are in the same set.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Exports every set as a [Tree] of its elements, following the current parent links. Trees are ordered by
+    /// their representatives, which are in root nodes. Nodes are numbered in BFS order, children of a node
+    /// ordered by their elements.
+    pub fn trees(&self) -> Vec<Tree<usize>> {
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); self.len()];
+        for (element, &parent) in self.parents.iter().enumerate() {
+            if parent != element {
+                children[parent].push(element);
+            }
+        }
+
+        (0..self.len())
+            .filter(|&element| self.parents[element] == element)
+            .map(|root| {
+                let mut elements = vec![root];
+                let mut next = 0;
+                while next < elements.len() {
+                    elements.extend_from_slice(&children[elements[next]]);
+                    next += 1;
+                }
+
+                let arity = elements
+                    .iter()
+                    .map(|&element| children[element].len())
+                    .max()
+                    .unwrap_or(0)
+                    .max(1);
+                let mut nodes = vec![vec![LEAF_NODE; elements.len()]; arity];
+                let mut child_id = 1;
+                for (node, &element) in elements.iter().enumerate() {
+                    for links in nodes.iter_mut().take(children[element].len()) {
+                        links[node] = child_id;
+                        child_id += 1;
+                    }
+                }
+
+                Tree::new(nodes, elements)
+                    .unwrap_or_else(|_| unreachable!("All dimensions have a link per element"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::disjoint::DisjointForest;
+
+    #[test]
+    fn union_find() {
+        let mut forest = DisjointForest::new(10);
+        for (a, b) in [(0, 1), (2, 3), (1, 3), (5, 6), (7, 8), (8, 9)] {
+            assert!(forest.union(a, b));
+        }
+        assert!(!forest.union(0, 2));
+        assert_eq!(forest.set_count(), 4);
+        assert!(forest.connected(0, 3));
+        assert!(forest.connected(7, 9));
+        assert!(!forest.connected(4, 5));
+
+        let representative = forest.find(3);
+        for element in [0, 1, 2, 3] {
+            assert_eq!(forest.find(element), representative);
+            assert!(forest
+                .parent(element)
+                .is_none_or(|parent| parent == representative));
+        }
+
+        let new = forest.push();
+        assert_eq!(new, 10);
+        assert_eq!(forest.set_count(), 5);
+        assert!(forest.union(new, 4));
+    }
+
+    #[test]
+    fn trees() {
+        let mut forest = DisjointForest::new(6);
+        forest.union(0, 1);
+        forest.union(2, 3);
+        forest.union(0, 2);
+        forest.union(4, 5);
+
+        let trees = forest.trees();
+        assert_eq!(trees.len(), 2);
+        // 0 is the representative, with 1 and 2 as children, 3 a child of 2
+        assert_eq!(trees[0].values(), &[0, 1, 2, 3]);
+        assert_eq!(trees[0].children(0), vec![1, 2]);
+        assert_eq!(trees[0].children(2), vec![3, -1]);
+        assert!(trees[0].is_leaf_node(3));
+
+        assert_eq!(trees[1].values(), &[4, 5]);
+        assert_eq!(trees[1].children(0), vec![1]);
+    }
+}
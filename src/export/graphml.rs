@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use crate::export::{escape, Attributes, Exportable, Exporter};
+
+impl<T> Exporter<'_, T> {
+    /// Writes `tree` as a directed GraphML graph. Every attribute name gets a key of string values, node ids are
+    /// tree node ids.
+    pub fn graphml(&self, tree: &impl Exportable<Value = T>) -> String {
+        let (nodes, edges) = self.attributes(tree);
+        let node_keys = keys(nodes.iter());
+        let edge_keys = keys(edges.iter().map(|(_, attributes)| attributes));
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (domain, prefix, keys) in [("node", 'n', &node_keys), ("edge", 'e', &edge_keys)] {
+            for (index, name) in keys.iter().enumerate() {
+                let _ = writeln!(
+                    xml,
+                    "  <key id=\"{prefix}{index}\" for=\"{domain}\" attr.name=\"{}\" attr.type=\"string\"/>",
+                    escape(name)
+                );
+            }
+        }
+        xml.push_str("  <graph id=\"tree\" edgedefault=\"directed\">\n");
+        for (node, attributes) in nodes.iter().enumerate() {
+            element(
+                &mut xml,
+                &format!("node id=\"{node}\""),
+                "node",
+                'n',
+                &node_keys,
+                attributes,
+            );
+        }
+        for (edge, attributes) in edges.iter() {
+            let tag = format!("edge source=\"{}\" target=\"{}\"", edge.source, edge.target);
+            element(&mut xml, &tag, "edge", 'e', &edge_keys, attributes);
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+/// Distinct attribute names in order of first appearance.
+fn keys<'a>(attributes: impl Iterator<Item = &'a Attributes>) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = Vec::new();
+    for (name, _) in attributes.flatten() {
+        if !keys.contains(&name.as_str()) {
+            keys.push(name);
+        }
+    }
+    keys
+}
+
+/// Writes a node or an edge element opened by `tag`, with a data element per attribute.
+fn element(
+    xml: &mut String,
+    tag: &str,
+    name: &str,
+    prefix: char,
+    keys: &[&str],
+    attributes: &Attributes,
+) {
+    if attributes.is_empty() {
+        let _ = writeln!(xml, "    <{tag}/>");
+        return;
+    }
+    let _ = writeln!(xml, "    <{tag}>");
+    for (key, value) in attributes {
+        let index = keys
+            .iter()
+            .position(|name| name == key)
+            .unwrap_or_else(|| unreachable!("Keys hold all attribute names"));
+        let _ = writeln!(
+            xml,
+            "      <data key=\"{prefix}{index}\">{}</data>",
+            escape(value)
+        );
+    }
+    let _ = writeln!(xml, "    </{name}>");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::Exporter;
+    use crate::tree::Tree;
+
+    #[test]
+    fn graphml() {
+        let tree = Tree::new(vec![vec![1, -1], vec![-1, -1]], vec!["<a>", "b & c"]).unwrap();
+        let exporter = Exporter::new()
+            .node_attributes(|node, value: &&str| {
+                let label = ("label".to_string(), value.to_string());
+                match node {
+                    0 => vec![label, ("root".to_string(), "true".to_string())],
+                    _ => vec![label],
+                }
+            })
+            .edge_attributes(|edge| vec![("dimension".to_string(), edge.dimension.to_string())]);
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="n0" for="node" attr.name="label" attr.type="string"/>
+  <key id="n1" for="node" attr.name="root" attr.type="string"/>
+  <key id="e0" for="edge" attr.name="dimension" attr.type="string"/>
+  <graph id="tree" edgedefault="directed">
+    <node id="0">
+      <data key="n0">&lt;a&gt;</data>
+      <data key="n1">true</data>
+    </node>
+    <node id="1">
+      <data key="n0">b &amp; c</data>
+    </node>
+    <edge source="0" target="1">
+      <data key="e0">0</data>
+    </edge>
+  </graph>
+</graphml>
+"#;
+        assert_eq!(exporter.graphml(&tree), expected);
+        assert!(Exporter::new()
+            .graphml(&tree)
+            .contains("    <node id=\"1\"/>\n"));
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt::Write;
+
+use crate::export::{Attributes, Exportable, Exporter};
+
+impl<T> Exporter<'_, T> {
+    /// Writes `tree` as a directed graph of the JSON Graph Format, version 2. Node ids are tree node ids as strings,
+    /// attributes become string values of `metadata` objects, left out if there are none.
+    pub fn json_graph(&self, tree: &impl Exportable<Value = T>) -> String {
+        let (nodes, edges) = self.attributes(tree);
+        let mut json = String::from(r#"{"graph":{"directed":true,"nodes":{"#);
+        for (node, attributes) in nodes.iter().enumerate() {
+            if node > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "\"{node}\":{{");
+            metadata(&mut json, attributes);
+            json.push('}');
+        }
+        json.push_str(r#"},"edges":["#);
+        for (index, (edge, attributes)) in edges.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                r#"{{"source":"{}","target":"{}""#,
+                edge.source, edge.target
+            );
+            if !attributes.is_empty() {
+                json.push(',');
+            }
+            metadata(&mut json, attributes);
+            json.push('}');
+        }
+        json.push_str("]}}");
+        json
+    }
+}
+
+/// Writes the `metadata` member of an object, nothing for no attributes.
+fn metadata(json: &mut String, attributes: &Attributes) {
+    if attributes.is_empty() {
+        return;
+    }
+    json.push_str(r#""metadata":{"#);
+    for (index, (name, value)) in attributes.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        string(json, name);
+        json.push(':');
+        string(json, value);
+    }
+    json.push('}');
+}
+
+fn string(json: &mut String, text: &str) {
+    json.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character.is_control() => {
+                let _ = write!(json, "\\u{:04x}", character as u32);
+            }
+            _ => json.push(character),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::Exporter;
+    use crate::sized::Tree;
+
+    #[test]
+    fn json_graph() {
+        let tree = Tree::new([[1, 2, -1], [-1, -1, -1]], ["root", "say \"hi\"", "a\nb"]);
+        let exporter = Exporter::new()
+            .node_attributes(|_, value: &&str| vec![("label".to_string(), value.to_string())]);
+        let expected = concat!(
+            r#"{"graph":{"directed":true,"nodes":{"0":{"metadata":{"label":"root"}},"#,
+            r#""1":{"metadata":{"label":"say \"hi\""}},"2":{"metadata":{"label":"a\nb"}}},"#,
+            r#""edges":[{"source":"0","target":"1"},{"source":"1","target":"2"}]}}"#
+        );
+        let json = exporter.json_graph(&tree);
+        assert_eq!(json, expected);
+        assert!(serde_json::from_str::<serde_json::Value>(&json).is_ok());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::consts::ROOT_NODE;
+use crate::implicit::ImplicitBTree;
+use crate::sized::{self, BTree};
+use crate::sparse::Indexed;
+use crate::tree::Tree;
+use crate::TreeLike;
+
+mod graphml;
+mod jgf;
+#[cfg(feature = "render-svg")]
+mod svg;
+
+/// Link from `source` to its child `target`, being in the `dimension`-th child slot.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Edge {
+    pub source: usize,
+    pub target: usize,
+    pub dimension: usize,
+}
+
+/// Tree exported by an [Exporter]: the nodes of a [TreeLike] and edges from parents to children. By default
+/// children take dimensions in their order, trees with missing children in between tell their dimensions.
+pub trait Exportable: TreeLike {
+    fn edges(&self) -> Vec<Edge> {
+        (0..self.len())
+            .flat_map(|source| {
+                self.children(source)
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(dimension, target)| Edge {
+                        source,
+                        target,
+                        dimension,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Edges of children `links` of `source`, skipping missing children and links out of bounds or to the root.
+fn edges_of(
+    source: usize,
+    links: impl IntoIterator<Item = isize>,
+    len: usize,
+) -> impl Iterator<Item = Edge> {
+    links
+        .into_iter()
+        .enumerate()
+        .filter(move |&(_, target)| target > ROOT_NODE && (target as usize) < len)
+        .map(move |(dimension, target)| Edge {
+            source,
+            target: target as usize,
+            dimension,
+        })
+}
+
+impl<T> Exportable for Tree<T> {
+    fn edges(&self) -> Vec<Edge> {
+        let len = self.len();
+        (0..len)
+            .flat_map(|node| edges_of(node, Tree::children(self, node), len))
+            .collect()
+    }
+}
+
+impl<T, const M: usize, const N: usize> Exportable for sized::Tree<T, M, N> {
+    fn edges(&self) -> Vec<Edge> {
+        (0..N)
+            .flat_map(|node| edges_of(node, sized::Tree::children(self, node), N))
+            .collect()
+    }
+}
+
+impl<T, const N: usize> Exportable for BTree<T, N> {
+    fn edges(&self) -> Vec<Edge> {
+        (0..N)
+            .flat_map(|node| {
+                let children = BTree::children(self, node);
+                edges_of(node, [children.left, children.right], N)
+            })
+            .collect()
+    }
+}
+
+impl<T> Exportable for ImplicitBTree<T> {}
+
+impl<T, const C: usize> Exportable for Indexed<T, C> {}
+
+/// Attributes of a node or an edge as `(name, value)` pairs.
+pub type Attributes = Vec<(String, String)>;
+
+type NodeAttributes<'a, T> = Box<dyn Fn(usize, &T) -> Attributes + 'a>;
+
+/// Writes trees in formats read by graph tools like Gephi or Cytoscape: GraphML and the JSON Graph Format. Attributes
+/// of nodes and edges are given by callbacks, none by default.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::export::Exporter;
+///         use treesome::sized::BTree;
+///         let tree = BTree::new([1, -1, -1], [2, -1, -1], ["root", "left", "right"]);
+///
+///         let exporter = Exporter::new()
+///             .node_attributes(|_, value: &&str| vec![("label".to_string(), value.to_string())])
+///             .edge_attributes(|edge| vec![("side".to_string(), ["left", "right"][edge.dimension].to_string())]);
+///         assert!(exporter.graphml(&tree).contains(r#"<data key="n0">left</data>"#));
+///         assert!(exporter.json_graph(&tree).contains(r#"{"source":"0","target":"2","metadata":{"side":"right"}}"#));
+/// ```
+pub struct Exporter<'a, T> {
+    node_attributes: NodeAttributes<'a, T>,
+    edge_attributes: Box<dyn Fn(Edge) -> Attributes + 'a>,
+}
+
+impl<'a, T> Exporter<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            node_attributes: Box::new(|_, _| Vec::new()),
+            edge_attributes: Box::new(|_| Vec::new()),
+        }
+    }
+
+    /// Attributes of every node, given its id and value.
+    pub fn node_attributes(mut self, attributes: impl Fn(usize, &T) -> Attributes + 'a) -> Self {
+        self.node_attributes = Box::new(attributes);
+        self
+    }
+
+    /// Attributes of every edge.
+    pub fn edge_attributes(mut self, attributes: impl Fn(Edge) -> Attributes + 'a) -> Self {
+        self.edge_attributes = Box::new(attributes);
+        self
+    }
+
+    /// Attributes of all nodes and edges of `tree`.
+    fn attributes(
+        &self,
+        tree: &impl Exportable<Value = T>,
+    ) -> (Vec<Attributes>, Vec<(Edge, Attributes)>) {
+        let nodes = (0..tree.len())
+            .map(|node| (self.node_attributes)(node, tree.value(node)))
+            .collect();
+        let edges = tree
+            .edges()
+            .into_iter()
+            .map(|edge| (edge, (self.edge_attributes)(edge)))
+            .collect();
+        (nodes, edges)
+    }
+}
+
+impl<T> Default for Exporter<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escapes `text` for XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,218 @@
+use std::fmt::Write;
+
+use crate::export::{escape, Exportable, Exporter};
+use crate::TreeLike;
+
+/// Horizontal distance between neighbouring nodes and vertical distance between levels, in pixels.
+const SPACING: f64 = 60.0;
+const LEVEL_HEIGHT: f64 = 70.0;
+const RADIUS: f64 = 18.0;
+const MARGIN: f64 = 30.0;
+
+/// Extent of a subtree at every depth below its root, `(leftmost, rightmost)` relative to the root.
+type Contour = Vec<(f64, f64)>;
+
+impl<T> Exporter<'_, T> {
+    /// Draws `tree` as an SVG picture of the nodes reachable from the root, laid out by the Reingold–Tilford tidy
+    /// tree algorithm: parents centered above their children, subtrees as close as possible without overlapping
+    /// and identical subtrees drawn the same. Every node is a circle with the text given by `label`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::export::Exporter;
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::new([1, -1, -1], [2, -1, -1], [5, 3, 8]);
+    ///
+    ///         let svg = Exporter::new().svg(&tree, |_, value| value.to_string());
+    ///         assert!(svg.starts_with("<svg"));
+    ///         assert_eq!(svg.matches("<circle").count(), 3);
+    ///         assert!(svg.contains(">8</text>"));
+    /// ```
+    pub fn svg(
+        &self,
+        tree: &impl Exportable<Value = T>,
+        label: impl Fn(usize, &T) -> String,
+    ) -> String {
+        let positions = layout(tree);
+        let depth = positions.iter().flatten().map(|&(_, depth)| depth).max();
+        let width = positions
+            .iter()
+            .flatten()
+            .map(|&(x, _)| x)
+            .fold(0.0, f64::max);
+        let point = |(x, depth): (f64, usize)| {
+            (
+                MARGIN + RADIUS + x * SPACING,
+                MARGIN + RADIUS + depth as f64 * LEVEL_HEIGHT,
+            )
+        };
+        let (width, height) = match depth {
+            Some(depth) => point((width, depth)),
+            None => (0.0, 0.0),
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+            width + RADIUS + MARGIN,
+            height + RADIUS + MARGIN
+        );
+        svg.push_str("  <g stroke=\"black\">\n");
+        for edge in tree.edges() {
+            let (Some(source), Some(target)) = (positions[edge.source], positions[edge.target])
+            else {
+                continue;
+            };
+            let ((x1, y1), (x2, y2)) = (point(source), point(target));
+            let _ = writeln!(
+                svg,
+                "    <line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\"/>"
+            );
+        }
+        svg.push_str("  </g>\n");
+        svg.push_str("  <g font-family=\"sans-serif\" font-size=\"12\" text-anchor=\"middle\">\n");
+        for (node, position) in positions.into_iter().enumerate() {
+            let Some(position) = position else {
+                continue;
+            };
+            let (x, y) = point(position);
+            let _ = writeln!(
+                svg,
+                "    <circle cx=\"{x}\" cy=\"{y}\" r=\"{RADIUS}\" fill=\"white\" stroke=\"black\"/>"
+            );
+            let text = escape(&label(node, tree.value(node)));
+            let _ = writeln!(
+                svg,
+                "    <text x=\"{x}\" y=\"{y}\" dy=\"0.35em\">{text}</text>"
+            );
+        }
+        svg.push_str("  </g>\n</svg>\n");
+        svg
+    }
+}
+
+/// Position of every node reachable from the root as `(x, depth)`, `x` in units of node spacing with the leftmost
+/// node at `0`. Unreachable nodes have no position.
+fn layout(tree: &impl TreeLike) -> Vec<Option<(f64, usize)>> {
+    let len = tree.len();
+    let children: Vec<Vec<usize>> = (0..len).map(|node| tree.children(node)).collect();
+    let mut positions = vec![None; len];
+    let Some(root) = tree.root() else {
+        return positions;
+    };
+
+    // Pre-order, so every subtree is placed after all of its own subtrees when walking it backwards
+    let mut order = Vec::new();
+    let mut visited = vec![false; len];
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if std::mem::replace(&mut visited[node], true) {
+            continue;
+        }
+        order.push(node);
+        stack.extend(children[node].iter().rev());
+    }
+
+    // Offset of every node from its parent
+    let mut offsets = vec![0.0; len];
+    let mut contours: Vec<Contour> = vec![Vec::new(); len];
+    for &node in order.iter().rev() {
+        let mut merged: Contour = Vec::new();
+        let mut placed: Vec<f64> = Vec::new();
+        for &child in children[node].iter().filter(|&&child| visited[child]) {
+            let contour = std::mem::take(&mut contours[child]);
+            let shift = merged
+                .iter()
+                .zip(contour.iter())
+                .map(|(&(_, right), &(left, _))| right - left + 1.0)
+                .fold(if placed.is_empty() { 0.0 } else { f64::MIN }, f64::max);
+            for (depth, &(left, right)) in contour.iter().enumerate() {
+                match merged.get_mut(depth) {
+                    Some(extent) => extent.1 = right + shift,
+                    None => merged.push((left + shift, right + shift)),
+                }
+            }
+            offsets[child] = shift;
+            placed.push(shift);
+        }
+        let center = match (placed.first(), placed.last()) {
+            (Some(first), Some(last)) => (first + last) / 2.0,
+            _ => 0.0,
+        };
+        for &child in children[node].iter().filter(|&&child| visited[child]) {
+            offsets[child] -= center;
+        }
+        let mut contour = vec![(0.0, 0.0)];
+        contour.extend(
+            merged
+                .into_iter()
+                .map(|(left, right)| (left - center, right - center)),
+        );
+        contours[node] = contour;
+    }
+
+    positions[root] = Some((0.0, 0));
+    for &node in order.iter() {
+        let Some((x, depth)) = positions[node] else {
+            continue;
+        };
+        for &child in children[node].iter() {
+            if positions[child].is_none() {
+                positions[child] = Some((x + offsets[child], depth + 1));
+            }
+        }
+    }
+    let leftmost = positions
+        .iter()
+        .flatten()
+        .map(|&(x, _)| x)
+        .fold(f64::MAX, f64::min);
+    for (x, _) in positions.iter_mut().flatten() {
+        *x -= leftmost;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::svg::layout;
+    use crate::export::Exporter;
+    use crate::tree::Tree;
+
+    #[test]
+    fn tidy_layout() {
+        // Root with a deep left subtree and a leaf on the right, node 5 is unreachable
+        let tree = Tree::new(
+            vec![vec![1, 3, -1, -1, -1, -1], vec![2, 4, -1, -1, -1, -1]],
+            vec!['r', 'a', 'b', 'c', 'd', 'x'],
+        )
+        .unwrap();
+        let positions = layout(&tree);
+        assert_eq!(positions[3], Some((0.0, 2)));
+        assert_eq!(positions[4], Some((1.0, 2)));
+        assert_eq!(positions[1], Some((0.5, 1)));
+        assert_eq!(positions[2], Some((1.5, 1)));
+        assert_eq!(positions[0], Some((1.0, 0)));
+        assert_eq!(positions[5], None);
+
+        // Subtrees are pushed apart only as far as their contours require
+        let wide = Tree::new(
+            vec![vec![1, 3, 5, -1, -1, -1, -1], vec![2, 4, 6, -1, -1, -1, -1]],
+            vec![0; 7],
+        )
+        .unwrap();
+        let xs: Vec<f64> = layout(&wide)
+            .into_iter()
+            .map(|position| position.unwrap().0)
+            .collect();
+        assert_eq!(xs, vec![1.5, 0.5, 2.5, 0.0, 1.0, 2.0, 3.0]);
+
+        let svg = Exporter::new().svg(&tree, |node, value| format!("{node}<{value}>"));
+        assert_eq!(svg.matches("<circle").count(), 5);
+        assert_eq!(svg.matches("<line").count(), 4);
+        assert!(svg.contains(">3&lt;c&gt;</text>"));
+        assert!(!svg.contains("5&lt;x&gt;"));
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt::{Display, Formatter, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::{Tree, TreeError};
+
+type TreeParts<T> = (Vec<Vec<isize>>, Vec<T>);
+
+/// Node of an expression tree - either an operator applied to its children, or a value.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExprNode<Op, Val> {
+    Operator(Op),
+    Value(Val),
+}
+
+/// Operator of an expression tree, applied to values of its operands in order.
+pub trait Operator<Val> {
+    type Error;
+
+    fn apply(&self, operands: Vec<Val>) -> Result<Val, Self::Error>;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ExprError<E> {
+    /// The operator failed, e.g. due to a division by zero.
+    Operator(E),
+    /// The tree contains no nodes.
+    Empty,
+    /// A value node with children.
+    ValueWithOperands(usize),
+}
+
+impl<E: Display> Display for ExprError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::Operator(error) => write!(f, "Operator failed: {error}"),
+            ExprError::Empty => write!(f, "Empty expression"),
+            ExprError::ValueWithOperands(node) => write!(f, "Value node {node} has operands"),
+        }
+    }
+}
+
+/// Expression tree over a [Tree] of [ExprNode]s, with operators in inner nodes and values in leaves.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::expr::{Expr, Operator};
+///
+///         #[derive(Clone)]
+///         enum Arithmetic { Add, Mul }
+///
+///         impl Operator<i64> for Arithmetic {
+///             type Error = ();
+///             fn apply(&self, operands: Vec<i64>) -> Result<i64, ()> {
+///                 Ok(match self {
+///                     Arithmetic::Add => operands.iter().sum(),
+///                     Arithmetic::Mul => operands.iter().product(),
+///                 })
+///             }
+///         }
+///
+///         impl std::fmt::Display for Arithmetic {
+///             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///                 f.write_str(match self { Arithmetic::Add => "+", Arithmetic::Mul => "*" })
+///             }
+///         }
+///
+///         // (2 + 3) * 4
+///         let sum = Expr::operator(Arithmetic::Add, vec![Expr::value(2), Expr::value(3)]);
+///         let expr = Expr::operator(Arithmetic::Mul, vec![sum, Expr::value(4)]);
+///
+///         assert_eq!(expr.evaluate(), Ok(20));
+///         assert_eq!(expr.to_infix(), "((2 + 3) * 4)");
+///         assert_eq!(expr.to_prefix(), "* + 2 3 4");
+///         assert_eq!(expr.to_postfix(), "2 3 + 4 *");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Expr<Op, Val> {
+    tree: Tree<ExprNode<Op, Val>>,
+}
+
+impl<Op, Val> Expr<Op, Val> {
+    /// Wraps an existing tree, node `0` being the root of the expression. The tree is checked by [Tree::validate],
+    /// so evaluation and rendering always end, and value nodes must be leaves, otherwise
+    /// [TreeError::CorruptedTree] is returned.
+    pub fn from_tree(tree: Tree<ExprNode<Op, Val>>) -> Result<Self, TreeError> {
+        tree.validate()?;
+        let expr = Self { tree };
+        let values = expr.tree.values();
+        if let Some(node) = (0..values.len()).find(|&node| {
+            matches!(values[node], ExprNode::Value(_)) && !expr.operands(node).is_empty()
+        }) {
+            return Err(TreeError::CorruptedTree(format!(
+                "Value node {node} has operands"
+            )));
+        }
+        Ok(expr)
+    }
+
+    /// A single value.
+    pub fn value(value: Val) -> Self {
+        Self {
+            tree: Tree::new(vec![], vec![ExprNode::Value(value)])
+                .unwrap_or_else(|_| unreachable!("A tree with no dimensions is always valid")),
+        }
+    }
+
+    /// `operator` applied to `operands`, in order. Copies all operand nodes into a new tree. Operands are moved
+    /// unless shared with a tree [Snapshot](crate::tree::Snapshot), in which case they're cloned.
+    pub fn operator(operator: Op, operands: Vec<Expr<Op, Val>>) -> Self
+    where
+        Op: Clone,
+        Val: Clone,
+    {
+        let parts: Vec<TreeParts<ExprNode<Op, Val>>> = operands
+            .into_iter()
+            .map(|operand| operand.tree.into_parts())
+            .collect();
+        let arity = parts
+            .iter()
+            .map(|(nodes, _)| nodes.len())
+            .max()
+            .unwrap_or(0)
+            .max(parts.len());
+
+        let mut nodes: Vec<Vec<isize>> = vec![vec![LEAF_NODE]; arity];
+        let mut values = vec![ExprNode::Operator(operator)];
+        for (slot, (operand_nodes, operand_values)) in parts.into_iter().enumerate() {
+            let offset = values.len() as isize;
+            nodes[slot][0] = offset;
+            for (dimension, links) in nodes.iter_mut().enumerate() {
+                let operand_links = operand_nodes.get(dimension);
+                links.extend((0..operand_values.len()).map(|node| {
+                    match operand_links.map(|links| links[node]) {
+                        Some(link) if link != LEAF_NODE => link + offset,
+                        _ => LEAF_NODE,
+                    }
+                }));
+            }
+            values.extend(operand_values);
+        }
+
+        Self {
+            tree: Tree::new(nodes, values)
+                .unwrap_or_else(|_| unreachable!("All dimensions are filled in lockstep")),
+        }
+    }
+
+    pub fn tree(&self) -> &Tree<ExprNode<Op, Val>> {
+        &self.tree
+    }
+
+    /// Evaluates the expression by an iterative post-order reduction, so deep expressions can't overflow the stack.
+    pub fn evaluate(&self) -> Result<Val, ExprError<Op::Error>>
+    where
+        Op: Operator<Val>,
+        Val: Clone,
+    {
+        let values = self.tree.values();
+        if values.is_empty() {
+            return Err(ExprError::Empty);
+        }
+
+        let mut results: Vec<Val> = Vec::new();
+        let mut stack = vec![(ROOT_NODE as usize, false)];
+        while let Some((node, reduced)) = stack.pop() {
+            let operands = self.operands(node);
+            match &values[node] {
+                ExprNode::Value(value) => {
+                    if !operands.is_empty() {
+                        return Err(ExprError::ValueWithOperands(node));
+                    }
+                    results.push(value.clone());
+                }
+                ExprNode::Operator(operator) if reduced => {
+                    let arguments = results.split_off(results.len() - operands.len());
+                    results.push(operator.apply(arguments).map_err(ExprError::Operator)?);
+                }
+                ExprNode::Operator(_) => {
+                    stack.push((node, true));
+                    stack.extend(operands.into_iter().rev().map(|operand| (operand, false)));
+                }
+            }
+        }
+
+        Ok(results.pop().expect("Root reduces to a single value"))
+    }
+
+    /// Renders the expression in infix notation. Binary operators are parenthesized, operators of other arities
+    /// are rendered as function calls. Rendered iteratively, like [Expr::evaluate].
+    pub fn to_infix(&self) -> String
+    where
+        Op: Display,
+        Val: Display,
+    {
+        let mut output = String::new();
+        // Pieces left to write, the next one on top
+        let mut stack: Vec<Infix> = self.root().map(Infix::Node).into_iter().collect();
+        while let Some(piece) = stack.pop() {
+            let node = match piece {
+                Infix::Text(text) => {
+                    output.push_str(&text);
+                    continue;
+                }
+                Infix::Node(node) => node,
+            };
+            let operands = self.operands(node);
+            match &self.tree.values()[node] {
+                ExprNode::Value(value) => write!(output, "{value}").expect("Writing to a String"),
+                ExprNode::Operator(operator) if operands.len() == 2 => {
+                    output.push('(');
+                    stack.extend([
+                        Infix::Text(")".to_string()),
+                        Infix::Node(operands[1]),
+                        Infix::Text(format!(" {operator} ")),
+                        Infix::Node(operands[0]),
+                    ]);
+                }
+                ExprNode::Operator(operator) => {
+                    write!(output, "{operator}(").expect("Writing to a String");
+                    stack.push(Infix::Text(")".to_string()));
+                    for (position, &operand) in operands.iter().enumerate().rev() {
+                        stack.push(Infix::Node(operand));
+                        if position > 0 {
+                            stack.push(Infix::Text(", ".to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    /// Renders the expression in prefix (Polish) notation, tokens separated by spaces.
+    pub fn to_prefix(&self) -> String
+    where
+        Op: Display,
+        Val: Display,
+    {
+        let mut tokens = Vec::new();
+        let mut stack: Vec<usize> = self.root().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            tokens.push(self.token(node));
+            stack.extend(self.operands(node).into_iter().rev());
+        }
+        tokens.join(" ")
+    }
+
+    /// Renders the expression in postfix (reverse Polish) notation, tokens separated by spaces.
+    pub fn to_postfix(&self) -> String
+    where
+        Op: Display,
+        Val: Display,
+    {
+        let mut tokens = Vec::new();
+        let mut stack: Vec<(usize, bool)> =
+            self.root().map(|root| (root, false)).into_iter().collect();
+        while let Some((node, expanded)) = stack.pop() {
+            if expanded {
+                tokens.push(self.token(node));
+            } else {
+                stack.push((node, true));
+                stack.extend(
+                    self.operands(node)
+                        .into_iter()
+                        .rev()
+                        .map(|operand| (operand, false)),
+                );
+            }
+        }
+        tokens.join(" ")
+    }
+
+    fn root(&self) -> Option<usize> {
+        (!self.tree.values().is_empty()).then_some(ROOT_NODE as usize)
+    }
+
+    fn operands(&self, node: usize) -> Vec<usize> {
+        self.tree
+            .children(node)
+            .into_iter()
+            .filter(|&child| child != LEAF_NODE)
+            .map(|child| child as usize)
+            .collect()
+    }
+
+    fn token(&self, node: usize) -> String
+    where
+        Op: Display,
+        Val: Display,
+    {
+        match &self.tree.values()[node] {
+            ExprNode::Operator(operator) => operator.to_string(),
+            ExprNode::Value(value) => value.to_string(),
+        }
+    }
+}
+
+/// Piece of an infix rendering still to be written, see [Expr::to_infix].
+enum Infix {
+    Node(usize),
+    Text(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter};
+
+    use crate::expr::{Expr, ExprError, ExprNode, Operator};
+    use crate::tree::{Tree, TreeError};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Op {
+        Sub,
+        Div,
+        Neg,
+        Max,
+    }
+
+    impl Operator<f64> for Op {
+        type Error = String;
+
+        fn apply(&self, operands: Vec<f64>) -> Result<f64, Self::Error> {
+            match (self, operands.as_slice()) {
+                (Op::Sub, [a, b]) => Ok(a - b),
+                (Op::Div, [_, b]) if *b == 0.0 => Err("Division by zero".to_string()),
+                (Op::Div, [a, b]) => Ok(a / b),
+                (Op::Neg, [a]) => Ok(-a),
+                (Op::Max, operands) => Ok(operands.iter().copied().fold(f64::MIN, f64::max)),
+                (op, operands) => Err(format!("{op} can't take {} operands", operands.len())),
+            }
+        }
+    }
+
+    impl Display for Op {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let symbol = match self {
+                Op::Sub => "-",
+                Op::Div => "/",
+                Op::Neg => "neg",
+                Op::Max => "max",
+            };
+            f.write_str(symbol)
+        }
+    }
+
+    #[test]
+    fn evaluate() {
+        // max(10 - 4, neg(2), 9 / 3)
+        let expr = Expr::operator(
+            Op::Max,
+            vec![
+                Expr::operator(Op::Sub, vec![Expr::value(10.0), Expr::value(4.0)]),
+                Expr::operator(Op::Neg, vec![Expr::value(2.0)]),
+                Expr::operator(Op::Div, vec![Expr::value(9.0), Expr::value(3.0)]),
+            ],
+        );
+        assert_eq!(expr.evaluate(), Ok(6.0));
+        assert_eq!(expr.to_infix(), "max((10 - 4), neg(2), (9 / 3))");
+        assert_eq!(expr.to_prefix(), "max - 10 4 neg 2 / 9 3");
+        assert_eq!(expr.to_postfix(), "10 4 - 2 neg 9 3 / max");
+
+        let failing = Expr::operator(Op::Div, vec![Expr::value(1.0), Expr::value(0.0)]);
+        assert_eq!(
+            failing.evaluate(),
+            Err(ExprError::Operator("Division by zero".to_string()))
+        );
+    }
+
+    #[test]
+    fn deep_expression() {
+        let mut expr = Expr::value(0.0);
+        for _ in 0..2_000 {
+            expr = Expr::operator(Op::Neg, vec![expr]);
+        }
+        assert_eq!(expr.evaluate(), Ok(0.0));
+        assert_eq!(expr.to_postfix().matches("neg").count(), 2_000);
+        assert_eq!(expr.to_infix().matches("neg(").count(), 2_000);
+    }
+
+    #[test]
+    fn from_tree() {
+        let values = vec![
+            ExprNode::Operator(Op::Sub),
+            ExprNode::Value(1.0),
+            ExprNode::Value(2.0),
+        ];
+        let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], values).unwrap();
+        assert_eq!(Expr::from_tree(tree).unwrap().evaluate(), Ok(-1.0));
+
+        let values = vec![ExprNode::Value(1.0), ExprNode::Value(2.0)];
+        let tree = Tree::new(vec![vec![1, -1]], values).unwrap();
+        assert_eq!(
+            Expr::<Op, f64>::from_tree(tree),
+            Err(TreeError::CorruptedTree(
+                "Value node 0 has operands".to_string()
+            ))
+        );
+
+        // Cycles and links out of bounds are rejected up front
+        let values = vec![ExprNode::Operator(Op::Neg), ExprNode::Operator(Op::Neg)];
+        let cycle = Tree::new(vec![vec![1, 0]], values.clone()).unwrap();
+        assert!(Expr::<Op, f64>::from_tree(cycle).is_err());
+        let dangling = Tree::new(vec![vec![1, 5]], values).unwrap();
+        assert_eq!(
+            Expr::<Op, f64>::from_tree(dangling),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: 5 })
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+/// Bytes of memory taken by a tree, as reported by `memory_footprint()` of the individual trees.
+///
+/// Values are counted by their inline size, memory they own on the heap (e.g. contents of a `String`) isn't
+/// followed. Allocator metadata isn't known and isn't included either. Buffers shared between clones or snapshots
+/// of a tree are counted in full by each of them.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::Tree;
+///         let tree = Tree::new(vec![vec![1, -1]], vec![1_u64, 2]).expect("Tree has a valid structure");
+///         let footprint = tree.memory_footprint();
+///
+///         assert_eq!(footprint.values, 2 * size_of::<u64>());
+///         assert_eq!(footprint.total(), footprint.structure + footprint.values + footprint.overhead);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct MemoryFootprint {
+    /// Links, ids and other bookkeeping of nodes, including the inline size of the tree itself.
+    pub structure: usize,
+    /// Inline size of values, `size_of::<T>()` per stored value.
+    pub values: usize,
+    /// Reserved but unused capacity, vacant slots, padding, reference counts and borrow flags.
+    pub overhead: usize,
+}
+
+impl MemoryFootprint {
+    pub fn total(&self) -> usize {
+        self.structure + self.values + self.overhead
+    }
+
+    /// Footprint of a tree of type `S` living inline, e.g. in an array. Holds `values` values of type `T` and
+    /// `structure` bytes of links, the rest of `S` is overhead.
+    pub(crate) fn inline<S, T>(values: usize, structure: usize) -> Self {
+        let values = values * size_of::<T>();
+        Self {
+            structure,
+            values,
+            overhead: size_of::<S>() - structure - values,
+        }
+    }
+
+    /// Footprint of a vector's heap buffer, the used part being values or structure as told by `values`.
+    pub(crate) fn vec<T>(vec: &Vec<T>, values: bool) -> Self {
+        let used = vec.len() * size_of::<T>();
+        Self {
+            structure: if values { 0 } else { used },
+            values: if values { used } else { 0 },
+            overhead: (vec.capacity() - vec.len()) * size_of::<T>(),
+        }
+    }
+
+    /// Footprint of a vector's heap buffer of entries, each holding `values` bytes of values and structure in the
+    /// rest of it.
+    pub(crate) fn entries<E>(entries: &Vec<E>, values: usize) -> Self {
+        let entry = size_of::<E>();
+        Self {
+            structure: entries.len() * (entry - values),
+            values: entries.len() * values,
+            overhead: (entries.capacity() - entries.len()) * entry,
+        }
+    }
+
+    /// Footprint of an arena of slots, `occupied` of them holding an entry with `values` bytes of values. Vacant
+    /// slots and the list of them are overhead.
+    pub(crate) fn slots<S, F>(
+        slots: &Vec<S>,
+        occupied: usize,
+        values: usize,
+        free: &Vec<F>,
+    ) -> Self {
+        let slot = size_of::<S>();
+        Self {
+            structure: occupied * (slot - values),
+            values: occupied * values,
+            overhead: (slots.capacity() - occupied) * slot + free.capacity() * size_of::<F>(),
+        }
+    }
+}
+
+impl Add for MemoryFootprint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            structure: self.structure + other.structure,
+            values: self.values + other.values,
+            overhead: self.overhead + other.overhead,
+        }
+    }
+}
+
+impl AddAssign for MemoryFootprint {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for MemoryFootprint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::ArenaTree;
+    use crate::footprint::MemoryFootprint;
+    use crate::implicit::ImplicitBTree;
+    use crate::radix::RadixTree;
+    use crate::search::{AvlTree, BPlusTree, Treap};
+    use crate::segment::{SegmentTree, Sum};
+    use crate::sized::{Heap, Tree};
+    use crate::sparse::Node;
+    use crate::spatial::KdTree;
+
+    #[test]
+    fn footprints() {
+        let word = size_of::<usize>();
+        let sized = Tree::new([[1, -1, -1], [2, -1, -1]], [0_u64; 3]);
+        assert_eq!(
+            sized.memory_footprint(),
+            MemoryFootprint {
+                structure: 6 * word,
+                values: 24,
+                overhead: 0
+            }
+        );
+
+        let mut heap = Heap::<u64, 4>::new();
+        heap.push(1).unwrap();
+        let footprint = heap.memory_footprint();
+        assert_eq!((footprint.values, footprint.structure), (8, word));
+        assert_eq!(footprint.total(), size_of::<Heap<u64, 4>>());
+
+        let mut implicit = ImplicitBTree::from(Vec::with_capacity(4));
+        implicit.push(1_u32);
+        let footprint = implicit.memory_footprint();
+        assert_eq!((footprint.values, footprint.overhead), (4, 12));
+
+        // Removed nodes turn into overhead
+        let mut arena = ArenaTree::new(0_u64);
+        let child = arena.append(arena.root(), 1).unwrap();
+        let before = arena.memory_footprint();
+        arena.remove(child).unwrap();
+        let after = arena.memory_footprint();
+        assert_eq!(after.values, 8);
+        assert!(after.structure < before.structure && after.overhead > before.overhead);
+
+        // Both nodes pay for reference counts, the root's children are spilled to the heap
+        let root = Node::root(0_u8);
+        root.create_child(1);
+        let footprint = root.memory_footprint();
+        assert_eq!(footprint.values, 2);
+        assert!(footprint.overhead >= 4 * word);
+    }
+
+    #[test]
+    fn other_trees() {
+        // Keys count as values, removed entries turn into overhead
+        let mut avl = AvlTree::new();
+        (0..4_u64).for_each(|key| {
+            avl.insert(key, 0_u32);
+        });
+        assert_eq!(avl.memory_footprint().values, 4 * 12);
+        avl.remove(&0);
+        let footprint = avl.memory_footprint();
+        assert_eq!(footprint.values, 3 * 12);
+        assert!(footprint.overhead > 0);
+
+        let mut bplus = BPlusTree::<u64, u64, 4>::new();
+        (0..10).for_each(|key| {
+            bplus.insert(key, key);
+        });
+        assert!(bplus.memory_footprint().values >= 10 * 16);
+
+        let treap: Treap<u32, u32> = (0..5).map(|key| (key, key)).collect();
+        assert_eq!(treap.memory_footprint().values, 5 * 8);
+
+        let kd = KdTree::build(vec![([0.0, 1.0], 1_u8), ([1.0, 0.0], 2)]);
+        let footprint = kd.memory_footprint();
+        assert_eq!(footprint.values, 2);
+        assert!(footprint.structure >= 4 * size_of::<f64>());
+
+        let segment = SegmentTree::<i64, Sum>::new(&[1, 2, 3]);
+        assert!(segment.memory_footprint().values >= 3 * size_of::<i64>());
+
+        let mut radix = RadixTree::new();
+        radix.insert("tree", 1_u64);
+        radix.insert("trie", 2);
+        let footprint = radix.memory_footprint();
+        assert_eq!(footprint.values, 16);
+        assert!(footprint.structure >= size_of::<RadixTree<u64>>() - 8 + "tr".len());
+    }
+}
@@ -0,0 +1,427 @@
+use std::cmp::Ordering;
+use std::ops::{Index, IndexMut, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::sized::{range_position, sift_down, sift_up};
+use crate::tree::NodeId;
+
+/// Complete binary tree in the implicit layout: only values are stored, children of node `i` are `2i + 1` and
+/// `2i + 2`, its parent is `(i - 1) / 2`. Every level but the last one is full and the last one is filled
+/// from the left, so pushing and popping values keeps the tree complete.
+///
+/// Suitable for heaps, segment trees or tournament brackets. Compared to [BTree](crate::sized::BTree), there are
+/// no child indices to store, read or validate.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::implicit::ImplicitBTree;
+///         use treesome::tree::NodeId;
+///         let tree = ImplicitBTree::from(vec!["a", "b", "c", "d"]);
+///
+///         assert_eq!(tree.children(NodeId::ROOT), (Some(NodeId::new(1)), Some(NodeId::new(2))));
+///         assert_eq!(tree.children(NodeId::new(1)), (Some(NodeId::new(3)), None));
+///         assert_eq!(tree.parent(NodeId::new(3)), Some(NodeId::new(1)));
+///         assert_eq!(tree[NodeId::new(3)], "d");
+///         assert_eq!(tree.height(), 3);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct ImplicitBTree<T> {
+    values: Vec<T>,
+}
+
+impl<T> ImplicitBTree<T> {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. There are no links, the structure is the vector of values.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::vec(&self.values, true)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Number of levels, zero for an empty tree.
+    pub fn height(&self) -> usize {
+        (usize::BITS - self.values.len().leading_zeros()) as usize
+    }
+
+    /// Depth of `node`, the root being at depth zero. The node doesn't need to exist yet.
+    pub fn depth(&self, node: NodeId) -> usize {
+        node.index()
+            .checked_add(1)
+            .map_or(usize::BITS, |position| position.ilog2()) as usize
+    }
+
+    pub fn root(&self) -> Option<&T> {
+        self.values.first()
+    }
+
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.values.get(node.index())
+    }
+
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut T> {
+        self.values.get_mut(node.index())
+    }
+
+    pub fn left(&self, node: NodeId) -> Option<NodeId> {
+        self.existing(node.index().checked_mul(2)?.checked_add(1)?)
+    }
+
+    pub fn right(&self, node: NodeId) -> Option<NodeId> {
+        self.existing(node.index().checked_mul(2)?.checked_add(2)?)
+    }
+
+    /// Left and right child of a node, `None` where there's no child.
+    pub fn children(&self, node: NodeId) -> (Option<NodeId>, Option<NodeId>) {
+        (self.left(node), self.right(node))
+    }
+
+    /// Parent of a node, `None` for the root and nodes that don't exist.
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.existing(node.index())?;
+        node.implicit_parent(2)
+    }
+
+    /// First child of `node`, i.e. its left child. `None` for leaves and nodes that don't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         use treesome::tree::NodeId;
+    ///         let tree = ImplicitBTree::from(vec!['a', 'b', 'c', 'd']);
+    ///         assert_eq!(tree.first_child(NodeId::new(1)), Some(NodeId::new(3)));
+    ///         assert_eq!(tree.last_child(NodeId::new(1)), Some(NodeId::new(3)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 1), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.nth_child(NodeId::new(1), 1), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.left(node)
+    }
+
+    /// Last child of `node`, the right one if it exists. `None` for leaves and nodes that don't exist.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        self.right(node).or_else(|| self.left(node))
+    }
+
+    /// Child of `node` at position `k`, left being zero. `None` if the node has at most `k` children or doesn't
+    /// exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        match k {
+            0 => self.left(node),
+            1 => self.right(node),
+            _ => None,
+        }
+    }
+
+    /// True if given `node` is a leaf node (no children), false otherwise.
+    pub fn is_leaf_node(&self, node: NodeId) -> bool {
+        self.left(node).is_none()
+    }
+
+    /// Appends a value as the next node of the last level.
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    /// Removes the last node of the last level.
+    pub fn pop(&mut self) -> Option<T> {
+        self.values.pop()
+    }
+
+    /// Values in level order, i.e. in order of node ids.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    /// Node ids in in-order, left subtree first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         let tree = ImplicitBTree::from(vec![4, 2, 6, 1, 3, 5]);
+    ///         let sorted: Vec<i32> = tree.inorder().map(|node| tree[node]).collect();
+    ///         assert_eq!(sorted, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn inorder(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.inorder_within(|_| Ordering::Equal)
+    }
+
+    /// Values of a binary search tree within `range`, in ascending order. Left subtrees of nodes below the start
+    /// of the range are never visited and the walk ends at the first value past its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         let tree = ImplicitBTree::from(vec![4, 2, 6, 1, 3, 5]);
+    ///         assert_eq!(tree.range(2..5).collect::<Vec<_>>(), vec![&2, &3, &4]);
+    ///         assert_eq!(tree.range(5..).collect::<Vec<_>>(), vec![&5, &6]);
+    /// ```
+    pub fn range<'a, R>(&'a self, range: R) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Ord,
+        R: RangeBounds<T> + 'a,
+    {
+        self.inorder_within(move |value| range_position(&range, value))
+            .map(|node| &self[node])
+    }
+
+    /// In-order walk over nodes whose value `position` places within a range. Nodes before the range are skipped
+    /// with their left subtrees, the walk ends at the first node past it.
+    fn inorder_within<'a, F>(&'a self, position: F) -> impl Iterator<Item = NodeId> + 'a
+    where
+        F: Fn(&T) -> Ordering + 'a,
+    {
+        let mut stack = Vec::new();
+        let mut node = self.existing(0);
+        std::iter::from_fn(move || {
+            while let Some(current) = node {
+                if position(&self[current]) == Ordering::Less {
+                    node = self.right(current);
+                } else {
+                    stack.push(current);
+                    node = self.left(current);
+                }
+            }
+            let current = stack.pop()?;
+            if position(&self[current]) == Ordering::Greater {
+                stack.clear();
+                return None;
+            }
+            node = self.right(current);
+            Some(current)
+        })
+    }
+
+    /// Values in in-order, see [ImplicitBTree::inorder]. Ascending if the values are laid out as a binary search
+    /// tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         let tree = ImplicitBTree::from(vec![4, 2, 6, 1, 3, 5]);
+    ///
+    ///         assert_eq!(tree.to_sorted_vec(), vec![1, 2, 3, 4, 5, 6]);
+    ///         assert_eq!(tree.min(), Some(&1));
+    ///         assert_eq!(tree.max(), Some(&6));
+    /// ```
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &T> + '_ {
+        self.inorder().map(|node| &self[node])
+    }
+
+    /// Values collected in in-order, see [ImplicitBTree::iter_sorted].
+    pub fn to_sorted_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter_sorted().cloned().collect()
+    }
+
+    /// Smallest value of a binary search tree, the leftmost node on the path of left children from the root.
+    /// `None` for an empty tree.
+    pub fn min(&self) -> Option<&T> {
+        let mut node = self.existing(0)?;
+        while let Some(left) = self.left(node) {
+            node = left;
+        }
+        Some(&self[node])
+    }
+
+    /// Greatest value of a binary search tree, the rightmost node on the path of right children from the root.
+    /// `None` for an empty tree.
+    pub fn max(&self) -> Option<&T> {
+        let mut node = self.existing(0)?;
+        while let Some(right) = self.right(node) {
+            node = right;
+        }
+        Some(&self[node])
+    }
+
+    /// Moves the value of `node` up while it's greater than its parent's, restoring the max-heap order after
+    /// the value has grown. Returns the node the value ends at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         use treesome::tree::NodeId;
+    ///         let mut tree = ImplicitBTree::from(vec![3, 9, 4, 1, 7]);
+    ///         assert!(!tree.is_heap());
+    ///
+    ///         tree.heapify();
+    ///         assert_eq!(tree.values(), &[9, 7, 4, 1, 3]);
+    ///         tree.push(8);
+    ///         assert_eq!(tree.sift_up(NodeId::new(5)), NodeId::new(2));
+    ///         tree[NodeId::ROOT] = 2;
+    ///         assert_eq!(tree.sift_down(NodeId::ROOT), NodeId::new(5));
+    ///         assert!(tree.is_heap());
+    /// ```
+    ///
+    /// Panics if `node` is out of bounds.
+    pub fn sift_up(&mut self, node: NodeId) -> NodeId
+    where
+        T: Ord,
+    {
+        assert!(
+            node.index() < self.values.len(),
+            "Node {node} is out of bounds"
+        );
+        let moved = sift_up(&mut self.values, node.index(), |node| {
+            NodeId::new(node).implicit_parent(2).map(NodeId::index)
+        });
+        NodeId::new(moved)
+    }
+
+    /// Moves the value of `node` down while it's smaller than one of its children, always swapping with the
+    /// greater child, restoring the max-heap order after the value has shrunk. Returns the node the value ends at.
+    ///
+    /// Panics if `node` is out of bounds.
+    pub fn sift_down(&mut self, node: NodeId) -> NodeId
+    where
+        T: Ord,
+    {
+        assert!(
+            node.index() < self.values.len(),
+            "Node {node} is out of bounds"
+        );
+        let len = self.values.len();
+        let moved = sift_down(&mut self.values, node.index(), |node| {
+            [2 * node + 1, 2 * node + 2].map(|child| (child < len).then_some(child))
+        });
+        NodeId::new(moved)
+    }
+
+    /// Reorders the values in place into a max-heap in O(n), the greatest value ending at the root.
+    pub fn heapify(&mut self)
+    where
+        T: Ord,
+    {
+        for node in (0..self.values.len() / 2).rev() {
+            self.sift_down(NodeId::new(node));
+        }
+    }
+
+    /// True if no value is greater than its parent's.
+    pub fn is_heap(&self) -> bool
+    where
+        T: Ord,
+    {
+        (1..self.values.len()).all(|node| self.values[node] <= self.values[(node - 1) / 2])
+    }
+
+    fn existing(&self, node_id: usize) -> Option<NodeId> {
+        (node_id < self.values.len()).then_some(NodeId::new(node_id))
+    }
+}
+
+impl<T> From<Vec<T>> for ImplicitBTree<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self { values }
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitBTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Index<NodeId> for ImplicitBTree<T> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self.values[node.index()]
+    }
+}
+
+impl<T> IndexMut<NodeId> for ImplicitBTree<T> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self.values[node.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::implicit::ImplicitBTree;
+    use crate::tree::NodeId;
+
+    #[test]
+    fn navigation() {
+        let id = NodeId::new;
+        let mut tree: ImplicitBTree<usize> = (0..6).collect();
+        assert_eq!(tree.children(id(2)), (Some(id(5)), None));
+        assert_eq!(tree.children(id(3)), (None, None));
+        assert!(tree.is_leaf_node(id(3)));
+        assert!(!tree.is_leaf_node(id(2)));
+        assert_eq!(tree.parent(NodeId::ROOT), None);
+        assert_eq!(tree.parent(id(5)), Some(id(2)));
+        assert_eq!(tree.parent(id(6)), None);
+        assert_eq!(tree.left(id(usize::MAX)), None);
+        assert_eq!(tree.right(id(usize::MAX / 2)), None);
+
+        assert_eq!(tree.height(), 3);
+        tree.push(6);
+        tree.push(7);
+        assert_eq!(tree.height(), 4);
+        assert_eq!(tree.depth(NodeId::ROOT), 0);
+        assert_eq!(tree.depth(id(6)), 2);
+        assert_eq!(tree.depth(id(7)), 3);
+        assert_eq!(tree.depth(id(usize::MAX)), usize::BITS as usize);
+        assert_eq!(tree.pop(), Some(7));
+        assert_eq!(
+            tree.inorder().map(NodeId::index).collect::<Vec<_>>(),
+            vec![3, 1, 4, 0, 5, 2, 6]
+        );
+
+        assert!(ImplicitBTree::<u8>::new().inorder().next().is_none());
+        assert_eq!(ImplicitBTree::<u8>::new().height(), 0);
+    }
+
+    #[test]
+    fn range() {
+        let tree = ImplicitBTree::from(vec![8, 4, 12, 2, 6, 10, 14, 1, 3, 5, 7, 9, 11, 13, 15]);
+        let within = |range| tree.range(range).copied().collect::<Vec<_>>();
+        assert_eq!(within(6..=11), vec![6, 7, 8, 9, 10, 11]);
+        assert!(within(16..=20).is_empty());
+        assert!(within(0..=0).is_empty());
+        assert_eq!(tree.range(..).count(), 15);
+        assert_eq!(
+            tree.range((Bound::Excluded(13), Bound::Unbounded))
+                .collect::<Vec<_>>(),
+            vec![&14, &15]
+        );
+        assert!(ImplicitBTree::<u8>::new().range(..).next().is_none());
+    }
+}
@@ -1,4 +1,53 @@
+/// Panics with the error of a failed `$validation` if the `debug-validate` feature is enabled,
+/// expands to nothing otherwise.
+macro_rules! debug_validate {
+    ($validation:expr) => {
+        #[cfg(feature = "debug-validate")]
+        if let Err(error) = $validation {
+            panic!("Tree invariant violated: {error}");
+        }
+    };
+}
+
 pub mod sized;
 pub mod tree;
 
+pub mod algo;
+pub mod arena;
+pub mod codec;
+pub mod consts;
+pub mod decision;
+pub mod disjoint;
+pub mod export;
+pub mod expr;
+pub mod footprint;
+pub mod implicit;
+pub mod merkle;
+pub mod pathmap;
+pub mod radix;
+pub mod search;
+pub mod segment;
 pub mod sparse;
+pub mod spatial;
+pub mod storage;
+pub mod text;
+pub mod zipper;
+
+mod treelike;
+pub use treelike::{Entry, MutableTreeLike, TreeLike};
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+// Trees hold plain indices without interior mutability, so they're `Send` and `Sync` whenever their values are
+const _: fn() = {
+    fn assert_send_sync<S: Send + Sync>() {}
+    fn for_any<'a, T: Send + Sync + 'a>() {
+        assert_send_sync::<tree::Tree<T>>();
+        assert_send_sync::<sized::Tree<T, 2, 7>>();
+        assert_send_sync::<sized::BTree<T, 7>>();
+        assert_send_sync::<sized::Walker<'a, T, 7>>();
+        assert_send_sync::<sized::CheckedWalker<'a, T, 7>>();
+    }
+    for_any::<String>
+};
@@ -0,0 +1,207 @@
+use std::marker::PhantomData;
+
+pub mod sha256;
+
+pub use sha256::Sha256;
+
+/// Hash function used to build a [MerkleTree].
+pub trait Digest {
+    type Output: Clone + Eq + AsRef<[u8]>;
+
+    /// Hashes data given as a sequence of byte slices, as if they were concatenated.
+    fn digest(parts: &[&[u8]]) -> Self::Output;
+}
+
+/// Domain separation prefixes (RFC 6962), so a leaf hash can never be passed off as an inner node hash.
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Side of the sibling hash in a single step of a [MerkleProof].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Proof of inclusion of a single leaf, listing sibling hashes from the leaf level up to the root.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MerkleProof<H: Digest> {
+    pub leaf_index: usize,
+    pub siblings: Vec<(Side, H::Output)>,
+}
+
+impl<H: Digest> MerkleProof<H> {
+    /// True if `leaf` hashed together with the proof's siblings yields `root`.
+    pub fn verify(&self, root: &H::Output, leaf: &[u8]) -> bool {
+        let mut hash = H::digest(&[&[LEAF_PREFIX], leaf]);
+        for (side, sibling) in &self.siblings {
+            hash = match side {
+                Side::Left => hash_nodes::<H>(sibling, &hash),
+                Side::Right => hash_nodes::<H>(&hash, sibling),
+            };
+        }
+        &hash == root
+    }
+}
+
+/// Binary hash tree over a list of leaf payloads. All nodes are stored level by level in a single vector,
+/// leaves first. A level with an odd number of nodes promotes its last node to the next level unchanged.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::merkle::{MerkleTree, Sha256};
+///         let leaves = ["alpha", "beta", "gamma", "delta", "epsilon"];
+///         let tree = MerkleTree::<Sha256>::new(&leaves);
+///
+///         let root = tree.root().expect("Tree isn't empty");
+///         let proof = tree.prove(2).expect("Leaf exists");
+///         assert!(proof.verify(root, b"gamma"));
+///         assert!(!proof.verify(root, b"omega"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MerkleTree<H: Digest> {
+    nodes: Vec<H::Output>,
+    /// Index of the first node and the number of nodes of every level, leaves first.
+    levels: Vec<(usize, usize)>,
+    hasher: PhantomData<H>,
+}
+
+impl<H: Digest> MerkleTree<H> {
+    /// Builds the tree over given leaf payloads in O(n).
+    pub fn new<L: AsRef<[u8]>>(leaves: &[L]) -> Self {
+        let mut nodes: Vec<H::Output> = leaves
+            .iter()
+            .map(|leaf| H::digest(&[&[LEAF_PREFIX], leaf.as_ref()]))
+            .collect();
+        let mut levels = vec![(0, nodes.len())];
+
+        while let Some(&(start, len)) = levels.last().filter(|(_, len)| *len > 1) {
+            let next_start = nodes.len();
+            for pair in (start..start + len).step_by(2) {
+                let hash = if pair + 1 < start + len {
+                    hash_nodes::<H>(&nodes[pair], &nodes[pair + 1])
+                } else {
+                    nodes[pair].clone()
+                };
+                nodes.push(hash);
+            }
+            levels.push((next_start, nodes.len() - next_start));
+        }
+
+        Self {
+            nodes,
+            levels,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Number of leaves.
+    pub fn len(&self) -> usize {
+        self.levels[0].1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Root hash, `None` for a tree without leaves.
+    pub fn root(&self) -> Option<&H::Output> {
+        let &(start, len) = self.levels.last()?;
+        (len == 1).then(|| &self.nodes[start])
+    }
+
+    /// Hash of the leaf at given index.
+    pub fn leaf_hash(&self, leaf_index: usize) -> Option<&H::Output> {
+        (leaf_index < self.len()).then(|| &self.nodes[leaf_index])
+    }
+
+    /// Inclusion proof for the leaf at `leaf_index`, `None` if there's no such leaf.
+    pub fn prove(&self, leaf_index: usize) -> Option<MerkleProof<H>> {
+        if leaf_index >= self.len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        let mut index = leaf_index;
+        for &(start, len) in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < len {
+                let side = if sibling < index {
+                    Side::Left
+                } else {
+                    Side::Right
+                };
+                siblings.push((side, self.nodes[start + sibling].clone()));
+            }
+            index /= 2;
+        }
+
+        Some(MerkleProof {
+            leaf_index,
+            siblings,
+        })
+    }
+}
+
+fn hash_nodes<H: Digest>(left: &H::Output, right: &H::Output) -> H::Output {
+    H::digest(&[&[NODE_PREFIX], left.as_ref(), right.as_ref()])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merkle::{Digest, MerkleTree, Sha256, Side};
+
+    /// Readable "hash" concatenating everything, makes the tree structure visible in assertions.
+    struct Concat;
+
+    impl Digest for Concat {
+        type Output = Vec<u8>;
+
+        fn digest(parts: &[&[u8]]) -> Self::Output {
+            let mut output = vec![b'('];
+            for part in parts {
+                output.extend(part.iter().map(|&byte| match byte {
+                    0x00 => b'L',
+                    0x01 => b'N',
+                    byte => byte,
+                }));
+            }
+            output.push(b')');
+            output
+        }
+    }
+
+    #[test]
+    fn structure() {
+        let tree = MerkleTree::<Concat>::new(&["a", "b", "c"]);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.root().unwrap(), b"(N(N(La)(Lb))(Lc))");
+
+        let proof = tree.prove(2).unwrap();
+        assert_eq!(proof.siblings, vec![(Side::Left, b"(N(La)(Lb))".to_vec())]);
+    }
+
+    #[test]
+    fn proofs() {
+        for count in 1..20 {
+            let leaves: Vec<String> = (0..count).map(|i| format!("leaf {i}")).collect();
+            let tree = MerkleTree::<Sha256>::new(&leaves);
+            let root = tree.root().unwrap();
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.prove(index).unwrap();
+                assert!(proof.verify(root, leaf.as_bytes()));
+                assert!(!proof.verify(root, b"forged"));
+            }
+            assert!(tree.prove(count).is_none());
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let tree = MerkleTree::<Sha256>::new::<&[u8]>(&[]);
+        assert!(tree.is_empty());
+        assert!(tree.root().is_none());
+        assert!(tree.prove(0).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
+
+/// Levels of the tree below the starting node whose subtrees are folded in parallel. Deeper subtrees are folded
+/// sequentially, which bounds the recursion depth regardless of the tree's depth.
+const PARALLEL_DEPTH: usize = 12;
+
+/// Shared state of a parallel fold. Nodes are claimed before being folded, so nodes reachable more than once
+/// (from corrupted trees) are folded only once and cycles terminate.
+struct Fold<'a, T, C, F> {
+    values: &'a [T],
+    children: C,
+    f: F,
+    claimed: Vec<AtomicBool>,
+}
+
+/// Folds the tree rooted at node `0` bottom-up: `f` is given a node's value and results of its children, in order.
+/// `children` returns valid child ids of a node. Subtrees are folded in parallel.
+pub(crate) fn fold_subtrees<T, A, C, F>(values: &[T], children: C, f: F) -> Option<A>
+where
+    T: Sync,
+    A: Send,
+    C: Fn(usize) -> Vec<usize> + Sync,
+    F: Fn(&T, Vec<A>) -> A + Sync,
+{
+    Fold::new(values, children, f).map(|fold| fold.node(0, 0))
+}
+
+/// Folds the tree rooted at node `0` bottom-up: a node's result is `leaf` of its value combined with results of
+/// its children, in order. `combine` has to be associative, children results are reduced in parallel.
+pub(crate) fn fold_reduce<T, A, C, L, R>(
+    values: &[T],
+    children: C,
+    leaf: L,
+    combine: R,
+) -> Option<A>
+where
+    T: Sync,
+    A: Send,
+    C: Fn(usize) -> Vec<usize> + Sync,
+    L: Fn(&T) -> A + Sync,
+    R: Fn(A, A) -> A + Sync,
+{
+    Fold::new(values, children, (leaf, combine)).map(|fold| fold.reduce(0, 0))
+}
+
+impl<'a, T, C, F> Fold<'a, T, C, F> {
+    /// Fold starting from the claimed root, `None` for an empty tree.
+    fn new(values: &'a [T], children: C, f: F) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let fold = Fold {
+            values,
+            children,
+            f,
+            claimed: values.iter().map(|_| AtomicBool::new(false)).collect(),
+        };
+        fold.claimed[0].store(true, Ordering::Relaxed);
+        Some(fold)
+    }
+}
+
+impl<T, C, L, R> Fold<'_, T, C, (L, R)>
+where
+    T: Sync,
+    C: Fn(usize) -> Vec<usize> + Sync,
+{
+    fn reduce<A>(&self, node: usize, depth: usize) -> A
+    where
+        A: Send,
+        L: Fn(&T) -> A + Sync,
+        R: Fn(A, A) -> A + Sync,
+    {
+        let (leaf, combine) = &self.f;
+        if depth >= PARALLEL_DEPTH {
+            return self.sequential_reduce(node);
+        }
+
+        let own = leaf(&self.values[node]);
+        let children = self
+            .claim(node)
+            .into_par_iter()
+            .map(|child| self.reduce(child, depth + 1))
+            .reduce_with(combine);
+        match children {
+            Some(children) => combine(own, children),
+            None => own,
+        }
+    }
+
+    fn sequential_reduce<A>(&self, node: usize) -> A
+    where
+        L: Fn(&T) -> A,
+        R: Fn(A, A) -> A,
+    {
+        let (leaf, combine) = &self.f;
+        let mut results: Vec<A> = Vec::new();
+        let mut stack = vec![(node, None)];
+        while let Some((node, children)) = stack.pop() {
+            match children {
+                Some(count) => {
+                    let own = leaf(&self.values[node]);
+                    let combined = results.drain(results.len() - count..).fold(own, combine);
+                    results.push(combined);
+                }
+                None => {
+                    let children = self.claim(node);
+                    stack.push((node, Some(children.len())));
+                    stack.extend(children.into_iter().rev().map(|child| (child, None)));
+                }
+            }
+        }
+        results.pop().expect("Subtree folds into a single result")
+    }
+}
+
+impl<T, C, F> Fold<'_, T, C, F>
+where
+    T: Sync,
+    C: Fn(usize) -> Vec<usize> + Sync,
+{
+    fn claim(&self, node: usize) -> Vec<usize> {
+        (self.children)(node)
+            .into_iter()
+            .filter(|&child| !self.claimed[child].swap(true, Ordering::Relaxed))
+            .collect()
+    }
+
+    fn node<A>(&self, node: usize, depth: usize) -> A
+    where
+        A: Send,
+        F: Fn(&T, Vec<A>) -> A + Sync,
+    {
+        if depth >= PARALLEL_DEPTH {
+            return self.sequential(node);
+        }
+
+        let results = self
+            .claim(node)
+            .into_par_iter()
+            .map(|child| self.node(child, depth + 1))
+            .collect();
+        (self.f)(&self.values[node], results)
+    }
+
+    /// Iterative post-order fold of the subtree rooted at an already claimed `node`.
+    fn sequential<A>(&self, node: usize) -> A
+    where
+        F: Fn(&T, Vec<A>) -> A,
+    {
+        let mut results: Vec<A> = Vec::new();
+        let mut stack = vec![(node, None)];
+        while let Some((node, children)) = stack.pop() {
+            match children {
+                Some(count) => {
+                    let arguments = results.split_off(results.len() - count);
+                    results.push((self.f)(&self.values[node], arguments));
+                }
+                None => {
+                    let children = self.claim(node);
+                    stack.push((node, Some(children.len())));
+                    stack.extend(children.into_iter().rev().map(|child| (child, None)));
+                }
+            }
+        }
+        results.pop().expect("Subtree folds into a single result")
+    }
+}
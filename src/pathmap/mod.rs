@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::sparse::ArenaTree;
+use crate::tree::NodeId;
+
+/// Map from paths of keys to values, layered over an n-ary [ArenaTree]. Every node addresses its children by key,
+/// like directories address their entries by name, so a path is looked up one key per level. Nodes on the way to
+/// a value are created as needed and hold no value themselves; the empty path addresses the root.
+///
+/// Children are kept in insertion order, which is the order of iteration.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::pathmap::PathMap;
+///         let mut config = PathMap::new();
+///         config.insert(&["server", "port"], 8080);
+///         config.insert(&["server", "host", "timeout"], 30);
+///         config.insert(&["client", "retries"], 3);
+///
+///         assert_eq!(config.get(&["server", "port"]), Some(&8080));
+///         assert_eq!(config.get(&["server"]), None);
+///         let server: Vec<(Vec<&str>, &i32)> = config.iter_prefix(&["server"]).collect();
+///         assert_eq!(server, vec![(vec!["server", "port"], &8080), (vec!["server", "host", "timeout"], &30)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathMap<K, V> {
+    tree: ArenaTree<Slot<K, V>>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<K, V> {
+    /// Key of the node in its parent, `None` for the root
+    key: Option<K>,
+    value: Option<V>,
+    children: HashMap<K, NodeId>,
+}
+
+impl<K, V> Slot<K, V> {
+    fn new(key: Option<K>) -> Self {
+        Self {
+            key,
+            value: None,
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> Default for PathMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> PathMap<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            tree: ArenaTree::root(Slot::new(None)),
+            len: 0,
+        }
+    }
+
+    /// Number of values in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value` at `path`, creating the missing nodes on the way. Returns the value previously at `path`.
+    pub fn insert(&mut self, path: &[K], value: V) -> Option<V> {
+        let mut node = NodeId::ROOT;
+        for key in path {
+            node = match self.tree[node].children.get(key) {
+                Some(&child) => child,
+                None => {
+                    let child = self.tree.create_child(node, Slot::new(Some(key.clone())));
+                    self.tree[node].children.insert(key.clone(), child);
+                    child
+                }
+            };
+        }
+        let previous = self.tree[node].value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn get(&self, path: &[K]) -> Option<&V> {
+        self.tree[self.find(path)?].value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, path: &[K]) -> Option<&mut V> {
+        let node = self.find(path)?;
+        self.tree[node].value.as_mut()
+    }
+
+    pub fn contains_path(&self, path: &[K]) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Removes the value at `path` and returns it. Nodes on the way are kept, the arena doesn't shrink.
+    pub fn remove(&mut self, path: &[K]) -> Option<V> {
+        let node = self.find(path)?;
+        let removed = self.tree[node].value.take();
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Paths and values at `prefix` and below it, in pre-order with children in insertion order.
+    pub fn iter_prefix(&self, prefix: &[K]) -> PrefixIter<'_, K, V> {
+        PrefixIter {
+            map: self,
+            stack: self
+                .find(prefix)
+                .map(|node| (node, prefix.to_vec()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// All paths and values, see [PathMap::iter_prefix].
+    pub fn iter(&self) -> PrefixIter<'_, K, V> {
+        self.iter_prefix(&[])
+    }
+
+    /// Node at `path`, if it exists.
+    fn find(&self, path: &[K]) -> Option<NodeId> {
+        path.iter().try_fold(NodeId::ROOT, |node, key| {
+            self.tree[node].children.get(key).copied()
+        })
+    }
+}
+
+/// Iterator over paths and values of a [PathMap], see [PathMap::iter_prefix].
+pub struct PrefixIter<'a, K, V> {
+    map: &'a PathMap<K, V>,
+    stack: Vec<(NodeId, Vec<K>)>,
+}
+
+impl<'a, K: Clone, V> Iterator for PrefixIter<'a, K, V> {
+    type Item = (Vec<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = &self.map.tree;
+        while let Some((node, path)) = self.stack.pop() {
+            let children: Vec<NodeId> = tree.children(node).collect();
+            for &child in children.iter().rev() {
+                let mut child_path = path.clone();
+                child_path.extend(tree[child].key.clone());
+                self.stack.push((child, child_path));
+            }
+            if let Some(value) = &tree[node].value {
+                return Some((path, value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pathmap::PathMap;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = PathMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(&[], "root"), None);
+        assert_eq!(map.insert(&[1, 2], "a"), None);
+        assert_eq!(map.insert(&[1, 2], "b"), Some("a"));
+        map.insert(&[1], "c");
+        map.insert(&[3, 1, 4], "d");
+        assert_eq!(map.len(), 4);
+
+        if let Some(value) = map.get_mut(&[1]) {
+            *value = "e";
+        }
+        assert_eq!(map.get(&[1]), Some(&"e"));
+        assert_eq!(map.get(&[3, 1]), None);
+        assert!(map.contains_path(&[]));
+        assert!(!map.contains_path(&[2]));
+
+        let all: Vec<(Vec<i32>, &str)> = map.iter().map(|(path, value)| (path, *value)).collect();
+        assert_eq!(
+            all,
+            vec![
+                (vec![], "root"),
+                (vec![1], "e"),
+                (vec![1, 2], "b"),
+                (vec![3, 1, 4], "d")
+            ]
+        );
+
+        assert_eq!(map.remove(&[1]), Some("e"));
+        assert_eq!(map.remove(&[1]), None);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.iter_prefix(&[1]).count(), 1);
+        assert_eq!(map.iter_prefix(&[4]).count(), 0);
+    }
+}
@@ -0,0 +1,365 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+
+/// Compressed prefix tree (radix tree, PATRICIA tree) over byte string keys. Chains of single-child nodes
+/// are merged into a single edge labeled by the whole byte sequence, so memory grows with the number of keys,
+/// not with their total length.
+///
+/// Children of every node are kept sorted by the first byte of their label, so iteration yields keys
+/// in lexicographical order.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::radix::RadixTree;
+///         let mut tree = RadixTree::new();
+///         tree.insert("romane", 1);
+///         tree.insert("romanus", 2);
+///         tree.insert("rubens", 3);
+///
+///         assert_eq!(tree.get("romanus"), Some(&2));
+///         assert_eq!(tree.get("roman"), None);
+///         let keys: Vec<Vec<u8>> = tree.iter_prefix("rom").map(|(key, _)| key).collect();
+///         assert_eq!(keys, vec![b"romane".to_vec(), b"romanus".to_vec()]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RadixTree<V> {
+    root: RadixNode<V>,
+    len: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct RadixNode<V> {
+    label: Vec<u8>,
+    value: Option<V>,
+    children: Vec<RadixNode<V>>,
+}
+
+impl<V> RadixNode<V> {
+    fn new(label: Vec<u8>, value: Option<V>) -> Self {
+        Self {
+            label,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// Position of the child whose label starts with `byte`, or the position it should be inserted at.
+    fn child_position(&self, byte: u8) -> Result<usize, usize> {
+        self.children
+            .binary_search_by_key(&byte, |child| child.label[0])
+    }
+
+    /// Merges an only child into this node, if this node holds no value.
+    fn compress(&mut self) {
+        if self.value.is_none() && self.children.len() == 1 {
+            let child = self.children.pop().expect("Exactly one child is present");
+            self.label.extend(child.label);
+            self.value = child.value;
+            self.children = child.children;
+        }
+    }
+}
+
+impl<V> Default for RadixTree<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> RadixTree<V> {
+    /// Creates an empty radix tree.
+    pub fn new() -> Self {
+        Self {
+            root: RadixNode::new(Vec::new(), None),
+            len: 0,
+        }
+    }
+
+    /// Number of keys stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Labels are structure, so are the slots of nodes without a
+    /// value.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let mut footprint = MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        };
+        // Nodes live inline in their parent's children, only the buffers are added up
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            footprint += MemoryFootprint::vec(&node.label, false)
+                + MemoryFootprint::vec(&node.children, false);
+            stack.extend(&node.children);
+        }
+        let values = self.len * size_of::<V>();
+        footprint.structure -= values;
+        footprint.values = values;
+        footprint
+    }
+
+    /// Inserts `value` under `key`, returning the previous value stored under the same key, if any.
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+        loop {
+            if key.is_empty() {
+                let previous = node.value.replace(value);
+                if previous.is_none() {
+                    self.len += 1;
+                }
+                return previous;
+            }
+
+            match node.child_position(key[0]) {
+                Err(position) => {
+                    node.children
+                        .insert(position, RadixNode::new(key.to_vec(), Some(value)));
+                    self.len += 1;
+                    return None;
+                }
+                Ok(position) => {
+                    let child = &mut node.children[position];
+                    let common = common_prefix_len(&child.label, key);
+                    if common < child.label.len() {
+                        // Split the edge, the original child continues below the common prefix
+                        let suffix = child.label.split_off(common);
+                        let mut lower = RadixNode::new(suffix, child.value.take());
+                        lower.children = std::mem::take(&mut child.children);
+                        child.children.push(lower);
+                    }
+                    key = &key[common..];
+                    node = child;
+                }
+            }
+        }
+    }
+
+    /// Returns value stored under `key`, if any.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        let mut key = key.as_ref();
+        let mut node = &self.root;
+        while !key.is_empty() {
+            let child = &node.children[node.child_position(key[0]).ok()?];
+            key = key.strip_prefix(child.label.as_slice())?;
+            node = child;
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value stored under `key`, if any.
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        let mut key = key.as_ref();
+        let mut node = &mut self.root;
+        while !key.is_empty() {
+            let position = node.child_position(key[0]).ok()?;
+            let child = &mut node.children[position];
+            key = key.strip_prefix(child.label.as_slice())?;
+            node = child;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` from the tree and returns its value, if it was present. Nodes left with a single child
+    /// and no value are merged back together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::radix::RadixTree;
+    ///         let mut tree = RadixTree::new();
+    ///         tree.insert("tea", 1);
+    ///         tree.insert("ten", 2);
+    ///
+    ///         assert_eq!(tree.remove("tea"), Some(1));
+    ///         assert_eq!(tree.remove("tea"), None);
+    ///         assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        let removed = Self::remove_from(&mut self.root, key.as_ref());
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    fn remove_from(node: &mut RadixNode<V>, key: &[u8]) -> Option<V> {
+        if key.is_empty() {
+            return node.value.take();
+        }
+
+        let position = node.child_position(key[0]).ok()?;
+        let child = &mut node.children[position];
+        let rest = key.strip_prefix(child.label.as_slice())?;
+        let removed = Self::remove_from(child, rest)?;
+
+        if child.value.is_none() && child.children.is_empty() {
+            node.children.remove(position);
+        } else {
+            child.compress();
+        }
+        Some(removed)
+    }
+
+    /// Streams all keys starting with `prefix` together with their values, in lexicographical order.
+    /// Keys are materialized lazily, one at a time.
+    pub fn iter_prefix<K: AsRef<[u8]>>(&self, prefix: K) -> PrefixIter<'_, V> {
+        let mut prefix = prefix.as_ref();
+        let mut node = &self.root;
+        let mut path = Vec::new();
+        while !prefix.is_empty() {
+            let Some(child) = node
+                .child_position(prefix[0])
+                .ok()
+                .map(|position| &node.children[position])
+            else {
+                return PrefixIter::empty();
+            };
+
+            let common = common_prefix_len(&child.label, prefix);
+            if common < prefix.len() && common < child.label.len() {
+                return PrefixIter::empty();
+            }
+            path.extend_from_slice(&child.label);
+            prefix = &prefix[common..];
+            node = child;
+        }
+
+        // The path already contains the label of the node the iteration starts at
+        let key_len = path.len() - node.label.len();
+        path.truncate(key_len);
+        PrefixIter {
+            stack: vec![(node, key_len)],
+            key: path,
+        }
+    }
+
+    /// Iterates over all keys and values, in lexicographical order.
+    pub fn iter(&self) -> PrefixIter<'_, V> {
+        self.iter_prefix([])
+    }
+}
+
+/// Iterator over keys sharing a common prefix, see [RadixTree::iter_prefix].
+pub struct PrefixIter<'a, V> {
+    /// Nodes to visit, together with the length of the key preceding each node's label.
+    stack: Vec<(&'a RadixNode<V>, usize)>,
+    key: Vec<u8>,
+}
+
+impl<'a, V> PrefixIter<'a, V> {
+    fn empty() -> Self {
+        Self {
+            stack: Vec::new(),
+            key: Vec::new(),
+        }
+    }
+}
+
+impl<'a, V> Iterator for PrefixIter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, key_len)) = self.stack.pop() {
+            self.key.truncate(key_len);
+            self.key.extend_from_slice(&node.label);
+            let node_key_len = self.key.len();
+            self.stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|child| (child, node_key_len)),
+            );
+            if let Some(value) = node.value.as_ref() {
+                return Some((self.key.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix::RadixTree;
+
+    #[test]
+    fn insert_and_get() {
+        let mut tree = RadixTree::new();
+        assert_eq!(tree.insert("test", 1), None);
+        assert_eq!(tree.insert("team", 2), None);
+        assert_eq!(tree.insert("te", 3), None);
+        assert_eq!(tree.insert("", 4), None);
+        assert_eq!(tree.insert("test", 5), Some(1));
+
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.get("test"), Some(&5));
+        assert_eq!(tree.get("team"), Some(&2));
+        assert_eq!(tree.get("te"), Some(&3));
+        assert_eq!(tree.get(""), Some(&4));
+        assert_eq!(tree.get("t"), None);
+        assert_eq!(tree.get("tests"), None);
+
+        *tree.get_mut("team").unwrap() += 10;
+        assert_eq!(tree.get("team"), Some(&12));
+    }
+
+    #[test]
+    fn remove_compresses_nodes() {
+        let mut tree = RadixTree::new();
+        tree.insert("slow", 1);
+        tree.insert("slower", 2);
+        tree.insert("slowly", 3);
+
+        assert_eq!(tree.remove("slow"), Some(1));
+        assert_eq!(tree.remove("slowe"), None);
+        assert_eq!(tree.remove("slower"), Some(2));
+
+        // Only a single, fully compressed edge remains
+        assert_eq!(tree.root.children.len(), 1);
+        assert_eq!(tree.root.children[0].label, b"slowly".to_vec());
+        assert!(tree.root.children[0].children.is_empty());
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn iter_prefix() {
+        let mut tree = RadixTree::new();
+        for (value, key) in ["romulus", "romane", "rubicon", "romanus", "rubens", "ruber"]
+            .iter()
+            .enumerate()
+        {
+            tree.insert(key, value);
+        }
+
+        let keys = |prefix: &str| -> Vec<String> {
+            tree.iter_prefix(prefix)
+                .map(|(key, _)| String::from_utf8(key).unwrap())
+                .collect()
+        };
+        assert_eq!(keys("rom"), vec!["romane", "romanus", "romulus"]);
+        assert_eq!(keys("rube"), vec!["rubens", "ruber"]);
+        assert_eq!(keys("romanus"), vec!["romanus"]);
+        assert_eq!(keys("x"), Vec::<String>::new());
+        assert_eq!(keys("romanusx"), Vec::<String>::new());
+        assert_eq!(tree.iter().count(), 6);
+    }
+}
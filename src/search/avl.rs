@@ -0,0 +1,856 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::search::{self, is_empty_range};
+
+#[cfg(any(feature = "debug-validate", feature = "serde"))]
+use crate::tree::TreeError;
+
+/// Ordered map backed by an AVL tree. Nodes live in a single vector and link to each other by indices,
+/// slots of removed nodes are reused by later insertions. The tree is kept height-balanced, so lookups,
+/// insertions and removals run in O(log n). Every node tracks the size of its subtree, which makes the tree
+/// an order-statistics tree with O(log n) [AvlTree::select] and [AvlTree::rank] queries.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::search::AvlTree;
+///         let mut tree = AvlTree::new();
+///         tree.insert(3, "three");
+///         tree.insert(1, "one");
+///         tree.insert(2, "two");
+///
+///         assert_eq!(tree.get(&2), Some(&"two"));
+///         assert_eq!(tree.remove(&1), Some("one"));
+///         let keys: Vec<i32> = tree.iter().map(|(key, _)| *key).collect();
+///         assert_eq!(keys, vec![2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "AvlTreeData<K, V>",
+        bound(deserialize = "K: Ord + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+pub struct AvlTree<K, V> {
+    nodes: Vec<Option<AvlNode<K, V>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct AvlNode<K, V> {
+    key: K,
+    value: V,
+    left: Option<usize>,
+    right: Option<usize>,
+    height: u8,
+    size: usize,
+}
+
+impl<K: Ord, V> Default for AvlTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> AvlTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Keys count as values, vacant slots and the list of them
+    /// are overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::slots(
+            &self.nodes,
+            self.len,
+            size_of::<K>() + size_of::<V>(),
+            &self.free,
+        )
+    }
+
+    /// Height of the tree. Zero for an empty tree, one for a single node.
+    pub fn height(&self) -> usize {
+        self.height_of(self.root) as usize
+    }
+
+    /// Inserts a key-value pair, returning the previous value stored under the same key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut previous = None;
+        self.root = Some(self.insert_at(self.root, key, value, &mut previous));
+        if previous.is_none() {
+            self.len += 1;
+        }
+        debug_validate!(self.validate());
+        previous
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key)?;
+        Some(&self.node(node).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key)?;
+        Some(&mut self.node_mut(node).value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut removed = None;
+        self.root = self.remove_at(self.root, key, &mut removed);
+        let removed = removed.map(|(_, value)| {
+            self.len -= 1;
+            value
+        });
+        debug_validate!(self.validate());
+        removed
+    }
+
+    /// Checks ordering of keys, balance, heights and subtree sizes of every node, and that vacant slots are listed
+    /// once as free.
+    #[cfg(any(feature = "debug-validate", feature = "serde"))]
+    fn validate(&self) -> Result<(), TreeError> {
+        let size = self.validate_at(self.root, None, None, u8::MAX)?;
+        if size != self.len {
+            return Err(TreeError::CorruptedTree(format!(
+                "tree holds {size} nodes, but its length is {}",
+                self.len
+            )));
+        }
+        search::check_slots(&self.nodes, &self.free, size)
+    }
+
+    /// Validates subtree of `node` with keys bounded by `lower` and `upper` and height at most `max_height`, returns
+    /// its size. Heights decrease on the way down, which bounds the recursion for corrupted links.
+    #[cfg(any(feature = "debug-validate", feature = "serde"))]
+    fn validate_at(
+        &self,
+        node: Option<usize>,
+        lower: Option<&K>,
+        upper: Option<&K>,
+        max_height: u8,
+    ) -> Result<usize, TreeError> {
+        let Some(id) = node else { return Ok(0) };
+        let current = self
+            .nodes
+            .get(id)
+            .and_then(Option::as_ref)
+            .ok_or(TreeError::NodeOutOfBounds { node: id })?;
+        if lower.is_some_and(|lower| current.key <= *lower)
+            || upper.is_some_and(|upper| current.key >= *upper)
+        {
+            return Err(TreeError::CorruptedTree(format!(
+                "key of node {id} is out of order"
+            )));
+        }
+        if current.height == 0 || current.height > max_height {
+            return Err(TreeError::CorruptedTree(format!(
+                "node {id} has a stale height"
+            )));
+        }
+        let below = current.height - 1;
+        let left = self.validate_at(current.left, lower, Some(&current.key), below)?;
+        let right = self.validate_at(current.right, Some(&current.key), upper, below)?;
+        let (left_height, right_height) =
+            (self.height_of(current.left), self.height_of(current.right));
+        if left_height.abs_diff(right_height) > 1
+            || current.height != 1 + left_height.max(right_height)
+        {
+            return Err(TreeError::CorruptedTree(format!(
+                "node {id} is unbalanced or has a stale height"
+            )));
+        }
+        if current.size != 1 + left + right {
+            return Err(TreeError::CorruptedTree(format!(
+                "node {id} has a stale subtree size"
+            )));
+        }
+        Ok(current.size)
+    }
+
+    /// Returns the entry with the `k`-th smallest key, counting from zero, or `None` if `k` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::AvlTree;
+    ///         let mut tree = AvlTree::new();
+    ///         for key in [50, 10, 40, 20, 30] {
+    ///             tree.insert(key, ());
+    ///         }
+    ///
+    ///         assert_eq!(tree.select(0), Some((&10, &())));
+    ///         assert_eq!(tree.select(3), Some((&40, &())));
+    ///         assert_eq!(tree.select(5), None);
+    ///         assert_eq!(tree.rank(&35), 3);
+    /// ```
+    pub fn select(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut node = self.root;
+        while let Some(id) = node {
+            let current = self.node(id);
+            let left_size = self.size_of(current.left);
+            node = match k.cmp(&left_size) {
+                Ordering::Less => current.left,
+                Ordering::Equal => return Some((&current.key, &current.value)),
+                Ordering::Greater => {
+                    k -= left_size + 1;
+                    current.right
+                }
+            };
+        }
+        None
+    }
+
+    /// Number of keys strictly smaller than `key`. The key itself doesn't need to be present.
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut node = self.root;
+        while let Some(id) = node {
+            let current = self.node(id);
+            if current.key.borrow() < key {
+                rank += self.size_of(current.left) + 1;
+                node = current.right;
+            } else {
+                node = current.left;
+            }
+        }
+        rank
+    }
+
+    /// Entry with the greatest key less than or equal to `key`, `None` if all keys are greater.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::AvlTree;
+    ///         let mut tree = AvlTree::new();
+    ///         for price in [100, 120, 150] {
+    ///             tree.insert(price, ());
+    ///         }
+    ///
+    ///         assert_eq!(tree.floor(&130), Some((&120, &())));
+    ///         assert_eq!(tree.ceiling(&130), Some((&150, &())));
+    ///         assert_eq!(tree.closest(&140, |a: &i32, b: &i32| a.abs_diff(*b)), Some((&150, &())));
+    ///         assert_eq!(tree.floor(&99), None);
+    /// ```
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Less)
+    }
+
+    /// Entry with the smallest key greater than or equal to `key`, `None` if all keys are smaller.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Greater)
+    }
+
+    /// Entry with the key nearest to `key` by `distance`, either its [AvlTree::floor] or its
+    /// [AvlTree::ceiling]. Ties go to the floor. `None` for an empty tree.
+    pub fn closest<Q, D>(&self, key: &Q, distance: impl Fn(&Q, &Q) -> D) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        D: Ord,
+    {
+        search::closest(key, self.floor(key), self.ceiling(key), distance)
+    }
+
+    /// Entry with the key equal to `key`, or else the nearest one on the `side` of it.
+    fn bound<Q>(&self, key: &Q, side: Ordering) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::bound(self.root, key, side, |id| {
+            let node = self.node(id);
+            (&node.key, &node.value, node.left, node.right)
+        })
+    }
+
+    /// Iterates over all entries in ascending key order.
+    pub fn iter(&self) -> AvlRange<'_, K, V> {
+        self.range::<K, _>(..)
+    }
+
+    /// Iterates over entries with keys within `range`, in ascending key order. Subtrees outside the range
+    /// are never visited. Nothing is yielded if the range starts past its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::AvlTree;
+    ///         let mut tree = AvlTree::new();
+    ///         for key in 0..10 {
+    ///             tree.insert(key, key * key);
+    ///         }
+    ///         let squares: Vec<i32> = tree.range(3..6).map(|(_, square)| *square).collect();
+    ///         assert_eq!(squares, vec![9, 16, 25]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> AvlRange<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut stack = Vec::new();
+        let mut node = self.root.filter(|_| !is_empty_range(&range));
+        while let Some(id) = node {
+            let key = self.node(id).key.borrow();
+            let above_start = match range.start_bound() {
+                Bound::Included(start) => key >= start,
+                Bound::Excluded(start) => key > start,
+                Bound::Unbounded => true,
+            };
+            if above_start {
+                stack.push(id);
+                node = self.node(id).left;
+            } else {
+                node = self.node(id).right;
+            }
+        }
+
+        let end = match range.end_bound() {
+            Bound::Unbounded => None,
+            _ => Some(self.first_beyond(&range)),
+        };
+        AvlRange {
+            tree: self,
+            stack,
+            end,
+        }
+    }
+
+    /// Id of the smallest node with a key past the end of `range`, or `usize::MAX` if there's none.
+    fn first_beyond<Q, R>(&self, range: &R) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut candidate = usize::MAX;
+        let mut node = self.root;
+        while let Some(id) = node {
+            let key = self.node(id).key.borrow();
+            let beyond = match range.end_bound() {
+                Bound::Included(end) => key > end,
+                Bound::Excluded(end) => key >= end,
+                Bound::Unbounded => false,
+            };
+            if beyond {
+                candidate = id;
+                node = self.node(id).left;
+            } else {
+                node = self.node(id).right;
+            }
+        }
+        candidate
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root;
+        while let Some(id) = node {
+            node = match key.cmp(self.node(id).key.borrow()) {
+                Ordering::Less => self.node(id).left,
+                Ordering::Greater => self.node(id).right,
+                Ordering::Equal => return Some(id),
+            };
+        }
+        None
+    }
+
+    fn insert_at(
+        &mut self,
+        node: Option<usize>,
+        key: K,
+        value: V,
+        previous: &mut Option<V>,
+    ) -> usize {
+        let Some(id) = node else {
+            return self.allocate(key, value);
+        };
+        match key.cmp(&self.node(id).key) {
+            Ordering::Less => {
+                let left = self.insert_at(self.node(id).left, key, value, previous);
+                self.node_mut(id).left = Some(left);
+            }
+            Ordering::Greater => {
+                let right = self.insert_at(self.node(id).right, key, value, previous);
+                self.node_mut(id).right = Some(right);
+            }
+            Ordering::Equal => {
+                *previous = Some(std::mem::replace(&mut self.node_mut(id).value, value));
+                return id;
+            }
+        }
+        self.rebalance(id)
+    }
+
+    fn remove_at<Q>(
+        &mut self,
+        node: Option<usize>,
+        key: &Q,
+        removed: &mut Option<(K, V)>,
+    ) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let id = node?;
+        match key.cmp(self.node(id).key.borrow()) {
+            Ordering::Less => {
+                let left = self.remove_at(self.node(id).left, key, removed);
+                self.node_mut(id).left = left;
+            }
+            Ordering::Greater => {
+                let right = self.remove_at(self.node(id).right, key, removed);
+                self.node_mut(id).right = right;
+            }
+            Ordering::Equal => {
+                let (left, right) = (self.node(id).left, self.node(id).right);
+                let replacement = match (left, right) {
+                    (None, child) | (child, None) => child,
+                    (Some(_), Some(right)) => {
+                        // Replace the node with its in-order successor
+                        let (rest, successor) = self.take_min(right);
+                        let successor_node = self.node_mut(successor);
+                        successor_node.right = rest;
+                        successor_node.left = left;
+                        Some(self.rebalance(successor))
+                    }
+                };
+                let node = self.nodes[id].take().expect("Node is present");
+                self.free.push(id);
+                *removed = Some((node.key, node.value));
+                return replacement;
+            }
+        }
+        Some(self.rebalance(id))
+    }
+
+    /// Detaches the minimum of the subtree rooted at `id`. Returns the remaining subtree and the detached node.
+    fn take_min(&mut self, id: usize) -> (Option<usize>, usize) {
+        match self.node(id).left {
+            None => (self.node(id).right, id),
+            Some(left) => {
+                let (rest, min) = self.take_min(left);
+                self.node_mut(id).left = rest;
+                (Some(self.rebalance(id)), min)
+            }
+        }
+    }
+
+    fn allocate(&mut self, key: K, value: V) -> usize {
+        let node = AvlNode {
+            key,
+            value,
+            left: None,
+            right: None,
+            height: 1,
+            size: 1,
+        };
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn node(&self, id: usize) -> &AvlNode<K, V> {
+        self.nodes[id].as_ref().expect("Linked nodes are present")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut AvlNode<K, V> {
+        self.nodes[id].as_mut().expect("Linked nodes are present")
+    }
+
+    fn height_of(&self, node: Option<usize>) -> u8 {
+        node.map_or(0, |id| self.node(id).height)
+    }
+
+    fn size_of(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |id| self.node(id).size)
+    }
+
+    /// Recomputes height and subtree size of a node from its children.
+    fn update(&mut self, id: usize) {
+        let (left, right) = (self.node(id).left, self.node(id).right);
+        let height = 1 + self.height_of(left).max(self.height_of(right));
+        let size = 1 + self.size_of(left) + self.size_of(right);
+        let node = self.node_mut(id);
+        node.height = height;
+        node.size = size;
+    }
+
+    fn balance_factor(&self, id: usize) -> i16 {
+        self.height_of(self.node(id).left) as i16 - self.height_of(self.node(id).right) as i16
+    }
+
+    fn rotate_right(&mut self, id: usize) -> usize {
+        let pivot = self.node(id).left.expect("Left child exists");
+        self.node_mut(id).left = self.node(pivot).right;
+        self.node_mut(pivot).right = Some(id);
+        self.update(id);
+        self.update(pivot);
+        pivot
+    }
+
+    fn rotate_left(&mut self, id: usize) -> usize {
+        let pivot = self.node(id).right.expect("Right child exists");
+        self.node_mut(id).right = self.node(pivot).left;
+        self.node_mut(pivot).left = Some(id);
+        self.update(id);
+        self.update(pivot);
+        pivot
+    }
+
+    /// Restores the AVL property of a node whose subtrees differ in height by at most two, returning the new
+    /// root of the subtree.
+    fn rebalance(&mut self, id: usize) -> usize {
+        self.update(id);
+        let balance = self.balance_factor(id);
+        if balance > 1 {
+            let left = self
+                .node(id)
+                .left
+                .expect("Left-heavy node has a left child");
+            if self.balance_factor(left) < 0 {
+                let rotated = self.rotate_left(left);
+                self.node_mut(id).left = Some(rotated);
+            }
+            self.rotate_right(id)
+        } else if balance < -1 {
+            let right = self
+                .node(id)
+                .right
+                .expect("Right-heavy node has a right child");
+            if self.balance_factor(right) > 0 {
+                let rotated = self.rotate_right(right);
+                self.node_mut(id).right = Some(rotated);
+            }
+            self.rotate_left(id)
+        } else {
+            id
+        }
+    }
+}
+
+/// In-order iterator over a key range of an [AvlTree].
+pub struct AvlRange<'a, K, V> {
+    tree: &'a AvlTree<K, V>,
+    /// Nodes whose left subtree has been visited, top of the stack being the next node to yield.
+    stack: Vec<usize>,
+    /// First node past the end of the range. `None` if the range is unbounded.
+    end: Option<usize>,
+}
+
+impl<'a, K: Ord, V> Iterator for AvlRange<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        if Some(id) == self.end {
+            self.stack.clear();
+            return None;
+        }
+
+        let node = self.tree.node(id);
+        let mut next = node.right;
+        while let Some(child) = next {
+            self.stack.push(child);
+            next = self.tree.node(child).left;
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
+/// Serialized form of an [AvlTree], validated before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>"))]
+struct AvlTreeData<K, V> {
+    nodes: Vec<Option<AvlNode<K, V>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V> TryFrom<AvlTreeData<K, V>> for AvlTree<K, V> {
+    type Error = TreeError;
+
+    fn try_from(data: AvlTreeData<K, V>) -> Result<Self, Self::Error> {
+        let tree = Self {
+            nodes: data.nodes,
+            free: data.free,
+            root: data.root,
+            len: data.len,
+        };
+        tree.validate()?;
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ops::Bound;
+
+    use crate::search::AvlTree;
+
+    /// Checks ordering, heights and balance of every node, returns the subtree height.
+    fn check<K: Ord, V>(tree: &AvlTree<K, V>, node: Option<usize>) -> u8 {
+        let Some(id) = node else { return 0 };
+        let current = tree.node(id);
+        let left = check(tree, current.left);
+        let right = check(tree, current.right);
+        assert!(left.abs_diff(right) <= 1);
+        assert_eq!(current.height, 1 + left.max(right));
+        assert_eq!(
+            current.size,
+            1 + tree.size_of(current.left) + tree.size_of(current.right)
+        );
+        if let Some(left) = current.left {
+            assert!(tree.node(left).key < current.key);
+        }
+        if let Some(right) = current.right {
+            assert!(tree.node(right).key > current.key);
+        }
+        current.height
+    }
+
+    #[test]
+    fn matches_btree_map() {
+        let mut tree = AvlTree::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 7_u32;
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let key = (seed >> 16) % 300;
+            if seed.is_multiple_of(3) {
+                assert_eq!(tree.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(tree.insert(key, seed), expected.insert(key, seed));
+            }
+            assert_eq!(tree.len(), expected.len());
+        }
+
+        check(&tree, tree.root);
+        assert!(tree.height() <= 2 * (tree.len() as f64).log2().ceil() as usize);
+        assert!(tree.iter().eq(expected.iter()));
+        assert!(tree.range(50..=120).eq(expected.range(50..=120)));
+        assert!(tree.range(..77).eq(expected.range(..77)));
+        assert!(tree.range(200..).eq(expected.range(200..)));
+        // Freed slots get reused
+        assert!(tree.nodes.len() < 300);
+    }
+
+    #[test]
+    fn order_statistics() {
+        let mut tree = AvlTree::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 11_u32;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let key = (seed >> 16) % 200;
+            if seed.is_multiple_of(4) {
+                tree.remove(&key);
+                expected.remove(&key);
+            } else {
+                tree.insert(key, ());
+                expected.insert(key, ());
+            }
+        }
+
+        check(&tree, tree.root);
+        for (k, entry) in expected.iter().enumerate() {
+            assert_eq!(tree.select(k), Some(entry));
+            assert_eq!(tree.rank(entry.0), k);
+        }
+        assert_eq!(tree.select(expected.len()), None);
+        assert_eq!(tree.rank(&1000), expected.len());
+    }
+
+    #[test]
+    fn nearest_keys() {
+        let mut tree = AvlTree::new();
+        let mut expected = BTreeMap::new();
+        for key in (0..500).step_by(7) {
+            tree.insert(key, key / 7);
+            expected.insert(key, key / 7);
+        }
+
+        for key in -3..510 {
+            assert_eq!(tree.floor(&key), expected.range(..=key).next_back());
+            assert_eq!(tree.ceiling(&key), expected.range(key..).next());
+        }
+        let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+        assert_eq!(tree.closest(&10, distance), Some((&7, &1)));
+        assert_eq!(tree.closest(&11, distance), Some((&14, &2)));
+        assert_eq!(tree.closest(&-50, distance), Some((&0, &0)));
+        assert_eq!(tree.closest(&1000, distance), Some((&497, &71)));
+        assert_eq!(AvlTree::<i32, ()>::new().closest(&1, distance), None);
+    }
+
+    #[test]
+    fn empty_ranges() {
+        let mut tree = AvlTree::new();
+        for key in 1..=10 {
+            tree.insert(key, ());
+        }
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 5..3;
+        assert_eq!(tree.range(reversed).count(), 0);
+        assert_eq!(tree.range(5..5).count(), 0);
+        assert_eq!(
+            tree.range((Bound::Excluded(5), Bound::Included(5))).count(),
+            0
+        );
+        assert_eq!(
+            tree.range((Bound::Excluded(4), Bound::Excluded(6))).count(),
+            1
+        );
+        assert_eq!(tree.range(5..=5).count(), 1);
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let mut tree = AvlTree::new();
+        tree.insert("b".to_string(), 2);
+        tree.insert("a".to_string(), 1);
+        *tree.get_mut("a").unwrap() += 10;
+        assert_eq!(tree.get("a"), Some(&11));
+        assert!(tree.contains_key("b"));
+        assert!(!tree.contains_key("c"));
+        assert_eq!(tree.remove("b"), Some(2));
+        assert_eq!(tree.remove("b"), None);
+    }
+
+    #[test]
+    fn empty() {
+        let tree = AvlTree::<i32, ()>::new();
+        assert_eq!(tree.height(), 0);
+        assert_eq!(tree.iter().count(), 0);
+        assert_eq!(tree.range(1..3).count(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let mut tree = AvlTree::new();
+        for key in [2, 1, 3, 4] {
+            tree.insert(key, key * 10);
+        }
+        tree.remove(&4);
+        let json = serde_json::to_string(&tree).expect("Tree is serializable");
+        let deserialized: AvlTree<i32, i32> = serde_json::from_str(&json).expect("Tree is valid");
+        assert!(deserialized.iter().eq(tree.iter()));
+
+        let leaf = |key: i32| {
+            format!(r#"{{"key":{key},"value":0,"left":null,"right":null,"height":1,"size":1}}"#)
+        };
+        let root = |left: &str, right: &str, height: u8, size: usize| {
+            format!(
+                r#"{{"key":2,"value":0,"left":{left},"right":{right},"height":{height},"size":{size}}}"#
+            )
+        };
+        for malformed in [
+            format!(r#"{{"nodes":[{}],"free":[],"root":5,"len":1}}"#, leaf(1)),
+            format!(r#"{{"nodes":[{}],"free":[],"root":0,"len":2}}"#, leaf(1)),
+            format!(
+                r#"{{"nodes":[{},null],"free":[0],"root":0,"len":1}}"#,
+                leaf(1)
+            ),
+            format!(
+                r#"{{"nodes":[{},{}],"free":[],"root":0,"len":2}}"#,
+                root("7", "null", 2, 2),
+                leaf(1)
+            ),
+            format!(
+                r#"{{"nodes":[{},{}],"free":[],"root":0,"len":2}}"#,
+                root("1", "null", 2, 2),
+                leaf(3)
+            ),
+            format!(
+                r#"{{"nodes":[{},{}],"free":[],"root":0,"len":2}}"#,
+                root("1", "null", 2, 5),
+                leaf(1)
+            ),
+            format!(
+                r#"{{"nodes":[{},{}],"free":[],"root":0,"len":2}}"#,
+                root("0", "null", 2, 2),
+                leaf(1)
+            ),
+        ] {
+            assert!(
+                serde_json::from_str::<AvlTree<i32, i32>>(&malformed).is_err(),
+                "{malformed}"
+            );
+        }
+    }
+}
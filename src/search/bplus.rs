@@ -0,0 +1,841 @@
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::search;
+#[cfg(feature = "serde")]
+use crate::tree::TreeError;
+
+/// A single node ("page") of a [BPlusTree]. Pages refer to each other by page ids, so they can be stored
+/// and loaded one by one, e.g. as fixed-size blocks of an index file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Page<K, V> {
+    /// Routing node. Child `i` holds keys in range `keys[i - 1]..keys[i]`.
+    Internal { keys: Vec<K>, children: Vec<usize> },
+    /// Data node, linked to the next leaf in key order.
+    Leaf {
+        keys: Vec<K>,
+        values: Vec<V>,
+        next: Option<usize>,
+    },
+}
+
+impl<K, V> Page<K, V> {
+    fn keys(&self) -> &Vec<K> {
+        match self {
+            Page::Internal { keys, .. } | Page::Leaf { keys, .. } => keys,
+        }
+    }
+}
+
+/// B+-tree ordered map with at most `FANOUT` children per internal page and `FANOUT - 1` keys per page.
+/// All values live in leaf pages chained in key order, making ordered iteration and range scans
+/// a sequential walk over leaves.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::search::BPlusTree;
+///         let mut tree = BPlusTree::<u32, &str, 4>::new();
+///         tree.insert(20, "twenty");
+///         tree.insert(10, "ten");
+///         tree.insert(30, "thirty");
+///
+///         assert_eq!(tree.get(&10), Some(&"ten"));
+///         let keys: Vec<u32> = tree.range(15..).map(|(key, _)| *key).collect();
+///         assert_eq!(keys, vec![20, 30]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "BPlusTreeData<K, V>",
+        bound(deserialize = "K: Ord + Clone + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+pub struct BPlusTree<K, V, const FANOUT: usize> {
+    pages: Vec<Option<Page<K, V>>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl<K: Ord + Clone, V, const FANOUT: usize> Default for BPlusTree<K, V, FANOUT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V, const FANOUT: usize> BPlusTree<K, V, FANOUT> {
+    const MAX_KEYS: usize = FANOUT - 1;
+    const MIN_KEYS: usize = (FANOUT - 1) / 2;
+
+    /// Creates an empty tree consisting of a single empty leaf page.
+    ///
+    /// # Panics
+    /// If `FANOUT` is less than 3.
+    pub fn new() -> Self {
+        assert!(FANOUT >= 3, "B+-tree fanout must be at least 3");
+        Self {
+            pages: vec![Some(Page::Leaf {
+                keys: Vec::new(),
+                values: Vec::new(),
+                next: None,
+            })],
+            free: Vec::new(),
+            root: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Keys and values in leaves count as values, keys of internal
+    /// pages are structure. Vacant pages and the list of them are overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let pages = self.pages.iter().flatten().map(|page| match page {
+            Page::Internal { keys, children } => {
+                MemoryFootprint::vec(keys, false) + MemoryFootprint::vec(children, false)
+            }
+            Page::Leaf { keys, values, .. } => {
+                MemoryFootprint::vec(keys, true) + MemoryFootprint::vec(values, true)
+            }
+        });
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::slots(
+            &self.pages,
+            self.pages.len() - self.free.len(),
+            0,
+            &self.free,
+        ) + pages.sum()
+    }
+
+    /// Id of the root page.
+    pub fn root_page(&self) -> usize {
+        self.root
+    }
+
+    /// Page with given id, `None` for ids of freed or nonexistent pages.
+    pub fn page(&self, page_id: usize) -> Option<&Page<K, V>> {
+        self.pages.get(page_id)?.as_ref()
+    }
+
+    /// All live pages with their ids.
+    pub fn pages(&self) -> impl Iterator<Item = (usize, &Page<K, V>)> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(id, page)| Some((id, page.as_ref()?)))
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let leaf = self.leaf_for(key);
+        let Page::Leaf { keys, values, .. } = self.page_ref(leaf) else {
+            unreachable!("Descent always ends in a leaf")
+        };
+        keys.binary_search(key)
+            .ok()
+            .map(|position| &values[position])
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returning the previous value stored under the same key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut previous = None;
+        if let Some((separator, right)) = self.insert_into(self.root, key, value, &mut previous) {
+            let new_root = self.allocate(Page::Internal {
+                keys: vec![separator],
+                children: vec![self.root, right],
+            });
+            self.root = new_root;
+        }
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Removes a key, returning its value if it was present. Underflowing pages borrow from or merge
+    /// with their siblings.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = self.remove_from(self.root, key)?;
+        self.len -= 1;
+        if let Page::Internal { keys, children } = self.page_ref(self.root) {
+            if keys.is_empty() {
+                let only_child = children[0];
+                self.release(self.root);
+                self.root = only_child;
+            }
+        }
+        Some(removed)
+    }
+
+    /// Entry with the greatest key less than or equal to `key`, `None` if all keys are greater.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::BPlusTree;
+    ///         let mut tree = BPlusTree::<u32, char, 4>::new();
+    ///         for (key, value) in [(10, 'a'), (20, 'b'), (30, 'c'), (40, 'd'), (50, 'e')] {
+    ///             tree.insert(key, value);
+    ///         }
+    ///
+    ///         assert_eq!(tree.floor(&35), Some((&30, &'c')));
+    ///         assert_eq!(tree.ceiling(&35), Some((&40, &'d')));
+    ///         assert_eq!(tree.closest(&36, |a: &u32, b: &u32| a.abs_diff(*b)), Some((&40, &'d')));
+    ///         assert_eq!(tree.floor(&5), None);
+    /// ```
+    pub fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        // Subtree left of the descent path closest to it, holding the floor if the leaf has no smaller key
+        let mut before = None;
+        let mut id = self.root;
+        while let Page::Internal { keys, children } = self.page_ref(id) {
+            let position = keys.partition_point(|separator| separator <= key);
+            if position > 0 {
+                before = Some(children[position - 1]);
+            }
+            id = children[position];
+        }
+        let Page::Leaf { keys, values, .. } = self.page_ref(id) else {
+            unreachable!("Descent always ends in a leaf")
+        };
+        match keys.partition_point(|leaf_key| leaf_key <= key) {
+            0 => self.last_entry(before?),
+            position => Some((&keys[position - 1], &values[position - 1])),
+        }
+    }
+
+    /// Entry with the smallest key greater than or equal to `key`, `None` if all keys are smaller.
+    pub fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.range(key..).next()
+    }
+
+    /// Entry with the key nearest to `key` by `distance`, either its [BPlusTree::floor] or its
+    /// [BPlusTree::ceiling]. Ties go to the floor. `None` for an empty tree.
+    pub fn closest<D: Ord>(&self, key: &K, distance: impl Fn(&K, &K) -> D) -> Option<(&K, &V)> {
+        search::closest(key, self.floor(key), self.ceiling(key), distance)
+    }
+
+    /// Iterates over all entries in ascending key order.
+    pub fn iter(&self) -> BPlusRange<'_, K, V, FANOUT> {
+        self.range(..)
+    }
+
+    /// Iterates over entries with keys within `range` in ascending key order, walking the leaf chain.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> BPlusRange<'_, K, V, FANOUT> {
+        let (leaf, position) = match range.start_bound() {
+            Bound::Unbounded => (self.leftmost_leaf(), 0),
+            Bound::Included(start) | Bound::Excluded(start) => {
+                let leaf = self.leaf_for(start);
+                let keys = self.page_ref(leaf).keys();
+                let position = match range.start_bound() {
+                    Bound::Included(_) => keys.partition_point(|key| key < start),
+                    _ => keys.partition_point(|key| key <= start),
+                };
+                (leaf, position)
+            }
+        };
+        BPlusRange {
+            tree: self,
+            leaf: Some(leaf),
+            position,
+            end: range.end_bound().cloned(),
+        }
+    }
+
+    /// Checks the fanout, that every linked page exists and is linked once, occupancy and order of keys within the
+    /// ranges given by separators, leaves at a single depth chained in key order, and the length and free pages.
+    #[cfg(feature = "serde")]
+    fn validate(&self) -> Result<(), TreeError> {
+        if FANOUT < 3 {
+            return Err(TreeError::CorruptedTree(format!(
+                "fanout {FANOUT} is less than 3"
+            )));
+        }
+        let corrupted = |id: usize, problem: &str| {
+            Err(TreeError::CorruptedTree(format!("page {id} {problem}")))
+        };
+        let mut linked = vec![false; self.pages.len()];
+        let mut leaves = Vec::new();
+        let (mut entries, mut leaf_depth) = (0, None);
+        // Children are pushed last to first, so pages are visited in key order
+        let mut stack = vec![(self.root, 0, None::<&K>, None::<&K>)];
+        while let Some((id, depth, lower, upper)) = stack.pop() {
+            let page = self
+                .page(id)
+                .ok_or(TreeError::NodeOutOfBounds { node: id })?;
+            if std::mem::replace(&mut linked[id], true) {
+                return corrupted(id, "is linked more than once");
+            }
+            let keys = page.keys();
+            if keys.len() > Self::MAX_KEYS || (id != self.root && keys.len() < Self::MIN_KEYS) {
+                return corrupted(id, "holds too many or too few keys");
+            }
+            let within = |key: &K| {
+                lower.is_none_or(|lower| key >= lower) && upper.is_none_or(|upper| key < upper)
+            };
+            if !keys.windows(2).all(|pair| pair[0] < pair[1]) || !keys.iter().all(within) {
+                return corrupted(id, "has keys out of order");
+            }
+            match page {
+                Page::Internal { keys, children } => {
+                    if keys.is_empty() || children.len() != keys.len() + 1 {
+                        return corrupted(id, "doesn't have one child more than keys");
+                    }
+                    for (position, &child) in children.iter().enumerate().rev() {
+                        let (lower, upper) = (
+                            position
+                                .checked_sub(1)
+                                .map_or(lower, |before| Some(&keys[before])),
+                            keys.get(position).or(upper),
+                        );
+                        stack.push((child, depth + 1, lower, upper));
+                    }
+                }
+                Page::Leaf { keys, values, .. } => {
+                    if values.len() != keys.len() {
+                        return corrupted(id, "doesn't have a value for every key");
+                    }
+                    if *leaf_depth.get_or_insert(depth) != depth {
+                        return corrupted(id, "is a leaf at a different depth than others");
+                    }
+                    entries += keys.len();
+                    leaves.push(id);
+                }
+            }
+        }
+        let chained = leaves.iter().enumerate().all(|(position, &id)| {
+            let Some(Page::Leaf { next, .. }) = self.page(id) else {
+                unreachable!("Only leaves are collected")
+            };
+            *next == leaves.get(position + 1).copied()
+        });
+        if !chained {
+            return Err(TreeError::CorruptedTree(
+                "leaves aren't chained in key order".to_string(),
+            ));
+        }
+        if entries != self.len {
+            return Err(TreeError::CorruptedTree(format!(
+                "tree holds {entries} entries, but its length is {}",
+                self.len
+            )));
+        }
+        search::check_slots(
+            &self.pages,
+            &self.free,
+            linked.iter().filter(|&&linked| linked).count(),
+        )
+    }
+
+    fn page_ref(&self, id: usize) -> &Page<K, V> {
+        self.pages[id].as_ref().expect("Linked pages are present")
+    }
+
+    fn take(&mut self, id: usize) -> Page<K, V> {
+        self.pages[id].take().expect("Linked pages are present")
+    }
+
+    fn put(&mut self, id: usize, page: Page<K, V>) {
+        self.pages[id] = Some(page);
+    }
+
+    fn allocate(&mut self, page: Page<K, V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.put(id, page);
+                id
+            }
+            None => {
+                self.pages.push(Some(page));
+                self.pages.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, id: usize) {
+        self.pages[id] = None;
+        self.free.push(id);
+    }
+
+    fn leaf_for(&self, key: &K) -> usize {
+        let mut id = self.root;
+        while let Page::Internal { keys, children } = self.page_ref(id) {
+            id = children[keys.partition_point(|separator| separator <= key)];
+        }
+        id
+    }
+
+    /// Entry with the greatest key in the subtree rooted at page `id`.
+    fn last_entry(&self, mut id: usize) -> Option<(&K, &V)> {
+        loop {
+            match self.page_ref(id) {
+                Page::Internal { children, .. } => id = *children.last()?,
+                Page::Leaf { keys, values, .. } => return keys.last().zip(values.last()),
+            }
+        }
+    }
+
+    fn leftmost_leaf(&self) -> usize {
+        let mut id = self.root;
+        while let Page::Internal { children, .. } = self.page_ref(id) {
+            id = children[0];
+        }
+        id
+    }
+
+    /// Inserts into the subtree rooted at page `id`. If the page splits, returns the separator and id of
+    /// the new right sibling.
+    fn insert_into(
+        &mut self,
+        id: usize,
+        key: K,
+        value: V,
+        previous: &mut Option<V>,
+    ) -> Option<(K, usize)> {
+        let mut page = self.take(id);
+        let split = match &mut page {
+            Page::Leaf { keys, values, next } => match keys.binary_search(&key) {
+                Ok(position) => {
+                    *previous = Some(std::mem::replace(&mut values[position], value));
+                    None
+                }
+                Err(position) => {
+                    keys.insert(position, key);
+                    values.insert(position, value);
+                    (keys.len() > Self::MAX_KEYS).then(|| {
+                        let middle = keys.len() / 2;
+                        let right_keys = keys.split_off(middle);
+                        let separator = right_keys[0].clone();
+                        let right = Page::Leaf {
+                            keys: right_keys,
+                            values: values.split_off(middle),
+                            next: *next,
+                        };
+                        (separator, right)
+                    })
+                }
+            },
+            Page::Internal { keys, children } => {
+                let position = keys.partition_point(|separator| separator <= &key);
+                let child_split = self.insert_into(children[position], key, value, previous);
+                child_split.and_then(|(separator, right)| {
+                    keys.insert(position, separator);
+                    children.insert(position + 1, right);
+                    (keys.len() > Self::MAX_KEYS).then(|| {
+                        let middle = keys.len() / 2;
+                        let right_keys = keys.split_off(middle + 1);
+                        let separator = keys.pop().expect("Middle key exists");
+                        let right = Page::Internal {
+                            keys: right_keys,
+                            children: children.split_off(middle + 1),
+                        };
+                        (separator, right)
+                    })
+                })
+            }
+        };
+
+        let split = split.map(|(separator, right)| {
+            let right_id = self.allocate(right);
+            if let Page::Leaf { next, .. } = &mut page {
+                *next = Some(right_id);
+            }
+            (separator, right_id)
+        });
+        self.put(id, page);
+        split
+    }
+
+    fn remove_from(&mut self, id: usize, key: &K) -> Option<V> {
+        let mut page = self.take(id);
+        let removed = match &mut page {
+            Page::Leaf { keys, values, .. } => keys.binary_search(key).ok().map(|position| {
+                keys.remove(position);
+                values.remove(position)
+            }),
+            Page::Internal { keys, children } => {
+                let position = keys.partition_point(|separator| separator <= key);
+                let removed = self.remove_from(children[position], key);
+                if removed.is_some()
+                    && self.page_ref(children[position]).keys().len() < Self::MIN_KEYS
+                {
+                    self.fix_underflow(keys, children, position);
+                }
+                removed
+            }
+        };
+        self.put(id, page);
+        removed
+    }
+
+    /// Restores the minimal occupancy of child at `position` by borrowing from a sibling or merging with it.
+    fn fix_underflow(&mut self, keys: &mut Vec<K>, children: &mut Vec<usize>, position: usize) {
+        if position > 0 && self.page_ref(children[position - 1]).keys().len() > Self::MIN_KEYS {
+            let mut left = self.take(children[position - 1]);
+            let mut child = self.take(children[position]);
+            match (&mut left, &mut child) {
+                (
+                    Page::Leaf {
+                        keys: left_keys,
+                        values: left_values,
+                        ..
+                    },
+                    Page::Leaf {
+                        keys: child_keys,
+                        values: child_values,
+                        ..
+                    },
+                ) => {
+                    child_keys.insert(0, left_keys.pop().expect("Sibling has spare keys"));
+                    child_values.insert(0, left_values.pop().expect("Sibling has spare values"));
+                    keys[position - 1] = child_keys[0].clone();
+                }
+                (
+                    Page::Internal {
+                        keys: left_keys,
+                        children: left_children,
+                    },
+                    Page::Internal {
+                        keys: child_keys,
+                        children: child_children,
+                    },
+                ) => {
+                    let borrowed = left_keys.pop().expect("Sibling has spare keys");
+                    child_keys.insert(0, std::mem::replace(&mut keys[position - 1], borrowed));
+                    child_children.insert(0, left_children.pop().expect("Sibling has children"));
+                }
+                _ => unreachable!("Siblings are on the same level"),
+            }
+            self.put(children[position - 1], left);
+            self.put(children[position], child);
+        } else if position + 1 < children.len()
+            && self.page_ref(children[position + 1]).keys().len() > Self::MIN_KEYS
+        {
+            let mut right = self.take(children[position + 1]);
+            let mut child = self.take(children[position]);
+            match (&mut right, &mut child) {
+                (
+                    Page::Leaf {
+                        keys: right_keys,
+                        values: right_values,
+                        ..
+                    },
+                    Page::Leaf {
+                        keys: child_keys,
+                        values: child_values,
+                        ..
+                    },
+                ) => {
+                    child_keys.push(right_keys.remove(0));
+                    child_values.push(right_values.remove(0));
+                    keys[position] = right_keys[0].clone();
+                }
+                (
+                    Page::Internal {
+                        keys: right_keys,
+                        children: right_children,
+                    },
+                    Page::Internal {
+                        keys: child_keys,
+                        children: child_children,
+                    },
+                ) => {
+                    let borrowed = right_keys.remove(0);
+                    child_keys.push(std::mem::replace(&mut keys[position], borrowed));
+                    child_children.push(right_children.remove(0));
+                }
+                _ => unreachable!("Siblings are on the same level"),
+            }
+            self.put(children[position + 1], right);
+            self.put(children[position], child);
+        } else {
+            // Merge with a sibling, the left page of the pair absorbs the right one
+            let left_position = if position > 0 { position - 1 } else { position };
+            let right_id = children.remove(left_position + 1);
+            let separator = keys.remove(left_position);
+            let right = self.take(right_id);
+            self.release(right_id);
+            let mut left = self.take(children[left_position]);
+            match (&mut left, right) {
+                (
+                    Page::Leaf {
+                        keys: left_keys,
+                        values: left_values,
+                        next,
+                    },
+                    Page::Leaf {
+                        keys: right_keys,
+                        values: right_values,
+                        next: right_next,
+                    },
+                ) => {
+                    left_keys.extend(right_keys);
+                    left_values.extend(right_values);
+                    *next = right_next;
+                }
+                (
+                    Page::Internal {
+                        keys: left_keys,
+                        children: left_children,
+                    },
+                    Page::Internal {
+                        keys: right_keys,
+                        children: right_children,
+                    },
+                ) => {
+                    left_keys.push(separator);
+                    left_keys.extend(right_keys);
+                    left_children.extend(right_children);
+                }
+                _ => unreachable!("Siblings are on the same level"),
+            }
+            self.put(children[left_position], left);
+        }
+    }
+}
+
+/// Iterator over a key range of a [BPlusTree], see [BPlusTree::range].
+pub struct BPlusRange<'a, K, V, const FANOUT: usize> {
+    tree: &'a BPlusTree<K, V, FANOUT>,
+    leaf: Option<usize>,
+    position: usize,
+    end: Bound<K>,
+}
+
+impl<'a, K: Ord + Clone, V, const FANOUT: usize> Iterator for BPlusRange<'a, K, V, FANOUT> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Page::Leaf { keys, values, next } = self.tree.page_ref(self.leaf?) else {
+                unreachable!("Range iteration walks leaves only")
+            };
+            if self.position < keys.len() {
+                let key = &keys[self.position];
+                let within = match &self.end {
+                    Bound::Included(end) => key <= end,
+                    Bound::Excluded(end) => key < end,
+                    Bound::Unbounded => true,
+                };
+                if !within {
+                    self.leaf = None;
+                    return None;
+                }
+                self.position += 1;
+                return Some((key, &values[self.position - 1]));
+            }
+            self.leaf = *next;
+            self.position = 0;
+        }
+    }
+}
+
+/// Serialized form of a [BPlusTree], validated before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>"))]
+struct BPlusTreeData<K, V> {
+    pages: Vec<Option<Page<K, V>>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord + Clone, V, const FANOUT: usize> TryFrom<BPlusTreeData<K, V>>
+    for BPlusTree<K, V, FANOUT>
+{
+    type Error = TreeError;
+
+    fn try_from(data: BPlusTreeData<K, V>) -> Result<Self, Self::Error> {
+        let tree = Self {
+            pages: data.pages,
+            free: data.free,
+            root: data.root,
+            len: data.len,
+        };
+        tree.validate()?;
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::search::{BPlusTree, Page};
+
+    /// Verifies page occupancy and that all leaves sit at the same depth. Returns the depth of the subtree.
+    fn check<const FANOUT: usize>(tree: &BPlusTree<u32, u32, FANOUT>, id: usize) -> usize {
+        let page = tree.page(id).unwrap();
+        let keys = match page {
+            Page::Internal { keys, .. } | Page::Leaf { keys, .. } => keys,
+        };
+        assert!(keys.len() < FANOUT);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        if id != tree.root_page() {
+            assert!(keys.len() >= (FANOUT - 1) / 2);
+        }
+        match page {
+            Page::Leaf { .. } => 1,
+            Page::Internal { children, .. } => {
+                assert_eq!(children.len(), keys.len() + 1);
+                let depths: Vec<usize> = children.iter().map(|&c| check(tree, c)).collect();
+                assert!(depths.windows(2).all(|pair| pair[0] == pair[1]));
+                depths[0] + 1
+            }
+        }
+    }
+
+    fn randomized<const FANOUT: usize>() {
+        let mut tree = BPlusTree::<u32, u32, FANOUT>::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 11_u32;
+        for round in 0..3000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let key = (seed >> 16) % 500;
+            if round > 1500 && seed.is_multiple_of(2) {
+                assert_eq!(tree.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(tree.insert(key, seed), expected.insert(key, seed));
+            }
+        }
+
+        check(&tree, tree.root_page());
+        assert_eq!(tree.len(), expected.len());
+        assert!(tree.iter().eq(expected.iter()));
+        assert!(tree.range(100..=200).eq(expected.range(100..=200)));
+        assert!(tree.range(..42).eq(expected.range(..42)));
+        for key in 0..=500 {
+            assert_eq!(tree.get(&key), expected.get(&key));
+            assert_eq!(tree.floor(&key), expected.range(..=key).next_back());
+            assert_eq!(tree.ceiling(&key), expected.range(key..).next());
+        }
+
+        for key in 0..500 {
+            assert_eq!(tree.remove(&key), expected.remove(&key));
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.pages().count(), 1);
+    }
+
+    #[test]
+    fn fanout_3() {
+        randomized::<3>();
+    }
+
+    #[test]
+    fn fanout_4() {
+        randomized::<4>();
+    }
+
+    #[test]
+    fn fanout_16() {
+        randomized::<16>();
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut tree = BPlusTree::<u32, (), 5>::new();
+        for key in (0..100).step_by(10) {
+            tree.insert(key, ());
+        }
+        let keys = |range: (std::ops::Bound<u32>, std::ops::Bound<u32>)| -> Vec<u32> {
+            tree.range(range).map(|(key, _)| *key).collect()
+        };
+        use std::ops::Bound::*;
+        assert_eq!(keys((Excluded(10), Included(40))), vec![20, 30, 40]);
+        assert_eq!(keys((Included(15), Excluded(40))), vec![20, 30]);
+        assert_eq!(keys((Excluded(90), Unbounded)), Vec::<u32>::new());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let mut tree = BPlusTree::<u32, u32, 4>::new();
+        for key in 0..40 {
+            tree.insert(key, key * 10);
+        }
+        for key in (0..40).step_by(3) {
+            tree.remove(&key);
+        }
+        let json = serde_json::to_string(&tree).expect("Tree is serializable");
+        let deserialized: BPlusTree<u32, u32, 4> =
+            serde_json::from_str(&json).expect("Tree is valid");
+        assert!(deserialized.iter().eq(tree.iter()));
+        check(&deserialized, deserialized.root_page());
+        assert!(serde_json::from_str::<BPlusTree<u32, u32, 2>>(&json).is_err());
+
+        let leaf = |keys: &str, next: &str| {
+            let values = keys
+                .split(',')
+                .filter(|key| !key.is_empty())
+                .map(|_| "0")
+                .collect::<Vec<_>>();
+            format!(
+                r#"{{"Leaf":{{"keys":[{keys}],"values":[{}],"next":{next}}}}}"#,
+                values.join(",")
+            )
+        };
+        let root = |keys: &str, children: &str| {
+            format!(r#"{{"Internal":{{"keys":[{keys}],"children":[{children}]}}}}"#)
+        };
+        let tree = |pages: &[&str], root: usize, len: usize| {
+            format!(
+                r#"{{"pages":[{}],"free":[],"root":{root},"len":{len}}}"#,
+                pages.join(",")
+            )
+        };
+        let valid = tree(
+            &[&root("5", "1,2"), &leaf("1,2", "2"), &leaf("5,6", "null")],
+            0,
+            4,
+        );
+        assert!(serde_json::from_str::<BPlusTree<u32, u32, 4>>(&valid).is_ok());
+        for malformed in [
+            tree(&[&leaf("1", "null")], 3, 1),
+            tree(&[&leaf("2,1", "null")], 0, 2),
+            tree(&[&leaf("1,2,3,4", "null")], 0, 4),
+            tree(&[&leaf("1,2", "null")], 0, 3),
+            // Child out of bounds, keys beyond the separator, broken leaf chain and a page linked twice
+            tree(&[&root("5", "1,7"), &leaf("1,2", "null")], 0, 2),
+            tree(
+                &[&root("5", "1,2"), &leaf("1,6", "2"), &leaf("5,7", "null")],
+                0,
+                4,
+            ),
+            tree(
+                &[
+                    &root("5", "1,2"),
+                    &leaf("1,2", "null"),
+                    &leaf("5,6", "null"),
+                ],
+                0,
+                4,
+            ),
+            tree(&[&root("5", "1,1"), &leaf("1,2", "1")], 0, 4),
+        ] {
+            assert!(
+                serde_json::from_str::<BPlusTree<u32, u32, 4>>(&malformed).is_err(),
+                "{malformed}"
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+pub mod avl;
+pub mod bplus;
+pub mod splay;
+pub mod treap;
+
+pub use avl::*;
+pub use bplus::*;
+pub use splay::*;
+pub use treap::*;
+
+#[cfg(any(feature = "debug-validate", feature = "serde"))]
+use crate::tree::TreeError;
+
+/// True if no key fits in `range`, its start lying past its end. [BTreeMap](std::collections::BTreeMap) panics on
+/// such ranges, the search trees yield nothing instead.
+pub(crate) fn is_empty_range<Q: Ord + ?Sized>(range: &impl RangeBounds<Q>) -> bool {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start >= end,
+        _ => false,
+    }
+}
+
+/// Entry with the key equal to `key`, or else the nearest one on the `side` of it, found by a single descent from
+/// `root`. `node` reads the key, value and left and right child of a node, so binary search trees of any layout
+/// share the search.
+pub(crate) fn bound<'a, N, K, V, Q>(
+    root: Option<N>,
+    key: &Q,
+    side: Ordering,
+    node: impl Fn(N) -> (&'a K, &'a V, Option<N>, Option<N>),
+) -> Option<(&'a K, &'a V)>
+where
+    K: Borrow<Q> + 'a,
+    V: 'a,
+    Q: Ord + ?Sized,
+{
+    let mut candidate = None;
+    let mut current = root;
+    while let Some(id) = current {
+        let (node_key, value, left, right) = node(id);
+        let ordering = node_key.borrow().cmp(key);
+        if ordering == Ordering::Equal {
+            return Some((node_key, value));
+        }
+        if ordering == side {
+            candidate = Some((node_key, value));
+        }
+        current = match ordering {
+            Ordering::Less => right,
+            _ => left,
+        };
+    }
+    candidate
+}
+
+/// The one of `floor` and `ceiling` entries nearer to `key` by `distance`, ties going to the floor.
+pub(crate) fn closest<'a, K, V, Q, D>(
+    key: &Q,
+    floor: Option<(&'a K, &'a V)>,
+    ceiling: Option<(&'a K, &'a V)>,
+    distance: impl Fn(&Q, &Q) -> D,
+) -> Option<(&'a K, &'a V)>
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+    D: Ord,
+{
+    match (floor, ceiling) {
+        (Some(below), Some(above))
+            if distance(key, above.0.borrow()) < distance(key, below.0.borrow()) =>
+        {
+            Some(above)
+        }
+        (below, above) => below.or(above),
+    }
+}
+
+/// Checks the slots of `nodes` taken are the `reached` nodes of a tree and the vacant ones are listed once in
+/// `free`, so trees keeping nodes in a vector of slots can't reuse a taken slot or lose track of a node.
+#[cfg(any(feature = "debug-validate", feature = "serde"))]
+pub(crate) fn check_slots<N>(
+    nodes: &[Option<N>],
+    free: &[usize],
+    reached: usize,
+) -> Result<(), TreeError> {
+    let taken = nodes.iter().flatten().count();
+    if taken != reached {
+        return Err(TreeError::CorruptedTree(format!(
+            "tree links {reached} nodes, but stores {taken}"
+        )));
+    }
+    let mut listed = vec![false; nodes.len()];
+    for &id in free {
+        if !matches!(nodes.get(id), Some(None)) || std::mem::replace(&mut listed[id], true) {
+            return Err(TreeError::CorruptedTree(format!(
+                "free slot {id} is out of bounds, taken or listed twice"
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,558 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::search;
+#[cfg(feature = "serde")]
+use crate::tree::TreeError;
+
+/// Ordered map backed by a splay tree. Every access rotates the accessed node up to the root, so recently
+/// used keys are found faster - a good fit for workloads with strong temporal locality, like caches.
+/// Operations run in amortized O(log n). Nodes live in a single vector and link to each other by indices.
+///
+/// Since lookups restructure the tree, [SplayTree::get] requires mutable access. Use [SplayTree::peek]
+/// to look up a key without splaying.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::search::SplayTree;
+///         let mut tree = SplayTree::new();
+///         for key in 0..100 {
+///             tree.insert(key, key * 10);
+///         }
+///
+///         assert_eq!(tree.get(&42), Some(&420));
+///         assert_eq!(tree.root_key(), Some(&42)); // Recently accessed keys move to the root
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "SplayTreeData<K, V>",
+        bound(deserialize = "K: Ord + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+pub struct SplayTree<K, V> {
+    nodes: Vec<Option<SplayNode<K, V>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct SplayNode<K, V> {
+    key: K,
+    value: V,
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl<K: Ord, V> Default for SplayTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> SplayTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Keys count as values, vacant slots and the list of them
+    /// are overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::slots(
+            &self.nodes,
+            self.len,
+            size_of::<K>() + size_of::<V>(),
+            &self.free,
+        )
+    }
+
+    /// Key currently stored in the root node - the most recently accessed one.
+    pub fn root_key(&self) -> Option<&K> {
+        Some(&self.node(self.root?).key)
+    }
+
+    /// Looks up a key and splays it (or the last node visited, if the key is missing) to the root.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.find_and_splay(key)?;
+        Some(&self.node(found).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.find_and_splay(key)?;
+        Some(&mut self.node_mut(found).value)
+    }
+
+    /// Looks up a key without restructuring the tree.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (last, ordering) = self.descend(key)?;
+        (ordering == Ordering::Equal).then(|| &self.node(last).value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.peek(key).is_some()
+    }
+
+    /// Inserts a key-value pair and splays it to the root. Returns the previous value stored under the same
+    /// key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let Some((last, ordering)) = self.descend(&key) else {
+            self.root = Some(self.allocate(key, value, None));
+            self.len += 1;
+            return None;
+        };
+
+        let inserted = match ordering {
+            Ordering::Equal => {
+                self.splay(last);
+                return Some(std::mem::replace(&mut self.node_mut(last).value, value));
+            }
+            Ordering::Less => {
+                let id = self.allocate(key, value, Some(last));
+                self.node_mut(last).left = Some(id);
+                id
+            }
+            Ordering::Greater => {
+                let id = self.allocate(key, value, Some(last));
+                self.node_mut(last).right = Some(id);
+                id
+            }
+        };
+        self.splay(inserted);
+        self.len += 1;
+        None
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.find_and_splay(key)?;
+        let node = self.nodes[found].take().expect("Splayed node is present");
+        self.free.push(found);
+        self.len -= 1;
+
+        for child in [node.left, node.right].into_iter().flatten() {
+            self.node_mut(child).parent = None;
+        }
+        self.root = match (node.left, node.right) {
+            (None, right) => right,
+            (left, None) => left,
+            (Some(left), Some(right)) => {
+                // Splay the maximum of the left subtree, it has no right child afterwards
+                let mut max = left;
+                while let Some(next) = self.node(max).right {
+                    max = next;
+                }
+                self.root = Some(left);
+                self.splay(max);
+                self.node_mut(max).right = Some(right);
+                self.node_mut(right).parent = Some(max);
+                Some(max)
+            }
+        };
+        Some(node.value)
+    }
+
+    /// Entry with the greatest key less than or equal to `key`, `None` if all keys are greater. Like
+    /// [SplayTree::peek], the tree isn't restructured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::SplayTree;
+    ///         let mut tree = SplayTree::new();
+    ///         for port in [80, 443, 8080] {
+    ///             tree.insert(port, ());
+    ///         }
+    ///
+    ///         assert_eq!(tree.floor(&500), Some((&443, &())));
+    ///         assert_eq!(tree.ceiling(&500), Some((&8080, &())));
+    ///         assert_eq!(tree.closest(&500, |a: &i32, b: &i32| a.abs_diff(*b)), Some((&443, &())));
+    ///         assert_eq!(tree.root_key(), Some(&8080));
+    /// ```
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Less)
+    }
+
+    /// Entry with the smallest key greater than or equal to `key`, `None` if all keys are smaller.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Greater)
+    }
+
+    /// Entry with the key nearest to `key` by `distance`, either its [SplayTree::floor] or its
+    /// [SplayTree::ceiling]. Ties go to the floor. `None` for an empty tree.
+    pub fn closest<Q, D>(&self, key: &Q, distance: impl Fn(&Q, &Q) -> D) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        D: Ord,
+    {
+        search::closest(key, self.floor(key), self.ceiling(key), distance)
+    }
+
+    /// Entry with the key equal to `key`, or else the nearest one on the `side` of it.
+    fn bound<Q>(&self, key: &Q, side: Ordering) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::bound(self.root, key, side, |id| {
+            let node = self.node(id);
+            (&node.key, &node.value, node.left, node.right)
+        })
+    }
+
+    /// Iterates over entries in ascending key order, without restructuring the tree.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut stack = Vec::new();
+        let mut node = self.root;
+        std::iter::from_fn(move || {
+            while let Some(id) = node {
+                stack.push(id);
+                node = self.node(id).left;
+            }
+            let id = stack.pop()?;
+            node = self.node(id).right;
+            let current = self.node(id);
+            Some((&current.key, &current.value))
+        })
+    }
+
+    fn find_and_splay<Q>(&mut self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (last, ordering) = self.descend(key)?;
+        self.splay(last);
+        (ordering == Ordering::Equal).then_some(last)
+    }
+
+    /// Descends towards `key`. Returns the last node visited and how `key` compares to it.
+    fn descend<Q>(&self, key: &Q) -> Option<(usize, Ordering)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut id = self.root?;
+        loop {
+            let node = self.node(id);
+            let ordering = key.cmp(node.key.borrow());
+            let next = match ordering {
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+                Ordering::Equal => None,
+            };
+            match next {
+                Some(next) => id = next,
+                None => return Some((id, ordering)),
+            }
+        }
+    }
+
+    /// Moves node `id` to the root by a series of zig, zig-zig and zig-zag steps.
+    fn splay(&mut self, id: usize) {
+        while let Some(parent) = self.node(id).parent {
+            if let Some(grandparent) = self.node(parent).parent {
+                let zig_zig = (self.node(grandparent).left == Some(parent))
+                    == (self.node(parent).left == Some(id));
+                if zig_zig {
+                    self.rotate_up(parent);
+                } else {
+                    self.rotate_up(id);
+                }
+            }
+            self.rotate_up(id);
+        }
+        self.root = Some(id);
+    }
+
+    /// Rotates node `id` above its parent, keeping the in-order sequence intact.
+    fn rotate_up(&mut self, id: usize) {
+        let parent = self.node(id).parent.expect("Rotated node has a parent");
+        let grandparent = self.node(parent).parent;
+
+        if self.node(parent).left == Some(id) {
+            let moved = self.node(id).right;
+            self.node_mut(parent).left = moved;
+            self.node_mut(id).right = Some(parent);
+            if let Some(moved) = moved {
+                self.node_mut(moved).parent = Some(parent);
+            }
+        } else {
+            let moved = self.node(id).left;
+            self.node_mut(parent).right = moved;
+            self.node_mut(id).left = Some(parent);
+            if let Some(moved) = moved {
+                self.node_mut(moved).parent = Some(parent);
+            }
+        }
+
+        self.node_mut(parent).parent = Some(id);
+        self.node_mut(id).parent = grandparent;
+        match grandparent {
+            Some(grandparent) => {
+                let grandparent = self.node_mut(grandparent);
+                if grandparent.left == Some(parent) {
+                    grandparent.left = Some(id);
+                } else {
+                    grandparent.right = Some(id);
+                }
+            }
+            None => self.root = Some(id),
+        }
+    }
+
+    fn allocate(&mut self, key: K, value: V, parent: Option<usize>) -> usize {
+        let node = SplayNode {
+            key,
+            value,
+            parent,
+            left: None,
+            right: None,
+        };
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Checks every linked node exists, links back to its parent and has its key in order, and that vacant slots
+    /// are listed once as free. Splay trees may be as deep as they are long, so the walk uses a stack.
+    #[cfg(feature = "serde")]
+    fn validate(&self) -> Result<(), TreeError> {
+        let mut reached = 0;
+        let mut stack = Vec::from_iter(self.root.map(|root| (root, None, None::<&K>, None::<&K>)));
+        while let Some((id, parent, lower, upper)) = stack.pop() {
+            let current = self
+                .nodes
+                .get(id)
+                .and_then(Option::as_ref)
+                .ok_or(TreeError::NodeOutOfBounds { node: id })?;
+            if current.parent != parent {
+                return Err(TreeError::CorruptedTree(format!(
+                    "node {id} doesn't link back to its parent"
+                )));
+            }
+            // Strict bounds also rule out reaching a node twice, as subtrees cover disjoint key ranges
+            if lower.is_some_and(|lower| current.key <= *lower)
+                || upper.is_some_and(|upper| current.key >= *upper)
+            {
+                return Err(TreeError::CorruptedTree(format!(
+                    "key of node {id} is out of order"
+                )));
+            }
+            reached += 1;
+            if let Some(left) = current.left {
+                stack.push((left, Some(id), lower, Some(&current.key)));
+            }
+            if let Some(right) = current.right {
+                stack.push((right, Some(id), Some(&current.key), upper));
+            }
+        }
+        if reached != self.len {
+            return Err(TreeError::CorruptedTree(format!(
+                "tree holds {reached} nodes, but its length is {}",
+                self.len
+            )));
+        }
+        search::check_slots(&self.nodes, &self.free, reached)
+    }
+
+    fn node(&self, id: usize) -> &SplayNode<K, V> {
+        self.nodes[id].as_ref().expect("Linked nodes are present")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut SplayNode<K, V> {
+        self.nodes[id].as_mut().expect("Linked nodes are present")
+    }
+}
+
+/// Serialized form of a [SplayTree], validated before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>"))]
+struct SplayTreeData<K, V> {
+    nodes: Vec<Option<SplayNode<K, V>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V> TryFrom<SplayTreeData<K, V>> for SplayTree<K, V> {
+    type Error = TreeError;
+
+    fn try_from(data: SplayTreeData<K, V>) -> Result<Self, Self::Error> {
+        let tree = Self {
+            nodes: data.nodes,
+            free: data.free,
+            root: data.root,
+            len: data.len,
+        };
+        tree.validate()?;
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::search::SplayTree;
+
+    #[test]
+    fn matches_btree_map() {
+        let mut tree = SplayTree::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 5_u32;
+        for _ in 0..3000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let key = (seed >> 16) % 256;
+            match seed % 4 {
+                0 => assert_eq!(tree.remove(&key), expected.remove(&key)),
+                1 => assert_eq!(tree.get(&key), expected.get(&key)),
+                _ => assert_eq!(tree.insert(key, seed), expected.insert(key, seed)),
+            }
+            assert_eq!(tree.len(), expected.len());
+        }
+        assert!(tree.iter().eq(expected.iter()));
+        for key in 0..=256 {
+            assert_eq!(tree.floor(&key), expected.range(..=key).next_back());
+            assert_eq!(tree.ceiling(&key), expected.range(key..).next());
+        }
+
+        // Parent links stay consistent with child links
+        for (id, node) in tree.nodes.iter().enumerate() {
+            if let Some(node) = node {
+                for child in [node.left, node.right].into_iter().flatten() {
+                    assert_eq!(tree.node(child).parent, Some(id));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn accessed_keys_move_to_root() {
+        let mut tree = SplayTree::new();
+        for key in 0..1000 {
+            tree.insert(key, ());
+        }
+        assert_eq!(tree.root_key(), Some(&999));
+
+        tree.get(&3);
+        assert_eq!(tree.root_key(), Some(&3));
+        assert!(tree.peek(&500).is_some());
+        assert_eq!(tree.root_key(), Some(&3));
+
+        // Missing keys splay the closest node visited
+        tree.get(&5000);
+        assert_eq!(tree.root_key(), Some(&999));
+    }
+
+    #[test]
+    fn remove_root() {
+        let mut tree = SplayTree::new();
+        tree.insert("b", 2);
+        tree.insert("a", 1);
+        tree.insert("c", 3);
+        assert_eq!(tree.remove("c"), Some(3));
+        assert_eq!(tree.remove("a"), Some(1));
+        assert_eq!(tree.remove("a"), None);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![(&"b", &2)]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let mut tree = SplayTree::new();
+        for key in 0..50 {
+            tree.insert(key, key * 10);
+        }
+        tree.remove(&20);
+        let json = serde_json::to_string(&tree).expect("Tree is serializable");
+        let deserialized: SplayTree<i32, i32> = serde_json::from_str(&json).expect("Tree is valid");
+        assert!(deserialized.iter().eq(tree.iter()));
+
+        let node = |key: i32, parent: &str, left: &str| {
+            format!(r#"{{"key":{key},"value":0,"parent":{parent},"left":{left},"right":null}}"#)
+        };
+        let data = |nodes: &[&str], free: &str, root: usize, len: usize| {
+            let nodes = nodes.join(",");
+            format!(r#"{{"nodes":[{nodes}],"free":[{free}],"root":{root},"len":{len}}}"#)
+        };
+        let leaf = node(1, "null", "null");
+        for malformed in [
+            data(&[&leaf], "", 3, 1),
+            data(&[&leaf], "", 0, 2),
+            data(&[&leaf], "0", 0, 1),
+            // Child not linking back, keys out of order and a node linking to itself
+            data(&[&node(2, "null", "1"), &leaf], "", 0, 2),
+            data(&[&node(2, "null", "1"), &node(3, "0", "null")], "", 0, 2),
+            data(&[&node(2, "null", "0")], "", 0, 1),
+        ] {
+            assert!(
+                serde_json::from_str::<SplayTree<i32, i32>>(&malformed).is_err(),
+                "{malformed}"
+            );
+        }
+    }
+}
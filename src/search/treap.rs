@@ -0,0 +1,924 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::search;
+#[cfg(feature = "serde")]
+use crate::tree::TreeError;
+
+type Link<K, V> = Option<Box<TreapNode<K, V>>>;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct TreapNode<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    /// Number of nodes in the subtree rooted at this node.
+    size: usize,
+    /// Children are to be swapped before the subtree is read, implicit treaps only.
+    reversed: bool,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> TreapNode<K, V> {
+    fn new(key: K, value: V, priority: u64) -> Box<Self> {
+        Box::new(Self {
+            key,
+            value,
+            priority,
+            size: 1,
+            reversed: false,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+
+    /// Applies a pending reversal to the children.
+    fn push_down(&mut self) {
+        if self.reversed {
+            std::mem::swap(&mut self.left, &mut self.right);
+            for child in [&mut self.left, &mut self.right].into_iter().flatten() {
+                child.reversed = !child.reversed;
+            }
+            self.reversed = false;
+        }
+    }
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Joins two treaps, all nodes of `left` preceding all nodes of `right`.
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.push_down();
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.push_down();
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+/// Splits a keyed treap into nodes with keys for which `goes_left` holds, and the rest.
+fn split_by<K, V, F>(link: Link<K, V>, goes_left: &F) -> (Link<K, V>, Link<K, V>)
+where
+    F: Fn(&K) -> bool,
+{
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    if goes_left(&node.key) {
+        let (lower, upper) = split_by(node.right.take(), goes_left);
+        node.right = lower;
+        node.update();
+        (Some(node), upper)
+    } else {
+        let (lower, upper) = split_by(node.left.take(), goes_left);
+        node.left = upper;
+        node.update();
+        (lower, Some(node))
+    }
+}
+
+/// Splits a treap into the first `index` nodes and the rest.
+fn split_at<K, V>(link: Link<K, V>, index: usize) -> (Link<K, V>, Link<K, V>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    node.push_down();
+    let left_size = size(&node.left);
+    if index <= left_size {
+        let (lower, upper) = split_at(node.left.take(), index);
+        node.left = upper;
+        node.update();
+        (lower, Some(node))
+    } else {
+        let (lower, upper) = split_at(node.right.take(), index - left_size - 1);
+        node.right = lower;
+        node.update();
+        (Some(node), upper)
+    }
+}
+
+/// Priorities generator. Splitmix64 is plenty for keeping treaps balanced.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Priorities(u64);
+
+impl Priorities {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Ordered map backed by a treap - a binary search tree by keys and a heap by random priorities, making it
+/// balanced in expectation. Besides the map API it exposes the treap primitives: splitting by a key and
+/// concatenating two treaps, both in O(log n), and order-statistics queries.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::search::Treap;
+///         let mut treap: Treap<i32, char> = (0..10).zip('a'..).collect();
+///
+///         let mut upper = treap.split_off(&6);
+///         assert_eq!(treap.len(), 6);
+///         assert_eq!(upper.kth(0), Some((&6, &'g')));
+///
+///         treap.append(&mut upper);
+///         assert_eq!(treap.kth(9), Some((&9, &'j')));
+///         assert_eq!(treap.rank(&4), 4);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "TreapData<K, V>",
+        bound(deserialize = "K: Ord + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+pub struct Treap<K, V> {
+    root: Link<K, V>,
+    priorities: Priorities,
+}
+
+impl<K: Ord, V> Default for Treap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> Treap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            priorities: Priorities(0x5EED),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Bytes taken by the treap, see [MemoryFootprint]. Every node is a separate allocation, keys count as
+    /// values.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        nodes_footprint::<Self, K, V>(self.len())
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root.as_deref();
+        while let Some(current) = node {
+            node = match key.cmp(current.key.borrow()) {
+                Ordering::Less => current.left.as_deref(),
+                Ordering::Greater => current.right.as_deref(),
+                Ordering::Equal => return Some(&current.value),
+            };
+        }
+        None
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root.as_deref_mut();
+        while let Some(current) = node {
+            node = match key.cmp(current.key.borrow()) {
+                Ordering::Less => current.left.as_deref_mut(),
+                Ordering::Greater => current.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut current.value),
+            };
+        }
+        None
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returning the previous value stored under the same key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(existing) = self.get_mut(&key) {
+            return Some(std::mem::replace(existing, value));
+        }
+        let node = TreapNode::new(key, value, self.priorities.next());
+        let (lower, upper) = split_by(self.root.take(), &|k: &K| k < &node.key);
+        self.root = merge(merge(lower, Some(node)), upper);
+        None
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (lower, rest) = split_by(self.root.take(), &|k: &K| k.borrow() < key);
+        let (found, upper) = split_by(rest, &|k: &K| k.borrow() <= key);
+        self.root = merge(lower, upper);
+        found.map(|node| node.value)
+    }
+
+    /// Splits the treap in two at `key`. Keys greater than or equal to `key` are moved into the returned treap.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (lower, upper) = split_by(self.root.take(), &|k: &K| k.borrow() < key);
+        self.root = lower;
+        Self {
+            root: upper,
+            priorities: self.priorities.clone(),
+        }
+    }
+
+    /// Moves all entries from `other` into this treap, leaving `other` empty. When all keys of `other` are
+    /// greater than keys of `self`, the treaps are merged in O(log n). Otherwise entries are inserted one by one
+    /// and values from `other` win for duplicate keys.
+    pub fn append(&mut self, other: &mut Self) {
+        let ordered = match (self.last(), other.first()) {
+            (Some((last, _)), Some((first, _))) => last < first,
+            _ => true,
+        };
+        if ordered {
+            self.root = merge(self.root.take(), other.root.take());
+        } else {
+            let mut stack: Vec<Box<TreapNode<K, V>>> = other.root.take().into_iter().collect();
+            while let Some(mut node) = stack.pop() {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+                self.insert(node.key, node.value);
+            }
+        }
+    }
+
+    /// Entry with the smallest key.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.kth(0)
+    }
+
+    /// Entry with the greatest key.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.kth(self.len().checked_sub(1)?)
+    }
+
+    /// The `k`-th smallest entry, counting from zero, in O(log n).
+    pub fn kth(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref();
+        while let Some(current) = node {
+            let left_size = size(&current.left);
+            node = match k.cmp(&left_size) {
+                Ordering::Less => current.left.as_deref(),
+                Ordering::Equal => return Some((&current.key, &current.value)),
+                Ordering::Greater => {
+                    k -= left_size + 1;
+                    current.right.as_deref()
+                }
+            };
+        }
+        None
+    }
+
+    /// Number of keys strictly less than `key`, in O(log n).
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut node = self.root.as_deref();
+        while let Some(current) = node {
+            if current.key.borrow() < key {
+                rank += size(&current.left) + 1;
+                node = current.right.as_deref();
+            } else {
+                node = current.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    /// Entry with the greatest key less than or equal to `key`, `None` if all keys are greater.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::search::Treap;
+    ///         let treap: Treap<u32, char> = [(10, 'a'), (20, 'b'), (30, 'c')].into_iter().collect();
+    ///
+    ///         assert_eq!(treap.floor(&25), Some((&20, &'b')));
+    ///         assert_eq!(treap.ceiling(&25), Some((&30, &'c')));
+    ///         assert_eq!(treap.closest(&14, |a: &u32, b: &u32| a.abs_diff(*b)), Some((&10, &'a')));
+    ///         assert_eq!(treap.ceiling(&31), None);
+    /// ```
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Less)
+    }
+
+    /// Entry with the smallest key greater than or equal to `key`, `None` if all keys are smaller.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bound(key, Ordering::Greater)
+    }
+
+    /// Entry with the key nearest to `key` by `distance`, either its [Treap::floor] or its [Treap::ceiling].
+    /// Ties go to the floor. `None` for an empty treap.
+    pub fn closest<Q, D>(&self, key: &Q, distance: impl Fn(&Q, &Q) -> D) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        D: Ord,
+    {
+        search::closest(key, self.floor(key), self.ceiling(key), distance)
+    }
+
+    /// Entry with the key equal to `key`, or else the nearest one on the `side` of it.
+    fn bound<Q>(&self, key: &Q, side: Ordering) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::bound(self.root.as_deref(), key, side, |node| {
+            (
+                &node.key,
+                &node.value,
+                node.left.as_deref(),
+                node.right.as_deref(),
+            )
+        })
+    }
+
+    /// Iterates over entries in ascending key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        InOrder::new(self.root.as_deref()).map(|node| (&node.key, &node.value))
+    }
+
+    /// Iterates over entries with keys within `range`, in ascending key order. Subtrees outside the range
+    /// are never visited, so the iteration runs in O(log n + k) for `k` yielded entries.
+    pub fn range<Q, R>(&self, range: R) -> impl Iterator<Item = (&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut stack = Vec::new();
+        let mut node = self
+            .root
+            .as_deref()
+            .filter(|_| !search::is_empty_range(&range));
+        while let Some(current) = node {
+            let key = current.key.borrow();
+            let above_start = match range.start_bound() {
+                Bound::Included(start) => key >= start,
+                Bound::Excluded(start) => key > start,
+                Bound::Unbounded => true,
+            };
+            if above_start {
+                stack.push(current);
+                node = current.left.as_deref();
+            } else {
+                node = current.right.as_deref();
+            }
+        }
+
+        let end = match range.end_bound() {
+            Bound::Unbounded => None,
+            _ => self.first_beyond(&range),
+        };
+        InOrder { stack, end }.map(|node| (&node.key, &node.value))
+    }
+
+    /// Smallest node with a key past the end of `range`, `None` if there's none.
+    fn first_beyond<Q, R>(&self, range: &R) -> Option<&TreapNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut candidate = None;
+        let mut node = self.root.as_deref();
+        while let Some(current) = node {
+            let key = current.key.borrow();
+            let beyond = match range.end_bound() {
+                Bound::Included(end) => key > end,
+                Bound::Excluded(end) => key >= end,
+                Bound::Unbounded => false,
+            };
+            if beyond {
+                candidate = Some(current);
+                node = current.left.as_deref();
+            } else {
+                node = current.right.as_deref();
+            }
+        }
+        candidate
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for Treap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut treap = Treap::new();
+        for (key, value) in iter {
+            treap.insert(key, value);
+        }
+        treap
+    }
+}
+
+/// A sequence backed by a treap with implicit keys - positions in the sequence. Splitting, concatenation,
+/// insertion and removal at arbitrary positions, as well as reversing a whole subsequence, run in O(log n).
+///
+/// # Examples
+///
+/// ```
+///         use treesome::search::ImplicitTreap;
+///         let mut sequence: ImplicitTreap<char> = "treesome".chars().collect();
+///         sequence.reverse(0..4);
+///         assert_eq!(sequence.iter().collect::<String>(), "eertsome");
+///
+///         let mut tail = sequence.split_off(4);
+///         tail.append(&mut sequence);
+///         assert_eq!(tail.iter().collect::<String>(), "someeert");
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "ImplicitTreapData<T>",
+        bound(deserialize = "T: Deserialize<'de>")
+    )
+)]
+pub struct ImplicitTreap<T> {
+    root: Link<(), T>,
+    priorities: Priorities,
+}
+
+impl<T> Default for ImplicitTreap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            priorities: Priorities(0x5EED),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Bytes taken by the treap, see [MemoryFootprint]. Every node is a separate allocation.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        nodes_footprint::<Self, (), T>(self.len())
+    }
+
+    /// Element at given position.
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        let mut node = self.root.as_deref();
+        let mut flipped = false;
+        while let Some(current) = node {
+            flipped ^= current.reversed;
+            let (left, right) = if flipped {
+                (&current.right, &current.left)
+            } else {
+                (&current.left, &current.right)
+            };
+            let left_size = size(left);
+            node = match index.cmp(&left_size) {
+                Ordering::Less => left.as_deref(),
+                Ordering::Equal => return Some(&current.value),
+                Ordering::Greater => {
+                    index -= left_size + 1;
+                    right.as_deref()
+                }
+            };
+        }
+        None
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = TreapNode::new((), value, self.priorities.next());
+        self.root = merge(self.root.take(), Some(node));
+    }
+
+    /// Inserts `value` at `index`, shifting all elements after it.
+    ///
+    /// # Panics
+    /// If `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "Insertion index {index} out of bounds");
+        let node = TreapNode::new((), value, self.priorities.next());
+        let (lower, upper) = split_at(self.root.take(), index);
+        self.root = merge(merge(lower, Some(node)), upper);
+    }
+
+    /// Removes and returns the element at `index`, if it exists.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        let (lower, rest) = split_at(self.root.take(), index);
+        let (found, upper) = split_at(rest, 1);
+        self.root = merge(lower, upper);
+        found.map(|node| node.value)
+    }
+
+    /// Splits the sequence at `index`, the returned sequence containing elements from `index` onwards.
+    pub fn split_off(&mut self, index: usize) -> Self {
+        let (lower, upper) = split_at(self.root.take(), index);
+        self.root = lower;
+        Self {
+            root: upper,
+            priorities: self.priorities.clone(),
+        }
+    }
+
+    /// Appends all elements of `other`, leaving it empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.root = merge(self.root.take(), other.root.take());
+    }
+
+    /// Reverses the elements within `range`, lazily.
+    pub fn reverse<R: RangeBounds<usize>>(&mut self, range: R) {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        if start >= end {
+            return;
+        }
+        let (lower, rest) = split_at(self.root.take(), start);
+        let (mut middle, upper) = split_at(rest, end - start);
+        if let Some(node) = middle.as_mut() {
+            node.reversed = !node.reversed;
+        }
+        self.root = merge(merge(lower, middle), upper);
+    }
+
+    /// Iterates over elements in sequence order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        // Pending reversals are resolved on the way down, without mutating the treap
+        let mut stack: Vec<(&TreapNode<(), T>, bool, bool)> = Vec::new();
+        if let Some(root) = self.root.as_deref() {
+            stack.push((root, root.reversed, false));
+        }
+        std::iter::from_fn(move || {
+            while let Some((node, flipped, expanded)) = stack.pop() {
+                if expanded {
+                    return Some(&node.value);
+                }
+                let (first, second) = if flipped {
+                    (&node.right, &node.left)
+                } else {
+                    (&node.left, &node.right)
+                };
+                if let Some(second) = second.as_deref() {
+                    stack.push((second, flipped ^ second.reversed, false));
+                }
+                stack.push((node, flipped, true));
+                if let Some(first) = first.as_deref() {
+                    stack.push((first, flipped ^ first.reversed, false));
+                }
+            }
+            None
+        })
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut treap = ImplicitTreap::new();
+        for value in iter {
+            treap.push_back(value);
+        }
+        treap
+    }
+}
+
+/// Footprint of a treap `S` of `len` boxed nodes.
+fn nodes_footprint<S, K, V>(len: usize) -> MemoryFootprint {
+    let values = size_of::<K>() + size_of::<V>();
+    MemoryFootprint {
+        structure: size_of::<S>() + len * (size_of::<TreapNode<K, V>>() - values),
+        values: len * values,
+        overhead: 0,
+    }
+}
+
+/// Serialized form of a [Treap], validated before it's turned into the treap.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>"))]
+struct TreapData<K, V> {
+    root: Link<K, V>,
+    priorities: Priorities,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V> TryFrom<TreapData<K, V>> for Treap<K, V> {
+    type Error = TreeError;
+
+    fn try_from(data: TreapData<K, V>) -> Result<Self, Self::Error> {
+        validate(&data.root, true)?;
+        Ok(Self {
+            root: data.root,
+            priorities: data.priorities,
+        })
+    }
+}
+
+/// Serialized form of an [ImplicitTreap], validated before it's turned into the treap.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct ImplicitTreapData<T> {
+    root: Link<(), T>,
+    priorities: Priorities,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<ImplicitTreapData<T>> for ImplicitTreap<T> {
+    type Error = TreeError;
+
+    fn try_from(data: ImplicitTreapData<T>) -> Result<Self, Self::Error> {
+        validate(&data.root, false)?;
+        Ok(Self {
+            root: data.root,
+            priorities: data.priorities,
+        })
+    }
+}
+
+/// Checks subtree sizes and the heap order of priorities at every node of a treap, without recursion. With
+/// `keyed`, also checks keys ascend in-order and no reversal is pending. Nodes are named by in-order positions.
+#[cfg(feature = "serde")]
+fn validate<K: Ord, V>(root: &Link<K, V>, keyed: bool) -> Result<(), TreeError> {
+    let mut previous: Option<&K> = None;
+    for (position, node) in InOrder::new(root.as_deref()).enumerate() {
+        let mut children = [&node.left, &node.right].into_iter().flatten();
+        if children.any(|child| child.priority > node.priority) {
+            return Err(TreeError::CorruptedTree(format!(
+                "node {position} has a child of higher priority"
+            )));
+        }
+        let expected = size(&node.left)
+            .checked_add(size(&node.right))
+            .and_then(|size| size.checked_add(1));
+        if expected != Some(node.size) {
+            return Err(TreeError::CorruptedTree(format!(
+                "node {position} has a stale subtree size"
+            )));
+        }
+        if keyed {
+            if node.reversed || previous.is_some_and(|previous| *previous >= node.key) {
+                return Err(TreeError::CorruptedTree(format!(
+                    "key of node {position} is out of order"
+                )));
+            }
+            previous = Some(&node.key);
+        }
+    }
+    Ok(())
+}
+
+/// In-order traversal of keyed treaps, which never carry pending reversals.
+struct InOrder<'a, K, V> {
+    /// Nodes whose left subtree has been visited, top of the stack being the next node to yield.
+    stack: Vec<&'a TreapNode<K, V>>,
+    /// First node past the end of the traversal. `None` if the traversal runs to the last node.
+    end: Option<&'a TreapNode<K, V>>,
+}
+
+impl<'a, K, V> InOrder<'a, K, V> {
+    fn new(root: Option<&'a TreapNode<K, V>>) -> Self {
+        let mut iter = Self {
+            stack: Vec::new(),
+            end: None,
+        };
+        iter.descend(root);
+        iter
+    }
+
+    fn descend(&mut self, mut node: Option<&'a TreapNode<K, V>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for InOrder<'a, K, V> {
+    type Item = &'a TreapNode<K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if self.end.is_some_and(|end| std::ptr::eq(end, node)) {
+            self.stack.clear();
+            return None;
+        }
+        self.descend(node.right.as_deref());
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ops::Bound;
+
+    use crate::search::{ImplicitTreap, Treap};
+
+    #[test]
+    fn matches_btree_map() {
+        let mut treap = Treap::new();
+        let mut expected = BTreeMap::new();
+        let mut seed = 3_u32;
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let key = (seed >> 16) % 400;
+            if seed.is_multiple_of(3) {
+                assert_eq!(treap.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(treap.insert(key, seed), expected.insert(key, seed));
+            }
+        }
+
+        assert_eq!(treap.len(), expected.len());
+        assert!(treap.iter().eq(expected.iter()));
+        assert!(treap.range(10..=100).eq(expected.range(10..=100)));
+        assert!(treap.range(..50).eq(expected.range(..50)));
+        assert!(treap.range(350..).eq(expected.range(350..)));
+        let excluded = (Bound::Excluded(20), Bound::Excluded(90));
+        assert!(treap.range(excluded).eq(expected.range(excluded)));
+        assert_eq!(
+            treap
+                .range((Bound::Included(60), Bound::Excluded(40)))
+                .count(),
+            0
+        );
+        for (k, entry) in expected.iter().enumerate() {
+            assert_eq!(treap.kth(k), Some(entry));
+            assert_eq!(treap.rank(entry.0), k);
+        }
+        assert_eq!(treap.kth(expected.len()), None);
+    }
+
+    #[test]
+    fn nearest_keys() {
+        let treap: Treap<i32, ()> = (0..300).step_by(5).map(|key| (key, ())).collect();
+        let expected: BTreeMap<i32, ()> = treap.iter().map(|(key, _)| (*key, ())).collect();
+        for key in -2..305 {
+            assert_eq!(treap.floor(&key), expected.range(..=key).next_back());
+            assert_eq!(treap.ceiling(&key), expected.range(key..).next());
+        }
+        let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+        assert_eq!(treap.closest(&12, distance).map(|(key, _)| *key), Some(10));
+        assert_eq!(treap.closest(&13, distance).map(|(key, _)| *key), Some(15));
+        assert_eq!(Treap::<i32, ()>::new().floor(&0), None);
+    }
+
+    #[test]
+    fn split_and_append() {
+        let mut treap: Treap<u32, u32> = (0..100).map(|key| (key, key * 2)).collect();
+        let mut upper = treap.split_off(&40);
+        assert_eq!(treap.len(), 40);
+        assert_eq!(upper.len(), 60);
+        assert_eq!(treap.last(), Some((&39, &78)));
+        assert_eq!(upper.first(), Some((&40, &80)));
+
+        treap.append(&mut upper);
+        assert!(upper.is_empty());
+        assert!(treap.iter().map(|(k, _)| *k).eq(0..100));
+
+        // Overlapping key ranges fall back to insertion
+        let mut overlapping: Treap<u32, u32> = (50..150).map(|key| (key, 0)).collect();
+        treap.append(&mut overlapping);
+        assert_eq!(treap.len(), 150);
+        assert_eq!(treap.get(&60), Some(&0));
+    }
+
+    #[test]
+    fn implicit_sequence() {
+        let mut sequence: ImplicitTreap<usize> = (0..10).collect();
+        let mut expected: Vec<usize> = (0..10).collect();
+
+        sequence.insert(3, 100);
+        expected.insert(3, 100);
+        assert_eq!(sequence.remove(7), Some(expected.remove(7)));
+        sequence.reverse(2..8);
+        expected[2..8].reverse();
+        sequence.reverse(..5);
+        expected[..5].reverse();
+
+        assert!(sequence.iter().eq(expected.iter()));
+        for (index, value) in expected.iter().enumerate() {
+            assert_eq!(sequence.get(index), Some(value));
+        }
+        assert_eq!(sequence.get(expected.len()), None);
+
+        let tail = sequence.split_off(4);
+        assert!(sequence.iter().eq(expected[..4].iter()));
+        assert!(tail.iter().eq(expected[4..].iter()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let treap: Treap<u32, char> = (0..20).zip('a'..).collect();
+        let json = serde_json::to_string(&treap).expect("Treap is serializable");
+        let deserialized: Treap<u32, char> = serde_json::from_str(&json).expect("Treap is valid");
+        assert!(deserialized.iter().eq(treap.iter()));
+
+        let mut sequence: ImplicitTreap<u32> = (0..20).collect();
+        sequence.reverse(5..15);
+        let json = serde_json::to_string(&sequence).expect("Treap is serializable");
+        let deserialized: ImplicitTreap<u32> = serde_json::from_str(&json).expect("Treap is valid");
+        assert!(deserialized.iter().eq(sequence.iter()));
+
+        let node = |key: u32, priority: u64, size: usize, left: &str| {
+            let links = format!(r#""reversed":false,"left":{left},"right":null"#);
+            format!(r#"{{"key":{key},"value":0,"priority":{priority},"size":{size},{links}}}"#)
+        };
+        for malformed in [
+            node(2, 5, 2, &node(1, 9, 1, "null")),
+            node(2, 5, 3, &node(1, 1, 1, "null")),
+            node(2, 5, 2, &node(3, 1, 1, "null")),
+        ] {
+            let json = format!(r#"{{"root":{malformed},"priorities":1}}"#);
+            assert!(
+                serde_json::from_str::<Treap<u32, u32>>(&json).is_err(),
+                "{json}"
+            );
+        }
+        let valid = format!(
+            r#"{{"root":{},"priorities":1}}"#,
+            node(2, 5, 2, &node(1, 1, 1, "null"))
+        );
+        assert!(serde_json::from_str::<Treap<u32, u32>>(&valid).is_ok());
+        let reversed = valid.replace(r#""reversed":false"#, r#""reversed":true"#);
+        assert!(serde_json::from_str::<Treap<u32, u32>>(&reversed).is_err());
+    }
+}
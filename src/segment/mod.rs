@@ -0,0 +1,531 @@
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+#[cfg(feature = "serde")]
+use crate::tree::TreeError;
+
+/// An associative operation a [SegmentTree] aggregates ranges with, together with a range update type applied
+/// lazily.
+///
+/// Implementations must satisfy `apply(combine(a, b), u, len_a + len_b) == combine(apply(a, u, len_a),
+/// apply(b, u, len_b))` and composing updates must be equivalent to applying them one by one.
+pub trait Operation<T> {
+    /// Range update, e.g. a value added to every element in the range.
+    type Update: Clone;
+
+    /// Neutral element of [Operation::combine], returned for empty ranges.
+    fn identity() -> T;
+
+    fn combine(left: &T, right: &T) -> T;
+
+    /// Applies an `update` to an aggregate of `len` elements.
+    fn apply(aggregate: &T, update: &Self::Update, len: usize) -> T;
+
+    /// Merges two pending updates, `first` having been issued before `second`.
+    fn compose(first: &Self::Update, second: &Self::Update) -> Self::Update;
+}
+
+/// Sum of a range, range updates add a value to every element.
+#[derive(Debug, Copy, Clone)]
+pub struct Sum;
+
+/// Minimum of a range, range updates add a value to every element.
+#[derive(Debug, Copy, Clone)]
+pub struct Min;
+
+/// Maximum of a range, range updates add a value to every element.
+#[derive(Debug, Copy, Clone)]
+pub struct Max;
+
+/// Number of elements of a range as the element type itself, `None` if it doesn't fit.
+trait Length: Sized {
+    fn from_len(len: usize) -> Option<Self>;
+}
+
+macro_rules! integer_length {
+    ($($t:ty),*) => {
+        $(
+            impl Length for $t {
+                fn from_len(len: usize) -> Option<$t> {
+                    <$t>::try_from(len).ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_length {
+    ($($t:ty),*) => {
+        $(
+            impl Length for $t {
+                fn from_len(len: usize) -> Option<$t> {
+                    Some(len as $t)
+                }
+            }
+        )*
+    };
+}
+
+integer_length!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+float_length!(f32, f64);
+
+macro_rules! numeric_operations {
+    ($($t:ty),*) => {
+        $(
+            impl Operation<$t> for Sum {
+                type Update = $t;
+
+                fn identity() -> $t {
+                    0 as $t
+                }
+
+                fn combine(left: &$t, right: &$t) -> $t {
+                    left + right
+                }
+
+                /// # Panics
+                /// If `len` doesn't fit into the element type and the update isn't zero, as the sum overflows.
+                fn apply(aggregate: &$t, update: &$t, len: usize) -> $t {
+                    match <$t>::from_len(len) {
+                        Some(len) => aggregate + update * len,
+                        None if *update == 0 as $t => *aggregate,
+                        None => panic!("Sum of {len} updated elements overflows {}", stringify!($t)),
+                    }
+                }
+
+                fn compose(first: &$t, second: &$t) -> $t {
+                    first + second
+                }
+            }
+
+            impl Operation<$t> for Min {
+                type Update = $t;
+
+                fn identity() -> $t {
+                    <$t>::MAX
+                }
+
+                fn combine(left: &$t, right: &$t) -> $t {
+                    if left < right { *left } else { *right }
+                }
+
+                fn apply(aggregate: &$t, update: &$t, _len: usize) -> $t {
+                    aggregate + update
+                }
+
+                fn compose(first: &$t, second: &$t) -> $t {
+                    first + second
+                }
+            }
+
+            impl Operation<$t> for Max {
+                type Update = $t;
+
+                fn identity() -> $t {
+                    <$t>::MIN
+                }
+
+                fn combine(left: &$t, right: &$t) -> $t {
+                    if left > right { *left } else { *right }
+                }
+
+                fn apply(aggregate: &$t, update: &$t, _len: usize) -> $t {
+                    aggregate + update
+                }
+
+                fn compose(first: &$t, second: &$t) -> $t {
+                    first + second
+                }
+            }
+        )*
+    };
+}
+
+numeric_operations!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// Segment tree answering range aggregate queries in O(log n), with point updates and lazily propagated
+/// range updates. Nodes are stored in the implicit binary layout, children of node `i` being `2i + 1`
+/// and `2i + 2`, with no child indices stored at all.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::segment::{Min, SegmentTree, Sum};
+///         let values = [5, 3, 8, 1, 9];
+///         let mut sums = SegmentTree::<i32, Sum>::new(&values);
+///         assert_eq!(sums.query(1..4), 3 + 8 + 1);
+///
+///         sums.update_range(0..3, 10);
+///         assert_eq!(sums.query(..), 26 + 30);
+///
+///         let mut minimums = SegmentTree::<i32, Min>::new(&values);
+///         minimums.update(3, 7);
+///         assert_eq!(minimums.query(2..=4), 7);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(
+        try_from = "SegmentTreeData<T, Op>",
+        bound(
+            serialize = "T: Serialize, Op::Update: Serialize",
+            deserialize = "T: Deserialize<'de>, Op::Update: Deserialize<'de>"
+        )
+    )
+)]
+pub struct SegmentTree<T, Op: Operation<T>> {
+    len: usize,
+    aggregates: Vec<T>,
+    /// Updates already reflected in the node's aggregate, but not yet pushed to its children.
+    pending: Vec<Option<Op::Update>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    operation: PhantomData<Op>,
+}
+
+impl<T, Op: Operation<T>> SegmentTree<T, Op> {
+    /// Number of elements the tree was built over.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Aggregates of the nodes are values, pending updates are
+    /// structure.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::vec(&self.aggregates, true)
+            + MemoryFootprint::vec(&self.pending, false)
+    }
+}
+
+impl<T: Clone, Op: Operation<T>> SegmentTree<T, Op> {
+    /// Builds a segment tree over given `values` in O(n).
+    pub fn new(values: &[T]) -> Self {
+        let nodes = if values.is_empty() {
+            0
+        } else {
+            4 * values.len()
+        };
+        let mut tree = Self {
+            len: values.len(),
+            aggregates: vec![Op::identity(); nodes],
+            pending: vec![None; nodes],
+            operation: PhantomData,
+        };
+        if !values.is_empty() {
+            tree.build(0, 0, values.len() - 1, values);
+        }
+        tree
+    }
+
+    /// Aggregate of all elements in `range`. Returns [Operation::identity] for empty ranges.
+    ///
+    /// # Panics
+    /// If the range ends past the end of the tree.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        match self.bounds(range) {
+            Some((from, to)) => self.query_node(0, 0, self.len - 1, from, to),
+            None => Op::identity(),
+        }
+    }
+
+    /// Replaces element at `index` with `value`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn update(&mut self, index: usize, value: T) {
+        assert!(index < self.len, "Index {index} out of bounds {}", self.len);
+        self.update_node(0, 0, self.len - 1, index, value);
+    }
+
+    /// Applies `update` to every element in `range`, in O(log n).
+    ///
+    /// # Panics
+    /// If the range ends past the end of the tree.
+    pub fn update_range<R: RangeBounds<usize>>(&mut self, range: R, update: Op::Update) {
+        if let Some((from, to)) = self.bounds(range) {
+            self.update_range_node(0, 0, self.len - 1, from, to, &update);
+        }
+    }
+
+    /// Inclusive bounds of a non-empty range. An inclusive end of `usize::MAX` is clamped to the length, so
+    /// `..=usize::MAX` spans the whole tree.
+    fn bounds<R: RangeBounds<usize>>(&self, range: R) -> Option<(usize, usize)> {
+        let from = match range.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) => from.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let to = match range.end_bound() {
+            Bound::Included(&to) => to.checked_add(1).unwrap_or(self.len),
+            Bound::Excluded(&to) => to,
+            Bound::Unbounded => self.len,
+        };
+        assert!(to <= self.len, "Range end {to} out of bounds {}", self.len);
+        (from < to).then(|| (from, to - 1))
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, values: &[T]) {
+        if lo == hi {
+            self.aggregates[node] = values[lo].clone();
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.build(2 * node + 1, lo, mid, values);
+        self.build(2 * node + 2, mid + 1, hi, values);
+        self.aggregates[node] = Op::combine(
+            &self.aggregates[2 * node + 1],
+            &self.aggregates[2 * node + 2],
+        );
+    }
+
+    fn query_node(&self, node: usize, lo: usize, hi: usize, from: usize, to: usize) -> T {
+        if from <= lo && hi <= to {
+            return self.aggregates[node].clone();
+        }
+        let mid = lo + (hi - lo) / 2;
+        let combined = if to <= mid {
+            self.query_node(2 * node + 1, lo, mid, from, to)
+        } else if from > mid {
+            self.query_node(2 * node + 2, mid + 1, hi, from, to)
+        } else {
+            Op::combine(
+                &self.query_node(2 * node + 1, lo, mid, from, to),
+                &self.query_node(2 * node + 2, mid + 1, hi, from, to),
+            )
+        };
+
+        // Children don't reflect updates still pending in this node
+        match &self.pending[node] {
+            Some(update) => {
+                let overlap = to.min(hi) - from.max(lo) + 1;
+                Op::apply(&combined, update, overlap)
+            }
+            None => combined,
+        }
+    }
+
+    fn update_node(&mut self, node: usize, lo: usize, hi: usize, index: usize, value: T) {
+        if lo == hi {
+            self.aggregates[node] = value;
+            return;
+        }
+        self.push_down(node, lo, hi);
+        let mid = lo + (hi - lo) / 2;
+        if index <= mid {
+            self.update_node(2 * node + 1, lo, mid, index, value);
+        } else {
+            self.update_node(2 * node + 2, mid + 1, hi, index, value);
+        }
+        self.aggregates[node] = Op::combine(
+            &self.aggregates[2 * node + 1],
+            &self.aggregates[2 * node + 2],
+        );
+    }
+
+    fn update_range_node(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        from: usize,
+        to: usize,
+        update: &Op::Update,
+    ) {
+        if to < lo || hi < from {
+            return;
+        }
+        if from <= lo && hi <= to {
+            self.apply_to_node(node, lo, hi, update);
+            return;
+        }
+        self.push_down(node, lo, hi);
+        let mid = lo + (hi - lo) / 2;
+        self.update_range_node(2 * node + 1, lo, mid, from, to, update);
+        self.update_range_node(2 * node + 2, mid + 1, hi, from, to, update);
+        self.aggregates[node] = Op::combine(
+            &self.aggregates[2 * node + 1],
+            &self.aggregates[2 * node + 2],
+        );
+    }
+
+    fn apply_to_node(&mut self, node: usize, lo: usize, hi: usize, update: &Op::Update) {
+        self.aggregates[node] = Op::apply(&self.aggregates[node], update, hi - lo + 1);
+        if lo != hi {
+            self.pending[node] = Some(match &self.pending[node] {
+                Some(previous) => Op::compose(previous, update),
+                None => update.clone(),
+            });
+        }
+    }
+
+    fn push_down(&mut self, node: usize, lo: usize, hi: usize) {
+        if let Some(update) = self.pending[node].take() {
+            let mid = lo + (hi - lo) / 2;
+            self.apply_to_node(2 * node + 1, lo, mid, &update);
+            self.apply_to_node(2 * node + 2, mid + 1, hi, &update);
+        }
+    }
+}
+
+/// Serialized form of a [SegmentTree], validated before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>, Op::Update: Deserialize<'de>"))]
+struct SegmentTreeData<T, Op: Operation<T>> {
+    len: usize,
+    aggregates: Vec<T>,
+    pending: Vec<Option<Op::Update>>,
+}
+
+#[cfg(feature = "serde")]
+impl<T, Op: Operation<T>> TryFrom<SegmentTreeData<T, Op>> for SegmentTree<T, Op> {
+    type Error = TreeError;
+
+    fn try_from(data: SegmentTreeData<T, Op>) -> Result<Self, Self::Error> {
+        let nodes = data
+            .len
+            .checked_mul(4)
+            .ok_or_else(|| TreeError::CorruptedTree(format!("length {} is too large", data.len)))?;
+        for found in [data.aggregates.len(), data.pending.len()] {
+            if found != nodes {
+                return Err(TreeError::LengthMismatch {
+                    expected: nodes,
+                    found,
+                });
+            }
+        }
+        Ok(Self {
+            len: data.len,
+            aggregates: data.aggregates,
+            pending: data.pending,
+            operation: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::segment::{Max, Min, SegmentTree, Sum};
+
+    #[test]
+    fn query() {
+        let values = [2, -1, 4, 7, 0, 3, -5, 6];
+        let tree = SegmentTree::<i64, Sum>::new(&values);
+        for from in 0..values.len() {
+            for to in from..=values.len() {
+                assert_eq!(tree.query(from..to), values[from..to].iter().sum::<i64>());
+            }
+        }
+        assert_eq!(tree.query(..), 16);
+        assert_eq!(tree.query(3..3), 0);
+    }
+
+    #[test]
+    fn point_and_range_updates() {
+        let mut values = vec![4_i32, 8, 15, 16, 23, 42, 1];
+        let mut sums = SegmentTree::<i32, Sum>::new(&values);
+        let mut minimums = SegmentTree::<i32, Min>::new(&values);
+        let mut maximums = SegmentTree::<i32, Max>::new(&values);
+
+        let updates = [(1, 5, 3), (0, 2, -10), (3, 7, 2), (2, 3, 100)];
+        for (from, to, delta) in updates {
+            sums.update_range(from..to, delta);
+            minimums.update_range(from..to, delta);
+            maximums.update_range(from..to, delta);
+            values[from..to]
+                .iter_mut()
+                .for_each(|value| *value += delta);
+
+            for a in 0..values.len() {
+                for b in a + 1..=values.len() {
+                    let expected = &values[a..b];
+                    assert_eq!(sums.query(a..b), expected.iter().sum::<i32>());
+                    assert_eq!(minimums.query(a..b), *expected.iter().min().unwrap());
+                    assert_eq!(maximums.query(a..b), *expected.iter().max().unwrap());
+                }
+            }
+        }
+
+        sums.update(4, 0);
+        values[4] = 0;
+        assert_eq!(sums.query(..), values.iter().sum::<i32>());
+        assert_eq!(sums.query(4..=4), 0);
+    }
+
+    #[test]
+    fn empty() {
+        let tree = SegmentTree::<f64, Sum>::new(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.query(..), 0.0);
+    }
+
+    #[test]
+    fn unbounded_inclusive_end() {
+        let tree = SegmentTree::<u8, Max>::new(&[1, 3, 2]);
+        assert_eq!(tree.query(..=usize::MAX), 3);
+        assert_eq!(tree.query(2..=usize::MAX), 2);
+        assert_eq!(
+            tree.query((Bound::Excluded(usize::MAX), Bound::Unbounded)),
+            u8::MIN
+        );
+    }
+
+    #[test]
+    fn sum_over_narrow_type() {
+        let mut tree = SegmentTree::<u8, Sum>::new(&[0; 300]);
+        tree.update_range(.., 0);
+        tree.update_range(100..200, 2);
+        assert_eq!(tree.query(..), 200);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sum_overflow() {
+        let mut tree = SegmentTree::<u8, Sum>::new(&[0; 300]);
+        tree.update_range(.., 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let mut tree = SegmentTree::<i32, Sum>::new(&[1, 2, 3]);
+        tree.update_range(0..2, 5);
+        let json = serde_json::to_string(&tree).expect("Tree is serializable");
+        let deserialized: SegmentTree<i32, Sum> =
+            serde_json::from_str(&json).expect("Tree is valid");
+        assert_eq!(deserialized.query(..), tree.query(..));
+        assert_eq!(deserialized.query(1..), tree.query(1..));
+
+        for malformed in [
+            r#"{"len":2,"aggregates":[3,1,2,0,0,0,0,0],"pending":[null]}"#,
+            r#"{"len":1,"aggregates":[3,1,2,0,0,0,0,0],"pending":[null,null,null,null]}"#,
+            r#"{"len":0,"aggregates":[0],"pending":[]}"#,
+            r#"{"len":18446744073709551615,"aggregates":[],"pending":[]}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<SegmentTree<i32, Sum>>(malformed).is_err(),
+                "{malformed}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        let tree = SegmentTree::<u8, Max>::new(&[1, 2, 3]);
+        tree.query(0..4);
+    }
+}
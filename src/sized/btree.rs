@@ -1,18 +1,32 @@
-use std::ops::Index;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::ops::{Bound, Index, IndexMut, RangeBounds};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::algo;
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::footprint::MemoryFootprint;
+#[cfg(feature = "bytemuck")]
+use crate::sized::read_bytes;
 use crate::sized::structs::Array;
-
-const LEAF_NODE_MARK: isize = -1;
-const ROOT_NODE: isize = 0;
+use crate::sized::{assert_links, check_links, sift_down, sift_up};
+use crate::storage::{self, SliceBTree, StoredBTree};
+use crate::tree::{NodeId, TreeError};
 
 /// Statically sized binary tree representation for fast traversal, suitable for dense trees.
 /// Special implementation for binary tree is offered for faster traversal times over the generalized
-/// `k` tree, where an array of arrays and indices checks happen.
-#[derive(Eq, PartialEq, Debug)]
+/// `k` tree, where an array of arrays and indices checks happen. `Send` and `Sync` whenever `T` is.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "BTreeData<T, N>",
+        bound(deserialize = "T: Deserialize<'de>")
+    )
+)]
 pub struct BTree<T, const N: usize> {
     pub l_nodes: Array<isize, N>,
     pub r_nodes: Array<isize, N>,
@@ -20,7 +34,7 @@ pub struct BTree<T, const N: usize> {
 }
 
 // Node's children ids
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
 pub struct Children {
     pub left: isize,
     pub right: isize,
@@ -47,17 +61,429 @@ impl<T, const N: usize> BTree<T, N> {
     ///         let values = [10, 51, 36, 90, 32, 16, 5];
     ///         let tree = BTree::new(left, right, values);
     /// ```
-    pub fn new(l_nodes: [isize; N], r_nodes: [isize; N], values: [T; N]) -> Self {
+    pub const fn new(l_nodes: [isize; N], r_nodes: [isize; N], values: [T; N]) -> Self {
         Self {
-            l_nodes: l_nodes.into(),
-            r_nodes: r_nodes.into(),
-            values: values.into(),
+            l_nodes: Array::wrap(l_nodes),
+            r_nodes: Array::wrap(r_nodes),
+            values: Array::wrap(values),
+        }
+    }
+
+    /// Constructs a new tree in const contexts, checking links like [BTree::try_new]. Panics on invalid links,
+    /// which fails the compilation when building a `const` or a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         static LOOKUP: BTree<char, 3> = BTree::new_checked([1, -1, -1], [2, -1, -1], ['b', 'a', 'c']);
+    ///
+    ///         assert_eq!(LOOKUP[LOOKUP.children(0).left as usize], 'a');
+    ///         assert!(LOOKUP.is_leaf_node(2));
+    /// ```
+    pub const fn new_checked(l_nodes: [isize; N], r_nodes: [isize; N], values: [T; N]) -> Self {
+        assert_links(&[l_nodes, r_nodes]);
+        Self::new(l_nodes, r_nodes, values)
+    }
+
+    /// Constructs a new tree from array representation, checking all child indices are in bounds, the root isn't
+    /// a child of any node and no node has more than one parent. Walks from the root therefore always terminate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         use treesome::tree::TreeError;
+    ///         let values = [10, 51, 36];
+    ///
+    ///         assert!(BTree::try_new([1, -1, -1], [2, -1, -1], values).is_ok());
+    ///         let out_of_bounds = BTree::try_new([1, -1, 3], [2, -1, -1], values);
+    ///         assert!(matches!(out_of_bounds, Err(TreeError::IndexOutOfBounds { node: 2, index: 3 })));
+    ///         let shared_child = BTree::try_new([1, 2, -1], [2, -1, -1], values);
+    ///         assert!(matches!(shared_child, Err(TreeError::MultipleParents { node: 2 })));
+    /// ```
+    pub fn try_new(
+        l_nodes: [isize; N],
+        r_nodes: [isize; N],
+        values: [T; N],
+    ) -> Result<Self, TreeError> {
+        check_links(N, |node| [l_nodes[node], r_nodes[node]])?;
+        Ok(Self::new(l_nodes, r_nodes, values))
+    }
+
+    /// Builds a Cartesian tree of `values` in O(N): a min-heap ordered binary tree whose in-order
+    /// traversal yields `values` in their original order. Equal values keep earlier ones as ancestors.
+    /// Node ids are assigned in pre-order, so the minimum ends up in the root node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::cartesian([9, 3, 7, 1, 8, 12, 10]);
+    ///
+    ///         assert_eq!(tree[0], 1); // Minimum is the root
+    ///         assert_eq!(tree[tree.children(0).left as usize], 3);
+    ///         assert_eq!(tree[tree.children(0).right as usize], 8);
+    /// ```
+    pub fn cartesian(values: [T; N]) -> Self
+    where
+        T: Ord,
+    {
+        // Links between original positions, built with a stack of the rightmost path
+        let mut left = [LEAF_NODE; N];
+        let mut right = [LEAF_NODE; N];
+        let mut rightmost_path: Vec<usize> = Vec::new();
+        for position in 0..N {
+            let mut last_popped = None;
+            while let Some(&top) = rightmost_path.last() {
+                if values[top] <= values[position] {
+                    break;
+                }
+                last_popped = rightmost_path.pop();
+            }
+            if let Some(popped) = last_popped {
+                left[position] = popped as isize;
+            }
+            if let Some(&top) = rightmost_path.last() {
+                right[top] = position as isize;
+            }
+            rightmost_path.push(position);
+        }
+
+        // Renumber nodes in pre-order
+        let mut preorder = Vec::with_capacity(N);
+        let mut stack: Vec<usize> = rightmost_path.first().copied().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            preorder.push(node);
+            for child in [right[node], left[node]] {
+                if child != LEAF_NODE {
+                    stack.push(child as usize);
+                }
+            }
+        }
+        let mut new_ids = [0_usize; N];
+        for (new_id, &old_id) in preorder.iter().enumerate() {
+            new_ids[old_id] = new_id;
+        }
+
+        let relink = |link: isize| {
+            if link == LEAF_NODE {
+                LEAF_NODE
+            } else {
+                new_ids[link as usize] as isize
+            }
+        };
+        let l_nodes: [isize; N] = std::array::from_fn(|new_id| relink(left[preorder[new_id]]));
+        let r_nodes: [isize; N] = std::array::from_fn(|new_id| relink(right[preorder[new_id]]));
+        let mut slots: Vec<Option<T>> = values.into_iter().map(Some).collect();
+        let values: [T; N] = std::array::from_fn(|new_id| {
+            slots[preorder[new_id]]
+                .take()
+                .expect("Every position is visited exactly once")
+        });
+
+        Self::new(l_nodes, r_nodes, values)
+    }
+
+    /// Copies the tree into the van Emde Boas layout: the top half of the levels is laid out recursively first,
+    /// followed by every subtree hanging below it, each laid out the same way. A root-to-leaf walk then touches
+    /// O(log<sub>B</sub> N) cache lines for any line size B, which speeds up lookups in large static search trees.
+    ///
+    /// Links stay explicit, so [Walker] and [Walker::descend_while] navigate the copy unchanged, node ids are
+    /// the only difference. Nodes not reachable from the root are placed last, in their original order.
+    /// Fails like [BTree::try_new] for trees that aren't tree-shaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let left = [1, 3, 5, 7, 9, 11, 13, -1, -1, -1, -1, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, 8, 10, 12, 14, -1, -1, -1, -1, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [8, 4, 12, 2, 6, 10, 14, 1, 3, 5, 7, 9, 11, 13, 15]);
+    ///
+    ///         let veb = tree.to_veb_layout().expect("Tree is tree-shaped");
+    ///         assert_eq!(*veb.values, [8, 4, 12, 2, 1, 3, 6, 5, 7, 10, 9, 11, 14, 13, 15]);
+    ///         assert_eq!(veb.children(0).left, 1);
+    ///         assert_eq!(veb.children(1).left, 3);
+    /// ```
+    pub fn to_veb_layout(&self) -> Result<Self, TreeError>
+    where
+        T: Clone,
+    {
+        check_links(N, |node| [self.l_nodes[node], self.r_nodes[node]])?;
+        let mut order = Vec::with_capacity(N);
+        if N > 0 {
+            self.veb_order(ROOT_NODE as usize, self.height(), &mut order);
+        }
+        // Scratch space lives on the heap, large trees would overflow the stack
+        let mut visited = vec![false; N];
+        for &node in &order {
+            visited[node] = true;
+        }
+        order.extend((0..N).filter(|&node| !visited[node]));
+
+        let mut new_ids = vec![0_usize; N];
+        for (new_id, &old_id) in order.iter().enumerate() {
+            new_ids[old_id] = new_id;
+        }
+        let relink = |link: isize| {
+            if link == LEAF_NODE {
+                LEAF_NODE
+            } else {
+                new_ids[link as usize] as isize
+            }
+        };
+        Ok(Self::new(
+            std::array::from_fn(|new_id| relink(self.l_nodes[order[new_id]])),
+            std::array::from_fn(|new_id| relink(self.r_nodes[order[new_id]])),
+            std::array::from_fn(|new_id| self.values[order[new_id]].clone()),
+        ))
+    }
+
+    /// Rebuilds the tree height-balanced, keeping the in-order of nodes reachable from the root. A skewed binary
+    /// search tree, e.g. one imported from sorted data, becomes one of minimal height with O(log N) lookups.
+    ///
+    /// The middle node of the in-order becomes the root, the middles of both halves its children and so on. Nodes
+    /// are numbered level by level from the root, nodes not reachable from the root are placed last, in their
+    /// original order and without children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let skewed = BTree::new([-1, -1, -1, -1], [1, 2, 3, -1], [1, 2, 3, 4]);
+    ///
+    ///         let balanced = skewed.balanced();
+    ///         assert_eq!(*balanced.values, [3, 2, 4, 1]);
+    ///         assert_eq!(*balanced.l_nodes, [1, 3, -1, -1]);
+    ///         assert_eq!(*balanced.r_nodes, [2, -1, -1, -1]);
+    ///         assert_eq!(balanced.to_sorted_vec(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn balanced(self) -> Self {
+        let order: Vec<usize> = self.inorder().collect();
+        let mut left = [LEAF_NODE; N];
+        let mut right = [LEAF_NODE; N];
+        // Old ids by new id, in-order ranges waiting for their middle are numbered in the order of the queue
+        let mut layout = Vec::with_capacity(N);
+        let mut ranges = VecDeque::new();
+        if !order.is_empty() {
+            ranges.push_back((0, order.len()));
+        }
+        while let Some((start, end)) = ranges.pop_front() {
+            let new_id = layout.len();
+            let middle = start + (end - start) / 2;
+            layout.push(order[middle]);
+            for (links, range) in [
+                (&mut left, (start, middle)),
+                (&mut right, (middle + 1, end)),
+            ] {
+                if range.0 < range.1 {
+                    links[new_id] = (new_id + 1 + ranges.len()) as isize;
+                    ranges.push_back(range);
+                }
+            }
+        }
+        let mut reachable = [false; N];
+        for &node in &order {
+            reachable[node] = true;
+        }
+        layout.extend((0..N).filter(|&node| !reachable[node]));
+
+        let mut slots: Vec<Option<T>> = self.values.into_inner().into_iter().map(Some).collect();
+        let values: [T; N] = std::array::from_fn(|new_id| {
+            slots[layout[new_id]]
+                .take()
+                .expect("Every node is placed exactly once")
+        });
+        Self::new(left, right, values)
+    }
+
+    /// Moves the tree into one with capacity for `N2` nodes. Node ids are kept, the extra nodes are detached
+    /// leaves with default values.
+    ///
+    /// # Panics
+    /// If `N2 < N`, see [BTree::shrink] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::new([1, -1], [-1, -1], ['a', 'b']);
+    ///
+    ///         let mut larger: BTree<char, 3> = tree.clone().resize();
+    ///         assert_eq!(*larger.values, ['a', 'b', '\0']);
+    ///         assert_eq!(larger.clone().shrink(), Ok(tree));
+    ///
+    ///         larger.r_nodes[0] = 2;
+    ///         assert!(larger.shrink::<2>().is_err());
+    /// ```
+    pub fn resize<const N2: usize>(self) -> BTree<T, N2>
+    where
+        T: Default,
+    {
+        assert!(N2 >= N, "Resizing a tree of {N} nodes to {N2}");
+        BTree {
+            l_nodes: self.l_nodes.resized(|| LEAF_NODE),
+            r_nodes: self.r_nodes.resized(|| LEAF_NODE),
+            values: self.values.resized(T::default),
+        }
+    }
+
+    /// Moves the first `N2` nodes into a tree of that capacity, dropping the rest. Node ids are kept.
+    ///
+    /// Fails like [BTree::try_new] if the kept nodes don't form a valid tree on their own, in particular with
+    /// [TreeError::IndexOutOfBounds] if any of them links to a dropped node. Nodes reachable from the root are
+    /// therefore never dropped.
+    ///
+    /// # Panics
+    /// If `N2 > N`, see [BTree::resize] instead.
+    pub fn shrink<const N2: usize>(self) -> Result<BTree<T, N2>, TreeError> {
+        assert!(N2 <= N, "Shrinking a tree of {N} nodes to {N2}");
+        check_links(N2, |node| [self.l_nodes[node], self.r_nodes[node]])?;
+        Ok(BTree {
+            l_nodes: self.l_nodes.truncated(),
+            r_nodes: self.r_nodes.truncated(),
+            values: self.values.truncated(),
+        })
+    }
+
+    /// Moves the value of `node_id` up while it's greater than its parent's, restoring the max-heap order after
+    /// the value has grown. Values move between nodes, links stay. Returns the node the value ends at.
+    ///
+    /// Parents are those on the path from the root, the value of a node unreachable from the root stays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let mut tree = BTree::new([1, 3, -1, -1, -1], [2, 4, -1, -1, -1], [3, 9, 4, 1, 7]);
+    ///         assert!(!tree.is_heap());
+    ///
+    ///         tree.heapify();
+    ///         assert_eq!(*tree.values, [9, 7, 4, 1, 3]);
+    ///         tree[3] = 10;
+    ///         assert_eq!(tree.sift_up(3), 0);
+    ///         tree[0] = 0;
+    ///         assert_eq!(tree.sift_down(0), 3);
+    ///         assert!(tree.is_heap());
+    /// ```
+    ///
+    /// Panics if `node_id` is out of bounds.
+    pub fn sift_up(&mut self, node_id: usize) -> usize
+    where
+        T: Ord,
+    {
+        assert!(node_id < N, "Node {node_id} is out of bounds");
+        let path = self.path_to(node_id).unwrap_or_default();
+        sift_up(&mut *self.values, node_id, |node| {
+            let depth = path.iter().position(|&ancestor| ancestor == node)?;
+            path.get(depth.checked_sub(1)?).copied()
+        })
+    }
+
+    /// Moves the value of `node_id` down while it's smaller than one of its children, always swapping with the
+    /// greater child, restoring the max-heap order after the value has shrunk. Values move between nodes, links
+    /// stay. Returns the node the value ends at.
+    ///
+    /// Panics if `node_id` is out of bounds.
+    pub fn sift_down(&mut self, node_id: usize) -> usize
+    where
+        T: Ord,
+    {
+        assert!(node_id < N, "Node {node_id} is out of bounds");
+        let (l_nodes, r_nodes) = (&self.l_nodes, &self.r_nodes);
+        let child =
+            |link: isize| (link > ROOT_NODE && (link as usize) < N).then_some(link as usize);
+        sift_down(&mut *self.values, node_id, |node| {
+            [child(l_nodes[node]), child(r_nodes[node])]
+        })
+    }
+
+    /// Reorders values of nodes reachable from the root into a max-heap, the greatest value ending at the root.
+    /// Works for any shape of the tree, in O(N) for balanced ones. Links stay unchanged.
+    pub fn heapify(&mut self)
+    where
+        T: Ord,
+    {
+        let mut order = self.level_order();
+        order.reverse();
+        for node in order {
+            self.sift_down(node);
+        }
+    }
+
+    /// True if no value of a node reachable from the root is greater than its parent's.
+    pub fn is_heap(&self) -> bool
+    where
+        T: Ord,
+    {
+        self.level_order().into_iter().all(|node| {
+            self.child_ids(node)
+                .all(|child| self.values[child] <= self.values[node])
+        })
+    }
+
+    /// Ids of nodes reachable from the root, level by level, each visited once even if links form a cycle.
+    fn level_order(&self) -> Vec<usize> {
+        let mut visited = [false; N];
+        let mut order: Vec<usize> = (0..N.min(1)).collect();
+        let mut next = 0;
+        while let Some(&node) = order.get(next) {
+            visited[node] = true;
+            next += 1;
+            for child in self.child_ids(node) {
+                if !std::mem::replace(&mut visited[child], true) {
+                    order.push(child);
+                }
+            }
+        }
+        order
+    }
+
+    /// Appends nodes of the subtree of `root` cut to `height` levels to `order`, in the van Emde Boas layout.
+    fn veb_order(&self, root: usize, height: usize, order: &mut Vec<usize>) {
+        if height <= 1 {
+            order.push(root);
+            return;
+        }
+        let top = height / 2;
+        self.veb_order(root, top, order);
+        let mut frontier = vec![root];
+        for _ in 0..top {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|node| [self.l_nodes[node], self.r_nodes[node]])
+                .filter(|&child| child != LEAF_NODE)
+                .map(|child| child as usize)
+                .collect();
+        }
+        for subtree in frontier {
+            self.veb_order(subtree, height - top, order);
         }
     }
 
+    /// Number of levels below and including the root, the tree must be tree-shaped.
+    fn height(&self) -> usize {
+        let mut height = 0;
+        let mut level = vec![ROOT_NODE as usize];
+        while !level.is_empty() {
+            height += 1;
+            level = level
+                .into_iter()
+                .flat_map(|node| [self.l_nodes[node], self.r_nodes[node]])
+                .filter(|&child| child != LEAF_NODE)
+                .map(|child| child as usize)
+                .collect();
+        }
+        height
+    }
+
     /// True if given `node_id` is a leaf node (no children), false otherwise.
-    pub fn is_leaf_node(&self, node_id: usize) -> bool {
-        self.l_nodes[node_id] == LEAF_NODE_MARK && self.r_nodes[node_id] == LEAF_NODE_MARK
+    ///
+    /// Panics if `node_id` is out of bounds, see [BTree::try_is_leaf] for a checked variant.
+    pub const fn is_leaf_node(&self, node_id: usize) -> bool {
+        self.l_nodes.as_array()[node_id] == LEAF_NODE
+            && self.r_nodes.as_array()[node_id] == LEAF_NODE
     }
 
     /// Returns left and right child of a node. The value of `-1` means no child in that direction.
@@ -73,17 +499,46 @@ impl<T, const N: usize> BTree<T, N> {
     ///         assert_eq!(tree.children(0), (1, 2).into());
     ///
     /// ```
-    pub fn children(&self, node_id: usize) -> Children {
+    ///
+    /// Panics if `node_id` is out of bounds, see [BTree::try_children] for a checked variant.
+    pub const fn children(&self, node_id: usize) -> Children {
         Children {
-            left: self.l_nodes[node_id],
-            right: self.r_nodes[node_id],
+            left: self.l_nodes.as_array()[node_id],
+            right: self.r_nodes.as_array()[node_id],
+        }
+    }
+
+    /// Same as [BTree::children], without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than `N`. Children returned for nodes of a tree built by [BTree::try_new] satisfy
+    /// this.
+    pub unsafe fn children_unchecked(&self, node_id: usize) -> Children {
+        debug_assert!(node_id < N, "Node {node_id} is out of bounds");
+        // SAFETY: The caller guarantees `node_id` is in bounds of all arrays of length `N`
+        unsafe {
+            Children {
+                left: *self.l_nodes.get_unchecked(node_id),
+                right: *self.r_nodes.get_unchecked(node_id),
+            }
         }
     }
 
+    /// Value of `node_id`, without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than `N`.
+    pub unsafe fn value_unchecked(&self, node_id: usize) -> &T {
+        debug_assert!(node_id < N, "Node {node_id} is out of bounds");
+        // SAFETY: The caller guarantees `node_id` is in bounds
+        unsafe { self.values.get_unchecked(node_id) }
+    }
+
     /// Return's node_id of its parent, if it exists.
     /// If there's no parent (root node, non-existent node_id) for given node, `None` is returned.
     /// Computational complexity of the lookup is O(1), as the formula used calculates the exact
-    /// position of the parent node.
+    /// position of the parent node. The formula assumes the complete-tree layout, use [BTree::linked_parent]
+    /// for trees in other layouts (e.g. [BTree::cartesian]).
     /// # Examples
     ///
     /// ```
@@ -101,89 +556,1138 @@ impl<T, const N: usize> BTree<T, N> {
         if node_id <= ROOT_NODE || node_id as usize >= self.values.len() {
             return None; // Root node doesn't have a parent.
         };
+        Some((node_id - 1) / 2)
+    }
+
+    /// Node actually linking to `node`, whatever the layout. `None` for the root, unlinked nodes and nodes that
+    /// don't exist. The complete-tree candidate is checked first in O(1), other layouts fall back to a linear scan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         use treesome::tree::NodeId;
+    ///         // A right-leaning chain 0 -> 2 -> 1, outside the complete-tree layout
+    ///         let tree = BTree::new([-1, -1, -1], [2, -1, 1], [10, 51, 36]);
+    ///         assert_eq!(tree.parent(1), Some(0));
+    ///         assert_eq!(tree.linked_parent(NodeId::new(1)), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.linked_parent(NodeId::ROOT), None);
+    /// ```
+    pub fn linked_parent(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        storage::linked_parent(self.l_nodes.as_slice(), self.r_nodes.as_slice(), node)
+            .map(NodeId::new)
+    }
+
+    /// Borrows the tree as a [SliceBTree], sharing the algorithms of trees kept in other
+    /// [TreeStorage](storage::TreeStorage). Links aren't checked again, invalid ones are skipped like in traversals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::new([1, -1, -1], [2, -1, -1], [20, 10, 30]);
+    ///
+    ///         let copy = tree.as_stored().to_vec_tree();
+    ///         assert_eq!(copy.values(), &[20, 10, 30]);
+    ///         assert!(copy.iter_sorted().eq(tree.iter_sorted()));
+    /// ```
+    pub fn as_stored(&self) -> SliceBTree<'_, T> {
+        StoredBTree::from_parts_unchecked(
+            self.l_nodes.as_slice(),
+            self.r_nodes.as_slice(),
+            self.values.as_slice(),
+        )
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Links and values live inline in the tree itself.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint::inline::<Self, T>(N, 2 * N * size_of::<isize>())
+    }
+
+    /// Number of children of `node_id`, not counting [LEAF_NODE] placeholders and links
+    /// out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::new([1, 3, -1, -1], [2, -1, -1, -1], [1, 2, 3, 4]);
+    ///         assert_eq!(tree.child_count(0), 2);
+    ///         assert_eq!(tree.child_count(1), 1);
+    ///         assert_eq!(tree.max_degree(), 2);
+    ///         assert_eq!(tree.degree_histogram(), vec![2, 1, 1]);
+    /// ```
+    ///
+    /// Panics if `node_id` is out of bounds.
+    pub fn child_count(&self, node_id: usize) -> usize {
+        assert!(node_id < N, "Node {node_id} is out of bounds");
+        self.child_ids(node_id).count()
+    }
+
+    /// Highest number of children of a node, zero for an empty tree. See [algo::max_degree].
+    pub fn max_degree(&self) -> usize {
+        algo::max_degree(self)
+    }
+
+    /// Number of nodes by their number of children, see [algo::degree_histogram].
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        algo::degree_histogram(self)
+    }
+
+    /// Returns the value of `node`, or `None` if the node doesn't exist.
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.values.get(node.index())
+    }
+
+    /// Values of all nodes, in order of node ids.
+    pub fn values(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    /// Checked variant of [BTree::children]. Returns the left and right child, `None` where there's no child,
+    /// and [TreeError::NodeOutOfBounds] instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         use treesome::tree::{NodeId, TreeError};
+    ///         let tree = BTree::new([1, -1, -1], [-1, -1, -1], [10, 51, 36]);
+    ///         assert_eq!(tree.try_children(NodeId::ROOT), Ok((Some(NodeId::new(1)), None)));
+    ///         assert_eq!(tree.try_children(NodeId::new(3)), Err(TreeError::NodeOutOfBounds { node: 3 }));
+    /// ```
+    pub fn try_children(
+        &self,
+        node: NodeId,
+    ) -> Result<(Option<NodeId>, Option<NodeId>), TreeError> {
+        let node = self.check_node(node)?;
+        Ok((
+            NodeId::from_link(self.l_nodes[node]),
+            NodeId::from_link(self.r_nodes[node]),
+        ))
+    }
+
+    /// Checked variant of [BTree::is_leaf_node]. Returns [TreeError::NodeOutOfBounds] instead of panicking.
+    pub fn try_is_leaf(&self, node: NodeId) -> Result<bool, TreeError> {
+        let node = self.check_node(node)?;
+        Ok(self.is_leaf_node(node))
+    }
+
+    /// Checked variant of [BTree::linked_parent]. Returns `Ok(None)` for the root and unlinked nodes, and
+    /// [TreeError::NodeOutOfBounds] for nodes that don't exist.
+    pub fn try_parent(&self, node: NodeId) -> Result<Option<NodeId>, TreeError> {
+        self.check_node(node)?;
+        Ok(self.linked_parent(node))
+    }
+
+    /// First existing child of `node`, the right one if there's no left child. `None` for leaves and nodes
+    /// that don't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         use treesome::tree::NodeId;
+    ///         let tree = BTree::new([-1, -1, -1], [1, -1, -1], [10, 51, 36]);
+    ///         assert_eq!(tree.first_child(NodeId::ROOT), Some(NodeId::new(1)));
+    ///         assert_eq!(tree.last_child(NodeId::ROOT), Some(NodeId::new(1)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 1), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.nth_child(node, 0)
+    }
+
+    /// Last existing child of `node`, the left one if there's no right child. `None` for leaves and nodes
+    /// that don't exist.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).last().map(NodeId::new)
+    }
+
+    /// Existing child of `node` at position `k`, counting from zero and skipping
+    /// [LEAF_NODE] placeholders. `None` if the node has at most `k` children or doesn't
+    /// exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).nth(k).map(NodeId::new)
+    }
+
+    /// Valid children of `node_id`, left first, skipping [LEAF_NODE] placeholders and links
+    /// out of bounds.
+    fn child_ids(&self, node_id: usize) -> impl Iterator<Item = usize> {
+        [self.l_nodes[node_id], self.r_nodes[node_id]]
+            .into_iter()
+            .filter(|&child| child > ROOT_NODE && (child as usize) < N)
+            .map(|child| child as usize)
+    }
+
+    fn check_node(&self, node: NodeId) -> Result<usize, TreeError> {
+        if node.index() < N {
+            Ok(node.index())
+        } else {
+            Err(TreeError::NodeOutOfBounds { node: node.index() })
+        }
+    }
+
+    /// Folds values on the path from the root to `node_id`, root first. Returns `None` if `node_id` isn't
+    /// reachable from the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let values = [10, 51, 36, 90, 32, 16, 5];
+    ///         let tree = BTree::new(left, right, values);
+    ///
+    ///         assert_eq!(tree.path_fold(4, 0, |acc, value| acc + value), Some(10 + 51 + 32));
+    ///         assert_eq!(tree.path_fold(5, 0, |max, value| max.max(*value)), Some(36));
+    /// ```
+    pub fn path_fold<A, F>(&self, node_id: usize, init: A, mut f: F) -> Option<A>
+    where
+        F: FnMut(A, &T) -> A,
+    {
+        let path = self.path_to(node_id)?;
+        Some(
+            path.into_iter()
+                .fold(init, |acc, node| f(acc, &self.values[node])),
+        )
+    }
+
+    /// Ids of nodes on the path from the root to `node_id`, both ends included. Invalid child indices are skipped.
+    fn path_to(&self, node_id: usize) -> Option<Vec<usize>> {
+        algo::path_to(N, node_id, |node| {
+            [self.l_nodes[node], self.r_nodes[node]]
+                .into_iter()
+                .filter(|&child| child > ROOT_NODE && (child as usize) < N)
+                .map(|child| child as usize)
+        })
+    }
+
+    /// Values in in-order, left subtree first. For a binary search tree, e.g. one built by [BTree::cartesian] from
+    /// sorted values, that's ascending order. Nodes unreachable from the root are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [40, 20, 60, 10, 30, 50, 70]);
+    ///
+    ///         assert_eq!(tree.to_sorted_vec(), vec![10, 20, 30, 40, 50, 60, 70]);
+    ///         assert_eq!(tree.min(), Some(&10));
+    ///         assert_eq!(tree.max(), Some(&70));
+    /// ```
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &T> + '_ {
+        self.inorder().map(|node| &self.values[node])
+    }
+
+    /// Values collected in in-order, see [BTree::iter_sorted].
+    pub fn to_sorted_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter_sorted().cloned().collect()
+    }
+
+    /// Smallest value of a binary search tree, found by following left children from the root. `None` for an
+    /// empty tree.
+    pub fn min(&self) -> Option<&T> {
+        self.outermost(&self.l_nodes)
+    }
+
+    /// Greatest value of a binary search tree, found by following right children from the root. `None` for an
+    /// empty tree.
+    pub fn max(&self) -> Option<&T> {
+        self.outermost(&self.r_nodes)
+    }
+
+    /// Value of the last node reached from the root by following `towards` links, at most `N` of them.
+    fn outermost(&self, towards: &[isize; N]) -> Option<&T> {
+        storage::outermost(towards).map(|node| &self.values[node])
+    }
+
+    /// Values of a binary search tree within `range`, in ascending order. Left subtrees of nodes below the start
+    /// of the range are never visited and the walk ends at the first value past its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [40, 20, 60, 10, 30, 50, 70]);
+    ///
+    ///         assert_eq!(tree.range(25..60).collect::<Vec<_>>(), vec![&30, &40, &50]);
+    ///         assert_eq!(tree.range(..=20).collect::<Vec<_>>(), vec![&10, &20]);
+    /// ```
+    pub fn range<'a, R>(&'a self, range: R) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Ord,
+        R: RangeBounds<T> + 'a,
+    {
+        self.inorder_within(move |value| range_position(&range, value))
+            .map(|node| &self.values[node])
+    }
+
+    /// Ids of nodes reachable from the root in in-order, each visited once even if links form a cycle.
+    fn inorder(&self) -> impl Iterator<Item = usize> + '_ {
+        self.inorder_within(|_| Ordering::Equal)
+    }
+
+    /// In-order walk over nodes whose value `position` places within a range. Nodes before the range are skipped
+    /// with their left subtrees, the walk ends at the first node past it.
+    fn inorder_within<'a, F>(&'a self, position: F) -> impl Iterator<Item = usize> + 'a
+    where
+        F: Fn(&T) -> Ordering + 'a,
+    {
+        storage::inorder_within(
+            self.l_nodes.as_slice(),
+            self.r_nodes.as_slice(),
+            self.values.as_slice(),
+            position,
+        )
+    }
+}
+
+/// Position of `value` relative to `range`: `Less` before its start, `Greater` past its end.
+pub(crate) fn range_position<T: Ord>(range: &impl RangeBounds<T>, value: &T) -> Ordering {
+    let above_start = match range.start_bound() {
+        Bound::Included(start) => value >= start,
+        Bound::Excluded(start) => value > start,
+        Bound::Unbounded => true,
+    };
+    let below_end = match range.end_bound() {
+        Bound::Included(end) => value <= end,
+        Bound::Excluded(end) => value < end,
+        Bound::Unbounded => true,
+    };
+    match (above_start, below_end) {
+        (false, _) => Ordering::Less,
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod, const N: usize> BTree<T, N> {
+    /// Views the backing arrays as bytes, without copying: left links, right links and values. Links are `N`
+    /// native-endian `isize`s each, `-1` marking a missing child. Values are laid out as `[T; N]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         let tree = BTree::new([1, -1, -1], [2, -1, -1], [0.5_f32, 0.25, 0.75]);
+    ///         let (left, right, values) = tree.as_bytes();
+    ///
+    ///         assert_eq!(left, [1_isize, -1, -1].map(isize::to_ne_bytes).concat());
+    ///         assert_eq!(values.len(), 3 * size_of::<f32>());
+    ///         assert_eq!(BTree::from_bytes(left, right, values).as_ref(), Ok(&tree));
+    /// ```
+    pub fn as_bytes(&self) -> (&[u8], &[u8], &[u8]) {
+        (
+            bytemuck::cast_slice(self.l_nodes.as_slice()),
+            bytemuck::cast_slice(self.r_nodes.as_slice()),
+            bytemuck::cast_slice(self.values.as_slice()),
+        )
+    }
+
+    /// Copies a tree out of bytes laid out like [BTree::as_bytes], from any alignment. Links are checked like
+    /// [BTree::try_new], byte slices of a wrong length are reported as [TreeError::LengthMismatch].
+    pub fn from_bytes(left: &[u8], right: &[u8], values: &[u8]) -> Result<Self, TreeError> {
+        Self::try_new(read_bytes(left)?, read_bytes(right)?, read_bytes(values)?)
+    }
+}
+
+impl<T, const N: usize> Index<usize> for BTree<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for BTree<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+impl<T, const N: usize> Index<NodeId> for BTree<T, N> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self[node.index()]
+    }
+}
+
+impl<T, const N: usize> IndexMut<NodeId> for BTree<T, N> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self[node.index()]
+    }
+}
+
+/// Walks a binary node by node, back and forth. From root to leaf nodes, and back.
+///
+///
+/// ## Thread safety
+/// `Send` and `Sync` whenever `T` is `Sync`, as the walker only borrows the tree. Each thread should use its own
+/// walker. Cheap to copy & clone, as this structure serves as a view to a tree with small state overhead.
+///
+/// ## Example
+///
+/// ```rust
+///        use treesome::sized::{BTree, Walker};
+/// let left = [1, 3, 5, -1, -1, -1, -1];
+///         let right = [2, 4, 6, -1, -1, -1, -1];
+///         let values = [10, 51, 36, 90, 32, 16, 5];
+///         let tree = BTree::new(left, right, values);
+///
+///         let mut walker = Walker::for_tree(&tree);
+///         let right_child = walker.go_right();
+///         assert_eq!(right_child, Some(&tree.values[2]));
+/// ```
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Walker<'a, T, const N: usize> {
+    tree: &'a BTree<T, N>,
+    curr_node_id: isize,
+    depth: usize,
+}
+
+impl<'a, T, const N: usize> Walker<'a, T, N> {
+    pub fn for_tree(tree: &'a BTree<T, N>) -> Self {
+        Self {
+            tree,
+            curr_node_id: 0,
+            depth: 0,
+        }
+    }
+
+    /// Visits the right child of current node and returns its value, of it exists.
+    pub fn go_right(&mut self) -> Option<&T> {
+        let right_child_id = { self.tree.r_nodes[self.curr_node_id as usize] };
+        if right_child_id != LEAF_NODE {
+            self.curr_node_id = right_child_id;
+            self.depth += 1;
+            Some(&self.tree[right_child_id as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Visits the left child of current node and returns its value, of it exists.
+    pub fn go_left(&mut self) -> Option<&T> {
+        let left_child_id = { self.tree.l_nodes[self.curr_node_id as usize] };
+        if left_child_id != LEAF_NODE {
+            self.curr_node_id = left_child_id;
+            self.depth += 1;
+            Some(&self.tree[left_child_id as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Goes back to parent of the current node and returns its value, if it exists.
+    /// Stays on current position and returns `None` if there's no parent.
+    /// Root nodes and nodes out of bounds have no parents.
+    pub fn go_parent(&mut self) -> Option<&T> {
+        let parent = self
+            .tree
+            .linked_parent(NodeId::from_link(self.curr_node_id)?)?
+            .index() as isize;
+        self.curr_node_id = parent;
+        self.depth = self.depth.saturating_sub(1);
+        Some(&self.tree.values[parent as usize])
+    }
+
+    /// Switches to the other child of the current node's parent and returns its value, if it exists.
+    /// Stays on current position and returns `None` if there's no sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Walker};
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree);
+    ///         walker.go_left();
+    ///         assert_eq!(walker.current_children(), (Some(&90), Some(&32)));
+    ///         assert_eq!(walker.go_sibling(), Some(&36));
+    ///         assert_eq!(walker.go_sibling(), Some(&51));
+    ///         assert_eq!(walker.go_root(), &10);
+    /// ```
+    pub fn go_sibling(&mut self) -> Option<&T> {
+        let parent = self
+            .tree
+            .linked_parent(NodeId::from_link(self.curr_node_id)?)?
+            .index();
+        let sibling = if self.tree.l_nodes[parent] == self.curr_node_id {
+            self.tree.r_nodes[parent]
+        } else {
+            self.tree.l_nodes[parent]
+        };
+        if sibling == LEAF_NODE {
+            return None;
+        }
+        self.curr_node_id = sibling;
+        Some(&self.tree.values[sibling as usize])
+    }
+
+    /// Goes to the root node and returns its value.
+    pub fn go_root(&mut self) -> &T {
+        self.curr_node_id = ROOT_NODE;
+        self.depth = 0;
+        self.value()
+    }
+
+    /// Values of the left and right child of the current node, without moving.
+    pub fn current_children(&self) -> (Option<&'a T>, Option<&'a T>) {
+        let child = |id: isize| (id != LEAF_NODE).then(|| &self.tree.values[id as usize]);
+        (
+            child(self.tree.l_nodes[self.curr_node_id as usize]),
+            child(self.tree.r_nodes[self.curr_node_id as usize]),
+        )
+    }
+
+    /// Descends from the current node as instructed by `guide`, which is given the value of every node on the way.
+    /// Stops when told to or when the requested child doesn't exist, and returns the value of the landing node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use std::cmp::Ordering;
+    ///         use treesome::sized::{BTree, Descent, Walker};
+    ///         // Binary search tree
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [40, 20, 60, 10, 30, 50, 70]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree);
+    ///         let landing = walker.descend_while(|value| match 35.cmp(value) {
+    ///             Ordering::Less => Descent::Left,
+    ///             Ordering::Greater => Descent::Right,
+    ///             Ordering::Equal => Descent::Stop,
+    ///         });
+    ///         assert_eq!(landing, &30); // Closest node on the search path
+    /// ```
+    pub fn descend_while<F>(&mut self, mut guide: F) -> &T
+    where
+        F: FnMut(&T) -> Descent,
+    {
+        loop {
+            let node = self.curr_node_id as usize;
+            let next = match guide(&self.tree.values[node]) {
+                Descent::Left => self.tree.l_nodes[node],
+                Descent::Right => self.tree.r_nodes[node],
+                Descent::Stop => LEAF_NODE,
+            };
+            if next == LEAF_NODE {
+                return self.value();
+            }
+            self.curr_node_id = next;
+            self.depth += 1;
+        }
+    }
+
+    /// Moves to the in-order successor of the current node and returns its value. Stays on current position and
+    /// returns `None` if the current node is the last one in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Walker};
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [40, 20, 60, 10, 30, 50, 70]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree);
+    ///         walker.go_left();
+    ///         walker.go_right(); // 30
+    ///         assert_eq!(walker.go_next_inorder(), Some(&40));
+    ///         assert_eq!(walker.go_next_inorder(), Some(&50));
+    ///         assert_eq!(walker.go_prev_inorder(), Some(&40));
+    /// ```
+    pub fn go_next_inorder(&mut self) -> Option<&T> {
+        let (next, depth) = self.inorder_neighbour(&self.tree.r_nodes, &self.tree.l_nodes)?;
+        self.curr_node_id = next;
+        self.depth = depth;
+        Some(&self.tree.values[next as usize])
+    }
+
+    /// Moves to the in-order predecessor of the current node and returns its value. Stays on current position and
+    /// returns `None` if the current node is the first one in order.
+    pub fn go_prev_inorder(&mut self) -> Option<&T> {
+        let (previous, depth) = self.inorder_neighbour(&self.tree.l_nodes, &self.tree.r_nodes)?;
+        self.curr_node_id = previous;
+        self.depth = depth;
+        Some(&self.tree.values[previous as usize])
+    }
+
+    /// In-order neighbour in the `towards` direction: the outermost node of the subtree in that direction, or the
+    /// first ancestor reached from its `away` side. Returns the neighbour with its depth.
+    fn inorder_neighbour(&self, towards: &[isize; N], away: &[isize; N]) -> Option<(isize, usize)> {
+        let (mut node, mut depth) = (self.curr_node_id, self.depth);
+        if towards[node as usize] != LEAF_NODE {
+            node = towards[node as usize];
+            depth += 1;
+            while away[node as usize] != LEAF_NODE {
+                node = away[node as usize];
+                depth += 1;
+            }
+            return Some((node, depth));
+        }
+
+        loop {
+            let parent = self.tree.linked_parent(NodeId::from_link(node)?)?.index() as isize;
+            depth = depth.saturating_sub(1);
+            if away[parent as usize] == node {
+                return Some((parent, depth));
+            }
+            node = parent;
+        }
+    }
+
+    /// Makes a single move in given direction. Stays on current position and returns `None` if the move
+    /// isn't possible.
+    pub fn go(&mut self, direction: Direction) -> Option<&T> {
+        match direction {
+            Direction::Left => self.go_left(),
+            Direction::Right => self.go_right(),
+            Direction::Parent => self.go_parent(),
+        }
+    }
+
+    /// Replays moves recorded by a [RecordingWalker], typically on another tree of the same shape, and returns
+    /// the value the walker lands on. Stops on the first move that isn't possible and returns `None`, staying
+    /// at the position reached so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Walker};
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+    ///         let other = BTree::new(left, right, [1, 2, 3, 4, 5, 6, 7]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree).record();
+    ///         walker.go_left();
+    ///         walker.go_right();
+    ///         assert_eq!(walker.value(), &32);
+    ///
+    ///         let mut replaying = Walker::for_tree(&other);
+    ///         assert_eq!(replaying.replay(&walker.path()), Some(&5));
+    /// ```
+    pub fn replay(&mut self, path: &[Direction]) -> Option<&T> {
+        for &direction in path {
+            self.go(direction)?;
+        }
+        Some(self.value())
+    }
+
+    /// Depth of the current node, zero for the root. Maintained as the walker moves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Walker};
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree);
+    ///         walker.go_left();
+    ///         walker.go_right();
+    ///         assert_eq!(walker.depth(), 2);
+    ///         walker.go_parent();
+    ///         assert_eq!(walker.depth(), 1);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Value of the current node.
+    pub fn value(&self) -> &'a T {
+        &self.tree.values[self.curr_node_id as usize]
+    }
+
+    /// Saves the current position, to be returned to with [Walker::restore].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Walker};
+    ///         let left = [1, 3, 5, -1, -1, -1, -1];
+    ///         let right = [2, 4, 6, -1, -1, -1, -1];
+    ///         let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+    ///
+    ///         let mut walker = Walker::for_tree(&tree);
+    ///         walker.go_left();
+    ///         let checkpoint = walker.checkpoint();
+    ///         walker.go_right();
+    ///         assert_eq!(walker.restore(checkpoint), &51);
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            node_id: self.curr_node_id,
+            depth: self.depth,
+        }
+    }
+
+    /// Returns to a position saved by [Walker::checkpoint] and returns its value. Checkpoints are only meaningful
+    /// for the tree they were created on.
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> &T {
+        self.curr_node_id = checkpoint.node_id;
+        self.depth = checkpoint.depth;
+        self.value()
+    }
 
-        Some((node_id - 1) / 2)
+    /// Turns the walker into a [CheckedWalker], which reports invalid child indices instead of panicking.
+    pub fn checked(self) -> CheckedWalker<'a, T, N> {
+        CheckedWalker { walker: self }
     }
-}
 
-impl<T, const N: usize> Index<usize> for BTree<T, N> {
-    type Output = T;
+    /// Turns the walker into an [UncheckedWalker], which doesn't check child indices at all.
+    ///
+    /// # Safety
+    /// Same as [UncheckedWalker::for_tree]. The current position must be a node of the tree, which it is unless
+    /// restored from a [Checkpoint] of another tree.
+    pub unsafe fn unchecked(self) -> UncheckedWalker<'a, T, N> {
+        UncheckedWalker { walker: self }
+    }
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.values[index]
+    /// Starts recording moves made from the current position.
+    pub fn record(self) -> RecordingWalker<'a, T, N> {
+        RecordingWalker {
+            walker: self,
+            moves: Vec::new(),
+        }
     }
 }
 
-/// Walks a binary node by node, back and forth. From root to leaf nodes, and back.
+/// A [Walker] skipping bounds checks on every move, for hot loops over trees known to be valid. Offers the moves
+/// towards leaves, searches typically consist of.
 ///
+/// # Examples
 ///
-/// ## Thread safety
-/// Not thread safe. Cheap to copy & clone, as this structure serves as a view to a tree with small
-/// state overhead.
+/// ```
+///         use std::cmp::Ordering;
+///         use treesome::sized::{BTree, Descent, UncheckedWalker};
+///         let left = [1, 3, 5, -1, -1, -1, -1];
+///         let right = [2, 4, 6, -1, -1, -1, -1];
+///         let tree = BTree::try_new(left, right, [40, 20, 60, 10, 30, 50, 70]).expect("Tree is tree-shaped");
 ///
-/// ## Example
+///         // SAFETY: The tree is non-empty and its links were checked by `try_new`
+///         let mut walker = unsafe { UncheckedWalker::for_tree(&tree) };
+///         let landing = walker.descend_while(|value| match 50.cmp(value) {
+///             Ordering::Less => Descent::Left,
+///             Ordering::Greater => Descent::Right,
+///             Ordering::Equal => Descent::Stop,
+///         });
+///         assert_eq!(landing, &50);
+///         assert_eq!(walker.depth(), 2);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct UncheckedWalker<'a, T, const N: usize> {
+    walker: Walker<'a, T, N>,
+}
+
+impl<'a, T, const N: usize> UncheckedWalker<'a, T, N> {
+    /// Starts walking `tree` at its root.
+    ///
+    /// # Safety
+    /// `tree` must have at least one node, and every link must be either `-1` or lower than `N`, e.g. as checked
+    /// by [BTree::try_new]. The walker borrows the tree, so links can't change while it walks.
+    pub unsafe fn for_tree(tree: &'a BTree<T, N>) -> Self {
+        // SAFETY: Forwarded to the caller
+        unsafe { Walker::for_tree(tree).unchecked() }
+    }
+
+    /// Visits the right child of current node and returns its value, if it exists.
+    pub fn go_right(&mut self) -> Option<&T> {
+        // SAFETY: The current node is the root or a linked node, both in bounds
+        let child = unsafe { self.walker.tree.children_unchecked(self.node()).right };
+        self.visit(child)
+    }
+
+    /// Visits the left child of current node and returns its value, if it exists.
+    pub fn go_left(&mut self) -> Option<&T> {
+        // SAFETY: The current node is the root or a linked node, both in bounds
+        let child = unsafe { self.walker.tree.children_unchecked(self.node()).left };
+        self.visit(child)
+    }
+
+    /// Same as [Walker::descend_while].
+    pub fn descend_while<F>(&mut self, mut guide: F) -> &T
+    where
+        F: FnMut(&T) -> Descent,
+    {
+        loop {
+            // SAFETY: The current node is the root or a linked node, both in bounds
+            let children = unsafe { self.walker.tree.children_unchecked(self.node()) };
+            let next = match guide(self.value()) {
+                Descent::Left => children.left,
+                Descent::Right => children.right,
+                Descent::Stop => LEAF_NODE,
+            };
+            if self.visit(next).is_none() {
+                return self.value();
+            }
+        }
+    }
+
+    /// Value of the current node.
+    pub fn value(&self) -> &'a T {
+        // SAFETY: The current node is the root or a linked node, both in bounds
+        unsafe { self.walker.tree.value_unchecked(self.node()) }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.walker.depth
+    }
+
+    /// The underlying walker, e.g. to create a [Checkpoint] or move to the parent.
+    pub fn walker(&self) -> &Walker<'a, T, N> {
+        &self.walker
+    }
+
+    fn node(&self) -> usize {
+        self.walker.curr_node_id as usize
+    }
+
+    fn visit(&mut self, child: isize) -> Option<&T> {
+        if child == LEAF_NODE {
+            return None;
+        }
+        self.walker.curr_node_id = child;
+        self.walker.depth += 1;
+        Some(self.value())
+    }
+}
+
+/// A [Walker] validating child indices before every move, for trees built from untrusted arrays. Moves return
+/// an error with the offending node and index instead of panicking on an out-of-bounds child index.
 ///
-/// ```rust
-///        use treesome::sized::{BTree, Walker};
-/// let left = [1, 3, 5, -1, -1, -1, -1];
-///         let right = [2, 4, 6, -1, -1, -1, -1];
-///         let values = [10, 51, 36, 90, 32, 16, 5];
-///         let tree = BTree::new(left, right, values);
+/// # Examples
 ///
-///         let mut walker = Walker::for_tree(&tree);
-///         let right_child = walker.go_right();
-///         assert_eq!(right_child, Some(&tree.values[2]));
 /// ```
+///         use treesome::sized::{BTree, CheckedWalker};
+///         let tree = BTree::new([1, -1], [7, -1], [1, 2]);
 ///
-#[derive(Debug, Copy, Clone)]
-pub struct Walker<'a, T, const N: usize> {
-    tree: &'a BTree<T, N>,
-    curr_node_id: isize,
+///         let mut walker = CheckedWalker::for_tree(&tree);
+///         assert!(walker.go_right().is_err());
+///         assert_eq!(walker.go_left().unwrap(), Some(&2));
+///         assert_eq!(walker.go_left().unwrap(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CheckedWalker<'a, T, const N: usize> {
+    walker: Walker<'a, T, N>,
 }
 
-impl<'a, T, const N: usize> Walker<'a, T, N> {
+impl<'a, T, const N: usize> CheckedWalker<'a, T, N> {
     pub fn for_tree(tree: &'a BTree<T, N>) -> Self {
-        Self {
-            tree,
-            curr_node_id: 0,
-        }
+        Walker::for_tree(tree).checked()
     }
 
-    /// Visits the right child of current node and returns its value, of it exists.
-    pub fn go_right(&mut self) -> Option<&T> {
-        let right_child_id = { self.tree.r_nodes[self.curr_node_id as usize] };
-        if right_child_id != LEAF_NODE_MARK {
-            self.curr_node_id = right_child_id;
-            Some(&self.tree[right_child_id as usize])
+    /// Visits the right child of current node and returns its value, if it exists.
+    pub fn go_right(&mut self) -> Result<Option<&T>, TreeError> {
+        self.check(&self.walker.tree.r_nodes)?;
+        Ok(self.walker.go_right())
+    }
+
+    /// Visits the left child of current node and returns its value, if it exists.
+    pub fn go_left(&mut self) -> Result<Option<&T>, TreeError> {
+        self.check(&self.walker.tree.l_nodes)?;
+        Ok(self.walker.go_left())
+    }
+
+    /// Goes back to parent of the current node and returns its value, if it exists. Parent lookups never index
+    /// out of bounds, so this never fails.
+    pub fn go_parent(&mut self) -> Result<Option<&T>, TreeError> {
+        Ok(self.walker.go_parent())
+    }
+
+    /// Value of the current node.
+    pub fn value(&self) -> &'a T {
+        self.walker.value()
+    }
+
+    /// Depth of the current node, zero for the root.
+    pub fn depth(&self) -> usize {
+        self.walker.depth()
+    }
+
+    /// Underlying walker, at the current position.
+    pub fn walker(&self) -> &Walker<'a, T, N> {
+        &self.walker
+    }
+
+    fn check(&self, links: &[isize; N]) -> Result<(), TreeError> {
+        let node = self.walker.curr_node_id;
+        let child = links[node as usize];
+        if child == LEAF_NODE || (0..N as isize).contains(&child) {
+            Ok(())
         } else {
-            None
+            Err(TreeError::IndexOutOfBounds {
+                node: node as usize,
+                index: child,
+            })
         }
     }
+}
+
+/// Saved position of a [Walker].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Checkpoint {
+    node_id: isize,
+    depth: usize,
+}
+
+/// Instruction for [Walker::descend_while].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Descent {
+    Left,
+    Right,
+    Stop,
+}
+
+/// A single move of a [Walker].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    Left,
+    Right,
+    Parent,
+}
+
+/// A [Walker] recording its successful moves, so they can be replayed with [Walker::replay]. Created
+/// with [Walker::record].
+#[derive(Debug, Clone)]
+pub struct RecordingWalker<'a, T, const N: usize> {
+    walker: Walker<'a, T, N>,
+    moves: Vec<Direction>,
+}
+
+impl<'a, T, const N: usize> RecordingWalker<'a, T, N> {
+    pub fn go_right(&mut self) -> Option<&T> {
+        self.go(Direction::Right)
+    }
 
-    /// Visits the left child of current node and returns its value, of it exists.
     pub fn go_left(&mut self) -> Option<&T> {
-        let left_child_id = { self.tree.l_nodes[self.curr_node_id as usize] };
-        if left_child_id != LEAF_NODE_MARK {
-            self.curr_node_id = left_child_id;
-            Some(&self.tree[left_child_id as usize])
-        } else {
-            None
-        }
+        self.go(Direction::Left)
     }
 
-    /// Goes back to parent of the current node and returns its value, if it exists.
-    /// Stays on current position and returns `None` if there's no parent.
-    /// Root nodes and nodes out of bounds have no parents.
     pub fn go_parent(&mut self) -> Option<&T> {
-        let parent = self.tree.parent(self.curr_node_id)?;
-        self.curr_node_id = parent;
-        Some(&self.tree.values[parent as usize])
+        self.go(Direction::Parent)
+    }
+
+    /// Makes a single move in given direction, recording it if successful.
+    pub fn go(&mut self, direction: Direction) -> Option<&T> {
+        let value = self.walker.go(direction)?;
+        self.moves.push(direction);
+        Some(value)
+    }
+
+    /// Value of the current node.
+    pub fn value(&self) -> &'a T {
+        self.walker.value()
+    }
+
+    /// Successful moves made since the recording started.
+    pub fn path(&self) -> Vec<Direction> {
+        self.moves.clone()
+    }
+
+    /// Underlying walker, at the current position.
+    pub fn walker(&self) -> &Walker<'a, T, N> {
+        &self.walker
+    }
+}
+
+/// Serialized form of a [BTree], checked like [BTree::try_new] before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct BTreeData<T, const N: usize> {
+    l_nodes: Array<isize, N>,
+    r_nodes: Array<isize, N>,
+    values: Array<T, N>,
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> TryFrom<BTreeData<T, N>> for BTree<T, N> {
+    type Error = TreeError;
+
+    fn try_from(data: BTreeData<T, N>) -> Result<Self, Self::Error> {
+        check_links(N, |node| [data.l_nodes[node], data.r_nodes[node]])?;
+        Ok(Self {
+            l_nodes: data.l_nodes,
+            r_nodes: data.r_nodes,
+            values: data.values,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::sized::{BTree, Walker, ROOT_NODE};
+    use crate::consts::ROOT_NODE;
+    use crate::sized::{BTree, CheckedWalker, Descent, Direction, Walker};
+    use crate::tree::{NodeId, TreeError};
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn bytes() {
+        let tree = BTree::new([1, -1, -1], [2, -1, -1], [10_u16, 20, 30]);
+        let (left, right, values) = tree.as_bytes();
+        assert_eq!(values, [10_u16, 20, 30].map(u16::to_ne_bytes).concat());
+
+        // Unaligned input is copied out
+        let mut unaligned = vec![0_u8];
+        unaligned.extend_from_slice(left);
+        assert_eq!(
+            BTree::from_bytes(&unaligned[1..], right, values).as_ref(),
+            Ok(&tree)
+        );
+
+        assert_eq!(
+            BTree::<u16, 3>::from_bytes(left, right, &values[1..]),
+            Err(TreeError::LengthMismatch {
+                expected: 6,
+                found: 5
+            })
+        );
+        let shared = [1_isize, -1, 1].map(isize::to_ne_bytes).concat();
+        assert_eq!(
+            BTree::<u16, 3>::from_bytes(&shared, right, values),
+            Err(TreeError::MultipleParents { node: 1 })
+        );
+    }
+
+    #[test]
+    fn unchecked_walker() {
+        let tree = BTree::try_new([1, 3, -1, -1, -1], [2, 4, -1, -1, -1], [0, 1, 2, 3, 4])
+            .expect("Tree is tree-shaped");
+        // SAFETY: Just checked by try_new
+        let mut unchecked = unsafe { Walker::for_tree(&tree).unchecked() };
+        let mut walker = Walker::for_tree(&tree);
+        for direction in [Direction::Left, Direction::Right, Direction::Right] {
+            let expected = walker.go(direction).copied();
+            let moved = match direction {
+                Direction::Left => unchecked.go_left(),
+                _ => unchecked.go_right(),
+            };
+            assert_eq!(moved.copied(), expected);
+        }
+        assert_eq!(unchecked.value(), &4);
+        assert_eq!(unchecked.depth(), 2);
+        assert_eq!(unchecked.walker().checkpoint(), walker.checkpoint());
+        // SAFETY: Node 4 is in bounds
+        assert_eq!(unsafe { tree.children_unchecked(4) }, (-1, -1).into());
+    }
+
+    #[test]
+    fn veb_layout() {
+        // Binary search tree of 0..63 with in-order node ids, built from the sorted range
+        fn build(range: std::ops::Range<usize>, l: &mut [isize; 63], r: &mut [isize; 63]) -> isize {
+            if range.is_empty() {
+                return -1;
+            }
+            let middle = (range.start + range.end) / 2;
+            l[middle] = build(range.start..middle, l, r);
+            r[middle] = build(middle + 1..range.end, l, r);
+            middle as isize
+        }
+        let (mut left, mut right) = ([-1; 63], [-1; 63]);
+        build(0..63, &mut left, &mut right);
+        // Make node 31 the root
+        let swap = |links: &mut [isize; 63]| {
+            links.swap(0, 31);
+            for link in links.iter_mut() {
+                *link = match *link {
+                    0 => 31,
+                    31 => 0,
+                    link => link,
+                };
+            }
+        };
+        swap(&mut left);
+        swap(&mut right);
+        let mut values: [usize; 63] = std::array::from_fn(|node| node);
+        values.swap(0, 31);
+        let tree = BTree::try_new(left, right, values).expect("Tree is tree-shaped");
+
+        let veb = tree.to_veb_layout().expect("Tree is tree-shaped");
+        let mut sorted: Vec<usize> = veb.values.to_vec();
+        sorted.sort();
+        assert_eq!(sorted, (0..63).collect::<Vec<_>>());
+        for key in 0..63 {
+            let mut original = Walker::for_tree(&tree);
+            let mut relaid = Walker::for_tree(&veb);
+            let guide = |value: &usize| match key.cmp(value) {
+                std::cmp::Ordering::Less => Descent::Left,
+                std::cmp::Ordering::Greater => Descent::Right,
+                std::cmp::Ordering::Equal => Descent::Stop,
+            };
+            assert_eq!(original.descend_while(guide), &key);
+            assert_eq!(relaid.descend_while(guide), &key);
+            assert_eq!(original.depth(), relaid.depth());
+        }
+
+        // Unreachable node 3 goes last, shared children are rejected
+        let orphan = BTree::new([1, -1, -1, -1], [2, -1, -1, -1], ['a', 'b', 'c', 'd']);
+        let veb = orphan.to_veb_layout().expect("Tree is tree-shaped");
+        assert_eq!(*veb.values, ['a', 'b', 'c', 'd']);
+        let shared = BTree::new([1, 2, -1], [2, -1, -1], [0; 3]);
+        assert_eq!(
+            shared.to_veb_layout(),
+            Err(TreeError::MultipleParents { node: 2 })
+        );
+        assert!(BTree::<u8, 0>::new([], [], []).to_veb_layout().is_ok());
+    }
+
+    #[test]
+    fn parent_boundaries() {
+        let tree = BTree::new([1, 3, -1, -1, -1], [2, 4, -1, -1, -1], [0; 5]);
+        for node_id in [isize::MIN, -2, -1, 0, 5, isize::MAX] {
+            assert_eq!(tree.parent(node_id), None);
+        }
+        assert_eq!(tree.parent(4), Some(1));
+
+        // Node 2 is unlinked, only the layout formula reports a parent
+        let detached = BTree::new([1, -1, -1], [-1, -1, -1], [0; 3]);
+        assert_eq!(detached.parent(2), Some(0));
+        assert_eq!(detached.linked_parent(NodeId::new(2)), None);
+        assert_eq!(detached.try_parent(NodeId::new(2)), Ok(None));
+    }
+
+    #[test]
+    fn checked_accessors() {
+        let tree = BTree::new([1, 3, -1, -1, -1], [2, 4, -1, -1, -1], [0, 1, 2, 3, 4]);
+        assert_eq!(tree.get(NodeId::new(3)), Some(&3));
+        assert_eq!(tree.get(NodeId::new(5)), None);
+        assert_eq!(tree.try_is_leaf(NodeId::new(4)), Ok(true));
+        assert_eq!(tree.try_parent(NodeId::ROOT), Ok(None));
+        assert_eq!(tree.try_parent(NodeId::new(4)), Ok(Some(NodeId::new(1))));
+        assert_eq!(
+            tree.try_children(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+        assert_eq!(
+            tree.try_is_leaf(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+        assert_eq!(
+            tree.try_parent(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+    }
 
     #[test]
     fn walker() {
@@ -204,6 +1708,213 @@ mod tests {
         assert_eq!(left_child, Some(&tree.values[2]));
     }
 
+    #[test]
+    fn record_and_replay() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+        let same_shape = BTree::new(left, right, ['a', 'b', 'c', 'd', 'e', 'f', 'g']);
+
+        let mut walker = Walker::for_tree(&tree).record();
+        walker.go_right();
+        walker.go_left();
+        assert_eq!(walker.go_left(), None); // Unsuccessful moves aren't recorded
+        walker.go_parent();
+        walker.go_right();
+        assert_eq!(
+            walker.path(),
+            vec![
+                Direction::Right,
+                Direction::Left,
+                Direction::Parent,
+                Direction::Right
+            ]
+        );
+        assert_eq!(walker.value(), &5);
+
+        let mut other = Walker::for_tree(&same_shape);
+        assert_eq!(other.replay(&walker.path()), Some(&'g'));
+
+        let smaller = BTree::new([1, -1, -1], [2, -1, -1], [1, 2, 3]);
+        let mut other = Walker::for_tree(&smaller);
+        assert_eq!(other.replay(&walker.path()), None);
+        assert_eq!(other.value(), &3); // Stopped after the first move
+    }
+
+    #[test]
+    fn checkpoints() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let tree = BTree::new(left, right, [10, 51, 36, 90, 32, 16, 5]);
+
+        // Backtracking search for a path to 16, trying left subtrees first
+        let mut walker = Walker::for_tree(&tree);
+        let mut pending = vec![walker.checkpoint()];
+        let mut found = None;
+        while let Some(checkpoint) = pending.pop() {
+            if *walker.restore(checkpoint) == 16 {
+                found = Some(checkpoint);
+                break;
+            }
+            if walker.go_right().is_some() {
+                pending.push(walker.checkpoint());
+                walker.restore(checkpoint);
+            }
+            if walker.go_left().is_some() {
+                pending.push(walker.checkpoint());
+            }
+        }
+
+        let mut walker = Walker::for_tree(&tree);
+        assert_eq!(walker.restore(found.unwrap()), &16);
+        assert_eq!(walker.go_parent(), Some(&36));
+    }
+
+    #[test]
+    fn siblings() {
+        let left = [1, 3, -1, -1, -1];
+        let right = [2, 4, -1, -1, -1];
+        let tree = BTree::new(left, right, [0, 1, 2, 3, 4]);
+
+        let mut walker = Walker::for_tree(&tree);
+        assert_eq!(walker.go_sibling(), None); // Root has no siblings
+        assert_eq!(walker.go_right(), Some(&2));
+        assert_eq!(walker.current_children(), (None, None));
+        assert_eq!(walker.go_sibling(), Some(&1));
+        assert_eq!(walker.go_right(), Some(&4));
+        assert_eq!(walker.go_sibling(), Some(&3));
+        assert_eq!(walker.go_root(), &0);
+        assert_eq!(walker.current_children(), (Some(&1), Some(&2)));
+
+        let one_sided = BTree::new([1, -1], [-1, -1], [0, 1]);
+        let mut walker = Walker::for_tree(&one_sided);
+        walker.go_left();
+        assert_eq!(walker.go_sibling(), None);
+        assert_eq!(walker.value(), &1);
+    }
+
+    #[test]
+    fn descend_while() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let tree = BTree::new(left, right, [40, 20, 60, 10, 30, 50, 70]);
+
+        let mut walker = Walker::for_tree(&tree);
+        assert_eq!(walker.descend_while(|_| Descent::Right), &70);
+        assert_eq!(walker.descend_while(|_| Descent::Left), &70); // Already at a leaf
+
+        let mut visited = Vec::new();
+        walker.go_root();
+        let landing = walker.descend_while(|value| {
+            visited.push(*value);
+            if *value == 20 {
+                Descent::Stop
+            } else {
+                Descent::Left
+            }
+        });
+        assert_eq!(landing, &20);
+        assert_eq!(visited, vec![40, 20]);
+        assert_eq!(walker.go_parent(), Some(&40));
+    }
+
+    #[test]
+    fn inorder_navigation() {
+        let tree = BTree::cartesian([5, 10, 40, 30, 28, 1, 7, 7, 15]);
+
+        let mut walker = Walker::for_tree(&tree);
+        while walker.go_left().is_some() {}
+        let mut forward = vec![*walker.value()];
+        while let Some(value) = walker.go_next_inorder() {
+            forward.push(*value);
+        }
+        assert_eq!(forward, vec![5, 10, 40, 30, 28, 1, 7, 7, 15]);
+
+        let mut backward = vec![*walker.value()];
+        while let Some(value) = walker.go_prev_inorder() {
+            backward.push(*value);
+        }
+        backward.reverse();
+        assert_eq!(backward, forward);
+    }
+
+    #[test]
+    fn depth() {
+        let tree = BTree::cartesian([5, 10, 40, 30, 28, 1, 7, 7, 15]);
+        let depth_of = |walker: &Walker<i32, 9>| {
+            let mut probe = *walker;
+            let mut depth = 0;
+            while probe.go_parent().is_some() {
+                depth += 1;
+            }
+            depth
+        };
+
+        let mut walker = Walker::for_tree(&tree);
+        assert_eq!(walker.depth(), 0);
+        walker.descend_while(|_| Descent::Right);
+        assert_eq!(walker.depth(), depth_of(&walker));
+        let checkpoint = walker.checkpoint();
+
+        walker.go_root();
+        while walker.go_left().is_some() {}
+        loop {
+            assert_eq!(walker.depth(), depth_of(&walker));
+            if walker.go_next_inorder().is_none() {
+                break;
+            }
+        }
+        while walker.go_prev_inorder().is_some() {
+            assert_eq!(walker.depth(), depth_of(&walker));
+        }
+        walker.go_sibling();
+        assert_eq!(walker.depth(), depth_of(&walker));
+
+        walker.restore(checkpoint);
+        assert_eq!(walker.depth(), depth_of(&walker));
+    }
+
+    #[test]
+    fn checked_walker() {
+        let tree = BTree::new([1, -2, -1], [2, -1, 3], [0, 1, 2]);
+
+        let mut walker = CheckedWalker::for_tree(&tree);
+        assert_eq!(walker.go_left().unwrap(), Some(&1));
+        assert!(walker.go_left().is_err());
+        assert_eq!(walker.value(), &1);
+        assert_eq!(walker.go_right().unwrap(), None);
+        assert_eq!(walker.go_parent().unwrap(), Some(&0));
+        assert_eq!(walker.go_right().unwrap(), Some(&2));
+        assert!(walker.go_right().is_err());
+        assert_eq!(walker.depth(), 1);
+        assert_eq!(walker.go_parent().unwrap(), Some(&0));
+        assert_eq!(walker.go_parent().unwrap(), None);
+    }
+
+    #[test]
+    fn try_new() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let values = [10, 51, 36, 90, 32, 16, 5];
+        assert_eq!(
+            BTree::try_new(left, right, values).unwrap(),
+            BTree::new(left, right, values)
+        );
+
+        assert!(matches!(
+            BTree::try_new([1, -2], [-1, -1], [1, 2]),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: -2 })
+        ));
+        assert!(matches!(
+            BTree::try_new([1, -1], [-1, 0], [1, 2]),
+            Err(TreeError::CycleDetected { node: 1 })
+        ));
+        assert!(matches!(
+            BTree::try_new([1, -1], [1, -1], [1, 2]),
+            Err(TreeError::MultipleParents { node: 1 })
+        ));
+    }
+
     #[test]
     fn parent() {
         let left = [1, 3, 5, -1, -1, -1, -1];
@@ -246,6 +1957,146 @@ mod tests {
         assert!(tree.is_leaf_node(3));
     }
 
+    #[test]
+    fn path_fold() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let values = [10, 51, 36, 90, 32, 16, 5];
+        let tree = BTree::new(left, right, values);
+
+        assert_eq!(tree.path_fold(0, 0, |acc, value| acc + value), Some(10));
+        assert_eq!(
+            tree.path_fold(6, 0, |acc, value| acc + value),
+            Some(10 + 36 + 5)
+        );
+        assert_eq!(tree.path_fold(3, 0, |depth, _| depth + 1), Some(3));
+        assert_eq!(tree.path_fold(7, 0, |acc, value| acc + value), None);
+    }
+
+    #[test]
+    fn cartesian() {
+        let values = [5, 10, 40, 30, 28, 1, 7, 7, 15];
+        let tree = BTree::cartesian(values);
+
+        // In-order traversal restores the input
+        let mut in_order = Vec::new();
+        let mut stack = Vec::new();
+        let mut node = 0_isize;
+        while node != -1 || !stack.is_empty() {
+            while node != -1 {
+                stack.push(node);
+                node = tree.children(node as usize).left;
+            }
+            let current = stack.pop().unwrap();
+            in_order.push(tree[current as usize]);
+            node = tree.children(current as usize).right;
+        }
+        assert_eq!(in_order, values);
+
+        // Heap property, with parents found even though the layout isn't complete
+        for node in 1..values.len() {
+            let parent = tree.linked_parent(NodeId::new(node)).unwrap();
+            assert!(tree[parent.index()] <= tree[node]);
+        }
+        assert_eq!(tree[0], 1);
+
+        let mut walker = Walker::for_tree(&tree);
+        assert_eq!(walker.go_left(), Some(&5));
+        assert_eq!(walker.go_parent(), Some(&1));
+    }
+
+    #[test]
+    fn sorted_views() {
+        let tree = BTree::cartesian([1, 2, 3, 5, 8]);
+        assert_eq!(tree.to_sorted_vec(), vec![1, 2, 3, 5, 8]);
+        assert_eq!((tree.min(), tree.max()), (Some(&1), Some(&8)));
+
+        // Links back to visited nodes and out of bounds end the walk, unreachable nodes are skipped
+        let cyclic = BTree::new([1, 0, -1], [9, 1, -1], ['b', 'a', 'z']);
+        assert_eq!(cyclic.to_sorted_vec(), vec!['a', 'b']);
+        assert_eq!((cyclic.min(), cyclic.max()), (Some(&'a'), Some(&'b')));
+
+        let empty: BTree<u8, 0> = BTree::new([], [], []);
+        assert!(empty.iter_sorted().next().is_none());
+        assert_eq!(empty.min(), None);
+    }
+
+    #[test]
+    fn balanced() {
+        let values: [u16; 100] = std::array::from_fn(|value| value as u16);
+        let chain = BTree::cartesian(values);
+        assert_eq!(chain.height(), 100);
+
+        let tree = chain.balanced();
+        assert_eq!(tree.height(), 7);
+        assert!(tree.iter_sorted().eq(values.iter()));
+        assert!(BTree::try_new(*tree.l_nodes, *tree.r_nodes, *tree.values).is_ok());
+
+        // Unreachable nodes are moved last, links out of bounds and cycles are dropped
+        let tree = BTree::new([1, 0, -1, -1], [7, 1, -1, -1], ['b', 'a', 'x', 'y']).balanced();
+        assert_eq!(*tree.values, ['b', 'a', 'x', 'y']);
+        assert_eq!(*tree.l_nodes, [1, -1, -1, -1]);
+        assert_eq!(*tree.r_nodes, [-1; 4]);
+
+        let empty: BTree<u8, 0> = BTree::new([], [], []);
+        assert_eq!(empty.balanced(), BTree::new([], [], []));
+    }
+
+    #[test]
+    fn capacity_migration() {
+        let values: [u16; 40] = std::array::from_fn(|value| value as u16);
+        let tree = BTree::cartesian(values);
+
+        let larger: BTree<u16, 64> = tree.clone().resize();
+        assert_eq!(larger.height(), 40);
+        assert_eq!(larger.values[40..], [0; 24]);
+        assert_eq!((larger.l_nodes[63], larger.r_nodes[63]), (-1, -1));
+        assert_eq!(larger.shrink(), Ok(tree.clone()));
+
+        assert_eq!(
+            tree.clone().shrink::<39>(),
+            Err(TreeError::IndexOutOfBounds {
+                node: 38,
+                index: 39
+            })
+        );
+        let empty: BTree<u16, 0> = tree.shrink().expect("No links remain");
+        assert_eq!(empty.resize::<1>(), BTree::new([-1], [-1], [0]));
+    }
+
+    #[test]
+    fn heap_operations() {
+        // Heap order along arbitrary links, not just the complete layout
+        let mut tree = BTree::new([3, -1, -1, -1, 1], [4, -1, -1, -1, 2], [1, 2, 3, 4, 5]);
+        tree.heapify();
+        assert!(tree.is_heap());
+        assert_eq!(tree[0], 5);
+        assert_eq!(tree.sift_down(0), 0);
+
+        tree[2] = 9;
+        assert_eq!(tree.sift_up(2), 0);
+        assert_eq!(*tree.values, [9, 2, 3, 4, 5]);
+        assert!(tree.is_heap());
+
+        // Cycles end the walks, unreachable nodes keep their values
+        let mut cyclic = BTree::new([1, -1, -1], [-1, 1, -1], [1, 2, 3]);
+        cyclic.heapify();
+        assert_eq!(*cyclic.values, [2, 1, 3]);
+        assert_eq!(cyclic.sift_up(2), 2);
+    }
+
+    #[test]
+    fn range() {
+        // Skewed to the right, as built from sorted values
+        let chain = BTree::cartesian([1, 2, 3, 4, 5, 6]);
+        assert_eq!(chain.range(3..5).collect::<Vec<_>>(), vec![&3, &4]);
+        assert_eq!(chain.range(7..).count(), 0);
+        assert_eq!(chain.range(..=1).collect::<Vec<_>>(), vec![&1]);
+
+        let cyclic = BTree::new([1, 0, -1], [9, 1, -1], ['b', 'a', 'z']);
+        assert_eq!(cyclic.range('a'..).collect::<Vec<_>>(), vec![&'a', &'b']);
+    }
+
     #[test]
     fn index() {
         let left = [1, 3, 5, -1, -1, -1, -1];
@@ -267,4 +2118,21 @@ mod tests {
         let deserialized_tree = serde_json::from_str::<BTree<i32, 7>>(&string_repr).unwrap();
         assert_eq!(tree, deserialized_tree);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_malformed() {
+        for malformed in [
+            r#"{"l_nodes":[1,-1,3],"r_nodes":[2,-1,-1],"values":[1,2,3]}"#,
+            r#"{"l_nodes":[1,2,-1],"r_nodes":[2,-1,-1],"values":[1,2,3]}"#,
+            r#"{"l_nodes":[1,-1],"r_nodes":[2,-1,-1],"values":[1,2,3]}"#,
+            r#"{"l_nodes":[1,-1,-1,-1],"r_nodes":[2,-1,-1],"values":[1,2,3]}"#,
+        ] {
+            assert!(serde_json::from_str::<BTree<u8, 3>>(malformed).is_err());
+        }
+
+        let short = r#"{"l_nodes":[1,-1],"r_nodes":[2,-1,-1],"values":[1,2,3]}"#;
+        let error = serde_json::from_str::<BTree<u8, 3>>(short).unwrap_err();
+        assert!(error.to_string().starts_with("invalid length 2"), "{error}");
+    }
 }
@@ -0,0 +1,232 @@
+use crate::footprint::MemoryFootprint;
+#[cfg(feature = "debug-validate")]
+use crate::tree::TreeError;
+
+/// Fixed-capacity binary max-heap, stored inline without any heap allocation. Uses the implicit complete
+/// tree layout - children of node `i` are `2i + 1` and `2i + 2`, so no child indices are stored.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sized::Heap;
+///         let mut heap = Heap::<i32, 4>::new();
+///         heap.push(3).unwrap();
+///         heap.push(42).unwrap();
+///         heap.push(7).unwrap();
+///
+///         assert_eq!(heap.peek(), Some(&42));
+///         assert_eq!(heap.pop(), Some(42));
+///         assert_eq!(heap.pop(), Some(7));
+///         assert_eq!(heap.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Heap<T: Ord, const N: usize> {
+    values: [Option<T>; N],
+    len: usize,
+}
+
+impl<T: Ord, const N: usize> Default for Heap<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, const N: usize> Heap<T, N> {
+    /// Creates an empty heap with capacity of `N` values.
+    pub fn new() -> Self {
+        Self {
+            values: std::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    /// Turns an array into a full heap in O(N).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Heap;
+    ///         let mut heap = Heap::heapify_from([5, 1, 8, 3, 9]);
+    ///         assert!(heap.is_full());
+    ///         assert_eq!(heap.pop(), Some(9));
+    ///         assert_eq!(heap.pop(), Some(8));
+    /// ```
+    pub fn heapify_from(values: [T; N]) -> Self {
+        let mut heap = Self {
+            values: values.map(Some),
+            len: N,
+        };
+        for node in (0..N / 2).rev() {
+            heap.sift_down(node);
+        }
+        debug_validate!(heap.validate());
+        heap
+    }
+
+    /// Bytes taken by the heap, see [MemoryFootprint]. Free slots are overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint::inline::<Self, T>(self.len, size_of::<usize>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The greatest value, if the heap isn't empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.values.first()?.as_ref()
+    }
+
+    /// Inserts a value in O(log N). If the heap is full, the value is handed back as an error.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.values[self.len] = Some(value);
+        self.len += 1;
+        self.sift_up(self.len - 1);
+        debug_validate!(self.validate());
+        Ok(())
+    }
+
+    /// Removes and returns the greatest value in O(log N).
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        self.values.swap(0, self.len);
+        let greatest = self.values[self.len].take();
+        self.sift_down(0);
+        debug_validate!(self.validate());
+        greatest
+    }
+
+    /// Checks the first `len` slots are occupied, the rest empty, and no value is greater than its parent.
+    #[cfg(feature = "debug-validate")]
+    fn validate(&self) -> Result<(), TreeError> {
+        if let Some(node) = (0..N).find(|&node| self.values[node].is_some() != (node < self.len)) {
+            return Err(TreeError::CorruptedTree(format!(
+                "heap slot {node} doesn't match heap length {}",
+                self.len
+            )));
+        }
+        match (1..self.len).find(|&node| self.values[node] > self.values[(node - 1) / 2]) {
+            Some(node) => Err(TreeError::CorruptedTree(format!(
+                "heap node {node} is greater than its parent"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn sift_up(&mut self, node: usize) {
+        sift_up(&mut self.values, node, |node| {
+            node.checked_sub(1).map(|node| node / 2)
+        });
+    }
+
+    fn sift_down(&mut self, node: usize) {
+        let len = self.len;
+        sift_down(&mut self.values, node, |node| {
+            [2 * node + 1, 2 * node + 2].map(|child| (child < len).then_some(child))
+        });
+    }
+}
+
+/// Swaps the value of `node` with its `parent`'s while it's greater, for max-heap ordered trees of any layout.
+/// Takes at most `values.len()` steps, so malformed links can't loop forever. Returns where the value ends.
+pub(crate) fn sift_up<T: Ord>(
+    values: &mut [T],
+    mut node: usize,
+    parent: impl Fn(usize) -> Option<usize>,
+) -> usize {
+    for _ in 0..values.len() {
+        match parent(node) {
+            Some(parent) if values[node] > values[parent] => {
+                values.swap(node, parent);
+                node = parent;
+            }
+            _ => break,
+        }
+    }
+    node
+}
+
+/// Swaps the value of `node` with its greatest child's while that's greater, for max-heap ordered trees of any
+/// layout. Takes at most `values.len()` steps, so malformed links can't loop forever. Returns where the value
+/// ends.
+pub(crate) fn sift_down<T: Ord>(
+    values: &mut [T],
+    mut node: usize,
+    children: impl Fn(usize) -> [Option<usize>; 2],
+) -> usize {
+    for _ in 0..values.len() {
+        let greatest = children(node)
+            .into_iter()
+            .flatten()
+            .fold(node, |greatest, child| {
+                if values[child] > values[greatest] {
+                    child
+                } else {
+                    greatest
+                }
+            });
+        if greatest == node {
+            break;
+        }
+        values.swap(node, greatest);
+        node = greatest;
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sized::Heap;
+
+    #[test]
+    fn push_pop() {
+        let mut heap = Heap::<u32, 8>::new();
+        for value in [5, 3, 17, 10, 84, 19, 6, 22] {
+            heap.push(value).unwrap();
+        }
+        assert_eq!(heap.push(1), Err(1));
+
+        let mut popped = Vec::new();
+        while let Some(value) = heap.pop() {
+            popped.push(value);
+        }
+        assert_eq!(popped, vec![84, 22, 19, 17, 10, 6, 5, 3]);
+        assert_eq!(heap.peek(), None);
+    }
+
+    #[test]
+    fn heapify_from() {
+        let mut heap = Heap::heapify_from([4, 10, 3, 5, 1, 10, 0]);
+        assert_eq!(heap.len(), 7);
+        heap.pop();
+        heap.push(7).unwrap();
+        let drained: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(drained, vec![10, 7, 5, 4, 3, 1, 0]);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut heap = Heap::<i8, 0>::new();
+        assert!(heap.is_full());
+        assert_eq!(heap.push(1), Err(1));
+        assert_eq!(heap.pop(), None);
+    }
+}
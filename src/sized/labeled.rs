@@ -0,0 +1,169 @@
+use std::borrow::Borrow;
+use std::ops::Deref;
+
+use crate::consts::ROOT_NODE;
+use crate::sized::Tree;
+use crate::tree::NodeId;
+
+/// [Tree] whose `M` child slots are named by labels, e.g. the branches of a decision tree, so children can be
+/// reached by their meaning instead of by position. Labels are shared by all nodes, slot `i` of every node being
+/// labeled by the `i`-th label. Labels can be any type, e.g. an enum or string slices. If labels repeat, the first
+/// slot with the label is used.
+///
+/// Dereferences to [Tree] for positional access.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sized::{LabeledTree, Tree};
+///         use treesome::tree::NodeId;
+///         static RULES: LabeledTree<&str, &str, 2, 5> = LabeledTree::new(
+///             Tree::new_checked([[1, 3, -1, -1, -1], [2, 4, -1, -1, -1]], ["raining?", "windy?", "walk", "stay", "drive"]),
+///             ["then", "else"],
+///         );
+///
+///         let windy = RULES.child_by_label(NodeId::ROOT, "then").expect("Root has a then branch");
+///         assert_eq!(RULES[windy.index()], "windy?");
+///         assert_eq!(RULES.at_labels(&["then", "else"]).map(|node| RULES[node.index()]), Some("drive"));
+///         assert_eq!(RULES.child_by_label(windy, "maybe"), None);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct LabeledTree<T, L, const M: usize, const N: usize> {
+    tree: Tree<T, M, N>,
+    labels: [L; M],
+}
+
+impl<T, L, const M: usize, const N: usize> LabeledTree<T, L, M, N> {
+    /// Labels child slots of `tree`, the `i`-th label naming slot `i`.
+    pub const fn new(tree: Tree<T, M, N>, labels: [L; M]) -> Self {
+        Self { tree, labels }
+    }
+
+    /// Label of child slot `slot`, `None` if the slot doesn't exist.
+    pub fn label(&self, slot: usize) -> Option<&L> {
+        self.labels.get(slot)
+    }
+
+    /// Child slot named by `label`, `None` for unknown labels.
+    pub fn slot<Q>(&self, label: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.labels
+            .iter()
+            .position(|candidate| candidate.borrow() == label)
+    }
+
+    /// Child of `node` in the slot named by `label`. `None` for unknown labels, missing children and nodes that
+    /// don't exist.
+    pub fn child_by_label<Q>(&self, node: NodeId, label: &Q) -> Option<NodeId>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.child_in(node, self.slot(label)?)
+    }
+
+    /// Node reached from the root by following the child named by each of `labels` in turn, the root for no
+    /// labels. `None` if a label is unknown or a node has no child under it.
+    pub fn at_labels<Q>(&self, labels: &[&Q]) -> Option<NodeId>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let root = (N > 0).then_some(NodeId::ROOT)?;
+        labels
+            .iter()
+            .try_fold(root, |node, label| self.child_by_label(node, *label))
+    }
+
+    /// Existing children of `node` with labels of their slots, in the order of slots. Empty for nodes that don't
+    /// exist.
+    pub fn labeled_children(&self, node: NodeId) -> impl Iterator<Item = (&L, NodeId)> + '_ {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter_map(move |(slot, label)| Some((label, self.child_in(node, slot)?)))
+    }
+
+    /// Labels of all child slots.
+    pub fn labels(&self) -> &[L; M] {
+        &self.labels
+    }
+
+    /// The labeled tree, without labels.
+    pub fn tree(&self) -> &Tree<T, M, N> {
+        &self.tree
+    }
+
+    /// Splits the labeled tree into the tree and its labels.
+    pub fn into_parts(self) -> (Tree<T, M, N>, [L; M]) {
+        (self.tree, self.labels)
+    }
+
+    /// Valid child of `node` in `slot`, links out of bounds or to the root are skipped.
+    fn child_in(&self, node: NodeId, slot: usize) -> Option<NodeId> {
+        let child = (*self.tree.try_children(node).ok()?.get(slot)?)?;
+        (child.index() > ROOT_NODE as usize && child.index() < N).then_some(child)
+    }
+}
+
+impl<T, L, const M: usize, const N: usize> Deref for LabeledTree<T, L, M, N> {
+    type Target = Tree<T, M, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sized::{LabeledTree, Tree};
+    use crate::tree::NodeId;
+
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    enum Branch {
+        Low,
+        Mid,
+        High,
+    }
+
+    #[test]
+    fn labeled_navigation() {
+        let tree = Tree::new(
+            [[1, -1, -1, 0], [-1, 9, -1, -1], [2, 3, -1, -1]],
+            [0, 1, 2, 3],
+        );
+        let labeled = LabeledTree::new(tree, [Branch::Low, Branch::Mid, Branch::High]);
+        assert_eq!(labeled.slot(&Branch::High), Some(2));
+        assert_eq!(labeled.label(3), None);
+        assert_eq!(
+            labeled.child_by_label(NodeId::ROOT, &Branch::Low),
+            Some(NodeId::new(1))
+        );
+        assert_eq!(labeled.child_by_label(NodeId::ROOT, &Branch::Mid), None);
+        assert_eq!(labeled.child_by_label(NodeId::new(4), &Branch::Low), None);
+        assert_eq!(
+            labeled.at_labels(&[&Branch::Low, &Branch::High]),
+            Some(NodeId::new(3))
+        );
+        assert_eq!(labeled.at_labels::<Branch>(&[]), Some(NodeId::ROOT));
+
+        // Links out of bounds or back to the root aren't children
+        assert_eq!(labeled.labeled_children(NodeId::new(1)).count(), 1);
+        assert_eq!(labeled.at_labels(&[&Branch::Low, &Branch::Mid]), None);
+        assert_eq!(labeled.child_by_label(NodeId::new(3), &Branch::Low), None);
+        assert_eq!(
+            labeled.labeled_children(NodeId::ROOT).collect::<Vec<_>>(),
+            vec![
+                (&Branch::Low, NodeId::new(1)),
+                (&Branch::High, NodeId::new(2))
+            ]
+        );
+        assert_eq!(
+            labeled.into_parts().1,
+            [Branch::Low, Branch::Mid, Branch::High]
+        );
+    }
+}
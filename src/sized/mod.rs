@@ -1,7 +1,13 @@
 pub mod btree;
+pub mod heap;
+pub mod labeled;
+#[cfg(feature = "simd")]
+mod simd;
 pub mod tree;
 
 pub mod structs;
 
 pub use btree::*;
+pub use heap::*;
+pub use labeled::*;
 pub use tree::*;
@@ -0,0 +1,51 @@
+//! Branch-free kernels for the `simd` feature. Nodes are processed in fixed-width lanes with no early exits inside
+//! a lane, a shape LLVM compiles to vector instructions on stable Rust, where `std::simd` isn't available yet.
+
+use crate::consts::LEAF_NODE;
+use crate::sized::structs::Array;
+
+const LANES: usize = 16;
+
+/// True if every link of `node` is [LEAF_NODE]. The bitwise AND of the links is [LEAF_NODE] exactly when all
+/// of them are, as it's the only link with all bits set.
+pub(crate) fn is_leaf<const N: usize>(dimensions: &[Array<isize, N>], node: usize) -> bool {
+    dimensions
+        .iter()
+        .fold(LEAF_NODE, |links, dimension| links & dimension[node])
+        == LEAF_NODE
+}
+
+/// Number of nodes with [LEAF_NODE] in all `dimensions`.
+pub(crate) fn leaf_count<const N: usize>(dimensions: &[Array<isize, N>]) -> usize {
+    let mut count = 0;
+    for start in (0..N).step_by(LANES) {
+        let end = (start + LANES).min(N);
+        let mut links = [LEAF_NODE; LANES];
+        for dimension in dimensions {
+            for (lane, &link) in links.iter_mut().zip(&dimension[start..end]) {
+                *lane &= link;
+            }
+        }
+        count += links[..end - start]
+            .iter()
+            .filter(|&&links| links == LEAF_NODE)
+            .count();
+    }
+    count
+}
+
+/// Position of the first occurrence of `value`, comparing a whole lane of values at a time.
+pub(crate) fn position<T: PartialEq>(values: &[T], value: &T) -> Option<usize> {
+    values
+        .chunks(LANES)
+        .enumerate()
+        .find(|(_, lane)| {
+            lane.iter()
+                .fold(false, |found, candidate| found | (candidate == value))
+        })
+        .and_then(|(chunk, lane)| {
+            lane.iter()
+                .position(|candidate| candidate == value)
+                .map(|offset| chunk * LANES + offset)
+        })
+}
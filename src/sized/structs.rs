@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::ops::{Deref, DerefMut};
 
 #[cfg(feature = "serde")]
 use {
@@ -9,16 +9,42 @@ use {
 };
 
 /// Serde doesn't know how to handle constant generics. [Self] serves as a zero-cost wrapper over the array.
-/// It implements [Deref], the underlying array is therefore exposed and reachable directly.
+/// It implements [Deref] and [DerefMut], the underlying array is therefore exposed and reachable directly.
+/// Transparent, so it has the memory layout of the array.
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::TransparentWrapper))]
+#[repr(transparent)]
 pub struct Array<T, const N: usize> {
     values: [T; N],
 }
 
 impl<T, const N: usize> Array<T, N> {
-    pub fn wrap(values: [T; N]) -> Self {
+    pub const fn wrap(values: [T; N]) -> Self {
         Self { values }
     }
+
+    /// The underlying array, usable in const contexts where [Deref] isn't.
+    pub const fn as_array(&self) -> &[T; N] {
+        &self.values
+    }
+
+    /// Unwraps the underlying array.
+    pub fn into_inner(self) -> [T; N] {
+        self.values
+    }
+
+    /// Moves the first `N2` values into an array of that length, see [Array::resized] for padding.
+    pub(crate) fn truncated<const N2: usize>(self) -> Array<T, N2> {
+        self.resized(|| panic!("Truncating an array of length {N} to {N2}"))
+    }
+
+    /// Moves the values into an array of length `N2`, truncating them or padding them with `fill`.
+    pub(crate) fn resized<const N2: usize>(self, mut fill: impl FnMut() -> T) -> Array<T, N2> {
+        let mut values = self.values.into_iter();
+        Array::wrap(std::array::from_fn(|_| {
+            values.next().unwrap_or_else(&mut fill)
+        }))
+    }
 }
 
 impl<T, const N: usize> From<[T; N]> for Array<T, N> {
@@ -34,6 +60,13 @@ impl<T, const N: usize> Deref for Array<T, N> {
         &self.values
     }
 }
+
+impl<T, const N: usize> DerefMut for Array<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<T: Serialize, const N: usize> Serialize for Array<T, N> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -70,16 +103,16 @@ where
         A: SeqAccess<'de>,
     {
         let mut data = Vec::<T>::with_capacity(N);
-        for _ in 0..N {
+        while data.len() < N {
             match seq.next_element()? {
                 Some(val) => data.push(val),
-                None => return Err(Error::invalid_length(N, &self)),
+                None => return Err(Error::invalid_length(data.len(), &self)),
             }
         }
 
-        let backing_array: [T; N] = data.try_into().unwrap_or_else(|v: Vec<T>| {
-            panic!("Data of length {} expected, obtained {}", N, v.len())
-        });
+        let Ok(backing_array) = <[T; N]>::try_from(data) else {
+            unreachable!("Exactly {N} elements have been read");
+        };
         Ok(Array::wrap(backing_array))
     }
 }
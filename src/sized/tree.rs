@@ -1,45 +1,87 @@
-use std::ops::Index;
+use std::ops::{Index, IndexMut};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::sized::structs::Array;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
-pub const LEAF_NODE: isize = -1;
-pub const ROOT_NODE: isize = 0;
+use crate::algo;
+pub use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::footprint::MemoryFootprint;
+use crate::sized::structs::Array;
+use crate::sized::BTree;
+use crate::sparse::Node;
+use crate::tree::{NodeId, TreeError};
+use crate::treelike::find_parent;
 
 /// Statically sized n-ary tree, using an array representation of nodes and edges internally. Suitable for dense graphs
-/// and fast serialization/deserialization.
-#[derive(Eq, PartialEq, Debug)]
+/// and fast serialization/deserialization. `Send` and `Sync` whenever `T` is.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "TreeData<T, M, N>",
+        bound(deserialize = "T: Deserialize<'de>")
+    )
+)]
 pub struct Tree<T, const M: usize, const N: usize> {
     nodes: Array<Array<isize, N>, M>,
     values: Array<T, N>,
 }
 
 impl<T, const M: usize, const N: usize> Tree<T, M, N> {
-    pub fn from_arrays(nodes: Array<Array<isize, N>, M>, values: Array<T, N>) -> Self {
+    pub const fn from_arrays(nodes: Array<Array<isize, N>, M>, values: Array<T, N>) -> Self {
         Self { nodes, values }
     }
-    pub fn new(nodes: [[isize; N]; M], values: [T; N]) -> Self {
-        let node_indices: Vec<Array<isize, N>> = nodes
-            .into_iter()
-            .map(|node| {
-                let arr: Array<isize, N> = node.into();
-                arr
-            })
-            .collect();
-        let node_indices_array: [Array<isize, N>; M] =
-            node_indices.try_into().unwrap_or_else(|_| {
-                unreachable!("Input size is guaranteed by constant generic args <M,N>")
-            });
-        let array_of_nodes: Array<Array<isize, N>, M> = node_indices_array.into();
+    pub const fn new(nodes: [[isize; N]; M], values: [T; N]) -> Self {
+        let mut dimensions = [Array::wrap([LEAF_NODE; N]); M];
+        let mut m = 0;
+        while m < M {
+            dimensions[m] = Array::wrap(nodes[m]);
+            m += 1;
+        }
         Self {
-            nodes: array_of_nodes,
-            values: values.into(),
+            nodes: Array::wrap(dimensions),
+            values: Array::wrap(values),
         }
     }
 
+    /// Constructs a new tree in const contexts, checking links like [Tree::try_new]. Panics on invalid links,
+    /// which fails the compilation when building a `const` or a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         const TREE: Tree<u8, 2, 3> = Tree::new_checked([[1, -1, -1], [2, -1, -1]], [10, 51, 36]);
+    ///
+    ///         assert_eq!(TREE.children(0), [1, 2]);
+    ///         assert_eq!(TREE[2], 36);
+    /// ```
+    pub const fn new_checked(nodes: [[isize; N]; M], values: [T; N]) -> Self {
+        assert_links(&nodes);
+        Self::new(nodes, values)
+    }
+
+    /// Constructs a new tree, checking all child indices are in bounds, the root isn't a child of any node and
+    /// no node has more than one parent. Walks from the root therefore always terminate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         use treesome::tree::TreeError;
+    ///         assert!(Tree::try_new([[1, -1, -1], [2, -1, -1]], [10, 51, 36]).is_ok());
+    ///         let out_of_bounds = Tree::try_new([[1, -1, 3], [2, -1, -1]], [10, 51, 36]);
+    ///         assert!(matches!(out_of_bounds, Err(TreeError::IndexOutOfBounds { node: 2, index: 3 })));
+    /// ```
+    pub fn try_new(nodes: [[isize; N]; M], values: [T; N]) -> Result<Self, TreeError> {
+        check_links(N, |node| nodes.iter().map(move |dimension| dimension[node]))?;
+        Ok(Self::new(nodes, values))
+    }
+
     /// True if given `node_id` is a leaf node (no children), false otherwise.
     ///
     ///
@@ -56,13 +98,83 @@ impl<T, const M: usize, const N: usize> Tree<T, M, N> {
     ///         assert!(!tree.is_leaf_node(0));
     ///         assert!(tree.is_leaf_node(4));
     /// ```
+    ///
+    /// Panics if `node_id` is out of bounds, see [Tree::try_is_leaf] for a checked variant.
     pub fn is_leaf_node(&self, node_id: usize) -> bool {
+        #[cfg(feature = "simd")]
+        return crate::sized::simd::is_leaf(&self.nodes[..], node_id);
+        #[cfg(not(feature = "simd"))]
         self.nodes
             .iter()
             .enumerate()
             .all(|(m, _)| self.nodes[m][node_id] == LEAF_NODE)
     }
 
+    /// Number of leaf nodes, i.e. nodes without any children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, 3, -1, -1], [2, -1, -1, -1]], [1, 2, 3, 4]);
+    ///         assert_eq!(tree.leaf_count(), 2);
+    /// ```
+    pub fn leaf_count(&self) -> usize {
+        #[cfg(feature = "simd")]
+        return crate::sized::simd::leaf_count(&self.nodes[..]);
+        #[cfg(not(feature = "simd"))]
+        (0..N).filter(|&node_id| self.is_leaf_node(node_id)).count()
+    }
+
+    /// Number of children of `node_id`, not counting [LEAF_NODE] placeholders and links out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, 3, -1, -1], [2, -1, -1, -1]], [1, 2, 3, 4]);
+    ///         assert_eq!(tree.child_count(0), 2);
+    ///         assert_eq!(tree.child_count(1), 1);
+    ///         assert_eq!(tree.max_degree(), 2);
+    ///         assert_eq!(tree.degree_histogram(), vec![2, 1, 1]);
+    /// ```
+    ///
+    /// Panics if `node_id` is out of bounds.
+    pub fn child_count(&self, node_id: usize) -> usize {
+        assert!(node_id < N, "Node {node_id} is out of bounds");
+        self.child_ids(node_id).count()
+    }
+
+    /// Highest number of children of a node, zero for an empty tree. See [algo::max_degree].
+    pub fn max_degree(&self) -> usize {
+        algo::max_degree(self)
+    }
+
+    /// Number of nodes by their number of children, see [algo::degree_histogram].
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        algo::degree_histogram(self)
+    }
+
+    /// Id of the first node holding `value`, `None` if there's no such node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, 3, -1, -1], [2, -1, -1, -1]], [1, 2, 3, 2]);
+    ///         assert_eq!(tree.position(&2), Some(1));
+    ///         assert_eq!(tree.position(&5), None);
+    /// ```
+    pub fn position(&self, value: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        #[cfg(feature = "simd")]
+        return crate::sized::simd::position(&self.values[..], value);
+        #[cfg(not(feature = "simd"))]
+        self.values.iter().position(|candidate| candidate == value)
+    }
+
     /// Returns an array of size [M] with node's children indices, or [LEAF_NODE] as a placeholder for every missing child.
     ///
     /// # Examples
@@ -78,22 +190,52 @@ impl<T, const M: usize, const N: usize> Tree<T, M, N> {
     ///         assert_eq!(tree.children(2), [7, 8, 9]);
     ///         assert_eq!(tree.children(6), [-1, -1, -1]); // Leaf node, no children
     /// ```
-    pub fn children(&self, node_id: usize) -> [isize; M] {
+    ///
+    /// Panics if `node_id` is out of bounds, see [Tree::try_children] for a checked variant.
+    pub const fn children(&self, node_id: usize) -> [isize; M] {
         let mut children = [0_isize; M];
-        for (m, _) in self.nodes.iter().enumerate() {
-            children[m] = self.nodes[m][node_id];
+        let mut m = 0;
+        while m < M {
+            children[m] = self.nodes.as_array()[m].as_array()[node_id];
+            m += 1;
         }
         children
     }
 
+    /// Same as [Tree::children], without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than `N`. Children returned for nodes of a tree built by [Tree::try_new] satisfy
+    /// this.
+    pub unsafe fn children_unchecked(&self, node_id: usize) -> [isize; M] {
+        debug_assert!(node_id < N, "Node {node_id} is out of bounds");
+        // SAFETY: The caller guarantees `node_id` is in bounds of all dimensions of length `N`
+        self.nodes
+            .each_ref()
+            .map(|dimension| unsafe { *dimension.get_unchecked(node_id) })
+    }
+
+    /// Value of `node_id`, without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than `N`.
+    pub unsafe fn value_unchecked(&self, node_id: usize) -> &T {
+        debug_assert!(node_id < N, "Node {node_id} is out of bounds");
+        // SAFETY: The caller guarantees `node_id` is in bounds
+        unsafe { self.values.get_unchecked(node_id) }
+    }
+
     /// Returns index of a node's parent, if the node has a parent. `None` otherwise.
     /// E.g. root nodes don't have a parent.
     ///
+    /// The node linking to `node_id` is looked up, so the answer holds whatever the layout. The parent's position
+    /// in the complete layout is checked first in O(1), other layouts fall back to a linear scan.
     ///
     /// # Examples
     ///
     /// ```
-    ///        use treesome::sized::{ROOT_NODE, Tree};
+    ///        use treesome::consts::ROOT_NODE;
+    ///        use treesome::sized::Tree;
     ///        let left = [1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
     ///        let mid = [2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
     ///        let right = [3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1];
@@ -115,11 +257,415 @@ impl<T, const M: usize, const N: usize> Tree<T, M, N> {
     ///         assert_eq!(tree.parent(10).unwrap(), 3);
     /// ```
     pub fn parent(&self, node_id: isize) -> Option<isize> {
-        if node_id <= ROOT_NODE || node_id as usize >= self.values.len() {
-            return None; // Root node doesn't have a parent.
-        };
+        let parent = self
+            .try_parent(NodeId::from_link(node_id)?)
+            .ok()
+            .flatten()?;
+        isize::try_from(parent.index()).ok()
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Links and values live inline in the tree itself.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint::inline::<Self, T>(N, M * N * size_of::<isize>())
+    }
+
+    /// Returns the value of `node`, or `None` if the node doesn't exist.
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.values.get(node.index())
+    }
+
+    /// Values of all nodes, in order of node ids.
+    pub fn values(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    /// Checked variant of [Tree::children]. Missing children are `None` instead of [LEAF_NODE], and
+    /// [TreeError::NodeOutOfBounds] is returned instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         use treesome::tree::{NodeId, TreeError};
+    ///         let tree = Tree::new([[1, -1], [-1, -1]], [1, 2]);
+    ///         assert_eq!(tree.try_children(NodeId::ROOT), Ok([Some(NodeId::new(1)), None]));
+    ///         assert_eq!(tree.try_children(NodeId::new(2)), Err(TreeError::NodeOutOfBounds { node: 2 }));
+    /// ```
+    pub fn try_children(&self, node: NodeId) -> Result<[Option<NodeId>; M], TreeError> {
+        let node = self.check_node(node)?;
+        Ok(self.children(node).map(NodeId::from_link))
+    }
+
+    /// Checked variant of [Tree::is_leaf_node]. Returns [TreeError::NodeOutOfBounds] instead of panicking.
+    pub fn try_is_leaf(&self, node: NodeId) -> Result<bool, TreeError> {
+        let node = self.check_node(node)?;
+        Ok(self.is_leaf_node(node))
+    }
+
+    /// Checked variant of [Tree::parent]. Returns `Ok(None)` for the root and unlinked nodes, and
+    /// [TreeError::NodeOutOfBounds] for nodes that don't exist.
+    pub fn try_parent(&self, node: NodeId) -> Result<Option<NodeId>, TreeError> {
+        let node = self.check_node(node)?;
+        let parent = find_parent(node, N, M, |parent| {
+            self.children(parent).contains(&(node as isize))
+        });
+        Ok(parent.map(NodeId::new))
+    }
+
+    /// First existing child of `node`, skipping [LEAF_NODE] placeholders. `None` for leaves and nodes that
+    /// don't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         use treesome::tree::NodeId;
+    ///         let tree = Tree::new([[-1, -1, -1], [1, -1, -1], [2, -1, -1]], [0, 1, 2]);
+    ///         assert_eq!(tree.first_child(NodeId::ROOT), Some(NodeId::new(1)));
+    ///         assert_eq!(tree.last_child(NodeId::ROOT), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 1), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 2), None);
+    ///         assert_eq!(tree.first_child(NodeId::new(1)), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.nth_child(node, 0)
+    }
+
+    /// Last existing child of `node`, skipping [LEAF_NODE] placeholders. `None` for leaves and nodes that
+    /// don't exist.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).last().map(NodeId::new)
+    }
+
+    /// Existing child of `node` at position `k`, counting from zero and skipping [LEAF_NODE] placeholders.
+    /// `None` if the node has at most `k` children or doesn't exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).nth(k).map(NodeId::new)
+    }
+
+    /// Valid children of `node_id`, skipping [LEAF_NODE] placeholders and links out of bounds.
+    fn child_ids(&self, node_id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .map(move |dimension| dimension[node_id])
+            .filter(|&child| child > ROOT_NODE && (child as usize) < N)
+            .map(|child| child as usize)
+    }
+
+    fn check_node(&self, node: NodeId) -> Result<usize, TreeError> {
+        if node.index() < N {
+            Ok(node.index())
+        } else {
+            Err(TreeError::NodeOutOfBounds { node: node.index() })
+        }
+    }
+
+    /// Folds values on the path from the root to `node_id`, root first. Returns `None` if `node_id` isn't
+    /// reachable from the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let left = [1, 3, -1, -1, -1];
+    ///         let right = [2, 4, -1, -1, -1];
+    ///         let values = [1, 2, 3, 4, 5];
+    ///         let tree = Tree::new([left, right], values);
+    ///
+    ///         assert_eq!(tree.path_fold(4, 0, |acc, value| acc + value), Some(1 + 2 + 5));
+    ///         assert_eq!(tree.path_fold(42, 0, |acc, value| acc + value), None);
+    /// ```
+    pub fn path_fold<A, F>(&self, node_id: usize, init: A, mut f: F) -> Option<A>
+    where
+        F: FnMut(A, &T) -> A,
+    {
+        let path = self.path_to(node_id)?;
+        Some(
+            path.into_iter()
+                .fold(init, |acc, node| f(acc, &self.values[node])),
+        )
+    }
+
+    /// Ids of nodes on the path from the root to `node_id`, both ends included. Invalid child indices are skipped.
+    fn path_to(&self, node_id: usize) -> Option<Vec<usize>> {
+        algo::path_to(N, node_id, |node| self.child_ids(node))
+    }
+
+    /// Encodes the tree as a binary tree in the left-child right-sibling form: the left link of a node points to
+    /// its first child, the right link to its next sibling. Binary tree algorithms then apply to trees of any
+    /// arity, and the encoding takes two links per node regardless of `M`. Node ids are kept, missing children in
+    /// between existing ones are skipped. [Tree::from_lcrs] decodes the tree back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::{BTree, Tree};
+    ///         let tree = Tree::new([[1, 4, -1, -1, -1], [2, -1, -1, -1, -1], [3, -1, -1, -1, -1]], ['a', 'b', 'c', 'd', 'e']);
+    ///
+    ///         let binary: BTree<char, 5> = tree.to_lcrs();
+    ///         assert_eq!(*binary.l_nodes, [1, 4, -1, -1, -1]);
+    ///         assert_eq!(*binary.r_nodes, [-1, 2, 3, -1, -1]);
+    ///         assert_eq!(Tree::from_lcrs(&binary), Ok(tree));
+    /// ```
+    pub fn to_lcrs(&self) -> BTree<T, N>
+    where
+        T: Clone,
+    {
+        let mut first_child = [LEAF_NODE; N];
+        let mut next_sibling = [LEAF_NODE; N];
+        for (node, first) in first_child.iter_mut().enumerate() {
+            let mut previous: Option<usize> = None;
+            for child in self.child_ids(node) {
+                match previous {
+                    Some(sibling) => next_sibling[sibling] = child as isize,
+                    None => *first = child as isize,
+                }
+                previous = Some(child);
+            }
+        }
+        BTree::new(first_child, next_sibling, (*self.values).clone())
+    }
+
+    /// Decodes a tree from the left-child right-sibling form, see [Tree::to_lcrs]. Children of a node take its
+    /// first dimensions, in order of the sibling chain.
+    ///
+    /// Fails like [BTree::try_new] for binary trees that aren't tree-shaped, and with [TreeError::ArityExceeded]
+    /// if a node has more than `M` children.
+    pub fn from_lcrs(binary: &BTree<T, N>) -> Result<Self, TreeError>
+    where
+        T: Clone,
+    {
+        check_links(N, |node| [binary.l_nodes[node], binary.r_nodes[node]])?;
+        let mut nodes = [[LEAF_NODE; N]; M];
+        for node in 0..N {
+            // Link checks rule out cycles along the chain, a sibling reached twice would have two parents
+            let mut siblings =
+                std::iter::successors(NodeId::from_link(binary.l_nodes[node]), |sibling| {
+                    NodeId::from_link(binary.r_nodes[sibling.index()])
+                });
+            for (dimension, child) in nodes.iter_mut().zip(&mut siblings) {
+                dimension[node] = child.index() as isize;
+            }
+            let rest = siblings.count();
+            if rest > 0 {
+                return Err(TreeError::ArityExceeded {
+                    node,
+                    children: M + rest,
+                    max: M,
+                });
+            }
+        }
+        Ok(Self::new(nodes, (*binary.values).clone()))
+    }
+
+    /// Converts the tree into one of a higher arity `M2`, the extra child slots are left empty. Node ids and
+    /// children positions are kept.
+    ///
+    /// # Panics
+    /// If `M2 < M`, see [Tree::narrow] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let ternary = Tree::new([[1, -1, -1], [-1, -1, -1], [2, -1, -1]], [0, 1, 2]);
+    ///
+    ///         let quaternary: Tree<u8, 4, 3> = ternary.clone().widen();
+    ///         assert_eq!(quaternary.children(0), [1, -1, 2, -1]);
+    ///         assert_eq!(quaternary.narrow(), Ok(ternary));
+    /// ```
+    pub fn widen<const M2: usize>(self) -> Tree<T, M2, N> {
+        assert!(M2 >= M, "Widening a tree of arity {M} to {M2}");
+        self.with_arity()
+    }
+
+    /// Converts the tree into one of arity `M2`, as long as no node has a child in the dropped slots `M2..M`.
+    /// Node ids and children positions are kept, children aren't moved into free slots.
+    ///
+    /// Fails with [TreeError::ArityExceeded] for the first node with a child in a dropped slot, reporting the
+    /// number of slots it occupies.
+    pub fn narrow<const M2: usize>(self) -> Result<Tree<T, M2, N>, TreeError> {
+        for node in 0..N {
+            let occupied = (M2..M)
+                .rev()
+                .find(|&slot| self.nodes[slot][node] != LEAF_NODE);
+            if let Some(slot) = occupied {
+                return Err(TreeError::ArityExceeded {
+                    node,
+                    children: slot + 1,
+                    max: M2,
+                });
+            }
+        }
+        Ok(self.with_arity())
+    }
+
+    /// Moves the tree into one with capacity for `N2` nodes. Node ids are kept, the extra nodes are detached
+    /// leaves with default values.
+    ///
+    /// # Panics
+    /// If `N2 < N`, see [Tree::shrink] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, -1], [-1, -1]], [4, 2]);
+    ///
+    ///         let larger: Tree<u8, 2, 4> = tree.clone().resize();
+    ///         assert_eq!(larger.values(), [4, 2, 0, 0]);
+    ///         assert_eq!(larger.children(3), [-1, -1]);
+    ///         assert_eq!(larger.shrink(), Ok(tree));
+    /// ```
+    pub fn resize<const N2: usize>(self) -> Tree<T, M, N2>
+    where
+        T: Default,
+    {
+        assert!(N2 >= N, "Resizing a tree of {N} nodes to {N2}");
+        let nodes = self
+            .nodes
+            .into_inner()
+            .map(|dimension| dimension.resized(|| LEAF_NODE));
+        Tree::from_arrays(Array::wrap(nodes), self.values.resized(T::default))
+    }
+
+    /// Moves the first `N2` nodes into a tree of that capacity, dropping the rest. Node ids are kept.
+    ///
+    /// Fails like [Tree::try_new] if the kept nodes don't form a valid tree on their own, in particular with
+    /// [TreeError::IndexOutOfBounds] if any of them links to a dropped node. Nodes reachable from the root are
+    /// therefore never dropped.
+    ///
+    /// # Panics
+    /// If `N2 > N`, see [Tree::resize] instead.
+    pub fn shrink<const N2: usize>(self) -> Result<Tree<T, M, N2>, TreeError> {
+        assert!(N2 <= N, "Shrinking a tree of {N} nodes to {N2}");
+        check_links(N2, |node| {
+            self.nodes.iter().map(move |dimension| dimension[node])
+        })?;
+        let nodes = self
+            .nodes
+            .into_inner()
+            .map(|dimension| dimension.truncated());
+        Ok(Tree::from_arrays(
+            Array::wrap(nodes),
+            self.values.truncated(),
+        ))
+    }
+
+    /// Keeps the first `M2` dimensions, padding them with empty ones up to `M2`.
+    fn with_arity<const M2: usize>(self) -> Tree<T, M2, N> {
+        let mut dimensions = self.nodes.into_inner().into_iter();
+        let nodes =
+            std::array::from_fn(|_| dimensions.next().unwrap_or(Array::wrap([LEAF_NODE; N])));
+        Tree::from_arrays(Array::wrap(nodes), self.values)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod, const M: usize, const N: usize> Tree<T, M, N> {
+    /// Views the backing arrays as bytes, without copying: links first, values second. Links are `M * N`
+    /// native-endian `isize`s, dimension after dimension, with [LEAF_NODE] for missing children. Values are the
+    /// `N` values in node id order, laid out as `[T; N]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, -1], [-1, -1]], [7_u32, 8]);
+    ///         let (links, values) = tree.as_bytes();
+    ///
+    ///         assert_eq!(links.len(), 4 * size_of::<isize>());
+    ///         assert_eq!(values, [7_u32.to_ne_bytes(), 8_u32.to_ne_bytes()].concat());
+    ///         assert_eq!(Tree::from_bytes(links, values).as_ref(), Ok(&tree));
+    /// ```
+    pub fn as_bytes(&self) -> (&[u8], &[u8]) {
+        let links: &[[isize; N]] = bytemuck::TransparentWrapper::peel_slice(self.nodes.as_slice());
+        (
+            bytemuck::cast_slice(links),
+            bytemuck::cast_slice(self.values.as_slice()),
+        )
+    }
+
+    /// Copies a tree out of bytes laid out like [Tree::as_bytes], from any alignment. Links are checked like
+    /// [Tree::try_new], byte slices of a wrong length are reported as [TreeError::LengthMismatch].
+    pub fn from_bytes(links: &[u8], values: &[u8]) -> Result<Self, TreeError> {
+        Self::try_new(read_bytes(links)?, read_bytes(values)?)
+    }
+}
+
+/// Copies a [bytemuck::Pod] out of `bytes` of exactly its size.
+#[cfg(feature = "bytemuck")]
+pub(crate) fn read_bytes<P: bytemuck::Pod>(bytes: &[u8]) -> Result<P, TreeError> {
+    bytemuck::try_pod_read_unaligned(bytes).map_err(|_| TreeError::LengthMismatch {
+        expected: size_of::<P>(),
+        found: bytes.len(),
+    })
+}
 
-        Some((node_id - 1) / M as isize)
+#[cfg(feature = "rayon")]
+impl<T: Sync, const M: usize, const N: usize> Tree<T, M, N> {
+    /// Parallel iterator over node values, in node id order. Use `enumerate()` to get node ids.
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, T> {
+        self.values.par_iter()
+    }
+
+    /// Maps every node value in parallel, keeping the tree structure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::Tree;
+    ///         let tree = Tree::new([[1, 3, -1, -1, -1], [2, 4, -1, -1, -1]], [1, 2, 3, 4, 5]);
+    ///
+    ///         let squares = tree.par_map(|value| value * value);
+    ///         assert_eq!(squares[4], 25);
+    /// ```
+    pub fn par_map<U, F>(&self, f: F) -> Tree<U, M, N>
+    where
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        let values: Vec<U> = self.values.par_iter().map(f).collect();
+        let values: [U; N] = values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("Mapping keeps the number of values"));
+        Tree {
+            nodes: self.nodes,
+            values: values.into(),
+        }
+    }
+
+    /// Folds the tree bottom-up, `f` being given a node's value and results of its children in order. Sibling
+    /// subtrees are folded in parallel. Returns `None` for an empty tree. Nodes unreachable from the root are skipped.
+    pub fn par_fold_subtrees<A, F>(&self, f: F) -> Option<A>
+    where
+        A: Send,
+        F: Fn(&T, Vec<A>) -> A + Sync,
+    {
+        crate::parallel::fold_subtrees(
+            self.values.as_slice(),
+            |node| self.child_ids(node).collect(),
+            f,
+        )
+    }
+
+    /// Folds the tree bottom-up in parallel: a node's result is `leaf` of its value, combined with results of its
+    /// children in order. As `combine` has to be associative, results of siblings are combined in parallel too.
+    /// Returns `None` for an empty tree. Nodes unreachable from the root are skipped.
+    pub fn par_fold<A, L, C>(&self, leaf: L, combine: C) -> Option<A>
+    where
+        A: Send,
+        L: Fn(&T) -> A + Sync,
+        C: Fn(A, A) -> A + Sync,
+    {
+        crate::parallel::fold_reduce(
+            self.values.as_slice(),
+            |node| self.child_ids(node).collect(),
+            leaf,
+            combine,
+        )
     }
 }
 
@@ -131,9 +677,436 @@ impl<T, const M: usize, const N: usize> Index<usize> for Tree<T, M, N> {
     }
 }
 
+impl<T, const M: usize, const N: usize> IndexMut<usize> for Tree<T, M, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+impl<T, const M: usize, const N: usize> Index<NodeId> for Tree<T, M, N> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self[node.index()]
+    }
+}
+
+impl<T, const M: usize, const N: usize> IndexMut<NodeId> for Tree<T, M, N> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self[node.index()]
+    }
+}
+
+/// Checks all child links of `len` nodes are in bounds, the root isn't a child of any node and no node has more
+/// than one parent.
+pub(crate) fn check_links<L>(len: usize, links: impl Fn(usize) -> L) -> Result<(), TreeError>
+where
+    L: IntoIterator<Item = isize>,
+{
+    let mut has_parent = vec![false; len];
+    for node in 0..len {
+        for child in links(node) {
+            if child == LEAF_NODE {
+                continue;
+            }
+            if !(0..len as isize).contains(&child) {
+                return Err(TreeError::IndexOutOfBounds { node, index: child });
+            }
+            if child == ROOT_NODE {
+                return Err(TreeError::CycleDetected { node });
+            }
+            if std::mem::replace(&mut has_parent[child as usize], true) {
+                return Err(TreeError::MultipleParents {
+                    node: child as usize,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Const counterpart of [check_links], panicking on the first invalid link instead.
+pub(crate) const fn assert_links<const M: usize, const N: usize>(nodes: &[[isize; N]; M]) {
+    let mut has_parent = [false; N];
+    let mut node = 0;
+    while node < N {
+        let mut m = 0;
+        while m < M {
+            let child = nodes[m][node];
+            if child != LEAF_NODE {
+                assert!(
+                    child >= 0 && (child as usize) < N,
+                    "Child index is out of bounds"
+                );
+                assert!(child != ROOT_NODE, "Root node can't be a child");
+                assert!(!has_parent[child as usize], "Node has multiple parents");
+                has_parent[child as usize] = true;
+            }
+            m += 1;
+        }
+        node += 1;
+    }
+}
+
+/// Copies the subtree of a sparse node into a fixed-size tree, nodes numbered in pre-order like in
+/// [Indexed](crate::sparse::Indexed), the node itself being the root. Children keep their order in the first
+/// dimensions, slots of nodes past the subtree are detached leaves with default values.
+///
+/// Sizes of a sparse tree aren't known at compile time, so the bounds are checked at runtime: fails with
+/// [TreeError::LengthMismatch] if the subtree has more than `N` nodes and with [TreeError::ArityExceeded] if a
+/// node has more than `M` children. `C` only sets how many children are stored inline and bounds neither.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::Node;
+///         use treesome::sized::Tree;
+///         let root = Node::root('a');
+///         root.create_child('b').create_child('c');
+///         root.create_child('d');
+///
+///         let tree = Tree::<char, 2, 5>::try_from(&*root).expect("Tree fits");
+///         assert_eq!(tree.children(0), [1, 3]);
+///         assert_eq!(tree.values(), ['a', 'b', 'c', 'd', '\0']);
+///         assert!(Tree::<char, 1, 5>::try_from(&*root).is_err());
+/// ```
+impl<T: Clone + Default, const C: usize, const M: usize, const N: usize> TryFrom<&Node<T, C>>
+    for Tree<T, M, N>
+{
+    type Error = TreeError;
+
+    fn try_from(root: &Node<T, C>) -> Result<Self, Self::Error> {
+        let indexed = root.indexed();
+        if indexed.nodes.len() > N {
+            return Err(TreeError::LengthMismatch {
+                expected: N,
+                found: indexed.nodes.len(),
+            });
+        }
+        let mut nodes = [[LEAF_NODE; N]; M];
+        for (node, children) in indexed.children.iter().enumerate() {
+            if children.len() > M {
+                return Err(TreeError::ArityExceeded {
+                    node,
+                    children: children.len(),
+                    max: M,
+                });
+            }
+            for (dimension, &child) in nodes.iter_mut().zip(children) {
+                dimension[node] = child as isize;
+            }
+        }
+        let mut values = indexed.nodes.iter().map(|node| node.value.clone());
+        let values = std::array::from_fn(|_| values.next().unwrap_or_default());
+        Ok(Self::new(nodes, values))
+    }
+}
+
+/// Serialized form of a [Tree], checked like [Tree::try_new] before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct TreeData<T, const M: usize, const N: usize> {
+    nodes: Array<Array<isize, N>, M>,
+    values: Array<T, N>,
+}
+
+#[cfg(feature = "serde")]
+impl<T, const M: usize, const N: usize> TryFrom<TreeData<T, M, N>> for Tree<T, M, N> {
+    type Error = TreeError;
+
+    fn try_from(data: TreeData<T, M, N>) -> Result<Self, Self::Error> {
+        check_links(N, |node| {
+            data.nodes.iter().map(move |dimension| dimension[node])
+        })?;
+        Ok(Self::from_arrays(data.nodes, data.values))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::sized::Tree;
+    use crate::sized::{BTree, Tree};
+    use crate::sparse::Node;
+    use crate::tree::{NodeId, TreeError};
+
+    static LOOKUP: Tree<&str, 3, 4> = Tree::new_checked(
+        [[1, -1, -1, -1], [2, -1, -1, -1], [3, -1, -1, -1]],
+        ["", "a", "b", "c"],
+    );
+    const BINARY: BTree<u8, 3> = BTree::new_checked([1, -1, -1], [2, -1, -1], [2, 1, 3]);
+
+    #[test]
+    fn const_construction() {
+        const CHILDREN: [isize; 3] = Tree::new([[1, -1], [-1, -1], [-1, -1]], [0, 1]).children(0);
+        assert_eq!(CHILDREN, [1, -1, -1]);
+        assert_eq!(LOOKUP.children(0), [1, 2, 3]);
+        assert_eq!(LOOKUP[3], "c");
+        assert!(BINARY.is_leaf_node(1));
+        assert_eq!(
+            BINARY,
+            BTree::try_new([1, -1, -1], [2, -1, -1], [2, 1, 3]).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Node has multiple parents")]
+    fn const_construction_shared_child() {
+        Tree::new_checked([[1, -1], [1, -1]], [0, 1]);
+    }
+
+    #[test]
+    fn leaf_scans() {
+        // Every node of the first 13 has three children, the remaining 27 are leaves
+        let nodes: [[isize; 40]; 3] = std::array::from_fn(|dimension| {
+            std::array::from_fn(|node| match node {
+                0..13 => (3 * node + dimension + 1) as isize,
+                _ => -1,
+            })
+        });
+        let values: [usize; 40] = std::array::from_fn(|node| node % 17);
+        let tree = Tree::new(nodes, values);
+
+        assert!(!tree.is_leaf_node(12));
+        assert!(tree.is_leaf_node(13));
+        assert_eq!(tree.leaf_count(), 27);
+        assert_eq!(tree.position(&16), Some(16));
+        assert_eq!(tree.position(&5), Some(5));
+        assert_eq!(tree.position(&17), None);
+        assert_eq!(Tree::<u8, 2, 0>::new([[], []], []).leaf_count(), 0);
+
+        // Negative links other than the leaf mark aren't leaves
+        let odd = Tree::new([[-2, -1], [-1, -1]], [0, 1]);
+        assert!(!odd.is_leaf_node(0));
+        assert_eq!(odd.leaf_count(), 1);
+    }
+
+    #[test]
+    fn degree_statistics() {
+        // Links out of bounds and negative links other than the leaf mark aren't children
+        let tree = Tree::new([[1, 7, -1], [2, -2, -1], [-1, -1, -1]], [0; 3]);
+        assert_eq!(tree.child_count(0), 2);
+        assert_eq!(tree.child_count(1), 0);
+        assert_eq!(tree.degree_histogram(), vec![2, 0, 1]);
+        assert_eq!(BINARY.degree_histogram(), vec![2, 0, 1]);
+
+        let empty: Tree<u8, 2, 0> = Tree::new([[], []], []);
+        assert_eq!(empty.max_degree(), 0);
+        assert!(empty.degree_histogram().is_empty());
+        assert_eq!(
+            Tree::<u8, 0, 2>::new([], [0; 2]).degree_histogram(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn positional_children() {
+        // The only real child sits behind a placeholder and next to a link out of bounds
+        let tree = Tree::new([[-1, -1, -1], [2, -1, -1], [9, -1, -1]], [0; 3]);
+        assert_eq!(tree.first_child(NodeId::ROOT), Some(NodeId::new(2)));
+        assert_eq!(tree.last_child(NodeId::ROOT), Some(NodeId::new(2)));
+        assert_eq!(tree.nth_child(NodeId::ROOT, 1), None);
+        assert_eq!(tree.first_child(NodeId::new(2)), None);
+        assert_eq!(tree.last_child(NodeId::new(3)), None);
+
+        assert_eq!(BINARY.nth_child(NodeId::ROOT, 0), Some(NodeId::new(1)));
+        assert_eq!(BINARY.last_child(NodeId::ROOT), Some(NodeId::new(2)));
+        assert_eq!(BINARY.first_child(NodeId::new(3)), None);
+    }
+
+    #[test]
+    fn lcrs_roundtrip() {
+        // Every node of the first 13 has three children, the last two of node 4 are missing
+        let nodes: [[isize; 40]; 3] = std::array::from_fn(|dimension| {
+            std::array::from_fn(|node| match node {
+                4 if dimension > 0 => -1,
+                0..13 => (3 * node + dimension + 1) as isize,
+                _ => -1,
+            })
+        });
+        let tree = Tree::new(nodes, [0_u8; 40]);
+        let binary = tree.to_lcrs();
+        assert_eq!((binary.l_nodes[1], binary.r_nodes[1]), (4, 2));
+        assert_eq!((binary.l_nodes[4], binary.r_nodes[4]), (13, 5));
+        assert_eq!((binary.l_nodes[13], binary.r_nodes[13]), (-1, -1));
+
+        let decoded = Tree::from_lcrs(&binary).expect("Encoding is tree-shaped");
+        assert_eq!(decoded, tree);
+        assert_eq!(
+            Tree::<u8, 2, 40>::from_lcrs(&binary),
+            Err(TreeError::ArityExceeded {
+                node: 0,
+                children: 3,
+                max: 2
+            })
+        );
+        assert_eq!(
+            Tree::<u8, 1, 40>::from_lcrs(&binary),
+            Err(TreeError::ArityExceeded {
+                node: 0,
+                children: 3,
+                max: 1
+            })
+        );
+        assert_eq!(
+            Tree::<u8, 3, 2>::from_lcrs(&BTree::new([1, -1], [-1, 0], [0, 1])),
+            Err(TreeError::CycleDetected { node: 1 })
+        );
+        assert_eq!(
+            Tree::<u8, 3, 3>::from_lcrs(&BTree::new([1, -1, -1], [-1, 2, 1], [0, 1, 2])),
+            Err(TreeError::MultipleParents { node: 1 })
+        );
+    }
+
+    #[test]
+    fn arity_migration() {
+        let nodes: [[isize; 40]; 2] = std::array::from_fn(|dimension| {
+            std::array::from_fn(|node| {
+                if node < 19 {
+                    (2 * node + dimension + 1) as isize
+                } else {
+                    -1
+                }
+            })
+        });
+        let binary = Tree::new(nodes, [1.5_f32; 40]);
+
+        let wide: Tree<f32, 5, 40> = binary.clone().widen();
+        assert_eq!(wide.children(3), [7, 8, -1, -1, -1]);
+        assert_eq!(wide.max_degree(), 2);
+        assert_eq!(wide.clone().narrow::<2>(), Ok(binary.clone()));
+        assert_eq!(
+            wide.narrow::<1>(),
+            Err(TreeError::ArityExceeded {
+                node: 0,
+                children: 2,
+                max: 1
+            })
+        );
+
+        let sparse = Tree::new([[1, -1, -1], [-1, -1, -1], [2, -1, -1]], [0_u8, 1, 2]);
+        assert_eq!(
+            sparse.narrow::<2>(),
+            Err(TreeError::ArityExceeded {
+                node: 0,
+                children: 3,
+                max: 2
+            })
+        );
+    }
+
+    #[test]
+    fn capacity_migration() {
+        let nodes: [[isize; 13]; 3] = std::array::from_fn(|dimension| {
+            std::array::from_fn(|node| match node {
+                0..=3 => (3 * node + dimension + 1) as isize,
+                _ => -1,
+            })
+        });
+        let tree = Tree::new(nodes, [7_u8; 13]);
+
+        let larger: Tree<u8, 3, 40> = tree.clone().resize();
+        assert_eq!(larger.leaf_count(), 36);
+        assert_eq!(larger.values()[13..], [0; 27]);
+        assert_eq!(larger.shrink(), Ok(tree.clone()));
+        assert_eq!(
+            tree.shrink::<12>(),
+            Err(TreeError::IndexOutOfBounds { node: 3, index: 12 })
+        );
+    }
+
+    #[test]
+    fn from_sparse() {
+        let root = Node::root(0_u32);
+        let mut parent = root.clone();
+        for value in 1..20 {
+            parent.create_child(value * 100);
+            parent = parent.create_child(value);
+        }
+
+        let tree = Tree::<u32, 2, 40>::try_from(&*root).expect("Chain with leaves fits");
+        assert_eq!(tree.children(0), [1, 2]);
+        assert_eq!(tree.children(2), [3, 4]);
+        assert_eq!(tree[37], 1900);
+        assert_eq!(tree.leaf_count(), 21);
+        assert_eq!(tree.max_degree(), 2);
+
+        let subtree = Tree::<u32, 2, 3>::try_from(&*root.children()[1].children()[1]);
+        assert_eq!(
+            subtree,
+            Err(TreeError::LengthMismatch {
+                expected: 3,
+                found: 35
+            })
+        );
+        root.create_child(2000);
+        assert_eq!(
+            Tree::<u32, 2, 40>::try_from(&*root),
+            Err(TreeError::ArityExceeded {
+                node: 0,
+                children: 3,
+                max: 2
+            })
+        );
+    }
+
+    #[test]
+    fn parent_boundaries() {
+        let tree = Tree::new([[1, 3, -1, -1, -1], [2, 4, -1, -1, -1]], [0; 5]);
+        for node_id in [isize::MIN, -2, -1, 0, 5, isize::MAX] {
+            assert_eq!(tree.parent(node_id), None);
+        }
+        assert_eq!(tree.parent(4), Some(1));
+
+        let no_dimensions: Tree<u8, 0, 3> = Tree::new([], [0; 3]);
+        assert_eq!(no_dimensions.parent(2), None);
+        assert_eq!(no_dimensions.try_parent(NodeId::new(2)), Ok(None));
+    }
+
+    #[test]
+    fn parent_outside_complete_layout() {
+        // A chain 0 -> 2 -> 1, with node 3 left unlinked
+        let tree = Tree::new([[2, -1, 1, -1]], ['a', 'b', 'c', 'd']);
+        assert_eq!(tree.parent(1), Some(2));
+        assert_eq!(tree.try_parent(NodeId::new(2)), Ok(Some(NodeId::ROOT)));
+        assert_eq!(tree.try_parent(NodeId::new(3)), Ok(None));
+    }
+
+    #[test]
+    fn checked_accessors() {
+        let tree = Tree::new([[1, 3, -1, -1, -1], [2, 4, -1, -1, -1]], [0, 1, 2, 3, 4]);
+        assert_eq!(tree.get(NodeId::new(4)), Some(&4));
+        assert_eq!(tree.get(NodeId::new(5)), None);
+        assert_eq!(tree.try_is_leaf(NodeId::new(2)), Ok(true));
+        assert_eq!(tree.try_parent(NodeId::ROOT), Ok(None));
+        assert_eq!(tree.try_parent(NodeId::new(4)), Ok(Some(NodeId::new(1))));
+        assert_eq!(
+            tree.try_children(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+        assert_eq!(
+            tree.try_is_leaf(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+        assert_eq!(
+            tree.try_parent(NodeId::new(5)),
+            Err(TreeError::NodeOutOfBounds { node: 5 })
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel() {
+        let left = [1, 3, 5, -1, -1, -1, -1];
+        let right = [2, 4, 6, -1, -1, -1, -1];
+        let tree = Tree::new([left, right], [10, 51, 36, 90, 32, 16, 5]);
+
+        let sum = tree
+            .par_fold_subtrees(|value, children: Vec<i32>| value + children.iter().sum::<i32>());
+        assert_eq!(sum, Some(240));
+        assert_eq!(tree.par_fold(|value| *value, |a, b| a + b), Some(240));
+        assert_eq!(tree.par_map(|value| value % 10).children(2), [5, 6]);
+        assert_eq!(tree.par_map(|value| value % 10)[3], 0);
+    }
 
     #[test]
     fn index() {
@@ -146,16 +1119,51 @@ mod tests {
         assert_eq!(tree[3], 4);
     }
 
-    #[cfg(feature = "serde")]
     #[test]
-    fn serde() {
+    fn path_fold() {
         let left = [1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
         let mid = [2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
-        let right = [3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1];
+        let right = [3, 6, 9, -1, -1, -1, -1, -1, -1, -1, -1, -1];
         let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
         let tree = Tree::new([left, mid, right], values);
+
+        assert_eq!(tree.path_fold(0, 0, |acc, value| acc + value), Some(1));
+        assert_eq!(
+            tree.path_fold(8, 0, |acc, value| acc + value),
+            Some(1 + 3 + 9)
+        );
+        assert_eq!(
+            tree.path_fold(11, 0, |max, value| max.max(*value)),
+            Some(12)
+        );
+        assert_eq!(tree.path_fold(12, 0, |acc, value| acc + value), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let left = [1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let mid = [2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let right = [3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+        let tree = Tree::new([left, mid, right], values);
         let string_repr = serde_json::to_string(&tree).unwrap();
-        let deserialized_tree = serde_json::from_str::<Tree<i32, 3, 12>>(&string_repr).unwrap();
+        let deserialized_tree = serde_json::from_str::<Tree<i32, 3, 13>>(&string_repr).unwrap();
         assert_eq!(tree, deserialized_tree);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_malformed() {
+        for malformed in [
+            r#"{"nodes":[[1,-1,3],[2,-1,-1]],"values":[1,2,3]}"#,
+            r#"{"nodes":[[1,-1,0],[2,-1,-1]],"values":[1,2,3]}"#,
+            r#"{"nodes":[[1,2,-1],[2,-1,-1]],"values":[1,2,3]}"#,
+            r#"{"nodes":[[1,-1],[2,-1,-1]],"values":[1,2,3]}"#,
+            r#"{"nodes":[[1,-1,-1],[2,-1,-1]],"values":[1,2]}"#,
+            r#"{"nodes":[[1,-1,-1]],"values":[1,2,3]}"#,
+        ] {
+            assert!(serde_json::from_str::<Tree<u8, 2, 3>>(malformed).is_err());
+        }
+    }
 }
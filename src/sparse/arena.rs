@@ -0,0 +1,264 @@
+use std::ops::{Index, IndexMut};
+
+use crate::footprint::MemoryFootprint;
+use crate::tree::{NodeId, TreeIndex};
+
+/// Growable, non-shrinkable n-ary tree like [Node](crate::sparse::Node), with all nodes in a single allocation.
+/// Nodes link each other by `u32` indices instead of reference counted pointers, so a node costs 16 bytes on top
+/// of its value and building a tree allocates only when the arena grows.
+///
+/// Nodes are identified by [NodeId]s, the root being [NodeId::ROOT]. Ids of other nodes follow creation order.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::ArenaTree;
+///         use treesome::tree::NodeId;
+///         let mut tree = ArenaTree::root("root");
+///         let first = tree.create_child(NodeId::ROOT, "first");
+///         tree.create_child(NodeId::ROOT, "second");
+///         tree.create_child(first, "grandchild");
+///
+///         let names: Vec<&str> = tree.children(NodeId::ROOT).map(|child| tree[child]).collect();
+///         assert_eq!(names, vec!["first", "second"]);
+///         assert_eq!(tree.parent(first), Some(NodeId::ROOT));
+///         assert_eq!(tree.len(), 4);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ArenaTree<T> {
+    nodes: Vec<ArenaNode<T>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct ArenaNode<T> {
+    parent: u32,
+    first_child: u32,
+    last_child: u32,
+    next_sibling: u32,
+    value: T,
+}
+
+impl<T> ArenaTree<T> {
+    /// Starts a new tree by creating a root node with no parent.
+    pub fn root(value: T) -> Self {
+        Self::with_capacity(value, 1)
+    }
+
+    /// Starts a new tree with room for `capacity` nodes, the root included.
+    pub fn with_capacity(value: T, capacity: usize) -> Self {
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        nodes.push(ArenaNode::new(u32::NONE, value));
+        Self { nodes }
+    }
+
+    /// Creates a new child of `parent`, placed after its existing children, and returns its id.
+    ///
+    /// Panics if `parent` doesn't exist or the arena already holds `u32::MAX` nodes.
+    pub fn create_child(&mut self, parent: NodeId, value: T) -> NodeId {
+        assert!(
+            parent.index() < self.nodes.len(),
+            "Parent {parent} doesn't exist"
+        );
+        let child =
+            u32::from_node(self.nodes.len()).expect("Arena holds fewer than u32::MAX nodes");
+        let parent_link = parent.index() as u32;
+        self.nodes.push(ArenaNode::new(parent_link, value));
+
+        let parent_node = &mut self.nodes[parent.index()];
+        match parent_node.last_child.node() {
+            None => parent_node.first_child = child,
+            Some(last) => self.nodes[last].next_sibling = child,
+        }
+        self.nodes[parent.index()].last_child = child;
+        NodeId::new(child as usize)
+    }
+
+    /// Unlinks `node` from its parent. The arena doesn't shrink: the subtree keeps its ids and values, but isn't
+    /// reachable from the root anymore and `node` has no parent. Does nothing for the root and nodes that don't
+    /// exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sparse::ArenaTree;
+    ///         use treesome::tree::NodeId;
+    ///         let mut tree = ArenaTree::root(0);
+    ///         let a = tree.create_child(NodeId::ROOT, 1);
+    ///         let b = tree.create_child(NodeId::ROOT, 2);
+    ///
+    ///         tree.detach(a);
+    ///         assert_eq!(tree.children(NodeId::ROOT).collect::<Vec<_>>(), vec![b]);
+    ///         assert_eq!(tree.parent(a), None);
+    /// ```
+    pub fn detach(&mut self, node: NodeId) {
+        let Some(parent) = self.parent(node) else {
+            return;
+        };
+        let next = self.nodes[node.index()].next_sibling;
+        let previous = self
+            .children(parent)
+            .take_while(|&child| child != node)
+            .last();
+        match previous {
+            Some(previous) => self.nodes[previous.index()].next_sibling = next,
+            None => self.nodes[parent.index()].first_child = next,
+        }
+        if next == u32::NONE {
+            self.nodes[parent.index()].last_child =
+                previous.map_or(u32::NONE, |previous| previous.index() as u32);
+        }
+        let detached = &mut self.nodes[node.index()];
+        detached.parent = u32::NONE;
+        detached.next_sibling = u32::NONE;
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let links = self.nodes.len() * (size_of::<ArenaNode<T>>() - size_of::<T>());
+        MemoryFootprint {
+            structure: size_of::<Self>() + links,
+            values: self.nodes.len() * size_of::<T>(),
+            overhead: (self.nodes.capacity() - self.nodes.len()) * size_of::<ArenaNode<T>>(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always false, the root can't be removed.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.nodes.get(node.index()).map(|node| &node.value)
+    }
+
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut T> {
+        self.nodes.get_mut(node.index()).map(|node| &mut node.value)
+    }
+
+    /// Returns node's parent, `None` for the root and nodes that don't exist.
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes.get(node.index())?.parent.node().map(NodeId::new)
+    }
+
+    /// Children of `node` in creation order, none for nodes that don't exist.
+    pub fn children(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let first = self
+            .nodes
+            .get(node.index())
+            .and_then(|node| node.first_child.node());
+        std::iter::successors(first, |&child| self.nodes[child].next_sibling.node())
+            .map(NodeId::new)
+    }
+
+    /// True if `node` has no children, including nodes that don't exist.
+    pub fn is_leaf(&self, node: NodeId) -> bool {
+        self.children(node).next().is_none()
+    }
+
+    /// First child of `node`, `None` for leaves and nodes that don't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sparse::ArenaTree;
+    ///         use treesome::tree::NodeId;
+    ///         let mut tree = ArenaTree::root("root");
+    ///         let first = tree.create_child(NodeId::ROOT, "first");
+    ///         let second = tree.create_child(NodeId::ROOT, "second");
+    ///
+    ///         assert_eq!(tree.first_child(NodeId::ROOT), Some(first));
+    ///         assert_eq!(tree.last_child(NodeId::ROOT), Some(second));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 1), Some(second));
+    ///         assert_eq!(tree.nth_child(first, 0), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.children(node).next()
+    }
+
+    /// Last child of `node`, `None` for leaves and nodes that don't exist. The node links its last child, so no
+    /// siblings are walked.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.nodes.get(node.index())?;
+        node.last_child.node().map(NodeId::new)
+    }
+
+    /// Child of `node` at position `k`, counting from zero. `None` if the node has at most `k` children or doesn't
+    /// exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        self.children(node).nth(k)
+    }
+
+    /// Values in order of node ids, the root first.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.nodes.iter().map(|node| &node.value)
+    }
+}
+
+impl<T> ArenaNode<T> {
+    fn new(parent: u32, value: T) -> Self {
+        Self {
+            parent,
+            first_child: u32::NONE,
+            last_child: u32::NONE,
+            next_sibling: u32::NONE,
+            value,
+        }
+    }
+}
+
+impl<T> Index<NodeId> for ArenaTree<T> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self.nodes[node.index()].value
+    }
+}
+
+impl<T> IndexMut<NodeId> for ArenaTree<T> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self.nodes[node.index()].value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::ArenaTree;
+    use crate::tree::NodeId;
+
+    #[test]
+    fn grow() {
+        let mut tree = ArenaTree::with_capacity(0, 8);
+        let a = tree.create_child(NodeId::ROOT, 1);
+        let b = tree.create_child(NodeId::ROOT, 2);
+        let a0 = tree.create_child(a, 3);
+        let c = tree.create_child(NodeId::ROOT, 4);
+        tree[a0] += 10;
+
+        assert_eq!(
+            tree.children(NodeId::ROOT).collect::<Vec<_>>(),
+            vec![a, b, c]
+        );
+        assert_eq!(tree.children(a).collect::<Vec<_>>(), vec![a0]);
+        assert!(tree.is_leaf(b));
+        assert!(tree.is_leaf(NodeId::new(42)));
+        assert_eq!(tree.parent(NodeId::ROOT), None);
+        assert_eq!(tree.parent(a0), Some(a));
+        assert_eq!(tree.parent(NodeId::new(42)), None);
+        assert_eq!(tree.get(a0), Some(&13));
+        assert_eq!(
+            tree.values().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 13, 4]
+        );
+        assert!(!tree.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Parent #3 doesn't exist")]
+    fn missing_parent() {
+        ArenaTree::root(0).create_child(NodeId::new(3), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use std::rc::Rc;
+
+use crate::sparse::Node;
+
+/// Navigates a [Node] tree, remembering the path of child indices from the root. As nodes can't be detached
+/// and children are only ever appended, the path stays valid while the tree grows. The cursor holds the root,
+/// keeping the whole tree alive.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::{Cursor, Node};
+///         let root = Node::root(0);
+///         let first = root.create_child(1);
+///         root.create_child(2);
+///         first.create_child(3);
+///
+///         let mut cursor = Cursor::new(root);
+///         assert_eq!(cursor.descend(0), Some(&1));
+///         assert_eq!(cursor.descend(0), Some(&3));
+///         assert_eq!(cursor.path(), &[0, 0]);
+///         assert_eq!(cursor.ascend(), Some(&1));
+///         assert_eq!(cursor.sibling(1), Some(&2));
+///         assert_eq!(cursor.path(), &[1]);
+/// ```
+pub struct Cursor<T, const C: usize = 0> {
+    root: Rc<Node<T, C>>,
+    node: Rc<Node<T, C>>,
+    path: Vec<usize>,
+}
+
+/// Clones the position, the cursor points at the same nodes.
+impl<T, const C: usize> Clone for Cursor<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            root: Rc::clone(&self.root),
+            node: Rc::clone(&self.node),
+            path: self.path.clone(),
+        }
+    }
+}
+
+impl<T, const C: usize> Cursor<T, C> {
+    /// Creates a cursor pointing at `node`. The path from the root is reconstructed from parent links.
+    pub fn new(node: Rc<Node<T, C>>) -> Self {
+        let mut path = Vec::new();
+        let mut current = node.clone();
+        while let Some(parent) = current.parent() {
+            let index = parent
+                .children
+                .borrow()
+                .iter()
+                .position(|child| Rc::ptr_eq(child, &current))
+                .expect("Node is among its parent's children");
+            path.push(index);
+            current = parent;
+        }
+        path.reverse();
+
+        Self {
+            root: current,
+            node,
+            path,
+        }
+    }
+
+    /// Node the cursor points at.
+    pub fn node(&self) -> &Rc<Node<T, C>> {
+        &self.node
+    }
+
+    pub fn value(&self) -> &T {
+        &self.node.value
+    }
+
+    /// Indices of children leading from the root to the current node. Empty for the root.
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    /// Moves to the child at `child_index`. If there's no such child, the cursor stays in place and `None` is returned.
+    pub fn descend(&mut self, child_index: usize) -> Option<&T> {
+        let child = self.node.children.borrow().get(child_index)?.clone();
+        self.node = child;
+        self.path.push(child_index);
+        Some(&self.node.value)
+    }
+
+    /// Moves to the parent. If there's none, the cursor stays in place and `None` is returned.
+    pub fn ascend(&mut self) -> Option<&T> {
+        let parent = self.node.parent()?;
+        self.node = parent;
+        self.path.pop();
+        Some(&self.node.value)
+    }
+
+    /// Moves to a sibling `offset` positions away, e.g. `1` for the next and `-1` for the previous sibling.
+    /// If there's no such sibling, the cursor stays in place and `None` is returned.
+    pub fn sibling(&mut self, offset: isize) -> Option<&T> {
+        let parent = self.node.parent()?;
+        let index = self.path.last()?.checked_add_signed(offset)?;
+        let sibling = parent.children.borrow().get(index)?.clone();
+        self.node = sibling;
+        *self.path.last_mut().expect("Non-root node has a path") = index;
+        Some(&self.node.value)
+    }
+
+    /// Moves to the root of the tree.
+    pub fn root(&mut self) -> &T {
+        self.node = self.root.clone();
+        self.path.clear();
+        &self.node.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::{Cursor, Node};
+
+    #[test]
+    fn navigation() {
+        let root = Node::root("root");
+        let a = root.create_child("a");
+        root.create_child("b");
+        let c = root.create_child("c");
+        let a0 = a.create_child("a0");
+
+        let mut cursor = Cursor::new(a0.clone());
+        drop(root);
+        assert_eq!(cursor.path(), &[0, 0]);
+        assert_eq!(cursor.value(), &"a0");
+        assert_eq!(cursor.descend(0), None);
+        assert_eq!(cursor.sibling(1), None);
+        assert_eq!(cursor.ascend(), Some(&"a"));
+        assert_eq!(cursor.sibling(-1), None);
+        assert_eq!(cursor.sibling(2), Some(&"c"));
+        assert_eq!(cursor.path(), &[2]);
+        assert_eq!(cursor.sibling(-1), Some(&"b"));
+
+        // Children created after the cursor was positioned are reachable
+        c.create_child("c0");
+        assert_eq!(cursor.sibling(1), Some(&"c"));
+        assert_eq!(cursor.descend(0), Some(&"c0"));
+        assert_eq!(cursor.path(), &[2, 0]);
+
+        assert_eq!(cursor.root(), &"root");
+        assert!(cursor.path().is_empty());
+        assert_eq!(cursor.ascend(), None);
+        assert_eq!(cursor.sibling(1), None);
+    }
+}
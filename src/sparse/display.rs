@@ -0,0 +1,196 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::rc::Rc;
+
+use crate::sparse::Node;
+
+/// Levels below the formatted node printed by [Debug], children of deeper nodes are elided as `..`.
+const DEBUG_DEPTH: usize = 32;
+
+enum Step<T, const C: usize> {
+    Child(Rc<Node<T, C>>, usize, bool),
+    Close(usize),
+}
+
+/// Formats the subtree like a derived implementation would, `{:#?}` printing a node per line. Written with an
+/// explicit stack, so deep trees don't overflow it, and limited to [DEBUG_DEPTH] levels to keep the output
+/// readable. Parent links aren't printed.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::Node;
+///         let root = Node::root(1);
+///         root.create_child(2).create_child(3);
+///
+///         assert_eq!(
+///             format!("{root:?}"),
+///             "Node { value: 1, children: [Node { value: 2, children: [Node { value: 3, children: [] }] }] }"
+///         );
+/// ```
+impl<T: Debug, const C: usize> Debug for Node<T, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut stack = Vec::new();
+        self.debug_open(f, 0, &mut stack)?;
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Child(node, depth, first) => {
+                    match (f.alternate(), first) {
+                        (true, true) => newline(f, 2 * depth)?,
+                        (true, false) => {
+                            f.write_str(",")?;
+                            newline(f, 2 * depth)?;
+                        }
+                        (false, true) => {}
+                        (false, false) => f.write_str(", ")?,
+                    }
+                    node.debug_open(f, depth, &mut stack)?;
+                }
+                Step::Close(depth) if f.alternate() => {
+                    f.write_str(",")?;
+                    newline(f, 2 * depth + 1)?;
+                    f.write_str("],")?;
+                    newline(f, 2 * depth)?;
+                    f.write_str("}")?;
+                }
+                Step::Close(_) => f.write_str("] }")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Debug, const C: usize> Node<T, C> {
+    /// Writes the node up to its first child, queueing the children and the closing brackets on `stack`.
+    fn debug_open(
+        &self,
+        f: &mut Formatter<'_>,
+        depth: usize,
+        stack: &mut Vec<Step<T, C>>,
+    ) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("Node {")?;
+            newline(f, 2 * depth + 1)?;
+            write!(f, "value: {:?},", self.value)?;
+            newline(f, 2 * depth + 1)?;
+            f.write_str("children: [")?;
+        } else {
+            write!(f, "Node {{ value: {:?}, children: [", self.value)?;
+        }
+
+        let children = self.children();
+        if !children.is_empty() && depth < DEBUG_DEPTH {
+            stack.push(Step::Close(depth));
+            stack.extend(
+                children
+                    .into_iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(index, child)| Step::Child(child, depth + 1, index == 0)),
+            );
+            return Ok(());
+        }
+        let contents = if children.is_empty() { "" } else { ".." };
+        if f.alternate() {
+            write!(f, "{contents}],")?;
+            newline(f, 2 * depth)?;
+            f.write_str("}")
+        } else {
+            write!(f, "{contents}] }}")
+        }
+    }
+}
+
+fn newline(f: &mut Formatter<'_>, level: usize) -> fmt::Result {
+    f.write_str("\n")?;
+    (0..level).try_for_each(|_| f.write_str("    "))
+}
+
+/// Prints the subtree one node per line in pre-order, each indented by four spaces per level below this node.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::Node;
+///         let root = Node::root("root");
+///         root.create_child("first").create_child("grandchild");
+///         root.create_child("second");
+///
+///         assert_eq!(root.to_string(), "root\n    first\n        grandchild\n    second");
+/// ```
+impl<T: Display, const C: usize> Display for Node<T, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)?;
+        let mut stack: Vec<(Rc<Self>, usize)> = self
+            .children()
+            .into_iter()
+            .rev()
+            .map(|child| (child, 1))
+            .collect();
+        while let Some((node, depth)) = stack.pop() {
+            newline(f, depth)?;
+            write!(f, "{}", node.value)?;
+            stack.extend(
+                node.children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::Node;
+
+    #[test]
+    fn debug_pretty() {
+        let root = Node::root(1);
+        root.create_child(2);
+        root.create_child(3).create_child(4);
+
+        let expected = "\
+Node {
+    value: 1,
+    children: [
+        Node {
+            value: 2,
+            children: [],
+        },
+        Node {
+            value: 3,
+            children: [
+                Node {
+                    value: 4,
+                    children: [],
+                },
+            ],
+        },
+    ],
+}";
+        assert_eq!(format!("{root:#?}"), expected);
+    }
+
+    #[test]
+    fn debug_deep_chain() {
+        let root = Node::root(0);
+        let mut leaf = root.clone();
+        for depth in 1..=100_000 {
+            leaf = leaf.create_child(depth);
+        }
+
+        let debug = format!("{root:?}");
+        assert_eq!(debug.matches("Node {").count(), super::DEBUG_DEPTH + 1);
+        assert!(debug.contains("value: 32, children: [..] }"));
+
+        let root = Node::root(0);
+        let mut leaf = root.clone();
+        for depth in 1..=1_000 {
+            leaf = leaf.create_child(depth);
+        }
+        let display = root.to_string();
+        assert_eq!(display.lines().count(), 1_001);
+        assert!(display.ends_with(&format!("\n{}1000", "    ".repeat(1_000))));
+    }
+}
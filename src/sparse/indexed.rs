@@ -0,0 +1,70 @@
+use std::rc::Rc;
+
+use crate::sparse::Node;
+
+/// Nodes of a sparse tree numbered in pre-order, the node it was made from being `0`, so the tree can be used
+/// as a [TreeLike](crate::TreeLike). Holds the nodes, which stay shared with the tree; children created later
+/// aren't numbered.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::Node;
+///         use treesome::TreeLike;
+///         let root = Node::root("root");
+///         root.create_child("first").create_child("grandchild");
+///         root.create_child("second");
+///
+///         let indexed = root.indexed();
+///         assert_eq!(TreeLike::children(&indexed, 0), vec![1, 3]);
+///         assert_eq!(*indexed.value(2), "grandchild");
+///         assert_eq!(TreeLike::parent(&indexed, 2), Some(1));
+/// ```
+pub struct Indexed<T, const C: usize = 0> {
+    pub(crate) nodes: Vec<Rc<Node<T, C>>>,
+    pub(crate) parents: Vec<Option<usize>>,
+    pub(crate) children: Vec<Vec<usize>>,
+}
+
+/// Shares the nodes, values aren't cloned.
+impl<T, const C: usize> Clone for Indexed<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            parents: self.parents.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl<T, const C: usize> Node<T, C> {
+    /// Numbers the nodes of the subtree of this node, see [Indexed].
+    pub fn indexed(&self) -> Indexed<T, C> {
+        let mut indexed = Indexed {
+            nodes: Vec::new(),
+            parents: Vec::new(),
+            children: Vec::new(),
+        };
+        let root = self
+            .this
+            .upgrade()
+            .unwrap_or_else(|| unreachable!("Node is alive while borrowed"));
+        let mut stack: Vec<(Rc<Self>, Option<usize>)> = vec![(root, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let id = indexed.nodes.len();
+            if let Some(parent) = parent {
+                indexed.children[parent].push(id);
+            }
+            stack.extend(
+                node.children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, Some(id))),
+            );
+            indexed.nodes.push(node);
+            indexed.parents.push(parent);
+            indexed.children.push(Vec::new());
+        }
+        indexed
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Sequence keeping up to `C` items inline, moving them to the heap once the `C + 1`-st one is pushed. Inline slots
+/// are `Option`s, so e.g. `Rc` items take no more space than in a plain array.
+pub(crate) enum InlineVec<T, const C: usize> {
+    Inline { len: usize, items: [Option<T>; C] },
+    Heap(Vec<T>),
+}
+
+impl<T, const C: usize> InlineVec<T, C> {
+    pub(crate) fn new() -> Self {
+        Self::Inline {
+            len: 0,
+            items: std::array::from_fn(|_| None),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Inline { len, .. } => *len,
+            Self::Heap(items) => items.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&T> {
+        match self {
+            Self::Inline { len, items } => items[..*len].get(index)?.as_ref(),
+            Self::Heap(items) => items.get(index),
+        }
+    }
+
+    pub(crate) fn push(&mut self, item: T) {
+        match self {
+            Self::Inline { len, items } if *len < C => {
+                items[*len] = Some(item);
+                *len += 1;
+            }
+            Self::Inline { items, .. } => {
+                let mut spilled = Vec::with_capacity(C * 2 + 1);
+                spilled.extend(items.iter_mut().filter_map(Option::take));
+                spilled.push(item);
+                *self = Self::Heap(spilled);
+            }
+            Self::Heap(items) => items.push(item),
+        }
+    }
+
+    /// Heap buffer of the items, if they don't fit inline.
+    pub(crate) fn spilled(&self) -> Option<&Vec<T>> {
+        match self {
+            Self::Inline { .. } => None,
+            Self::Heap(items) => Some(items),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Removes all items, leaving the storage in place.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        let (inline, heap) = match self {
+            Self::Inline { len, items } => {
+                let taken = &mut items[..*len];
+                *len = 0;
+                (Some(taken.iter_mut().filter_map(Option::take)), None)
+            }
+            Self::Heap(items) => (None, Some(items.drain(..))),
+        };
+        inline
+            .into_iter()
+            .flatten()
+            .chain(heap.into_iter().flatten())
+    }
+}
+
+impl<T, const C: usize> FromIterator<T> for InlineVec<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut items = Self::new();
+        for item in iter {
+            items.push(item);
+        }
+        items
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize, const C: usize> Serialize for InlineVec<T, C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, const C: usize> Deserialize<'de> for InlineVec<T, C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::inline::InlineVec;
+
+    #[test]
+    fn spill() {
+        let mut items: InlineVec<u8, 2> = InlineVec::new();
+        items.push(1);
+        items.push(2);
+        assert!(matches!(items, InlineVec::Inline { len: 2, .. }));
+        items.push(3);
+        assert!(matches!(items, InlineVec::Heap(_)));
+        assert_eq!(items.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(items.get(2), Some(&3));
+        assert_eq!(items.drain().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(items.is_empty());
+
+        let mut inline: InlineVec<u8, 4> = (0..3).collect();
+        assert_eq!(inline.get(3), None);
+        assert_eq!(inline.drain().count(), 3);
+        assert_eq!(inline.len(), 0);
+
+        let mut none: InlineVec<u8, 0> = InlineVec::new();
+        none.push(1);
+        assert!(matches!(none, InlineVec::Heap(_)));
+    }
+}
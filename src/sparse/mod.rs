@@ -4,6 +4,18 @@ use std::rc::{Rc, Weak};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+mod arena;
+mod cursor;
+mod display;
+mod indexed;
+mod inline;
+pub use arena::*;
+pub use cursor::*;
+pub use indexed::*;
+
+use crate::footprint::MemoryFootprint;
+use inline::InlineVec;
+
 /// A growable, non-shrinkable n-ary tree. Traversable in both ways. Suitable for sparse tree structures, at the cost of extra
 /// runtime overhead (reference counting).
 ///
@@ -25,10 +37,14 @@ use serde::{Deserialize, Serialize};
 /// without the child -> parent link should be implemented to make the resulting structure more compact.
 /// In cases where the resulting tree is "dense enough", converting it to [crate::tree::Tree] would be the most efficient.
 ///
+/// ## Inline children
+/// Up to `C` children are stored inline in the node, saving the allocation of a children vector when the fan-out
+/// is small. The default of zero keeps all children on the heap. See [Node::inline_root].
+///
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Node<T> {
-    parent: Option<Weak<Node<T>>>,
-    children: RefCell<Vec<Rc<Node<T>>>>,
+pub struct Node<T, const C: usize = 0> {
+    parent: Option<Weak<Node<T, C>>>,
+    children: RefCell<InlineVec<Rc<Node<T, C>>, C>>,
     pub value: T,
     this: Weak<Self>,
 }
@@ -45,9 +61,26 @@ impl<T> Node<T> {
     ///         assert_eq!(root.value, 42);
     /// ```
     pub fn root(value: T) -> Rc<Self> {
+        Self::inline_root(value)
+    }
+}
+
+impl<T, const C: usize> Node<T, C> {
+    /// Starts a new tree whose nodes keep up to `C` children inline, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    ///         use treesome::sparse::Node;
+    ///         let root = Node::<_, 4>::inline_root(42);
+    ///         let child = root.create_child(43);
+    ///
+    ///         assert_eq!(root.children()[0].value, 43);
+    ///         assert_eq!(child.parent().expect("Child has a parent").value, 42);
+    /// ```
+    pub fn inline_root(value: T) -> Rc<Self> {
         Rc::new_cyclic(|node| Self {
             parent: None,
-            children: RefCell::new(Vec::new()),
+            children: RefCell::new(InlineVec::new()),
             value,
             this: node.clone(),
         })
@@ -68,10 +101,10 @@ impl<T> Node<T> {
     /// ```
     ///
     ///
-    pub fn create_child(&self, value: T) -> Rc<Node<T>> {
+    pub fn create_child(&self, value: T) -> Rc<Node<T, C>> {
         let child = Rc::new_cyclic(|child| Self {
             parent: Some(self.this.clone()),
-            children: RefCell::new(Vec::new()),
+            children: RefCell::new(InlineVec::new()),
             value,
             this: child.clone(),
         });
@@ -80,6 +113,46 @@ impl<T> Node<T> {
         child
     }
 
+    /// Bytes taken by the subtree of this node, see [MemoryFootprint]. Reference counts and borrow flags of
+    /// every node are overhead, as is the spare capacity of children spilled to the heap.
+    ///
+    /// # Examples
+    /// ```
+    ///         use treesome::sparse::Node;
+    ///         let root = Node::root(1_u8);
+    ///         root.create_child(2);
+    ///
+    ///         let footprint = root.memory_footprint();
+    ///         assert_eq!(footprint.values, 2);
+    ///         assert!(footprint.overhead >= 2 * 2 * size_of::<usize>());
+    /// ```
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        type Children<T, const C: usize> = InlineVec<Rc<Node<T, C>>, C>;
+        let borrow_flag = size_of::<RefCell<Children<T, C>>>() - size_of::<Children<T, C>>();
+        let node = MemoryFootprint {
+            structure: size_of::<Self>() - size_of::<T>() - borrow_flag,
+            values: size_of::<T>(),
+            overhead: 2 * size_of::<usize>() + borrow_flag,
+        };
+
+        let spilled = |node: &Self| {
+            node.children
+                .borrow()
+                .spilled()
+                .map_or(MemoryFootprint::default(), |children| {
+                    MemoryFootprint::vec(children, false)
+                })
+        };
+
+        let mut footprint = node + spilled(self);
+        let mut stack: Vec<Rc<Self>> = self.children.borrow().iter().cloned().collect();
+        while let Some(child) = stack.pop() {
+            footprint += node + spilled(&child);
+            stack.extend(child.children.borrow().iter().cloned());
+        }
+        footprint
+    }
+
     pub fn is_leaf(&self) -> bool {
         self.children.borrow().is_empty()
     }
@@ -98,10 +171,38 @@ impl<T> Node<T> {
     /// ```
     ///
     ///
-    pub fn parent(&self) -> Option<Rc<Node<T>>> {
+    pub fn parent(&self) -> Option<Rc<Node<T, C>>> {
         self.parent.as_ref()?.upgrade()
     }
 
+    /// First of node's current children, `None` for leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sparse::Node;
+    ///         let root = Node::root(42);
+    ///         root.create_child(43);
+    ///         root.create_child(44);
+    ///
+    ///         assert_eq!(root.first_child().map(|child| child.value), Some(43));
+    ///         assert_eq!(root.last_child().map(|child| child.value), Some(44));
+    ///         assert!(root.nth_child(2).is_none());
+    /// ```
+    pub fn first_child(&self) -> Option<Rc<Node<T, C>>> {
+        self.nth_child(0)
+    }
+
+    /// Last of node's current children, `None` for leaves.
+    pub fn last_child(&self) -> Option<Rc<Node<T, C>>> {
+        self.children.borrow().iter().last().cloned()
+    }
+
+    /// Node's current child at position `k`, counting from zero. `None` if the node has at most `k` children.
+    pub fn nth_child(&self, k: usize) -> Option<Rc<Node<T, C>>> {
+        self.children.borrow().iter().nth(k).cloned()
+    }
+
     /// Returns a newly allocated vector of node's current children. The vector doesn't
     /// reflect any posterior changes.
     ///
@@ -114,7 +215,135 @@ impl<T> Node<T> {
     ///
     ///         assert_eq!(root.children().len(), 1);
     /// ```
-    pub fn children(&self) -> Vec<Rc<Node<T>>> {
+    pub fn children(&self) -> Vec<Rc<Node<T, C>>> {
         self.children.borrow().iter().cloned().collect()
     }
 }
+
+/// Compares subtrees of both nodes by values and shape, children in order. Parents and [Rc] identity aren't
+/// compared, and the comparison is iterative, so deep trees don't overflow the stack.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::sparse::Node;
+///         let build = |leaf| {
+///             let root = Node::root(1);
+///             root.create_child(2).create_child(leaf);
+///             root
+///         };
+///
+///         assert_eq!(build(3), build(3));
+///         assert_ne!(build(3), build(4));
+///         assert_eq!(build(3).children()[0], build(3).children()[0]);
+/// ```
+impl<T: PartialEq, const C: usize> PartialEq for Node<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        let shallow_eq = |a: &Self, b: &Self| {
+            a.value == b.value && a.children.borrow().len() == b.children.borrow().len()
+        };
+        if !shallow_eq(self, other) {
+            return false;
+        }
+        let pairs = |a: &Self, b: &Self| {
+            a.children()
+                .into_iter()
+                .zip(b.children())
+                .collect::<Vec<_>>()
+        };
+        let mut stack = pairs(self, other);
+        while let Some((a, b)) = stack.pop() {
+            if !shallow_eq(&a, &b) {
+                return false;
+            }
+            stack.extend(pairs(&a, &b));
+        }
+        true
+    }
+}
+
+impl<T: Eq, const C: usize> Eq for Node<T, C> {}
+
+/// Drops descendants with an explicit worklist instead of recursing through nested [Rc]s, so dropping deep trees
+/// doesn't overflow the stack. Subtrees still referenced from elsewhere are left alive.
+impl<T, const C: usize> Drop for Node<T, C> {
+    fn drop(&mut self) {
+        let mut worklist: Vec<_> = self.children.get_mut().drain().collect();
+        while let Some(child) = worklist.pop() {
+            if let Some(mut child) = Rc::into_inner(child) {
+                worklist.extend(child.children.get_mut().drain());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::Node;
+
+    #[test]
+    fn drop_deep_chain() {
+        let root = Node::root(0);
+        let mut leaf = root.clone();
+        for depth in 1..=1_000_000 {
+            leaf = leaf.create_child(depth);
+        }
+        assert_eq!(leaf.value, 1_000_000);
+        drop(leaf);
+        drop(root);
+    }
+
+    #[test]
+    fn drop_keeps_shared_subtrees() {
+        let root = Node::root(0);
+        let child = root.create_child(1);
+        let grandchild = child.create_child(2);
+        grandchild.create_child(3);
+        drop(child);
+        drop(root);
+
+        assert!(grandchild.parent().is_none());
+        assert_eq!(grandchild.children()[0].value, 3);
+    }
+
+    #[test]
+    fn structural_eq() {
+        let chain = |length: i32, leaf: i32| {
+            let root = Node::root(0);
+            let mut node = root.clone();
+            for depth in 1..length {
+                node = node.create_child(depth);
+            }
+            node.create_child(leaf);
+            root
+        };
+        assert_eq!(chain(100_000, 7), chain(100_000, 7));
+        assert_ne!(chain(100_000, 7), chain(100_000, 8));
+        assert_ne!(chain(3, 3), chain(4, 3));
+
+        let wide = Node::root(0);
+        wide.create_child(1);
+        wide.create_child(2);
+        let narrow = Node::root(0);
+        narrow.create_child(1).create_child(2);
+        assert_ne!(wide, narrow);
+    }
+
+    #[test]
+    fn inline_children() {
+        let root = Node::<_, 2>::inline_root(0);
+        let first = root.create_child(1);
+        root.create_child(2);
+        root.create_child(3); // Spills to the heap
+        first.create_child(4);
+
+        let values: Vec<i32> = root.children().iter().map(|child| child.value).collect();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(!first.is_leaf());
+        assert!(root.children()[1].is_leaf());
+        assert_eq!(
+            first.children()[0].parent().map(|parent| parent.value),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,331 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::spatial::Aabb;
+use crate::tree::Tree;
+
+/// How the set of boxes is divided into two halves on every level of a [Bvh].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SplitStrategy {
+    /// Split at the median centroid along the widest axis. Fast to build.
+    Median,
+    /// Split minimizing the surface area heuristic (SAH) cost. Slower to build, faster to traverse.
+    SurfaceArea,
+}
+
+/// Half-line starting at `origin`, heading in `direction`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray<const D: usize> {
+    pub origin: [f64; D],
+    pub direction: [f64; D],
+}
+
+impl<const D: usize> Ray<D> {
+    pub fn new(origin: [f64; D], direction: [f64; D]) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Slab test. Returns the ray parameter of the entry point into `aabb`, if the ray hits it.
+    pub fn hits(&self, aabb: &Aabb<D>) -> Option<f64> {
+        let mut entry = 0.0_f64;
+        let mut exit = f64::INFINITY;
+        for d in 0..D {
+            if self.direction[d] == 0.0 {
+                if self.origin[d] < aabb.min[d] || self.origin[d] > aabb.max[d] {
+                    return None;
+                }
+                continue;
+            }
+            let inverse = 1.0 / self.direction[d];
+            let near = (aabb.min[d] - self.origin[d]) * inverse;
+            let far = (aabb.max[d] - self.origin[d]) * inverse;
+            entry = entry.max(near.min(far));
+            exit = exit.min(near.max(far));
+        }
+        (entry <= exit).then_some(entry)
+    }
+}
+
+/// Node of a [Bvh], as stored in the underlying [Tree].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BvhNode<const D: usize> {
+    /// Box enclosing all items in the subtree.
+    pub bounds: Aabb<D>,
+    /// Index of the item, leaf nodes only.
+    pub item: Option<usize>,
+}
+
+/// Bounding volume hierarchy - a binary tree of nested bounding boxes, every leaf holding a single item.
+/// Stored as a binary [Tree] with node `0` as the root.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::spatial::{Aabb, Bvh, Ray, SplitStrategy};
+///         let boxes = vec![
+///             (Aabb::new([0.0, 0.0], [1.0, 1.0]), "crate"),
+///             (Aabb::new([5.0, 0.0], [6.0, 1.0]), "barrel"),
+///             (Aabb::new([0.0, 5.0], [1.0, 6.0]), "door"),
+///         ];
+///         let bvh = Bvh::build(boxes, SplitStrategy::SurfaceArea);
+///
+///         let ray = Ray::new([-1.0, 0.5], [1.0, 0.0]);
+///         let mut hit: Vec<&str> = bvh.ray(&ray).map(|(_, item)| *item).collect();
+///         hit.sort();
+///         assert_eq!(hit, vec!["barrel", "crate"]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Bvh<T, const D: usize> {
+    tree: Tree<BvhNode<D>>,
+    items: Vec<(Aabb<D>, T)>,
+}
+
+impl<T, const D: usize> Bvh<T, D> {
+    /// Builds a hierarchy over given items and their bounding boxes.
+    pub fn build(items: Vec<(Aabb<D>, T)>, strategy: SplitStrategy) -> Self {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            let mut indices: Vec<usize> = (0..items.len()).collect();
+            Self::build_node(
+                &items,
+                &mut indices,
+                strategy,
+                &mut left,
+                &mut right,
+                &mut nodes,
+            );
+        }
+
+        let tree = Tree::new(vec![left, right], nodes)
+            .unwrap_or_else(|_| unreachable!("All dimensions are filled in lockstep"));
+        Self { tree, items }
+    }
+
+    /// Creates a node over `indices`, returning its id.
+    fn build_node(
+        items: &[(Aabb<D>, T)],
+        indices: &mut [usize],
+        strategy: SplitStrategy,
+        left: &mut Vec<isize>,
+        right: &mut Vec<isize>,
+        nodes: &mut Vec<BvhNode<D>>,
+    ) -> isize {
+        let bounds = indices
+            .iter()
+            .map(|&index| items[index].0)
+            .reduce(|a, b| a.union(&b))
+            .expect("Nodes are never empty");
+        let id = nodes.len();
+        left.push(-1);
+        right.push(-1);
+        nodes.push(BvhNode { bounds, item: None });
+
+        if let [item] = indices {
+            nodes[id].item = Some(*item);
+            return id as isize;
+        }
+
+        let split = match strategy {
+            SplitStrategy::Median => Self::median_split(items, indices, &bounds),
+            SplitStrategy::SurfaceArea => Self::sah_split(items, indices),
+        };
+        let (lower, upper) = indices.split_at_mut(split);
+        left[id] = Self::build_node(items, lower, strategy, left, right, nodes);
+        right[id] = Self::build_node(items, upper, strategy, left, right, nodes);
+        id as isize
+    }
+
+    fn median_split(items: &[(Aabb<D>, T)], indices: &mut [usize], bounds: &Aabb<D>) -> usize {
+        let axis = (0..D)
+            .max_by(|&a, &b| {
+                (bounds.max[a] - bounds.min[a]).total_cmp(&(bounds.max[b] - bounds.min[b]))
+            })
+            .unwrap_or(0);
+        let middle = indices.len() / 2;
+        indices.select_nth_unstable_by(middle, |&a, &b| {
+            items[a].0.center()[axis].total_cmp(&items[b].0.center()[axis])
+        });
+        middle
+    }
+
+    fn sah_split(items: &[(Aabb<D>, T)], indices: &mut [usize]) -> usize {
+        let mut best: Option<(f64, usize, usize)> = None;
+        for axis in 0..D {
+            indices
+                .sort_by(|&a, &b| items[a].0.center()[axis].total_cmp(&items[b].0.center()[axis]));
+
+            // Cost of every split position, computed from prefix and suffix bounds
+            let mut suffix_areas = vec![0.0; indices.len()];
+            let mut suffix = items[indices[indices.len() - 1]].0;
+            for position in (1..indices.len()).rev() {
+                suffix = suffix.union(&items[indices[position]].0);
+                suffix_areas[position] = suffix.surface_area() * (indices.len() - position) as f64;
+            }
+            let mut prefix = items[indices[0]].0;
+            for position in 1..indices.len() {
+                prefix = prefix.union(&items[indices[position - 1]].0);
+                let cost = prefix.surface_area() * position as f64 + suffix_areas[position];
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, position));
+                }
+            }
+        }
+
+        let (_, axis, position) = best.expect("At least two items are split");
+        indices.sort_by(|&a, &b| items[a].0.center()[axis].total_cmp(&items[b].0.center()[axis]));
+        position
+    }
+
+    /// Number of items in the hierarchy.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Bytes taken by the hierarchy, see [MemoryFootprint]. Its nodes and the bounds of the items are structure.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let nodes = self.tree.memory_footprint();
+        MemoryFootprint {
+            structure: size_of::<Self>() - size_of::<Tree<BvhNode<D>>>()
+                + nodes.structure
+                + nodes.values,
+            values: 0,
+            overhead: nodes.overhead,
+        } + MemoryFootprint::entries(&self.items, size_of::<T>())
+    }
+
+    /// The underlying binary tree.
+    pub fn tree(&self) -> &Tree<BvhNode<D>> {
+        &self.tree
+    }
+
+    /// Item at given index, as passed to [Bvh::build].
+    pub fn item(&self, index: usize) -> Option<&(Aabb<D>, T)> {
+        self.items.get(index)
+    }
+
+    /// Items whose boxes pass the `predicate`, descending only into subtrees whose bounds pass it as well.
+    pub fn traverse<P>(&self, predicate: P) -> BvhIter<'_, T, D, P>
+    where
+        P: FnMut(&Aabb<D>) -> bool,
+    {
+        BvhIter {
+            bvh: self,
+            stack: if self.is_empty() { vec![] } else { vec![0] },
+            predicate,
+        }
+    }
+
+    /// Items whose boxes are hit by the `ray`, in no particular order.
+    pub fn ray<'a>(
+        &'a self,
+        ray: &'a Ray<D>,
+    ) -> BvhIter<'a, T, D, impl FnMut(&Aabb<D>) -> bool + 'a> {
+        self.traverse(move |bounds| ray.hits(bounds).is_some())
+    }
+
+    /// Items whose boxes intersect `region`, in no particular order.
+    pub fn overlapping<'a>(
+        &'a self,
+        region: &'a Aabb<D>,
+    ) -> BvhIter<'a, T, D, impl FnMut(&Aabb<D>) -> bool + 'a> {
+        self.traverse(move |bounds| bounds.intersects(region))
+    }
+}
+
+/// Lazy traversal over a [Bvh], see [Bvh::traverse]. Yields item indices and items.
+pub struct BvhIter<'a, T, const D: usize, P> {
+    bvh: &'a Bvh<T, D>,
+    stack: Vec<usize>,
+    predicate: P,
+}
+
+impl<'a, T, const D: usize, P> Iterator for BvhIter<'a, T, D, P>
+where
+    P: FnMut(&Aabb<D>) -> bool,
+{
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            let BvhNode { bounds, item } = &self.bvh.tree.values()[node];
+            if !(self.predicate)(bounds) {
+                continue;
+            }
+            if let Some(item) = item {
+                return Some((*item, &self.bvh.items[*item].1));
+            }
+            self.stack.extend(
+                self.bvh
+                    .tree
+                    .children(node)
+                    .into_iter()
+                    .rev()
+                    .filter(|&child| child >= 0)
+                    .map(|child| child as usize),
+            );
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spatial::{Aabb, Bvh, Ray, SplitStrategy};
+
+    fn boxes() -> Vec<(Aabb<3>, usize)> {
+        (0..50)
+            .map(|i| {
+                let x = (i % 10) as f64 * 3.0;
+                let y = (i / 10) as f64 * 3.0;
+                (Aabb::new([x, y, 0.0], [x + 1.0, y + 1.0, 1.0]), i)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build() {
+        for strategy in [SplitStrategy::Median, SplitStrategy::SurfaceArea] {
+            let bvh = Bvh::build(boxes(), strategy);
+            assert_eq!(bvh.len(), 50);
+            // A full binary tree with 50 leaves
+            assert_eq!(bvh.tree().values().len(), 99);
+            let root = &bvh.tree().values()[0];
+            assert_eq!(root.bounds, Aabb::new([0.0; 3], [28.0, 13.0, 1.0]));
+        }
+    }
+
+    #[test]
+    fn ray_traversal() {
+        for strategy in [SplitStrategy::Median, SplitStrategy::SurfaceArea] {
+            let bvh = Bvh::build(boxes(), strategy);
+            let ray = Ray::new([-5.0, 3.5, 0.5], [1.0, 0.0, 0.0]);
+            let mut hits: Vec<usize> = bvh.ray(&ray).map(|(_, item)| *item).collect();
+            hits.sort();
+            assert_eq!(hits, (10..20).collect::<Vec<_>>());
+
+            let backwards = Ray::new([-5.0, 3.5, 0.5], [-1.0, 0.0, 0.0]);
+            assert_eq!(bvh.ray(&backwards).count(), 0);
+        }
+    }
+
+    #[test]
+    fn box_traversal() {
+        let bvh = Bvh::build(boxes(), SplitStrategy::Median);
+        let region = Aabb::new([2.5, 2.5, 0.0], [6.5, 3.5, 0.0]);
+        let mut hits: Vec<usize> = bvh.overlapping(&region).map(|(_, item)| *item).collect();
+        hits.sort();
+        assert_eq!(hits, vec![11, 12]);
+
+        let empty = Bvh::<u8, 2>::build(vec![], SplitStrategy::SurfaceArea);
+        assert_eq!(empty.overlapping(&Aabb::point([0.0, 0.0])).count(), 0);
+    }
+}
@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::spatial::Aabb;
+
+/// Static k-d tree over points in `K` dimensions, built in bulk. Uses the implicit array layout: points are
+/// reordered so that the median of every range is its root, the left half being the left subtree and
+/// the right half the right subtree. No child links are stored at all.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::spatial::{Aabb, KdTree};
+///         let points = vec![([2.0, 3.0], 'a'), ([5.0, 4.0], 'b'), ([9.0, 6.0], 'c'), ([4.0, 7.0], 'd')];
+///         let tree = KdTree::build(points);
+///
+///         assert_eq!(tree.nearest(&[8.0, 5.0]).map(|(_, value, _)| *value), Some('c'));
+///         let mut found: Vec<char> = tree.range(&Aabb::new([0.0, 0.0], [5.0, 5.0])).map(|(_, v)| *v).collect();
+///         found.sort();
+///         assert_eq!(found, vec!['a', 'b']);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KdTree<const K: usize, T> {
+    points: Vec<KdPoint<K, T>>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct KdPoint<const K: usize, T> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::spatial::coordinates"))]
+    coordinates: [f64; K],
+    value: T,
+}
+
+impl<const K: usize, T> KdTree<K, T> {
+    /// Builds a balanced tree in O(n log n), splitting on axes in round-robin fashion.
+    pub fn build(points: Vec<([f64; K], T)>) -> Self {
+        let mut points: Vec<KdPoint<K, T>> = points
+            .into_iter()
+            .map(|(coordinates, value)| KdPoint { coordinates, value })
+            .collect();
+        Self::arrange(&mut points, 0);
+        Self { points }
+    }
+
+    fn arrange(points: &mut [KdPoint<K, T>], depth: usize) {
+        if points.len() <= 1 || K == 0 {
+            return;
+        }
+        let axis = depth % K;
+        let middle = points.len() / 2;
+        points.select_nth_unstable_by(middle, |a, b| {
+            a.coordinates[axis].total_cmp(&b.coordinates[axis])
+        });
+        let (lower, upper) = points.split_at_mut(middle);
+        Self::arrange(lower, depth + 1);
+        Self::arrange(&mut upper[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Coordinates of the points are structure.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::entries(&self.points, size_of::<T>())
+    }
+
+    /// Point closest to `target`, its value and euclidean distance.
+    pub fn nearest(&self, target: &[f64; K]) -> Option<(&[f64; K], &T, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        self.nearest_in(0, self.points.len(), 0, target, &mut best);
+        best.map(|(index, distance_squared)| {
+            let point = &self.points[index];
+            (&point.coordinates, &point.value, distance_squared.sqrt())
+        })
+    }
+
+    fn nearest_in(
+        &self,
+        from: usize,
+        to: usize,
+        depth: usize,
+        target: &[f64; K],
+        best: &mut Option<(usize, f64)>,
+    ) {
+        if from >= to {
+            return;
+        }
+        let middle = from + (to - from) / 2;
+        let point = &self.points[middle].coordinates;
+        let distance_squared = squared_distance(point, target);
+        if best.is_none_or(|(_, best_distance)| distance_squared < best_distance) {
+            *best = Some((middle, distance_squared));
+        }
+        // Without axes all points are at distance zero, the first one is as near as any
+        let Some(axis) = depth.checked_rem(K) else {
+            return;
+        };
+
+        let delta = target[axis] - point[axis];
+        let (near, far) = match delta.partial_cmp(&0.0) {
+            Some(Ordering::Less) => ((from, middle), (middle + 1, to)),
+            _ => ((middle + 1, to), (from, middle)),
+        };
+        self.nearest_in(near.0, near.1, depth + 1, target, best);
+        if best.is_none_or(|(_, best_distance)| delta * delta < best_distance) {
+            self.nearest_in(far.0, far.1, depth + 1, target, best);
+        }
+    }
+
+    /// All points within `region`, boundaries included, in no particular order.
+    pub fn range<'a>(
+        &'a self,
+        region: &'a Aabb<K>,
+    ) -> impl Iterator<Item = (&'a [f64; K], &'a T)> + 'a {
+        let mut stack = vec![(0, self.points.len(), 0_usize)];
+        std::iter::from_fn(move || {
+            while let Some((from, to, depth)) = stack.pop() {
+                if from >= to {
+                    continue;
+                }
+                let middle = from + (to - from) / 2;
+                let point = &self.points[middle];
+                // Without axes every point is within the region, both halves are visited
+                let axis = depth.checked_rem(K);
+                if axis.is_none_or(|axis| region.max[axis] >= point.coordinates[axis]) {
+                    stack.push((middle + 1, to, depth + 1));
+                }
+                if axis.is_none_or(|axis| region.min[axis] <= point.coordinates[axis]) {
+                    stack.push((from, middle, depth + 1));
+                }
+                if region.contains(&Aabb::point(point.coordinates)) {
+                    return Some((&point.coordinates, &point.value));
+                }
+            }
+            None
+        })
+    }
+}
+
+fn squared_distance<const K: usize>(a: &[f64; K], b: &[f64; K]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spatial::{Aabb, KdTree};
+
+    fn points() -> Vec<([f64; 3], usize)> {
+        // Deterministic pseudo-random points
+        let mut seed = 42_u64;
+        let mut next = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (seed >> 33) as f64 / (1_u64 << 31) as f64 * 100.0
+        };
+        (0..500).map(|i| ([next(), next(), next()], i)).collect()
+    }
+
+    #[test]
+    fn nearest() {
+        let points = points();
+        let tree = KdTree::build(points.clone());
+        assert_eq!(tree.len(), 500);
+
+        for target in [[50.0, 50.0, 50.0], [0.0, 0.0, 0.0], [-20.0, 150.0, 42.0]] {
+            let expected = points
+                .iter()
+                .min_by(|a, b| {
+                    super::squared_distance(&a.0, &target)
+                        .total_cmp(&super::squared_distance(&b.0, &target))
+                })
+                .unwrap();
+            let (_, value, distance) = tree.nearest(&target).unwrap();
+            assert_eq!(*value, expected.1);
+            assert!((distance - super::squared_distance(&expected.0, &target).sqrt()).abs() < 1e-9);
+        }
+
+        assert!(KdTree::<2, u8>::build(vec![])
+            .nearest(&[0.0, 0.0])
+            .is_none());
+    }
+
+    #[test]
+    fn range() {
+        let points = points();
+        let tree = KdTree::build(points.clone());
+        let region = Aabb::new([10.0, 20.0, 30.0], [60.0, 50.0, 90.0]);
+
+        let mut found: Vec<usize> = tree.range(&region).map(|(_, value)| *value).collect();
+        found.sort();
+        let expected: Vec<usize> = points
+            .iter()
+            .filter(|(point, _)| region.contains(&Aabb::point(*point)))
+            .map(|(_, value)| *value)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn zero_dimensions() {
+        let tree = KdTree::build(vec![([], 'a'), ([], 'b'), ([], 'c')]);
+        assert_eq!(
+            tree.nearest(&[]).map(|(_, _, distance)| distance),
+            Some(0.0)
+        );
+        assert_eq!(tree.range(&Aabb::new([], [])).count(), 3);
+    }
+}
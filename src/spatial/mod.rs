@@ -0,0 +1,131 @@
+pub mod bvh;
+pub mod kdtree;
+pub mod region;
+
+pub use bvh::*;
+pub use kdtree::*;
+pub use region::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Axis-aligned bounding box in `D` dimensions. A point is a box with `min == max`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Aabb<const D: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "coordinates"))]
+    pub min: [f64; D],
+    #[cfg_attr(feature = "serde", serde(with = "coordinates"))]
+    pub max: [f64; D],
+}
+
+impl<const D: usize> Aabb<D> {
+    pub fn new(min: [f64; D], max: [f64; D]) -> Self {
+        Self { min, max }
+    }
+
+    /// A degenerate box covering a single point.
+    pub fn point(point: [f64; D]) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// True if `other` lies entirely within this box, boundaries included.
+    pub fn contains(&self, other: &Aabb<D>) -> bool {
+        (0..D).all(|d| self.min[d] <= other.min[d] && other.max[d] <= self.max[d])
+    }
+
+    /// True if the boxes share at least one point, boundaries included.
+    pub fn intersects(&self, other: &Aabb<D>) -> bool {
+        (0..D).all(|d| self.min[d] <= other.max[d] && other.min[d] <= self.max[d])
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb<D>) -> Self {
+        let mut union = *self;
+        for d in 0..D {
+            union.min[d] = union.min[d].min(other.min[d]);
+            union.max[d] = union.max[d].max(other.max[d]);
+        }
+        union
+    }
+
+    pub fn center(&self) -> [f64; D] {
+        let mut center = [0.0; D];
+        for (d, coordinate) in center.iter_mut().enumerate() {
+            *coordinate = (self.min[d] + self.max[d]) / 2.0;
+        }
+        center
+    }
+
+    /// Sum of areas of all faces - the surface area for `D = 3`, the perimeter for `D = 2`.
+    pub fn surface_area(&self) -> f64 {
+        (0..D)
+            .map(|skipped| {
+                (0..D)
+                    .filter(|&d| d != skipped)
+                    .map(|d| self.max[d] - self.min[d])
+                    .product::<f64>()
+            })
+            .sum::<f64>()
+            * 2.0
+    }
+
+    /// Squared euclidean distance between `point` and the closest point of this box. Zero for points inside.
+    pub fn distance_squared(&self, point: &[f64; D]) -> f64 {
+        (0..D)
+            .map(|d| {
+                let delta = (self.min[d] - point[d])
+                    .max(point[d] - self.max[d])
+                    .max(0.0);
+                delta * delta
+            })
+            .sum()
+    }
+}
+
+/// Serde has no support for arrays of generic length, coordinates are (de)serialized as sequences.
+#[cfg(feature = "serde")]
+pub(crate) mod coordinates {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, const D: usize>(
+        coordinates: &[f64; D],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        coordinates.as_slice().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, De: Deserializer<'de>, const D: usize>(
+        deserializer: De,
+    ) -> Result<[f64; D], De::Error> {
+        let coordinates = Vec::<f64>::deserialize(deserializer)?;
+        let len = coordinates.len();
+        coordinates
+            .try_into()
+            .map_err(|_| De::Error::invalid_length(len, &format!("{D} coordinates").as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spatial::Aabb;
+
+    #[test]
+    fn aabb() {
+        let a = Aabb::new([0.0, 0.0], [2.0, 2.0]);
+        let b = Aabb::new([1.0, 1.0], [3.0, 3.0]);
+        assert!(a.intersects(&b));
+        assert!(!a.contains(&b));
+        assert!(a.union(&b).contains(&b));
+        assert_eq!(a.center(), [1.0, 1.0]);
+        assert_eq!(a.distance_squared(&[1.0, 1.0]), 0.0);
+        assert_eq!(a.distance_squared(&[5.0, 6.0]), 9.0 + 16.0);
+        assert!(!a.intersects(&Aabb::point([2.5, 0.0])));
+        assert_eq!(a.surface_area(), 8.0);
+        assert_eq!(Aabb::new([0.0; 3], [1.0, 2.0, 3.0]).surface_area(), 22.0);
+    }
+}
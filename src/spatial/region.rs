@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::MemoryFootprint;
+use crate::spatial::Aabb;
+
+/// Maximum number of items a node holds before it's split.
+const NODE_CAPACITY: usize = 8;
+/// Nodes at this depth are never split.
+const MAX_DEPTH: usize = 16;
+
+/// Region quadtree in two dimensions.
+pub type Quadtree<T> = RegionTree<T, 2>;
+/// Region octree in three dimensions.
+pub type Octree<T> = RegionTree<T, 3>;
+
+/// Region tree splitting space into `2^D` equally sized orthants per node - a quadtree for `D = 2`,
+/// an octree for `D = 3`. Items are boxes (or points), each stored in the deepest node whose region contains
+/// it entirely.
+///
+/// Nodes are kept in a single vector, children of a node being stored next to each other, so traversal
+/// doesn't chase pointers all over the heap. Items lying outside of the root region are kept in the root node.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::spatial::{Aabb, Quadtree};
+///         let mut tree = Quadtree::new(Aabb::new([0.0, 0.0], [100.0, 100.0]));
+///         tree.insert_point([10.0, 10.0], "tree");
+///         tree.insert_point([80.0, 75.0], "rock");
+///         tree.insert(Aabb::new([40.0, 40.0], [60.0, 60.0]), "house");
+///
+///         let found = tree.query(&Aabb::new([0.0, 0.0], [50.0, 50.0]));
+///         assert_eq!(found.len(), 2);
+///         assert_eq!(tree.nearest(&[90.0, 90.0]).map(|(item, _)| *item), Some("rock"));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RegionTree<T, const D: usize> {
+    nodes: Vec<RegionNode<D>>,
+    items: Vec<(Aabb<D>, T)>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct RegionNode<const D: usize> {
+    region: Aabb<D>,
+    depth: usize,
+    /// Index of the first of `2^D` consecutive children, if the node was split.
+    first_child: Option<usize>,
+    /// Indices of items stored directly in this node.
+    items: Vec<usize>,
+}
+
+impl<T, const D: usize> RegionTree<T, D> {
+    const FANOUT: usize = 1 << D;
+
+    /// Creates an empty tree covering given `region`.
+    pub fn new(region: Aabb<D>) -> Self {
+        Self {
+            nodes: vec![RegionNode {
+                region,
+                depth: 0,
+                first_child: None,
+                items: Vec::new(),
+            }],
+            items: Vec::new(),
+        }
+    }
+
+    /// Number of items stored.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. Regions, bounds of the items and the lists of items in
+    /// nodes are structure.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::entries(&self.nodes, 0)
+            + self
+                .nodes
+                .iter()
+                .map(|node| MemoryFootprint::vec(&node.items, false))
+                .sum()
+            + MemoryFootprint::entries(&self.items, size_of::<T>())
+    }
+
+    /// Region covered by the root node.
+    pub fn region(&self) -> &Aabb<D> {
+        &self.nodes[0].region
+    }
+
+    pub fn insert_point(&mut self, point: [f64; D], value: T) {
+        self.insert(Aabb::point(point), value)
+    }
+
+    /// Inserts an item occupying given `bounds`.
+    pub fn insert(&mut self, bounds: Aabb<D>, value: T) {
+        let item = self.items.len();
+        self.items.push((bounds, value));
+
+        let mut node = 0;
+        while let Some(child) = self.child_containing(node, &bounds) {
+            node = child;
+        }
+        self.nodes[node].items.push(item);
+
+        let node_ref = &self.nodes[node];
+        if node_ref.first_child.is_none()
+            && node_ref.items.len() > NODE_CAPACITY
+            && node_ref.depth < MAX_DEPTH
+            && node_ref.region.contains(&bounds)
+        {
+            self.split(node);
+        }
+    }
+
+    /// All items intersecting `region`, boundaries included.
+    pub fn query(&self, region: &Aabb<D>) -> Vec<(&Aabb<D>, &T)> {
+        let mut found = Vec::new();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            found.extend(node.items.iter().filter_map(|&item| {
+                let (bounds, value) = &self.items[item];
+                bounds.intersects(region).then_some((bounds, value))
+            }));
+            if let Some(first_child) = node.first_child {
+                stack.extend(
+                    (first_child..first_child + Self::FANOUT)
+                        .filter(|&child| self.nodes[child].region.intersects(region)),
+                );
+            }
+        }
+        found
+    }
+
+    /// Item closest to `point` together with its euclidean distance, or `None` for an empty tree.
+    /// Nodes are visited best-first, so only nodes which may contain a closer item are searched.
+    pub fn nearest(&self, point: &[f64; D]) -> Option<(&T, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        let mut queue = BinaryHeap::from([Candidate {
+            distance_squared: 0.0,
+            node: 0,
+        }]);
+        while let Some(Candidate {
+            distance_squared,
+            node,
+        }) = queue.pop()
+        {
+            if best.is_some_and(|(_, best_distance)| best_distance <= distance_squared) {
+                break;
+            }
+
+            let node = &self.nodes[node];
+            for &item in &node.items {
+                let distance = self.items[item].0.distance_squared(point);
+                if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                    best = Some((item, distance));
+                }
+            }
+            if let Some(first_child) = node.first_child {
+                queue.extend(
+                    (first_child..first_child + Self::FANOUT).map(|child| Candidate {
+                        distance_squared: self.nodes[child].region.distance_squared(point),
+                        node: child,
+                    }),
+                );
+            }
+        }
+
+        best.map(|(item, distance)| (&self.items[item].1, distance.sqrt()))
+    }
+
+    /// Iterates over all items, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Aabb<D>, &T)> {
+        self.items.iter().map(|(bounds, value)| (bounds, value))
+    }
+
+    /// Child of `node` whose region contains `bounds` entirely, if `node` has been split.
+    fn child_containing(&self, node: usize, bounds: &Aabb<D>) -> Option<usize> {
+        let first_child = self.nodes[node].first_child?;
+        (first_child..first_child + Self::FANOUT)
+            .find(|&child| self.nodes[child].region.contains(bounds))
+    }
+
+    fn split(&mut self, node: usize) {
+        let region = self.nodes[node].region;
+        let center = region.center();
+        let depth = self.nodes[node].depth + 1;
+        let first_child = self.nodes.len();
+        for orthant in 0..Self::FANOUT {
+            let mut child_region = region;
+            for (d, &half) in center.iter().enumerate() {
+                if orthant & (1 << d) == 0 {
+                    child_region.max[d] = half;
+                } else {
+                    child_region.min[d] = half;
+                }
+            }
+            self.nodes.push(RegionNode {
+                region: child_region,
+                depth,
+                first_child: None,
+                items: Vec::new(),
+            });
+        }
+        self.nodes[node].first_child = Some(first_child);
+
+        let items = std::mem::take(&mut self.nodes[node].items);
+        for item in items {
+            let bounds = self.items[item].0;
+            let target = self.child_containing(node, &bounds).unwrap_or(node);
+            self.nodes[target].items.push(item);
+        }
+    }
+}
+
+/// Node waiting to be searched, ordered so that [BinaryHeap] pops the closest node first.
+struct Candidate {
+    distance_squared: f64,
+    node: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance_squared.total_cmp(&self.distance_squared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spatial::{Aabb, Octree, Quadtree};
+
+    fn grid() -> Quadtree<(usize, usize)> {
+        let mut tree = Quadtree::new(Aabb::new([0.0, 0.0], [64.0, 64.0]));
+        for x in 0..32 {
+            for y in 0..32 {
+                tree.insert_point([x as f64 * 2.0 + 0.5, y as f64 * 2.0 + 0.5], (x, y));
+            }
+        }
+        tree
+    }
+
+    #[test]
+    fn query() {
+        let tree = grid();
+        assert_eq!(tree.len(), 1024);
+        assert!(tree.nodes.len() > 1);
+
+        let region = Aabb::new([0.0, 0.0], [8.0, 4.0]);
+        let mut found: Vec<(usize, usize)> =
+            tree.query(&region).into_iter().map(|(_, v)| *v).collect();
+        found.sort();
+        let expected: Vec<(usize, usize)> =
+            (0..4).flat_map(|x| (0..2).map(move |y| (x, y))).collect();
+        assert_eq!(found, expected);
+
+        assert!(tree
+            .query(&Aabb::new([100.0, 100.0], [200.0, 200.0]))
+            .is_empty());
+    }
+
+    #[test]
+    fn nearest() {
+        let tree = grid();
+        let (value, distance) = tree.nearest(&[10.4, 20.6]).unwrap();
+        assert_eq!(*value, (5, 10));
+        assert!((distance - (0.1_f64 * 0.1 + 0.1 * 0.1).sqrt()).abs() < 1e-9);
+
+        // Outside of the root region
+        assert_eq!(tree.nearest(&[-10.0, -10.0]).map(|(v, _)| *v), Some((0, 0)));
+
+        let empty = Quadtree::<u8>::new(Aabb::new([0.0, 0.0], [1.0, 1.0]));
+        assert!(empty.nearest(&[0.5, 0.5]).is_none());
+    }
+
+    #[test]
+    fn boxes_and_octree() {
+        let mut tree = Octree::new(Aabb::new([0.0; 3], [10.0; 3]));
+        tree.insert(Aabb::new([1.0; 3], [9.0; 3]), "big");
+        for i in 0..20 {
+            tree.insert_point([0.25 * i as f64; 3], "small");
+        }
+        tree.insert_point([50.0; 3], "outside");
+
+        let found = tree.query(&Aabb::point([5.0; 3]));
+        assert_eq!(found.iter().filter(|(_, v)| **v == "big").count(), 1);
+        assert_eq!(tree.query(&Aabb::new([40.0; 3], [60.0; 3])).len(), 1);
+        assert_eq!(tree.iter().count(), 22);
+    }
+}
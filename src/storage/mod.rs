@@ -0,0 +1,447 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+use crate::consts::ROOT_NODE;
+use crate::sized::structs::Array;
+use crate::sized::{check_links, range_position, BTree};
+use crate::tree::{NodeId, TreeError};
+
+/// Contiguous storage of nodes' links or values, read as a slice. Implemented for vectors, boxed slices, arrays
+/// and borrowed slices, so a [StoredBTree] can be kept in any of them. Other backends, e.g. memory-mapped files,
+/// plug in by implementing it, or are borrowed as a [SliceBTree].
+pub trait TreeStorage<T> {
+    fn as_slice(&self) -> &[T];
+}
+
+/// [TreeStorage] whose elements can be changed in place. The length is fixed either way.
+pub trait TreeStorageMut<T>: TreeStorage<T> {
+    fn as_mut_slice(&mut self) -> &mut [T];
+}
+
+impl<T> TreeStorage<T> for Vec<T> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> TreeStorageMut<T> for Vec<T> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> TreeStorage<T> for Box<[T]> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> TreeStorageMut<T> for Box<[T]> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> TreeStorage<T> for [T; N] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> TreeStorageMut<T> for [T; N] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> TreeStorage<T> for Array<T, N> {
+    fn as_slice(&self) -> &[T] {
+        self.as_array()
+    }
+}
+
+impl<T, const N: usize> TreeStorageMut<T> for Array<T, N> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut **self
+    }
+}
+
+impl<T> TreeStorage<T> for &[T] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> TreeStorage<T> for &mut [T] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> TreeStorageMut<T> for &mut [T] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+/// Binary tree kept in a vector, growable before it's wrapped.
+pub type VecBTree<T> = StoredBTree<T, Vec<isize>, Vec<T>>;
+
+/// Binary tree kept in boxed slices, without spare capacity.
+pub type BoxedBTree<T> = StoredBTree<T, Box<[isize]>, Box<[T]>>;
+
+/// Binary tree kept in arrays sized at compile time, like [BTree].
+pub type ArrayBTree<T, const N: usize> = StoredBTree<T, [isize; N], [T; N]>;
+
+/// Binary tree borrowing its links and values, e.g. from a [BTree] or a memory-mapped file.
+pub type SliceBTree<'a, T> = StoredBTree<T, &'a [isize], &'a [T]>;
+
+/// Binary tree over any [TreeStorage]: left and right links of every node, [LEAF_NODE](crate::consts::LEAF_NODE)
+/// marking missing children, and values, all indexed by node ids with the root at `0`. Algorithms are written
+/// once against slices, so every storage behaves the same and [BTree] shares them.
+///
+/// The links are checked on construction, see [StoredBTree::try_new]. The structure can't change afterwards,
+/// values can if the storage allows it.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::storage::{BoxedBTree, SliceBTree, VecBTree};
+///         use treesome::tree::NodeId;
+///         let tree = VecBTree::try_new(vec![1, -1, -1], vec![2, -1, -1], vec![20, 10, 30])
+///             .expect("Tree has a valid structure");
+///         assert_eq!(tree.right(NodeId::ROOT), Some(NodeId::new(2)));
+///
+///         let boxed: BoxedBTree<i32> = tree.clone().into_boxed();
+///         let borrowed: SliceBTree<i32> = boxed.as_view();
+///         assert!(borrowed.iter_sorted().eq(tree.iter_sorted()));
+///         assert_eq!(borrowed.range(15..).collect::<Vec<_>>(), vec![&20, &30]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StoredBTree<T, L = Vec<isize>, V = Vec<T>> {
+    left: L,
+    right: L,
+    values: V,
+    value: PhantomData<T>,
+}
+
+impl<T, L: TreeStorage<isize>, V: TreeStorage<T>> StoredBTree<T, L, V> {
+    /// Wraps `left` and `right` links with `values`. Fails with [TreeError::LengthMismatch] if their lengths
+    /// differ, and like [BTree::try_new] if the links don't form a tree.
+    pub fn try_new(left: L, right: L, values: V) -> Result<Self, TreeError> {
+        let len = values.as_slice().len();
+        for links in [&left, &right] {
+            if links.as_slice().len() != len {
+                return Err(TreeError::LengthMismatch {
+                    expected: len,
+                    found: links.as_slice().len(),
+                });
+            }
+        }
+        let (l_links, r_links) = (left.as_slice(), right.as_slice());
+        check_links(len, |node| [l_links[node], r_links[node]])?;
+        Ok(Self {
+            left,
+            right,
+            values,
+            value: PhantomData,
+        })
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.values.as_slice().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Values of all nodes, in order of node ids.
+    pub fn values(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    /// Value of `node`, `None` if the node doesn't exist.
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.values().get(node.index())
+    }
+
+    /// Left child of `node`, `None` if there's none or the node doesn't exist.
+    pub fn left(&self, node: NodeId) -> Option<NodeId> {
+        let link = *self.left.as_slice().get(node.index())?;
+        child(link, self.len()).map(NodeId::new)
+    }
+
+    /// Right child of `node`, `None` if there's none or the node doesn't exist.
+    pub fn right(&self, node: NodeId) -> Option<NodeId> {
+        let link = *self.right.as_slice().get(node.index())?;
+        child(link, self.len()).map(NodeId::new)
+    }
+
+    /// Node linking to `node`, `None` for the root and nodes that don't exist. See [BTree::linked_parent].
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        (node.index() < self.len())
+            .then(|| linked_parent(self.left.as_slice(), self.right.as_slice(), node.index()))
+            .flatten()
+            .map(NodeId::new)
+    }
+
+    /// True if `node` has no children, including nodes that don't exist.
+    pub fn is_leaf(&self, node: NodeId) -> bool {
+        self.left(node).is_none() && self.right(node).is_none()
+    }
+
+    /// Values in in-order, see [BTree::iter_sorted].
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &T> + '_ {
+        let values = self.values();
+        inorder_within(self.left.as_slice(), self.right.as_slice(), values, |_| {
+            Ordering::Equal
+        })
+        .map(move |node| &values[node])
+    }
+
+    /// Values of a binary search tree within `range`, in ascending order, see [BTree::range].
+    pub fn range<'a, R>(&'a self, range: R) -> impl Iterator<Item = &'a T> + 'a
+    where
+        T: Ord,
+        R: RangeBounds<T> + 'a,
+    {
+        let values = self.values();
+        inorder_within(
+            self.left.as_slice(),
+            self.right.as_slice(),
+            values,
+            move |value| range_position(&range, value),
+        )
+        .map(move |node| &values[node])
+    }
+
+    /// Smallest value of a binary search tree, `None` for an empty tree. See [BTree::min].
+    pub fn min(&self) -> Option<&T> {
+        outermost(self.left.as_slice()).map(|node| &self.values()[node])
+    }
+
+    /// Greatest value of a binary search tree, `None` for an empty tree. See [BTree::max].
+    pub fn max(&self) -> Option<&T> {
+        outermost(self.right.as_slice()).map(|node| &self.values()[node])
+    }
+
+    /// Borrows links and values as a [SliceBTree], without checking them again.
+    pub fn as_view(&self) -> SliceBTree<'_, T> {
+        StoredBTree {
+            left: self.left.as_slice(),
+            right: self.right.as_slice(),
+            values: self.values(),
+            value: PhantomData,
+        }
+    }
+
+    /// Copies the tree into a [VecBTree].
+    pub fn to_vec_tree(&self) -> VecBTree<T>
+    where
+        T: Clone,
+    {
+        StoredBTree {
+            left: self.left.as_slice().to_vec(),
+            right: self.right.as_slice().to_vec(),
+            values: self.values().to_vec(),
+            value: PhantomData,
+        }
+    }
+
+    /// Wraps links and values without checking them, for trees checked elsewhere or read leniently.
+    pub(crate) fn from_parts_unchecked(left: L, right: L, values: V) -> Self {
+        Self {
+            left,
+            right,
+            values,
+            value: PhantomData,
+        }
+    }
+
+    /// Left links, right links and values of the tree.
+    pub fn into_parts(self) -> (L, L, V) {
+        (self.left, self.right, self.values)
+    }
+}
+
+impl<T, L: TreeStorage<isize>, V: TreeStorageMut<T>> StoredBTree<T, L, V> {
+    /// Mutable value of `node`, `None` if the node doesn't exist.
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut T> {
+        self.values.as_mut_slice().get_mut(node.index())
+    }
+
+    /// Mutable values of all nodes, in order of node ids.
+    pub fn values_mut(&mut self) -> &mut [T] {
+        self.values.as_mut_slice()
+    }
+}
+
+impl<T> VecBTree<T> {
+    /// Drops spare capacity of the vectors, keeping the tree in boxed slices.
+    pub fn into_boxed(self) -> BoxedBTree<T> {
+        StoredBTree {
+            left: self.left.into_boxed_slice(),
+            right: self.right.into_boxed_slice(),
+            values: self.values.into_boxed_slice(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> From<BTree<T, N>> for ArrayBTree<T, N> {
+    fn from(tree: BTree<T, N>) -> Self {
+        StoredBTree {
+            left: tree.l_nodes.into_inner(),
+            right: tree.r_nodes.into_inner(),
+            values: tree.values.into_inner(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> From<ArrayBTree<T, N>> for BTree<T, N> {
+    fn from(tree: ArrayBTree<T, N>) -> Self {
+        BTree::new(tree.left, tree.right, tree.values)
+    }
+}
+
+/// Node `link` points to, `None` for [LEAF_NODE](crate::consts::LEAF_NODE), the root and links out of `len` nodes.
+pub(crate) fn child(link: isize, len: usize) -> Option<usize> {
+    (link > ROOT_NODE && (link as usize) < len).then_some(link as usize)
+}
+
+/// Node linking to `node` by `left` or `right` links, `None` for the root. The complete-tree candidate is checked
+/// first in O(1), other layouts fall back to a linear scan.
+pub(crate) fn linked_parent(left: &[isize], right: &[isize], node: usize) -> Option<usize> {
+    let link = isize::try_from(node)
+        .ok()
+        .filter(|&link| link != ROOT_NODE)?;
+    let links_to = |parent: usize| left[parent] == link || right[parent] == link;
+    NodeId::new(node)
+        .implicit_parent(2)
+        .map(NodeId::index)
+        .filter(|&candidate| links_to(candidate))
+        .or_else(|| (0..left.len()).find(|&parent| links_to(parent)))
+}
+
+/// Last node reached from the root by following `towards` links, at most one per node. `None` for an empty tree.
+pub(crate) fn outermost(towards: &[isize]) -> Option<usize> {
+    let mut node = (!towards.is_empty()).then_some(ROOT_NODE as usize)?;
+    for _ in 0..towards.len() {
+        match child(towards[node], towards.len()) {
+            Some(next) => node = next,
+            None => break,
+        }
+    }
+    Some(node)
+}
+
+/// In-order walk over nodes reachable from the root whose value `position` places within a range, each visited
+/// once even if links form a cycle. Nodes before the range are skipped with their left subtrees, the walk ends at
+/// the first node past it.
+pub(crate) fn inorder_within<'a, T, F>(
+    left: &'a [isize],
+    right: &'a [isize],
+    values: &'a [T],
+    position: F,
+) -> impl Iterator<Item = usize> + 'a
+where
+    F: Fn(&T) -> Ordering + 'a,
+{
+    let len = values.len();
+    let mut visited = vec![false; len];
+    let mut stack = Vec::new();
+    let mut node = (len > 0).then_some(ROOT_NODE as usize);
+    std::iter::from_fn(move || {
+        while let Some(current) = node.filter(|&current| !visited[current]) {
+            visited[current] = true;
+            if position(&values[current]) == Ordering::Less {
+                node = child(right[current], len);
+            } else {
+                stack.push(current);
+                node = child(left[current], len);
+            }
+        }
+        let current = stack.pop()?;
+        if position(&values[current]) == Ordering::Greater {
+            stack.clear();
+            return None;
+        }
+        node = child(right[current], len);
+        Some(current)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algo;
+    use crate::sized::BTree;
+    use crate::storage::{ArrayBTree, BoxedBTree, SliceBTree, StoredBTree, VecBTree};
+    use crate::tree::{NodeId, TreeError};
+
+    const LEFT: [isize; 7] = [1, 3, 5, -1, -1, -1, -1];
+    const RIGHT: [isize; 7] = [2, 4, 6, -1, -1, -1, -1];
+    const VALUES: [i32; 7] = [40, 20, 60, 10, 30, 50, 70];
+
+    #[test]
+    fn storages_agree() {
+        let array = ArrayBTree::try_new(LEFT, RIGHT, VALUES).expect("Tree has a valid structure");
+        let vec = VecBTree::try_new(LEFT.to_vec(), RIGHT.to_vec(), VALUES.to_vec())
+            .expect("Tree has a valid structure");
+        let boxed: BoxedBTree<i32> = vec.clone().into_boxed();
+        let slice: SliceBTree<i32> = StoredBTree::try_new(&LEFT[..], &RIGHT[..], &VALUES[..])
+            .expect("Tree has a valid structure");
+        let btree = BTree::new(LEFT, RIGHT, VALUES);
+
+        let sorted = vec![10, 20, 30, 40, 50, 60, 70];
+        for view in [
+            array.as_view(),
+            vec.as_view(),
+            boxed.as_view(),
+            slice.as_view(),
+        ] {
+            assert_eq!(view, btree.as_stored());
+            assert_eq!(view.iter_sorted().copied().collect::<Vec<_>>(), sorted);
+            assert_eq!(
+                view.range(25..=60).collect::<Vec<_>>(),
+                vec![&30, &40, &50, &60]
+            );
+            assert_eq!((view.min(), view.max()), (Some(&10), Some(&70)));
+            assert_eq!(view.parent(NodeId::new(4)), Some(NodeId::new(1)));
+            assert_eq!(view.left(NodeId::new(2)), Some(NodeId::new(5)));
+            assert_eq!(algo::height(&view), 3);
+        }
+        assert_eq!(BTree::from(array.clone()), btree);
+        assert_eq!(ArrayBTree::from(btree), array);
+    }
+
+    #[test]
+    fn checks_links() {
+        assert_eq!(
+            VecBTree::try_new(vec![1, -1], vec![-1], vec!['a', 'b']),
+            Err(TreeError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            VecBTree::try_new(vec![1, -1, -1], vec![1, -1, -1], vec!['a', 'b', 'c']),
+            Err(TreeError::MultipleParents { node: 1 })
+        );
+        let empty = VecBTree::<u8>::try_new(Vec::new(), Vec::new(), Vec::new())
+            .expect("Empty tree is valid");
+        assert!(empty.is_empty() && empty.min().is_none());
+        assert_eq!(empty.iter_sorted().count(), 0);
+
+        let mut values = VALUES;
+        let mut tree = StoredBTree::try_new(&LEFT[..], &RIGHT[..], &mut values[..])
+            .expect("Tree has a valid structure");
+        *tree.get_mut(NodeId::ROOT).expect("Root exists") += 1;
+        assert_eq!(tree.get(NodeId::ROOT), Some(&41));
+        assert_eq!(tree.get_mut(NodeId::new(7)), None);
+        assert_eq!(tree.left(NodeId::new(7)), None);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod suffix;
+
+pub use suffix::*;
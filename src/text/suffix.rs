@@ -0,0 +1,342 @@
+/// Symbols past the byte range, used as unique string terminators.
+const FIRST_TERMINATOR: u16 = 256;
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone)]
+struct SuffixNode {
+    /// Label of the edge leading into this node, `text[start..=end]`. Leaves have no end, their edges run
+    /// to the end of the text.
+    start: usize,
+    end: Option<usize>,
+    suffix_link: usize,
+    children: Vec<(u16, usize)>,
+    /// Start of the suffix spelled by the path to this leaf.
+    suffix_start: Option<usize>,
+}
+
+/// Suffix tree built by Ukkonen's algorithm in O(n) for a constant alphabet. Nodes live in a single vector
+/// and refer to each other by indices, edge labels are ranges into the indexed text.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::text::SuffixTree;
+///         let tree = SuffixTree::new(b"abracadabra");
+///
+///         assert!(tree.contains(b"cada"));
+///         assert!(!tree.contains(b"abba"));
+///         assert_eq!(tree.find_all(b"abra"), vec![0, 7]);
+///         assert_eq!(SuffixTree::longest_common_substring(b"xabcdy", b"zzbcdzz"), b"bcd");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SuffixTree {
+    text: Vec<u16>,
+    nodes: Vec<SuffixNode>,
+}
+
+impl SuffixTree {
+    /// Builds a suffix tree of `text`.
+    pub fn new(text: &[u8]) -> Self {
+        Self::build(Self::terminated(&[text]))
+    }
+
+    /// Concatenates strings, each followed by its own unique terminator.
+    fn terminated(strings: &[&[u8]]) -> Vec<u16> {
+        strings
+            .iter()
+            .enumerate()
+            .flat_map(|(index, string)| {
+                string
+                    .iter()
+                    .map(|&byte| byte as u16)
+                    .chain([FIRST_TERMINATOR + index as u16])
+            })
+            .collect()
+    }
+
+    fn build(text: Vec<u16>) -> Self {
+        let mut tree = Self {
+            text,
+            nodes: vec![SuffixNode {
+                start: 0,
+                end: Some(0),
+                suffix_link: ROOT,
+                children: Vec::new(),
+                suffix_start: None,
+            }],
+        };
+
+        let mut active_node = ROOT;
+        let mut active_edge = 0;
+        let mut active_length = 0;
+        let mut remainder = 0;
+        for position in 0..tree.text.len() {
+            let symbol = tree.text[position];
+            remainder += 1;
+            let mut last_internal: Option<usize> = None;
+
+            while remainder > 0 {
+                if active_length == 0 {
+                    active_edge = position;
+                }
+                match tree.child(active_node, tree.text[active_edge]) {
+                    None => {
+                        let leaf = tree.add_node(position, None);
+                        tree.set_child(active_node, tree.text[active_edge], leaf);
+                        if let Some(internal) = last_internal.take() {
+                            tree.nodes[internal].suffix_link = active_node;
+                        }
+                    }
+                    Some(next) => {
+                        let edge_length = tree.edge_length(next, position);
+                        if active_length >= edge_length {
+                            // Walk down, the active point lies past this edge
+                            active_edge += edge_length;
+                            active_length -= edge_length;
+                            active_node = next;
+                            continue;
+                        }
+                        if tree.text[tree.nodes[next].start + active_length] == symbol {
+                            if let Some(internal) = last_internal.take() {
+                                if active_node != ROOT {
+                                    tree.nodes[internal].suffix_link = active_node;
+                                }
+                            }
+                            active_length += 1;
+                            break;
+                        }
+
+                        // Split the edge and hang a new leaf from the split point
+                        let next_start = tree.nodes[next].start;
+                        let split = tree.add_node(next_start, Some(next_start + active_length - 1));
+                        tree.set_child(active_node, tree.text[active_edge], split);
+                        let leaf = tree.add_node(position, None);
+                        tree.set_child(split, symbol, leaf);
+                        tree.nodes[next].start += active_length;
+                        let next_symbol = tree.text[tree.nodes[next].start];
+                        tree.set_child(split, next_symbol, next);
+                        if let Some(internal) = last_internal {
+                            tree.nodes[internal].suffix_link = split;
+                        }
+                        last_internal = Some(split);
+                    }
+                }
+
+                remainder -= 1;
+                if active_node == ROOT && active_length > 0 {
+                    active_length -= 1;
+                    active_edge = position + 1 - remainder;
+                } else if active_node != ROOT {
+                    active_node = tree.nodes[active_node].suffix_link;
+                }
+            }
+        }
+
+        tree.assign_suffix_starts();
+        tree
+    }
+
+    fn add_node(&mut self, start: usize, end: Option<usize>) -> usize {
+        self.nodes.push(SuffixNode {
+            start,
+            end,
+            suffix_link: ROOT,
+            children: Vec::new(),
+            suffix_start: None,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn child(&self, node: usize, symbol: u16) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&symbol, |(first, _)| *first)
+            .ok()
+            .map(|position| children[position].1)
+    }
+
+    fn set_child(&mut self, node: usize, symbol: u16, child: usize) {
+        let children = &mut self.nodes[node].children;
+        match children.binary_search_by_key(&symbol, |(first, _)| *first) {
+            Ok(position) => children[position].1 = child,
+            Err(position) => children.insert(position, (symbol, child)),
+        }
+    }
+
+    /// Length of the edge into `node`, leaf edges reaching up to `position`.
+    fn edge_length(&self, node: usize, position: usize) -> usize {
+        let SuffixNode { start, end, .. } = self.nodes[node];
+        end.unwrap_or(position) + 1 - start
+    }
+
+    fn assign_suffix_starts(&mut self) {
+        let text_end = self.text.len() - 1;
+        let mut stack: Vec<(usize, usize)> = self.nodes[ROOT]
+            .children
+            .iter()
+            .map(|&(_, child)| (child, 0))
+            .collect();
+        while let Some((node, parent_depth)) = stack.pop() {
+            let depth = parent_depth + self.edge_length(node, text_end);
+            if self.nodes[node].end.is_none() {
+                self.nodes[node].suffix_start = Some(self.text.len() - depth);
+            }
+            stack.extend(
+                self.nodes[node]
+                    .children
+                    .iter()
+                    .map(|&(_, child)| (child, depth)),
+            );
+        }
+    }
+
+    /// Node at or below the end of `pattern`'s path from the root, `None` if the pattern doesn't occur.
+    fn locate(&self, pattern: &[u8]) -> Option<usize> {
+        let text_end = self.text.len() - 1;
+        let mut node = ROOT;
+        let mut matched = 0;
+        while matched < pattern.len() {
+            node = self.child(node, pattern[matched] as u16)?;
+            let start = self.nodes[node].start;
+            let length = self.edge_length(node, text_end);
+            for offset in 0..length.min(pattern.len() - matched) {
+                if self.text[start + offset] != pattern[matched + offset] as u16 {
+                    return None;
+                }
+            }
+            matched += length;
+        }
+        Some(node)
+    }
+
+    /// True if `pattern` occurs in the text, in O(pattern length).
+    pub fn contains(&self, pattern: &[u8]) -> bool {
+        self.locate(pattern).is_some()
+    }
+
+    /// Starting positions of all occurrences of `pattern`, sorted.
+    pub fn find_all(&self, pattern: &[u8]) -> Vec<usize> {
+        let Some(node) = self.locate(pattern) else {
+            return Vec::new();
+        };
+        let mut positions = Vec::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if node != ROOT {
+                positions.extend(self.nodes[node].suffix_start);
+            }
+            stack.extend(self.nodes[node].children.iter().map(|&(_, child)| child));
+        }
+        // The empty pattern also matches at the very end, in front of the terminator
+        positions.retain(|&position| position + pattern.len() < self.text.len());
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Longest string occurring in both `a` and `b`, found via a generalized suffix tree in O(|a| + |b|).
+    /// If there are more candidates of the same length, the one occurring first in `a` is returned.
+    pub fn longest_common_substring<'a>(a: &'a [u8], b: &[u8]) -> &'a [u8] {
+        let tree = Self::build(Self::terminated(&[a, b]));
+        let text_end = tree.text.len() - 1;
+
+        // Post-order pass marking which strings every subtree contains suffixes of
+        let mut best: (usize, usize) = (0, 0); // (length, start in a)
+        let mut origins = vec![(false, false); tree.nodes.len()];
+        let mut earliest = vec![usize::MAX; tree.nodes.len()];
+        let mut stack = vec![(ROOT, 0_usize, false)];
+        while let Some((node, depth, expanded)) = stack.pop() {
+            if !expanded {
+                stack.push((node, depth, true));
+                for &(_, child) in &tree.nodes[node].children {
+                    let child_depth = depth + tree.edge_length(child, text_end);
+                    stack.push((child, child_depth, false));
+                }
+                continue;
+            }
+
+            match tree.nodes[node].suffix_start {
+                Some(start) if start <= a.len() => {
+                    origins[node] = (true, false);
+                    earliest[node] = start;
+                }
+                Some(_) => origins[node] = (false, true),
+                None => {
+                    for &(_, child) in &tree.nodes[node].children {
+                        origins[node].0 |= origins[child].0;
+                        origins[node].1 |= origins[child].1;
+                        earliest[node] = earliest[node].min(earliest[child]);
+                    }
+                    let label_depth = depth;
+                    if origins[node] == (true, true)
+                        && (label_depth > best.0
+                            || (label_depth == best.0 && earliest[node] < best.1))
+                    {
+                        best = (label_depth, earliest[node]);
+                    }
+                }
+            }
+        }
+
+        let (length, start) = best;
+        if length == 0 {
+            return &a[..0];
+        }
+        &a[start..start + length]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::SuffixTree;
+
+    fn naive_find_all(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        (0..=text.len().saturating_sub(pattern.len()))
+            .filter(|&start| text[start..].starts_with(pattern))
+            .filter(|_| pattern.len() <= text.len())
+            .collect()
+    }
+
+    #[test]
+    fn find_all() {
+        let texts: [&[u8]; 4] = [b"mississippi", b"aaaaaaa", b"abcabxabcd", b"banana"];
+        for text in texts {
+            let tree = SuffixTree::new(text);
+            for start in 0..text.len() {
+                for end in start + 1..=text.len() {
+                    let pattern = &text[start..end];
+                    assert_eq!(tree.find_all(pattern), naive_find_all(text, pattern));
+                }
+            }
+            assert!(!tree.contains(b"zz"));
+            assert!(tree.find_all(b"xyz").is_empty());
+        }
+    }
+
+    #[test]
+    fn leaves_cover_all_suffixes() {
+        let text = b"abcabxabcd";
+        let tree = SuffixTree::new(text);
+        let mut starts: Vec<usize> = tree.nodes.iter().filter_map(|n| n.suffix_start).collect();
+        starts.sort_unstable();
+        assert_eq!(starts, (0..=text.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn longest_common_substring() {
+        assert_eq!(
+            SuffixTree::longest_common_substring(b"GeeksforGeeks", b"GeeksQuiz"),
+            b"Geeks"
+        );
+        assert_eq!(SuffixTree::longest_common_substring(b"abc", b"xyz"), b"");
+        assert_eq!(SuffixTree::longest_common_substring(b"", b"xyz"), b"");
+        assert_eq!(
+            SuffixTree::longest_common_substring(b"xyzabcd", b"abcdxyz"),
+            b"abcd"
+        );
+        // Ties resolve to the earliest occurrence in the first string
+        assert_eq!(
+            SuffixTree::longest_common_substring(b"cdab", b"abcd"),
+            b"cd"
+        );
+    }
+}
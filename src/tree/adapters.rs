@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::consts::LEAF_NODE;
+use crate::tree::{Tree, TreeError};
+
+/// Builds a tree from the nodes of a node arena reachable from `root`. The root gets id `0`, remaining nodes follow
+/// in the order of their arena ids, so ids are kept whenever the arena holds just the tree rooted at its first node.
+fn from_arena<K, T>(root: K, children: impl Fn(K) -> Vec<K>, value: impl Fn(K) -> T) -> Tree<T>
+where
+    K: Copy + Ord + Hash,
+{
+    let mut reachable = vec![root];
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let node_children = children(node);
+        reachable.extend_from_slice(&node_children);
+        stack.extend(node_children);
+    }
+    reachable[1..].sort_unstable();
+    let ids: HashMap<K, usize> = reachable
+        .iter()
+        .enumerate()
+        .map(|(id, &node)| (node, id))
+        .collect();
+
+    let mut nodes: Vec<Vec<isize>> = Vec::new();
+    for (id, &node) in reachable.iter().enumerate() {
+        for (dimension, child) in children(node).into_iter().enumerate() {
+            if dimension == nodes.len() {
+                nodes.push(vec![LEAF_NODE; reachable.len()]);
+            }
+            nodes[dimension][id] = ids[&child] as isize;
+        }
+    }
+    if nodes.is_empty() {
+        nodes.push(vec![LEAF_NODE; reachable.len()]);
+    }
+    let values = reachable.into_iter().map(value).collect();
+    Tree::new(nodes, values).unwrap_or_else(|_| unreachable!("Every dimension has a link per node"))
+}
+
+#[cfg(feature = "indextree")]
+impl<T: Clone> Tree<T> {
+    /// Copies the tree into an [indextree::Arena], returning it with the id of the root. Node `i` gets the `i`-th
+    /// arena id, children are appended in the order of their dimensions. The tree is checked by [Tree::validate]
+    /// first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec!['a', 'b', 'c']).expect("Tree has a valid structure");
+    ///
+    ///         let (arena, root) = tree.to_indextree().expect("Tree is valid");
+    ///         assert_eq!(root.children(&arena).map(|child| arena[child].get()).collect::<String>(), "bc");
+    ///         assert_eq!(Tree::from_indextree(&arena, root), Ok(tree));
+    /// ```
+    pub fn to_indextree(&self) -> Result<(indextree::Arena<T>, indextree::NodeId), TreeError> {
+        self.validate()?;
+        let mut arena = indextree::Arena::with_capacity(self.values.len());
+        let ids: Vec<indextree::NodeId> = self
+            .values
+            .iter()
+            .map(|value| arena.new_node(value.clone()))
+            .collect();
+        for (node, &id) in ids.iter().enumerate() {
+            for child in self.child_ids(node) {
+                id.append(ids[child], &mut arena);
+            }
+        }
+        ids.first()
+            .map(|&root| (arena, root))
+            .ok_or(TreeError::EmptyTree)
+    }
+
+    /// Copies the subtree of `root` out of an [indextree::Arena], children taking dimensions in their order. The
+    /// root gets id `0`, other nodes follow in the order of their arena ids; ids are kept for an arena holding only
+    /// the tree rooted at its first node.
+    ///
+    /// Removed `root` is reported as [TreeError::StaleNode], a `root` out of bounds of the arena as
+    /// [TreeError::NodeOutOfBounds]. Both carry the 0-based position of `root` in the arena, i.e. its 1-based
+    /// indextree id minus one, the id it would get from [Tree::to_indextree].
+    pub fn from_indextree(
+        arena: &indextree::Arena<T>,
+        root: indextree::NodeId,
+    ) -> Result<Self, TreeError> {
+        let node = usize::from(root) - 1;
+        if node >= arena.as_slice().len() {
+            return Err(TreeError::NodeOutOfBounds { node });
+        }
+        if root.is_removed(arena) {
+            return Err(TreeError::StaleNode { node });
+        }
+        Ok(from_arena(
+            root,
+            |node| node.children(arena).collect(),
+            |node| arena[node].get().clone(),
+        ))
+    }
+}
+
+#[cfg(feature = "ego-tree")]
+impl<T: Clone> Tree<T> {
+    /// Copies the tree into an [ego_tree::Tree]. Node `i` gets the `i`-th id of the arena, children are appended in
+    /// the order of their dimensions. The tree is checked by [Tree::validate] first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec!['a', 'b', 'c']).expect("Tree has a valid structure");
+    ///
+    ///         let ego = tree.to_ego_tree().expect("Tree is valid");
+    ///         assert_eq!(ego.root().children().map(|child| *child.value()).collect::<String>(), "bc");
+    ///         assert_eq!(Tree::from_ego_tree(&ego), tree);
+    /// ```
+    pub fn to_ego_tree(&self) -> Result<ego_tree::Tree<T>, TreeError> {
+        self.validate()?;
+        let Some(root) = self.values.first() else {
+            return Err(TreeError::EmptyTree);
+        };
+        let mut tree = ego_tree::Tree::with_capacity(root.clone(), self.values.len());
+        let mut ids = vec![tree.root().id()];
+        for value in self.values[1..].iter() {
+            ids.push(tree.orphan(value.clone()).id());
+        }
+        for (node, &id) in ids.iter().enumerate() {
+            let mut parent = tree
+                .get_mut(id)
+                .unwrap_or_else(|| unreachable!("Node has just been added"));
+            for child in self.child_ids(node) {
+                parent.append_id(ids[child]);
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Copies the nodes reachable from the root of an [ego_tree::Tree], children taking dimensions in their order.
+    /// Orphans are left out, ids of other nodes keep their order and are unchanged if there are no orphans.
+    pub fn from_ego_tree(tree: &ego_tree::Tree<T>) -> Self {
+        let node = |id| {
+            tree.get(id)
+                .unwrap_or_else(|| unreachable!("Ids come from the tree"))
+        };
+        from_arena(
+            tree.root().id(),
+            |id| node(id).children().map(|child| child.id()).collect(),
+            |id| node(id).value().clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    fn tree() -> Tree<u8> {
+        Tree::new(
+            vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]],
+            vec![0, 1, 2, 3, 4],
+        )
+        .expect("Tree has a valid structure")
+    }
+
+    #[test]
+    #[cfg(feature = "indextree")]
+    fn indextree() {
+        use crate::tree::TreeError;
+
+        let tree = tree();
+        let (mut arena, root) = tree.to_indextree().expect("Tree is valid");
+        assert_eq!(Tree::from_indextree(&arena, root), Ok(tree));
+
+        // Other roots are left out, the moved subtree keeps the order of arena ids
+        let other = arena.new_node(9);
+        let first = root.children(&arena).next().expect("Root has children");
+        first.detach(&mut arena);
+        other.append(first, &mut arena);
+        let moved = Tree::from_indextree(&arena, other).expect("Node exists");
+        assert_eq!(moved.values(), &vec![9, 1, 3, 4]);
+        assert_eq!(moved.children(1), vec![2, 3]);
+
+        other.remove_subtree(&mut arena);
+        assert_eq!(
+            Tree::from_indextree(&arena, other),
+            Err(TreeError::StaleNode { node: 5 })
+        );
+
+        // Ids of a larger arena are out of bounds
+        let mut larger = indextree::Arena::new();
+        let beyond = (0..=6)
+            .map(|value| larger.new_node(value))
+            .last()
+            .expect("Arena has nodes");
+        assert_eq!(
+            Tree::from_indextree(&arena, beyond),
+            Err(TreeError::NodeOutOfBounds { node: 6 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ego-tree")]
+    fn ego_tree() {
+        let tree = tree();
+        let mut ego = tree.to_ego_tree().expect("Tree is valid");
+        assert_eq!(Tree::from_ego_tree(&ego), tree);
+
+        let mut root = ego.root_mut();
+        root.first_child().expect("Root has children").detach();
+        root.prepend(7);
+        let changed = Tree::from_ego_tree(&ego);
+        assert_eq!(changed.values(), &vec![0, 2, 7]);
+        assert_eq!(changed.children(0), vec![2, 1]);
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use arrow_array::{
+    Array, ArrayRef, ArrowPrimitiveType, BooleanArray, PrimitiveArray, RecordBatch, StringArray,
+    UInt32Array, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema};
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::{Tree, TreeError};
+
+/// Value stored in the `value` column of a tree exported to Arrow. Implemented for integers, floats, `bool` and
+/// `String`.
+pub trait ArrowValue: Sized {
+    fn data_type() -> DataType;
+
+    fn to_array<'a>(values: impl Iterator<Item = &'a Self>) -> ArrayRef
+    where
+        Self: 'a;
+
+    /// Values of `array`, `None` if it holds another type or nulls.
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>>;
+}
+
+macro_rules! primitive_values {
+    ($($native:ty => $arrow:ty),+) => {
+        $(
+            impl ArrowValue for $native {
+                fn data_type() -> DataType {
+                    <$arrow>::DATA_TYPE
+                }
+
+                fn to_array<'a>(values: impl Iterator<Item = &'a Self>) -> ArrayRef {
+                    Arc::new(PrimitiveArray::<$arrow>::from_iter_values(values.copied()))
+                }
+
+                fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+                    array
+                        .as_any()
+                        .downcast_ref::<PrimitiveArray<$arrow>>()?
+                        .iter()
+                        .collect()
+                }
+            }
+        )+
+    };
+}
+
+primitive_values!(
+    i8 => Int8Type, i16 => Int16Type, i32 => Int32Type, i64 => Int64Type,
+    u8 => UInt8Type, u16 => UInt16Type, u32 => UInt32Type, u64 => UInt64Type,
+    f32 => Float32Type, f64 => Float64Type
+);
+
+impl ArrowValue for bool {
+    fn data_type() -> DataType {
+        DataType::Boolean
+    }
+
+    fn to_array<'a>(values: impl Iterator<Item = &'a Self>) -> ArrayRef {
+        Arc::new(BooleanArray::from(values.copied().collect::<Vec<_>>()))
+    }
+
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+        array
+            .as_any()
+            .downcast_ref::<BooleanArray>()?
+            .iter()
+            .collect()
+    }
+}
+
+impl ArrowValue for String {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn to_array<'a>(values: impl Iterator<Item = &'a Self>) -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(values))
+    }
+
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+        array
+            .as_any()
+            .downcast_ref::<StringArray>()?
+            .iter()
+            .map(|value| value.map(str::to_owned))
+            .collect()
+    }
+}
+
+impl<T: ArrowValue> Tree<T> {
+    /// Schema of exported trees: non-null `node_id`, nullable `parent_id` (null for the root), non-null `depth` and
+    /// `value` columns.
+    pub fn arrow_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("node_id", DataType::UInt64, false),
+            Field::new("parent_id", DataType::UInt64, true),
+            Field::new("depth", DataType::UInt32, false),
+            Field::new("value", T::data_type(), false),
+        ])
+    }
+
+    /// Exports the tree as a row per node, see [Tree::arrow_schema]. Rows are in breadth-first order, siblings
+    /// following their dimensions. The tree is checked by [Tree::validate] first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![2, -1, -1], vec![1, -1, -1]], vec![1.5, 2.5, 3.5]).expect("Tree has a valid structure");
+    ///
+    ///         let batch = tree.to_record_batch().expect("Tree is valid");
+    ///         assert_eq!(batch.num_rows(), 3);
+    ///         assert_eq!(Tree::from_record_batch(&batch), Ok(tree));
+    /// ```
+    pub fn to_record_batch(&self) -> Result<RecordBatch, TreeError> {
+        self.validate()?;
+        let mut rows = Vec::with_capacity(self.values.len());
+        let mut queue: VecDeque<(usize, Option<u64>, u32)> = VecDeque::new();
+        if !self.values.is_empty() {
+            queue.push_back((ROOT_NODE as usize, None, 0));
+        }
+        while let Some((node, parent, depth)) = queue.pop_front() {
+            rows.push((node, parent, depth));
+            for child in self.child_ids(node) {
+                queue.push_back((child, Some(node as u64), depth + 1));
+            }
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(
+                rows.iter().map(|&(node, _, _)| node as u64),
+            )),
+            Arc::new(UInt64Array::from_iter(
+                rows.iter().map(|&(_, parent, _)| parent),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|&(_, _, depth)| depth),
+            )),
+            T::to_array(rows.iter().map(|&(node, _, _)| &self.values[node])),
+        ];
+        Ok(
+            RecordBatch::try_new(Arc::new(Self::arrow_schema()), columns).unwrap_or_else(|_| {
+                unreachable!("Columns match the schema and have a row per node")
+            }),
+        )
+    }
+
+    /// Imports a tree from rows of [Tree::arrow_schema] in any order, checked by [Tree::validate]. Children take
+    /// dimensions in the order of their rows, the `depth` column is ignored. Missing columns, columns of other
+    /// types and repeated node ids are reported as [TreeError::CorruptedTree].
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self, TreeError> {
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| TreeError::CorruptedTree(format!("Column {name} is missing")))
+        };
+        let ids = column("node_id")?
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .filter(|ids| ids.null_count() == 0)
+            .ok_or_else(|| {
+                TreeError::CorruptedTree("Column node_id must hold UInt64 values".to_string())
+            })?;
+        let parents = column("parent_id")?
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .ok_or_else(|| {
+                TreeError::CorruptedTree("Column parent_id must hold UInt64 values".to_string())
+            })?;
+        let values = T::from_array(column("value")?.as_ref()).ok_or_else(|| {
+            TreeError::CorruptedTree(format!("Column value must hold {} values", T::data_type()))
+        })?;
+
+        let len = batch.num_rows();
+        let node_id = |id: u64| {
+            usize::try_from(id)
+                .ok()
+                .filter(|&node| node < len)
+                .ok_or(TreeError::NodeOutOfBounds { node: id as usize })
+        };
+        let mut slots: Vec<Option<T>> = (0..len).map(|_| None).collect();
+        let mut children: Vec<Vec<isize>> = vec![Vec::new(); len];
+        for (row, value) in values.into_iter().enumerate() {
+            let node = node_id(ids.value(row))?;
+            if slots[node].replace(value).is_some() {
+                return Err(TreeError::CorruptedTree(format!(
+                    "Node {node} is listed more than once"
+                )));
+            }
+            if parents.is_valid(row) {
+                children[node_id(parents.value(row))?].push(node as isize);
+            }
+        }
+
+        let dimensions = children.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let mut nodes = vec![vec![LEAF_NODE; len]; dimensions];
+        for (parent, children) in children.into_iter().enumerate() {
+            for (dimension, child) in children.into_iter().enumerate() {
+                nodes[dimension][parent] = child;
+            }
+        }
+        Tree::new_validated(nodes, slots.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, UInt32Array, UInt64Array};
+
+    use crate::tree::{Tree, TreeError};
+
+    #[test]
+    fn record_batch() {
+        let values = ["root", "a", "b", "a1"].map(String::from).to_vec();
+        let tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], values)
+            .expect("Tree has a valid structure");
+        let batch = tree.to_record_batch().expect("Tree is valid");
+        let depths = batch.column(2).as_any().downcast_ref::<UInt32Array>();
+        assert_eq!(
+            depths.map(|depths| depths.values().to_vec()),
+            Some(vec![0, 1, 1, 2])
+        );
+        assert_eq!(batch.column(1).null_count(), 1);
+        assert_eq!(Tree::from_record_batch(&batch), Ok(tree));
+
+        // Rows in any order, children keep the order of their rows
+        let rows = |ids: Vec<u64>, parents: Vec<Option<u64>>| {
+            let values: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+            let columns: Vec<ArrayRef> = vec![
+                Arc::new(UInt64Array::from(ids)),
+                Arc::new(UInt64Array::from(parents)),
+                Arc::new(StringArray::from(values)),
+            ];
+            RecordBatch::try_from_iter(["node_id", "parent_id", "value"].into_iter().zip(columns))
+                .expect("Columns have the same length")
+        };
+        let shuffled =
+            Tree::<String>::from_record_batch(&rows(vec![2, 0, 1], vec![Some(0), None, Some(0)]))
+                .expect("Rows form a tree");
+        assert_eq!(shuffled.children(0), vec![2, 1]);
+
+        assert_eq!(
+            Tree::<String>::from_record_batch(&rows(vec![0, 0], vec![None, Some(0)])),
+            Err(TreeError::CorruptedTree(
+                "Node 0 is listed more than once".to_string()
+            ))
+        );
+        assert_eq!(
+            Tree::<String>::from_record_batch(&rows(vec![0, 1], vec![None, None])),
+            Err(TreeError::MultipleRoots { node: 1 })
+        );
+        assert_eq!(
+            Tree::<String>::from_record_batch(&rows(vec![0, 1], vec![None, Some(7)])),
+            Err(TreeError::NodeOutOfBounds { node: 7 })
+        );
+        assert!(matches!(
+            Tree::<u8>::from_record_batch(&rows(vec![0], vec![None])),
+            Err(TreeError::CorruptedTree(_))
+        ));
+    }
+}
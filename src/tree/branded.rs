@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+use std::ops::Index;
+
+use crate::consts::ROOT_NODE;
+use crate::tree::{NodeId, Tree};
+
+/// Invariant lifetime unique to one [Tree::branded] call. Ids carrying the brand can't be used with any other tree.
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// [NodeId] branded with the tree it was obtained from. A branded id is only handed out for existing nodes,
+/// so reading through it never goes out of bounds.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BrandedId<'id> {
+    id: NodeId,
+    brand: Brand<'id>,
+}
+
+impl BrandedId<'_> {
+    /// Plain id of the node, valid for the tree the brand came from.
+    pub fn id(self) -> NodeId {
+        self.id
+    }
+}
+
+/// Read-only view of a [Tree] inside [Tree::branded], handing out [BrandedId]s tied to this tree only.
+///
+/// Ids of a different branded tree are rejected at compile time:
+///
+/// ```compile_fail
+///         use treesome::tree::Tree;
+///         let first = Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+///         let second = Tree::new(vec![vec![-1]], vec![3]).expect("Tree has a valid structure");
+///         first.branded(|first| {
+///             second.branded(|second| {
+///                 let child = first.children(first.root()).next().unwrap();
+///                 second[child]
+///             })
+///         });
+/// ```
+#[derive(Debug)]
+pub struct BrandedTree<'id, 't, T> {
+    tree: &'t Tree<T>,
+    brand: Brand<'id>,
+}
+
+impl<T> Tree<T> {
+    /// Runs `f` with a [BrandedTree] view of a non-empty tree. Ids obtained from the view can't escape the
+    /// closure or be mixed with ids of another tree. Returns `None` for an empty tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let left = vec![1, 3, -1, -1, -1];
+    ///         let right = vec![2, 4, -1, -1, -1];
+    ///         let tree = Tree::new(vec![left, right], vec![1, 2, 3, 4, 5]).expect("Tree has a valid structure");
+    ///
+    ///         let sum = tree.branded(|tree| {
+    ///             let root = tree.root();
+    ///             tree.children(root).map(|child| tree[child]).sum::<i32>()
+    ///         });
+    ///         assert_eq!(sum, Some(5));
+    /// ```
+    pub fn branded<R>(&self, f: impl for<'id> FnOnce(BrandedTree<'id, '_, T>) -> R) -> Option<R> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        Some(f(BrandedTree {
+            tree: self,
+            brand: PhantomData,
+        }))
+    }
+}
+
+impl<'id, 't, T> BrandedTree<'id, 't, T> {
+    /// The branded tree is never empty, so it always has a root.
+    pub fn root(&self) -> BrandedId<'id> {
+        self.brand(NodeId::ROOT)
+    }
+
+    /// Brands `id` if it exists in this tree.
+    pub fn id(&self, id: NodeId) -> Option<BrandedId<'id>> {
+        (id.index() < self.tree.values.len()).then(|| self.brand(id))
+    }
+
+    pub fn value(&self, id: BrandedId<'id>) -> &'t T {
+        &self.tree.values[id.id.index()]
+    }
+
+    /// Existing children of `id`, missing children and out of bounds links are skipped.
+    pub fn children(&self, id: BrandedId<'id>) -> BrandedChildren<'id, 't> {
+        BrandedChildren {
+            nodes: self.tree.nodes.as_slice(),
+            len: self.tree.values.len(),
+            node: id.id.index(),
+            dimension: 0,
+            brand: self.brand,
+        }
+    }
+
+    /// Node linking to `id`, `None` for the root. See [Tree::try_parent].
+    pub fn parent(&self, id: BrandedId<'id>) -> Option<BrandedId<'id>> {
+        self.tree
+            .try_parent(id.id)
+            .ok()
+            .flatten()
+            .map(|parent| self.brand(parent))
+    }
+
+    /// The underlying tree, for operations taking plain ids.
+    pub fn tree(&self) -> &'t Tree<T> {
+        self.tree
+    }
+
+    fn brand(&self, id: NodeId) -> BrandedId<'id> {
+        BrandedId {
+            id,
+            brand: self.brand,
+        }
+    }
+}
+
+/// Iterator over the children of a node in a [BrandedTree], see [BrandedTree::children].
+#[derive(Debug, Clone)]
+pub struct BrandedChildren<'id, 't> {
+    nodes: &'t [Vec<isize>],
+    len: usize,
+    node: usize,
+    dimension: usize,
+    brand: Brand<'id>,
+}
+
+impl<'id> Iterator for BrandedChildren<'id, '_> {
+    type Item = BrandedId<'id>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(dimension) = self.nodes.get(self.dimension) {
+            self.dimension += 1;
+            let child = dimension[self.node];
+            if child > ROOT_NODE && (child as usize) < self.len {
+                return Some(BrandedId {
+                    id: NodeId::new(child as usize),
+                    brand: self.brand,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl<'id, T> Index<BrandedId<'id>> for BrandedTree<'id, '_, T> {
+    type Output = T;
+
+    fn index(&self, id: BrandedId<'id>) -> &Self::Output {
+        self.value(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, Tree};
+
+    #[test]
+    fn branded() {
+        let left = vec![1, 3, -1, -1, -1];
+        let right = vec![2, 4, -1, -1, -1];
+        let tree = Tree::new(vec![left, right], vec!["a", "b", "c", "d", "e"])
+            .expect("Tree has a valid structure");
+
+        tree.branded(|branded| {
+            let root = branded.root();
+            assert_eq!(branded.parent(root), None);
+            let leaf = branded.id(NodeId::new(4)).expect("Node exists");
+            assert_eq!(branded[leaf], "e");
+            assert_eq!(
+                branded.parent(leaf).map(|parent| parent.id()),
+                Some(NodeId::new(1))
+            );
+            assert_eq!(branded.children(leaf).count(), 0);
+            assert!(branded.id(NodeId::new(5)).is_none());
+        })
+        .expect("Tree isn't empty");
+
+        let empty: Tree<u8> = Tree::new(vec![vec![]], vec![]).expect("Tree has a valid structure");
+        assert!(empty.branded(|_| ()).is_none());
+    }
+
+    #[test]
+    fn parent_outside_complete_layout() {
+        let mut tree = Tree::new(vec![], vec!["root"]).expect("Tree has a valid structure");
+        tree.add_child(0, "a").expect("Root exists");
+        let b = tree.add_child(0, "b").expect("Root exists");
+        let c = tree.add_child(b, "c").expect("Node b exists");
+
+        tree.branded(|branded| {
+            let c = branded.id(NodeId::new(c)).expect("Node exists");
+            assert_eq!(
+                branded.parent(c).map(|parent| parent.id()),
+                Some(NodeId::new(b))
+            );
+        })
+        .expect("Tree isn't empty");
+    }
+}
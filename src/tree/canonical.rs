@@ -0,0 +1,222 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::Tree;
+
+impl<T: Ord> Tree<T> {
+    /// Returns a mapping from current node ids to ids in the canonical form of this tree, see [Tree::canonicalize].
+    /// Nodes unreachable from the root map to `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let left = vec![1, -1, -1];
+    ///         let right = vec![2, -1, -1];
+    ///         let values = vec![0, 20, 10];
+    ///         let tree = Tree::new(vec![left, right], values).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.canonical_relabeling(), vec![Some(0), Some(2), Some(1)]);
+    /// ```
+    pub fn canonical_relabeling(&self) -> Vec<Option<usize>> {
+        let (levels, parents) = self.levels();
+
+        // AHU: label subtrees level by level, starting with the deepest one. Siblings always share a level,
+        // so ranks within a level are enough to order any set of siblings.
+        let mut labels = vec![0_usize; self.values.len()];
+        let mut sorted_children: Vec<Vec<usize>> = vec![Vec::new(); self.values.len()];
+        for level in levels.iter().rev() {
+            for &node in level {
+                let mut children: Vec<usize> = self.tree_children(node, &parents).collect();
+                children.sort_by_key(|&child| labels[child]);
+                sorted_children[node] = children;
+            }
+
+            let mut ranked = level.clone();
+            ranked.sort_by(|&a, &b| self.compare_subtrees(a, b, &labels, &sorted_children));
+            let mut rank = 0;
+            for (position, &node) in ranked.iter().enumerate() {
+                if position > 0
+                    && self.compare_subtrees(ranked[position - 1], node, &labels, &sorted_children)
+                        != Ordering::Equal
+                {
+                    rank += 1;
+                }
+                labels[node] = rank;
+            }
+        }
+
+        // Canonical ids are assigned breadth-first, visiting children in their canonical order.
+        let mut relabeling = vec![None; self.values.len()];
+        if self.values.is_empty() {
+            return relabeling;
+        }
+        let mut queue = std::collections::VecDeque::from([ROOT_NODE as usize]);
+        let mut next_id = 0;
+        while let Some(node) = queue.pop_front() {
+            relabeling[node] = Some(next_id);
+            next_id += 1;
+            queue.extend(sorted_children[node].iter().copied());
+        }
+
+        relabeling
+    }
+
+    /// Returns a canonical form of this tree, with children of every node sorted by a canonical key
+    /// of their subtrees (AHU encoding extended with node values). Two trees with the same arity
+    /// that only differ in the order of children have equal canonical forms. Nodes are numbered
+    /// breadth-first, unreachable nodes are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec![0, 20, 10]).expect("Valid tree");
+    ///         let mirrored = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec![0, 10, 20]).expect("Valid tree");
+    ///
+    ///         assert_ne!(tree, mirrored);
+    ///         assert_eq!(tree.canonicalize(), mirrored.canonicalize());
+    /// ```
+    pub fn canonicalize(&self) -> Self
+    where
+        T: Clone,
+    {
+        let relabeling = self.canonical_relabeling();
+        let size = relabeling.iter().flatten().count();
+
+        let mut old_ids = vec![0_usize; size];
+        for (old_id, new_id) in relabeling.iter().enumerate() {
+            if let Some(new_id) = new_id {
+                old_ids[*new_id] = old_id;
+            }
+        }
+
+        let (_, parents) = self.levels();
+        let mut nodes = vec![vec![LEAF_NODE; size]; self.nodes.len()];
+        for (new_id, &old_id) in old_ids.iter().enumerate() {
+            let mut children: Vec<usize> = self
+                .tree_children(old_id, &parents)
+                .filter_map(|child| relabeling[child])
+                .collect();
+            children.sort_unstable();
+            for (slot, child) in children.into_iter().enumerate() {
+                nodes[slot][new_id] = child as isize;
+            }
+        }
+        let values = old_ids
+            .iter()
+            .map(|&old_id| self.values[old_id].clone())
+            .collect();
+
+        Self {
+            nodes: Arc::new(nodes),
+            values: Arc::new(values),
+        }
+    }
+
+    fn compare_subtrees(
+        &self,
+        a: usize,
+        b: usize,
+        labels: &[usize],
+        sorted_children: &[Vec<usize>],
+    ) -> Ordering {
+        self.values[a].cmp(&self.values[b]).then_with(|| {
+            let a_children = sorted_children[a].iter().map(|&child| labels[child]);
+            let b_children = sorted_children[b].iter().map(|&child| labels[child]);
+            a_children.cmp(b_children)
+        })
+    }
+}
+
+impl<T> Tree<T> {
+    /// Nodes reachable from the root grouped by their depth, together with the parent each node was reached
+    /// through. Every node is listed at most once.
+    fn levels(&self) -> (Vec<Vec<usize>>, Vec<Option<usize>>) {
+        let mut levels = Vec::new();
+        let mut parents = vec![None; self.values.len()];
+        if self.values.is_empty() {
+            return (levels, parents);
+        }
+
+        let mut visited = vec![false; self.values.len()];
+        visited[ROOT_NODE as usize] = true;
+        let mut level = vec![ROOT_NODE as usize];
+        while !level.is_empty() {
+            let mut next_level = Vec::new();
+            for &node in &level {
+                for child in self.child_ids(node) {
+                    if !visited[child] {
+                        visited[child] = true;
+                        parents[child] = Some(node);
+                        next_level.push(child);
+                    }
+                }
+            }
+            levels.push(level);
+            level = next_level;
+        }
+
+        (levels, parents)
+    }
+
+    /// Children of `node` which were reached through it, see [Tree::levels].
+    fn tree_children<'a>(
+        &'a self,
+        node: usize,
+        parents: &'a [Option<usize>],
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.child_ids(node)
+            .filter(move |&child| parents[child] == Some(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    #[test]
+    fn canonicalize_ignores_child_order() {
+        let first = vec![1, 3, -1, -1, -1, -1];
+        let second = vec![2, 4, -1, -1, -1, -1];
+        let third = vec![-1, 5, -1, -1, -1, -1];
+        let values = vec!['r', 'a', 'b', 'c', 'd', 'e'];
+        let tree =
+            Tree::new(vec![first, second, third], values).expect("Tree has a valid structure");
+
+        // Same tree, with children of the root swapped and children of 'a' rotated
+        let first = vec![1, -1, 3, -1, -1, -1];
+        let second = vec![2, -1, 4, -1, -1, -1];
+        let third = vec![-1, -1, 5, -1, -1, -1];
+        let values = vec!['r', 'b', 'a', 'e', 'c', 'd'];
+        let shuffled =
+            Tree::new(vec![first, second, third], values).expect("Tree has a valid structure");
+
+        assert_ne!(tree, shuffled);
+        assert_eq!(tree.canonicalize(), shuffled.canonicalize());
+    }
+
+    #[test]
+    fn canonicalize_distinguishes_structure() {
+        // Same values, but 'c' hangs under a different node
+        let tree = Tree::new(
+            vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]],
+            vec![0, 1, 2, 3],
+        )
+        .expect("Tree has a valid structure");
+        let other = Tree::new(
+            vec![vec![1, -1, 3, -1], vec![2, -1, -1, -1]],
+            vec![0, 1, 2, 3],
+        )
+        .expect("Tree has a valid structure");
+        assert_ne!(tree.canonicalize(), other.canonicalize());
+    }
+
+    #[test]
+    fn canonicalize_drops_unreachable_nodes() {
+        let tree = Tree::new(vec![vec![-1, -1, 1]], vec![1, 2, 3]).expect("Valid structure");
+        let canonical = tree.canonicalize();
+        assert_eq!(canonical, Tree::new(vec![vec![-1]], vec![1]).unwrap());
+        assert_eq!(tree.canonical_relabeling(), vec![Some(0), None, None]);
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+
+use crate::consts::ROOT_NODE;
+use crate::tree::Tree;
+
+impl<T> Tree<T> {
+    /// Returns the centroid of the tree - a node whose removal leaves the largest remaining component as small
+    /// as possible. Only nodes reachable from the root are considered. If there are two centroids, the one
+    /// closer to the root is returned. `None` for an empty tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         // A path 0 - 1 - 2 - 3 - 4
+    ///         let only_child = vec![1, 2, 3, 4, -1];
+    ///         let values = vec!['a', 'b', 'c', 'd', 'e'];
+    ///         let tree = Tree::new(vec![only_child], values).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.centroid(), Some(2));
+    /// ```
+    pub fn centroid(&self) -> Option<usize> {
+        let (order, _) = self.preorder_with_parents()?;
+        let total = order.len();
+
+        let mut subtree_sizes = vec![1_usize; self.values.len()];
+        for &node in order.iter().rev() {
+            for child in self.child_ids(node) {
+                subtree_sizes[node] += subtree_sizes[child];
+            }
+        }
+
+        order.iter().copied().min_by_key(|&node| {
+            let largest_child = self
+                .child_ids(node)
+                .map(|child| subtree_sizes[child])
+                .max()
+                .unwrap_or(0);
+            largest_child.max(total - subtree_sizes[node])
+        })
+    }
+
+    /// Returns the center of the tree - the middle node of its longest path (diameter), which is also the node
+    /// with the smallest eccentricity. Only nodes reachable from the root are considered. If the diameter has
+    /// an odd number of edges, there are two middle nodes and the one closer to the root is returned.
+    /// `None` for an empty tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         // 0 has children 1 and 2, 2 has a single child 3 which has a single child 4
+    ///         let left = vec![1, -1, 3, 4, -1];
+    ///         let right = vec![2, -1, -1, -1, -1];
+    ///         let values = vec!['a', 'b', 'c', 'd', 'e'];
+    ///         let tree = Tree::new(vec![left, right], values).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.center(), Some(2));
+    /// ```
+    pub fn center(&self) -> Option<usize> {
+        let (_, parents) = self.preorder_with_parents()?;
+
+        let (one_end, _) = self.farthest_from(ROOT_NODE as usize, &parents);
+        let (other_end, predecessors) = self.farthest_from(one_end, &parents);
+
+        let mut diameter = vec![other_end];
+        let mut node = other_end;
+        while let Some(predecessor) = predecessors[node] {
+            diameter.push(predecessor);
+            node = predecessor;
+        }
+
+        let candidates = if diameter.len() % 2 == 1 {
+            vec![diameter[diameter.len() / 2]]
+        } else {
+            vec![
+                diameter[diameter.len() / 2 - 1],
+                diameter[diameter.len() / 2],
+            ]
+        };
+        candidates
+            .into_iter()
+            .min_by_key(|&node| self.depth_along(node, &parents))
+    }
+
+    /// Pre-order of nodes reachable from the root, together with a parent of every reached node.
+    fn preorder_with_parents(&self) -> Option<(Vec<usize>, Vec<Option<usize>>)> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        let mut visited = vec![false; self.values.len()];
+        let mut parents = vec![None; self.values.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![ROOT_NODE as usize];
+        while let Some(node) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            order.push(node);
+            for child in self.child_ids(node) {
+                if !visited[child] {
+                    parents[child] = Some(node);
+                    stack.push(child);
+                }
+            }
+        }
+
+        Some((order, parents))
+    }
+
+    /// Breadth-first search treating edges as undirected. Returns the farthest node from `start` and the
+    /// predecessor of every visited node on the way from `start`.
+    fn farthest_from(
+        &self,
+        start: usize,
+        parents: &[Option<usize>],
+    ) -> (usize, Vec<Option<usize>>) {
+        let mut visited = vec![false; self.values.len()];
+        let mut predecessors = vec![None; self.values.len()];
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+        let mut last = start;
+        while let Some(node) = queue.pop_front() {
+            last = node;
+            let neighbours = self
+                .child_ids(node)
+                .filter(|&child| parents[child] == Some(node))
+                .chain(parents[node]);
+            for neighbour in neighbours {
+                if !visited[neighbour] {
+                    visited[neighbour] = true;
+                    predecessors[neighbour] = Some(node);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        (last, predecessors)
+    }
+
+    fn depth_along(&self, mut node: usize, parents: &[Option<usize>]) -> usize {
+        let mut depth = 0;
+        while let Some(parent) = parents[node] {
+            depth += 1;
+            node = parent;
+        }
+        depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    #[test]
+    fn centroid() {
+        // Star-like tree: root with a long chain hanging from its first child
+        let first = vec![1, 2, 3, 4, 5, -1, -1];
+        let second = vec![6, -1, -1, -1, -1, -1, -1];
+        let values = vec![0; 7];
+        let tree = Tree::new(vec![first, second], values).expect("Tree has a valid structure");
+        assert_eq!(tree.centroid(), Some(2));
+
+        let single = Tree::new(vec![vec![-1]], vec![42]).expect("Tree has a valid structure");
+        assert_eq!(single.centroid(), Some(0));
+    }
+
+    #[test]
+    fn center() {
+        let left = vec![1, 3, 5, -1, -1, -1, -1];
+        let right = vec![2, 4, 6, -1, -1, -1, -1];
+        let values = vec![0; 7];
+        let tree = Tree::new(vec![left, right], values).expect("Tree has a valid structure");
+        assert_eq!(tree.center(), Some(0));
+
+        // Path 0 - 1 - 2 - 3 has two middle nodes, the one closer to the root wins
+        let path = Tree::new(vec![vec![1, 2, 3, -1]], vec![0; 4]).expect("Valid structure");
+        assert_eq!(path.center(), Some(1));
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree: Tree<i32> = Tree::new(vec![vec![]], vec![]).expect("Tree has a valid structure");
+        assert_eq!(tree.centroid(), None);
+        assert_eq!(tree.center(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::footprint::MemoryFootprint;
+use crate::tree::{Tree, TreeError};
+
+/// Tuple of values stored column by column, one vector per tuple field. Implemented for tuples of two to six
+/// fields.
+pub trait Columns: Sized {
+    /// One vector per field, e.g. `(Vec<A>, Vec<B>)` for `(A, B)`.
+    type Storage: Default;
+    /// References to fields of a single row, e.g. `(&A, &B)` for `(A, B)`.
+    type Row<'a>
+    where
+        Self: 'a;
+
+    /// Number of rows, [TreeError::LengthMismatch] if columns differ in length.
+    fn rows(storage: &Self::Storage) -> Result<usize, TreeError>;
+
+    fn push(storage: &mut Self::Storage, row: Self);
+
+    fn row(storage: &Self::Storage, index: usize) -> Option<Self::Row<'_>>;
+
+    /// Bytes of the columns' buffers, see [MemoryFootprint].
+    fn footprint(storage: &Self::Storage) -> MemoryFootprint;
+}
+
+macro_rules! tuple_columns {
+    ($($field:ident $index:tt),+) => {
+        impl<$($field),+> Columns for ($($field,)+) {
+            type Storage = ($(Vec<$field>,)+);
+            type Row<'a> = ($(&'a $field,)+) where Self: 'a;
+
+            fn rows(storage: &Self::Storage) -> Result<usize, TreeError> {
+                let expected = storage.0.len();
+                for found in [$(storage.$index.len()),+] {
+                    if found != expected {
+                        return Err(TreeError::LengthMismatch { expected, found });
+                    }
+                }
+                Ok(expected)
+            }
+
+            fn push(storage: &mut Self::Storage, row: Self) {
+                $(storage.$index.push(row.$index);)+
+            }
+
+            fn row(storage: &Self::Storage, index: usize) -> Option<Self::Row<'_>> {
+                Some(($(storage.$index.get(index)?,)+))
+            }
+
+            fn footprint(storage: &Self::Storage) -> MemoryFootprint {
+                [$(MemoryFootprint::vec(&storage.$index, true)),+].into_iter().sum()
+            }
+        }
+    };
+}
+
+tuple_columns!(A 0, B 1);
+tuple_columns!(A 0, B 1, C 2);
+tuple_columns!(A 0, B 1, C 2, D 3);
+tuple_columns!(A 0, B 1, C 2, D 3, E 4);
+tuple_columns!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Dense tree with tuple values stored as a structure of arrays: a separate vector per field. Traversals reading a
+/// single field, e.g. a decision tree's split threshold, only load that field into cache.
+///
+/// The structure is a [Tree] without values, offering all of its navigation, see [ColumnTree::structure].
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::ColumnTree;
+///         let rows = vec![(0_u16, 0.5_f32, "root"), (1, 0.25, "left"), (2, 0.75, "right")];
+///         let tree = ColumnTree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], rows).expect("Tree has a valid structure");
+///
+///         let (_, thresholds, _) = tree.columns();
+///         assert_eq!(thresholds, &[0.5, 0.25, 0.75]);
+///         assert_eq!(tree.structure().children(0), vec![1, 2]);
+///         assert_eq!(tree.row(2), Some((&2, &0.75, &"right")));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ColumnTree<C: Columns> {
+    structure: Tree<()>,
+    columns: C::Storage,
+}
+
+impl<C: Columns> ColumnTree<C> {
+    /// Constructs a tree from links like [Tree::new], splitting `rows` into columns.
+    pub fn new(nodes: Vec<Vec<isize>>, rows: Vec<C>) -> Result<Self, TreeError> {
+        let mut columns = C::Storage::default();
+        for row in rows {
+            C::push(&mut columns, row);
+        }
+        Self::from_columns(nodes, columns)
+    }
+
+    /// Constructs a tree from links like [Tree::new] and columns of equal length.
+    pub fn from_columns(nodes: Vec<Vec<isize>>, columns: C::Storage) -> Result<Self, TreeError> {
+        let rows = C::rows(&columns)?;
+        Ok(Self {
+            structure: Tree::new(nodes, vec![(); rows])?,
+            columns,
+        })
+    }
+
+    /// Splits values of `tree` into columns. Buffers shared with snapshots or clones are copied.
+    pub fn from_tree(tree: Tree<C>) -> Self
+    where
+        C: Clone,
+    {
+        let (nodes, rows) = tree.into_parts();
+        Self::new(nodes, rows)
+            .unwrap_or_else(|_| unreachable!("Tree has a link per value in every dimension"))
+    }
+
+    /// Links of the tree, with a `()` value per node.
+    pub fn structure(&self) -> &Tree<()> {
+        &self.structure
+    }
+
+    pub fn columns(&self) -> &C::Storage {
+        &self.columns
+    }
+
+    /// Mutable access to the columns. Assigning to values is fine, columns must keep their length.
+    pub fn columns_mut(&mut self) -> &mut C::Storage {
+        &mut self.columns
+    }
+
+    /// Fields of the value of `node_id`, `None` if the node doesn't exist.
+    pub fn row(&self, node_id: usize) -> Option<C::Row<'_>> {
+        C::row(&self.columns, node_id)
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            structure: size_of::<C::Storage>(),
+            ..C::footprint(&self.columns)
+        } + self.structure.memory_footprint()
+    }
+
+    pub fn len(&self) -> usize {
+        self.structure.values().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{ColumnTree, Tree, TreeError};
+
+    #[test]
+    fn columns() {
+        let rows = vec![(7_u8, 'a'), (8, 'b'), (9, 'c')];
+        let tree = Tree::new(vec![vec![1, 2, -1]], rows).expect("Tree has a valid structure");
+        let mut columns = ColumnTree::from_tree(tree);
+        assert_eq!(columns.columns(), &(vec![7, 8, 9], vec!['a', 'b', 'c']));
+        assert_eq!(columns.structure().detect_cycle(), None);
+        assert_eq!(columns.structure().parent(2), Some(1));
+        assert_eq!(columns.row(3), None);
+        assert_eq!(columns.len(), 3);
+
+        columns.columns_mut().0[1] = 80;
+        assert_eq!(columns.row(1), Some((&80, &'b')));
+
+        let uneven =
+            ColumnTree::<(u8, char)>::from_columns(vec![vec![-1, -1]], (vec![1, 2], vec!['a']));
+        assert_eq!(
+            uneven,
+            Err(TreeError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert!(ColumnTree::<(u8, u8)>::new(vec![vec![-1]], vec![]).is_err());
+    }
+}
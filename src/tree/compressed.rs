@@ -0,0 +1,278 @@
+use std::ops::{Index, IndexMut};
+
+use crate::consts::LEAF_NODE;
+use crate::footprint::MemoryFootprint;
+use crate::tree::{NodeId, Tree};
+
+/// Sparse gaps shorter than this are stored as they are, a new run costs two words.
+const MIN_GAP: usize = 3;
+
+/// Read-only form of a [Tree] for trees where most child slots are empty. Runs of [LEAF_NODE] of three or more
+/// links are left out of every dimension, the remaining links are stored in runs found by binary search, so looking
+/// a child up takes O(log r) for `r` runs in the dimension.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{CompressedTree, Tree};
+///         let left = vec![1, 3, -1, -1, -1, -1, -1, -1];
+///         let right = vec![2, 4, -1, -1, -1, -1, -1, -1];
+///         let tree = Tree::new(vec![left, right], vec![0; 8]).expect("Tree has a valid structure");
+///
+///         let compressed = CompressedTree::from_tree(&tree);
+///         assert_eq!(compressed.children(1), vec![3, 4]);
+///         assert_eq!(compressed.children(6), vec![-1, -1]);
+///         assert_eq!(compressed.stored_links(), 4);
+///         assert_eq!(compressed.to_tree(), tree);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CompressedTree<T> {
+    dimensions: Vec<Runs>,
+    values: Vec<T>,
+}
+
+/// Links of a dimension, run `i` holding links of nodes `starts[i]..starts[i] + offsets[i + 1] - offsets[i]`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Runs {
+    starts: Vec<usize>,
+    offsets: Vec<usize>,
+    links: Vec<isize>,
+}
+
+impl<T> CompressedTree<T> {
+    pub fn from_tree(tree: &Tree<T>) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            dimensions: tree
+                .nodes
+                .iter()
+                .map(|links| Runs::compress(links))
+                .collect(),
+            values: tree.values.to_vec(),
+        }
+    }
+
+    /// Expands the tree back to the dense form.
+    pub fn to_tree(&self) -> Tree<T>
+    where
+        T: Clone,
+    {
+        let nodes = self
+            .dimensions
+            .iter()
+            .map(|runs| (0..self.values.len()).map(|node| runs.get(node)).collect())
+            .collect();
+        Tree::new(nodes, self.values.clone())
+            .unwrap_or_else(|_| unreachable!("Every dimension has a link per node"))
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let runs: MemoryFootprint = self
+            .dimensions
+            .iter()
+            .map(|runs| {
+                MemoryFootprint::vec(&runs.starts, false)
+                    + MemoryFootprint::vec(&runs.offsets, false)
+                    + MemoryFootprint::vec(&runs.links, false)
+            })
+            .sum();
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::vec(&self.dimensions, false)
+            + runs
+            + MemoryFootprint::vec(&self.values, true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Child of `node_id` in given `dimension`, [LEAF_NODE] if there's none.
+    ///
+    /// Panics if `dimension` is out of bounds.
+    pub fn child(&self, node_id: usize, dimension: usize) -> isize {
+        self.dimensions[dimension].get(node_id)
+    }
+
+    /// Returns a [Vec] with node's children indices, or [LEAF_NODE] as a placeholder for every missing child.
+    pub fn children(&self, node_id: usize) -> Vec<isize> {
+        self.dimensions
+            .iter()
+            .map(|runs| runs.get(node_id))
+            .collect()
+    }
+
+    /// True if given `node_id` is a leaf node (no children), false otherwise.
+    pub fn is_leaf_node(&self, node_id: usize) -> bool {
+        self.dimensions
+            .iter()
+            .all(|runs| runs.get(node_id) == LEAF_NODE)
+    }
+
+    /// First existing child of `node`, skipping [LEAF_NODE] placeholders. `None` for leaves and nodes that don't
+    /// exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{CompressedTree, NodeId, Tree};
+    ///         let tree = Tree::new(vec![vec![-1, -1, -1], vec![1, -1, -1], vec![2, -1, -1]], vec![0, 1, 2])
+    ///             .expect("Tree has a valid structure");
+    ///
+    ///         let compressed = CompressedTree::from_tree(&tree);
+    ///         assert_eq!(compressed.first_child(NodeId::ROOT), Some(NodeId::new(1)));
+    ///         assert_eq!(compressed.last_child(NodeId::ROOT), Some(NodeId::new(2)));
+    ///         assert_eq!(compressed.nth_child(NodeId::ROOT, 2), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.nth_child(node, 0)
+    }
+
+    /// Last existing child of `node`, skipping [LEAF_NODE] placeholders. `None` for leaves and nodes that don't
+    /// exist.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        self.child_ids(node).last()
+    }
+
+    /// Existing child of `node` at position `k`, counting from zero and skipping [LEAF_NODE] placeholders. `None` if
+    /// the node has at most `k` children or doesn't exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        self.child_ids(node).nth(k)
+    }
+
+    /// Existing children of `node` in order of dimensions, links out of bounds left out. None for nodes that don't
+    /// exist.
+    fn child_ids(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let dimensions = match node.index() < self.values.len() {
+            true => &self.dimensions[..],
+            false => &[],
+        };
+        dimensions
+            .iter()
+            .filter_map(move |runs| NodeId::from_link(runs.get(node.index())))
+            .filter(|child| child.index() < self.values.len())
+    }
+
+    /// Number of links kept in memory over all dimensions, out of `len() * dimensions`.
+    pub fn stored_links(&self) -> usize {
+        self.dimensions.iter().map(|runs| runs.links.len()).sum()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl Runs {
+    fn compress(links: &[isize]) -> Self {
+        let mut runs = Self {
+            starts: Vec::new(),
+            offsets: vec![0],
+            links: Vec::new(),
+        };
+        let mut node = 0;
+        while node < links.len() {
+            if links[node] == LEAF_NODE {
+                node += 1;
+                continue;
+            }
+            // Extend the run over gaps too short to be worth a new one
+            let start = node;
+            let mut end = node + 1;
+            loop {
+                let gap = links[end..]
+                    .iter()
+                    .take_while(|&&link| link == LEAF_NODE)
+                    .count();
+                if end + gap == links.len() || gap >= MIN_GAP {
+                    break;
+                }
+                end += gap + 1;
+            }
+            runs.starts.push(start);
+            runs.links.extend_from_slice(&links[start..end]);
+            runs.offsets.push(runs.links.len());
+            node = end;
+        }
+        runs
+    }
+
+    fn get(&self, node: usize) -> isize {
+        let run = self.starts.partition_point(|&start| start <= node);
+        let Some(run) = run.checked_sub(1) else {
+            return LEAF_NODE;
+        };
+        let position = self.offsets[run] + node - self.starts[run];
+        if position < self.offsets[run + 1] {
+            self.links[position]
+        } else {
+            LEAF_NODE
+        }
+    }
+}
+
+impl<T> Index<usize> for CompressedTree<T> {
+    type Output = T;
+
+    fn index(&self, node_id: usize) -> &Self::Output {
+        &self.values[node_id]
+    }
+}
+
+impl<T> IndexMut<usize> for CompressedTree<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+impl<T> Index<NodeId> for CompressedTree<T> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self[node.index()]
+    }
+}
+
+impl<T> IndexMut<NodeId> for CompressedTree<T> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self[node.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{CompressedTree, Tree};
+
+    #[test]
+    fn roundtrip() {
+        // Gaps of one and two leaves stay inside runs, longer ones split them
+        let first = vec![1, -1, 2, -1, -1, 3, -1, -1, -1, 4, -1, -1, -1, -1];
+        let second = vec![-1; 14];
+        let third = vec![5, 6, 7, 8, 9, 10, 11, 12, 13, -1, -1, -1, -1, -1];
+        let tree = Tree::new(vec![first, second, third], (0..14).collect())
+            .expect("Tree has a valid structure");
+
+        let compressed = CompressedTree::from_tree(&tree);
+        assert_eq!(compressed.stored_links(), 6 + 1 + 9);
+        for node in 0..14 {
+            assert_eq!(compressed.children(node), tree.children(node));
+            assert_eq!(compressed.is_leaf_node(node), tree.is_leaf_node(node));
+            assert_eq!(compressed[node], node);
+        }
+        assert_eq!(compressed.child(99, 0), -1);
+        assert_eq!(compressed.to_tree(), tree);
+
+        let empty: Tree<u8> = Tree::new(vec![vec![]], vec![]).expect("Tree has a valid structure");
+        let compressed = CompressedTree::from_tree(&empty);
+        assert!(compressed.is_empty());
+        assert_eq!(compressed.to_tree(), empty);
+    }
+}
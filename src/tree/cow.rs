@@ -0,0 +1,114 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::tree::Tree;
+
+/// Shared [Tree] forked on the first mutation. Clones share the tree, [CowTree::to_mut] clones it only if it's
+/// shared. Dereferences to [Tree] for reading.
+///
+/// Forking is O(1) too, the forked tree shares nodes and values with the original until they're mutated, see
+/// [Tree] snapshots.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{CowTree, Tree};
+///         let tree = Tree::new(vec![vec![1, -1, -1]], vec!["a", "b", "c"]).expect("Tree has a valid structure");
+///         let original = CowTree::from(tree);
+///
+///         let mut edited = original.clone();
+///         assert!(edited.ptr_eq(&original));
+///         edited.to_mut().set_child(0, 0, 2);
+///
+///         assert!(!edited.ptr_eq(&original));
+///         assert_eq!(original.children(0), vec![1]);
+///         assert_eq!(edited.children(0), vec![2]);
+/// ```
+#[derive(Debug)]
+pub struct CowTree<T, Idx = isize> {
+    tree: Arc<Tree<T, Idx>>,
+}
+
+impl<T, Idx> CowTree<T, Idx> {
+    /// Mutable access to the tree, forking it first if it's shared with other clones.
+    pub fn to_mut(&mut self) -> &mut Tree<T, Idx>
+    where
+        T: Clone,
+        Idx: Clone,
+    {
+        Arc::make_mut(&mut self.tree)
+    }
+
+    /// True if other clones share the tree, so the next [CowTree::to_mut] forks it.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.tree) > 1
+    }
+
+    /// True if both share the same tree, i.e. neither was forked since one was cloned from the other.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.tree, &other.tree)
+    }
+
+    /// Unwraps the tree, cloning it if it's shared.
+    pub fn into_tree(self) -> Tree<T, Idx>
+    where
+        T: Clone,
+        Idx: Clone,
+    {
+        Arc::unwrap_or_clone(self.tree)
+    }
+}
+
+impl<T, Idx> From<Tree<T, Idx>> for CowTree<T, Idx> {
+    fn from(tree: Tree<T, Idx>) -> Self {
+        Self {
+            tree: Arc::new(tree),
+        }
+    }
+}
+
+impl<T, Idx> Clone for CowTree<T, Idx> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: Arc::clone(&self.tree),
+        }
+    }
+}
+
+impl<T, Idx> Deref for CowTree<T, Idx> {
+    type Target = Tree<T, Idx>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::tree::{CowTree, Tree};
+
+    #[test]
+    fn fork_on_mutation() {
+        let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec![1, 2, 3])
+            .expect("Tree has a valid structure");
+        let mut cow = CowTree::from(tree);
+        assert!(!cow.is_shared());
+        *cow.to_mut().value_mut(0) = 10;
+        assert_eq!(cow.values(), &[10, 2, 3]);
+
+        let shared = cow.clone();
+        assert!(cow.is_shared() && shared.is_shared());
+        *cow.to_mut().value_mut(2) = 30;
+        assert!(!cow.is_shared() && !shared.is_shared());
+
+        // The fork copied only the buffer it mutated
+        assert!(Arc::ptr_eq(&cow.nodes, &shared.nodes));
+        assert!(!Arc::ptr_eq(&cow.values, &shared.values));
+        cow.to_mut().add_child(1, 4).expect("Node 1 exists");
+        assert_eq!(cow.children(1), vec![3, -1]);
+        assert_eq!(shared.into_tree().values(), &[10, 2, 3]);
+        assert_eq!(cow.into_tree().values(), &[10, 2, 30, 4]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::consts::LEAF_NODE;
+use crate::tree::Tree;
+use crate::tree::TreeError;
+
+impl<T> Tree<T> {
+    /// Builds a tree from `(parent, child)` edges over nodes `0..values.len()`, node `0` being the root. Node ids
+    /// are kept, children of a node are ordered by their ids and the number of dimensions is the highest number of
+    /// children of any node. Fails if an edge points out of bounds or to the root, or if a node has more than one
+    /// parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let edges = vec![(0, 2), (2, 3), (0, 1)];
+    ///         let tree = Tree::from_edges(edges, vec!["root", "a", "b", "b0"]).unwrap();
+    ///
+    ///         assert_eq!(tree.children(0), vec![1, 2]);
+    ///         assert_eq!(tree.children(2), vec![3, -1]);
+    ///         assert!(Tree::from_edges(vec![(0, 1), (2, 1)], vec![0, 1, 2]).is_err());
+    /// ```
+    pub fn from_edges(mut edges: Vec<(usize, usize)>, values: Vec<T>) -> Result<Self, TreeError> {
+        let len = values.len();
+        edges.sort_unstable();
+        check_bounds(&edges, len)?;
+
+        let mut children: Vec<usize> = edges.iter().map(|&(_, child)| child).collect();
+        children.sort_unstable();
+        if let Some(pair) = children.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(multiple_parents(pair[0]));
+        }
+
+        let starts: Vec<usize> = (0..=len)
+            .map(|node| edges.partition_point(|&(parent, _)| parent < node))
+            .collect();
+        let arity = starts
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .max()
+            .unwrap_or(0);
+        let nodes = (0..arity.max(1))
+            .map(|dimension| {
+                (0..len)
+                    .map(|node| child_link(&edges, &starts, node, dimension))
+                    .collect()
+            })
+            .collect();
+        Tree::new(nodes, values)
+    }
+
+    /// Parallel version of [Tree::from_edges], sorting edges and building every dimension on multiple threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let edges: Vec<(usize, usize)> = (1..10_000).map(|node| ((node - 1) / 2, node)).collect();
+    ///         let tree = Tree::from_edges_par(edges, vec![0; 10_000]).unwrap();
+    ///
+    ///         assert_eq!(tree.children(0), vec![1, 2]);
+    ///         assert_eq!(tree.children(4999), vec![9999, -1]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn from_edges_par(mut edges: Vec<(usize, usize)>, values: Vec<T>) -> Result<Self, TreeError>
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        let len = values.len();
+        edges.par_sort_unstable();
+        check_bounds(&edges, len)?;
+
+        let mut children: Vec<usize> = edges.par_iter().map(|&(_, child)| child).collect();
+        children.par_sort_unstable();
+        if let Some(pair) = children
+            .par_windows(2)
+            .find_first(|pair| pair[0] == pair[1])
+        {
+            return Err(multiple_parents(pair[0]));
+        }
+
+        let starts: Vec<usize> = (0..=len)
+            .into_par_iter()
+            .map(|node| edges.partition_point(|&(parent, _)| parent < node))
+            .collect();
+        let arity = starts
+            .par_windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .max()
+            .unwrap_or(0);
+        let nodes = (0..arity.max(1))
+            .map(|dimension| {
+                (0..len)
+                    .into_par_iter()
+                    .map(|node| child_link(&edges, &starts, node, dimension))
+                    .collect()
+            })
+            .collect();
+        Tree::new(nodes, values)
+    }
+}
+
+/// Checks that edges sorted by parent point within `0..len` and never to the root.
+fn check_bounds(edges: &[(usize, usize)], len: usize) -> Result<(), TreeError> {
+    if let Some(&(parent, _)) = edges.last().filter(|&&(parent, _)| parent >= len) {
+        return Err(TreeError::NodeOutOfBounds { node: parent });
+    }
+    match edges.iter().find(|&&(_, child)| child == 0 || child >= len) {
+        Some(&(parent, 0)) => Err(TreeError::CycleDetected { node: parent }),
+        Some(&(parent, child)) => Err(TreeError::IndexOutOfBounds {
+            node: parent,
+            index: child as isize,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn multiple_parents(node: usize) -> TreeError {
+    TreeError::MultipleParents { node }
+}
+
+/// Link to the `dimension`-th child of `node`, given edges sorted by parent and `starts` of every node's edges.
+fn child_link(edges: &[(usize, usize)], starts: &[usize], node: usize, dimension: usize) -> isize {
+    let edge = starts[node] + dimension;
+    if edge < starts[node + 1] {
+        edges[edge].1 as isize
+    } else {
+        LEAF_NODE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    #[test]
+    fn from_edges() {
+        let edges = vec![(0, 3), (3, 4), (0, 1), (0, 2), (1, 5)];
+        let tree = Tree::from_edges(edges.clone(), vec![0, 1, 2, 3, 4, 5]).unwrap();
+        let expected = Tree::new(
+            vec![
+                vec![1, 5, -1, 4, -1, -1],
+                vec![2, -1, -1, -1, -1, -1],
+                vec![3, -1, -1, -1, -1, -1],
+            ],
+            vec![0, 1, 2, 3, 4, 5],
+        )
+        .unwrap();
+        assert_eq!(tree, expected);
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            Tree::from_edges_par(edges, vec![0, 1, 2, 3, 4, 5]).unwrap(),
+            expected
+        );
+
+        let single = Tree::from_edges(vec![], vec!["root"]).unwrap();
+        assert!(single.is_leaf_node(0));
+    }
+
+    #[test]
+    fn invalid_edges() {
+        assert!(Tree::from_edges(vec![(0, 3)], vec![0, 1, 2]).is_err());
+        assert!(Tree::from_edges(vec![(3, 1)], vec![0, 1, 2]).is_err());
+        assert!(Tree::from_edges(vec![(1, 0)], vec![0, 1, 2]).is_err());
+        assert!(Tree::from_edges(vec![(0, 1), (0, 2), (1, 2)], vec![0, 1, 2]).is_err());
+        #[cfg(feature = "rayon")]
+        {
+            assert!(Tree::from_edges_par(vec![(0, 3)], vec![0, 1, 2]).is_err());
+            assert!(Tree::from_edges_par(vec![(0, 1), (2, 1)], vec![0, 1, 2]).is_err());
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Structural error of a tree, reported by constructors and validations across the crate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TreeError {
+    /// Structural violation not covered by more specific variants, e.g. of invariants of a specialized tree.
+    CorruptedTree(String),
+    /// Links of a dimension don't match the number of values.
+    LengthMismatch { expected: usize, found: usize },
+    /// A `node` links to a child `index` out of bounds.
+    IndexOutOfBounds { node: usize, index: isize },
+    /// A `node` id out of bounds of the tree.
+    NodeOutOfBounds { node: usize },
+    /// A `node` id refers to a node that has been removed.
+    StaleNode { node: usize },
+    /// A `node` is a child of more than one node.
+    MultipleParents { node: usize },
+    /// A non-root `node` isn't a child of any node, i.e. it's another root.
+    MultipleRoots { node: usize },
+    /// A `node` lies on a cycle of links.
+    CycleDetected { node: usize },
+    /// A `node` has more `children` than the `max` the tree can hold.
+    ArityExceeded {
+        node: usize,
+        children: usize,
+        max: usize,
+    },
+    /// The tree has no nodes where at least one is required.
+    EmptyTree,
+    /// An operation would remove the root, leaving the tree without one.
+    RootRemoval,
+}
+
+impl Display for TreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeError::CorruptedTree(reason) => write!(f, "Corrupted tree: {reason}"),
+            TreeError::LengthMismatch { expected, found } => write!(
+                f,
+                "Tree nodes and values length do not match. Expected length: {expected}, found: {found}"
+            ),
+            TreeError::IndexOutOfBounds { node, index } => {
+                write!(f, "Node {node} links to child {index} out of bounds")
+            }
+            TreeError::NodeOutOfBounds { node } => write!(f, "Node {node} is out of bounds"),
+            TreeError::StaleNode { node } => write!(f, "Node {node} has been removed"),
+            TreeError::MultipleParents { node } => {
+                write!(f, "Node {node} is a child of more than one node")
+            }
+            TreeError::MultipleRoots { node } => {
+                write!(f, "Node {node} has no parent, only the root may have none")
+            }
+            TreeError::CycleDetected { node } => write!(f, "Node {node} lies on a cycle"),
+            TreeError::ArityExceeded {
+                node,
+                children,
+                max,
+            } => write!(f, "Node {node} has {children} children, at most {max} fit"),
+            TreeError::EmptyTree => write!(f, "Tree has no nodes"),
+            TreeError::RootRemoval => write!(f, "The root can't be removed"),
+        }
+    }
+}
+
+impl Error for TreeError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Tree, TreeError};
+
+    #[test]
+    fn display() {
+        let error = Tree::new(vec![vec![-1, -1]], vec![1]).unwrap_err();
+        assert_eq!(
+            error,
+            TreeError::LengthMismatch {
+                expected: 1,
+                found: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Tree nodes and values length do not match. Expected length: 1, found: 2"
+        );
+
+        let boxed: Box<dyn std::error::Error> = Box::new(TreeError::CycleDetected { node: 3 });
+        assert_eq!(boxed.to_string(), "Node 3 lies on a cycle");
+    }
+}
@@ -0,0 +1,136 @@
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::{Tree, TreeError, TreeIndex};
+
+/// Directed graph with an edge from every node to each of its children, weighted by the child's dimension. Node
+/// indices of the graph are node ids of the tree. Links out of bounds or to the root are left out, like in
+/// traversals.
+///
+/// # Examples
+///
+/// ```
+///         use petgraph::graph::{DiGraph, NodeIndex};
+///         use treesome::tree::Tree;
+///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec!['a', 'b', 'c']).expect("Tree has a valid structure");
+///
+///         let graph = DiGraph::from(&tree);
+///         assert_eq!(graph.edge_count(), 2);
+///         assert_eq!(graph[NodeIndex::new(2)], 'c');
+///         assert_eq!(Tree::try_from(&graph), Ok(tree));
+/// ```
+impl<T: Clone, Idx: TreeIndex> From<&Tree<T, Idx>> for DiGraph<T, usize> {
+    fn from(tree: &Tree<T, Idx>) -> Self {
+        let len = tree.values.len();
+        let mut graph = DiGraph::with_capacity(len, len.saturating_sub(1));
+        for value in tree.values.iter() {
+            graph.add_node(value.clone());
+        }
+        for node in 0..len {
+            for (dimension, links) in tree.nodes.iter().enumerate() {
+                let Some(child) = links[node]
+                    .node()
+                    .filter(|&child| child > ROOT_NODE as usize && child < len)
+                else {
+                    continue;
+                };
+                graph.add_edge(NodeIndex::new(node), NodeIndex::new(child), dimension);
+            }
+        }
+        graph
+    }
+}
+
+/// Reads a tree from a graph shaped like the one made from a [Tree], checked by [Tree::validate]. Edge weights are
+/// dimensions of the children, a node with two children in the same dimension or a dimension not lower than the
+/// node count is reported as [TreeError::CorruptedTree].
+impl<T: Clone> TryFrom<&DiGraph<T, usize>> for Tree<T> {
+    type Error = TreeError;
+
+    fn try_from(graph: &DiGraph<T, usize>) -> Result<Self, Self::Error> {
+        let len = graph.node_count();
+        let dimension = graph.edge_weights().copied().max();
+        // Checked before allocating, a weight can't be used as a dimension count as is
+        if let Some(dimension) = dimension.filter(|&dimension| dimension >= len) {
+            return Err(TreeError::CorruptedTree(format!(
+                "Edge dimension {dimension} is out of bounds for {len} nodes"
+            )));
+        }
+        let dimensions = dimension.map_or(1, |dimension| dimension + 1);
+        let mut nodes = vec![vec![LEAF_NODE; len]; dimensions];
+        for edge in graph.raw_edges() {
+            let (node, child) = (edge.source().index(), edge.target().index());
+            let link = &mut nodes[edge.weight][node];
+            if *link != LEAF_NODE {
+                return Err(TreeError::CorruptedTree(format!(
+                    "Node {node} has more than one child in dimension {}",
+                    edge.weight
+                )));
+            }
+            *link = child as isize;
+        }
+        let values = graph.node_weights().cloned().collect();
+        Tree::new_validated(nodes, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::algo::is_cyclic_directed;
+    use petgraph::graph::{DiGraph, NodeIndex};
+
+    use crate::tree::{Tree, TreeError};
+
+    #[test]
+    fn graph_roundtrip() {
+        let tree = Tree::new(
+            vec![
+                vec![1, 3, -1, -1, -1],
+                vec![2, -1, -1, -1, -1],
+                vec![-1, 4, -1, -1, -1],
+            ],
+            vec![0, 1, 2, 3, 4],
+        )
+        .expect("Tree has a valid structure");
+        let graph = DiGraph::from(&tree);
+        assert!(!is_cyclic_directed(&graph));
+        assert_eq!(graph.neighbors(NodeIndex::new(1)).count(), 2);
+        assert_eq!(Tree::try_from(&graph), Ok(tree));
+
+        let mut shared = DiGraph::<u8, usize>::new();
+        let nodes = [shared.add_node(0), shared.add_node(1), shared.add_node(2)];
+        shared.add_edge(nodes[0], nodes[1], 0);
+        shared.add_edge(nodes[0], nodes[2], 1);
+        shared.add_edge(nodes[1], nodes[2], 0);
+        assert_eq!(
+            Tree::try_from(&shared),
+            Err(TreeError::MultipleParents { node: 2 })
+        );
+
+        let mut cycle = shared.clone();
+        cycle.clear_edges();
+        cycle.add_edge(nodes[1], nodes[2], 0);
+        cycle.add_edge(nodes[2], nodes[1], 0);
+        assert!(Tree::try_from(&cycle).is_err());
+
+        let mut same_slot = shared;
+        same_slot.clear_edges();
+        same_slot.add_edge(nodes[0], nodes[1], 0);
+        same_slot.add_edge(nodes[0], nodes[2], 0);
+        assert!(matches!(
+            Tree::try_from(&same_slot),
+            Err(TreeError::CorruptedTree(_))
+        ));
+
+        for weight in [3, usize::MAX] {
+            let mut wide = DiGraph::<u8, usize>::new();
+            let (root, child) = (wide.add_node(0), wide.add_node(1));
+            wide.add_node(2);
+            wide.add_edge(root, child, weight);
+            assert!(matches!(
+                Tree::try_from(&wide),
+                Err(TreeError::CorruptedTree(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::consts::LEAF_NODE;
+
+/// Typed index of a node in a tree.
+///
+/// Trees store links as `isize` with [LEAF_NODE] marking a missing child. `NodeId` only ever holds an existing
+/// position, so an absent node is an `Option<NodeId>` rather than a sentinel, and no casts between signed links
+/// and unsigned indices are needed by the caller.
+///
+/// `NodeId` is the id of the checked API: [Tree::get](crate::tree::Tree::get), the `try_*` accessors and the
+/// helpers reporting optional nodes, such as `first_child`. The raw accessors (`children`, `parent`,
+/// `is_leaf_node` and the mutations taking a node) keep the `isize` links and `usize` positions of the underlying
+/// buffers as the unchecked fast path, convert between the two with [NodeId::from_link], [NodeId::to_link] and
+/// [NodeId::index].
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::NodeId;
+///         assert_eq!(NodeId::from_link(3), Some(NodeId::new(3)));
+///         assert_eq!(NodeId::from_link(-1), None);
+///         assert_eq!(NodeId::to_link(None), -1);
+///         assert_eq!(NodeId::new(3).index(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Id of the root node of every non-empty tree.
+    pub const ROOT: NodeId = NodeId(0);
+
+    pub const fn new(index: usize) -> Self {
+        Self(index)
+    }
+
+    /// Position of the node in the tree's buffers.
+    pub const fn index(self) -> usize {
+        self.0
+    }
+
+    /// Parent in the implicit complete-tree layout with given `arity`, where children of node `i` are nodes
+    /// `arity * i + 1` to `arity * i + arity`. `None` for the root and for zero `arity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::NodeId;
+    ///         assert_eq!(NodeId::new(5).implicit_parent(2), Some(NodeId::new(2)));
+    ///         assert_eq!(NodeId::new(usize::MAX).implicit_parent(1), Some(NodeId::new(usize::MAX - 1)));
+    ///         assert_eq!(NodeId::ROOT.implicit_parent(2), None);
+    ///         assert_eq!(NodeId::new(5).implicit_parent(0), None);
+    /// ```
+    pub fn implicit_parent(self, arity: usize) -> Option<Self> {
+        self.0.checked_sub(1)?.checked_div(arity).map(Self)
+    }
+
+    /// Converts a raw link into an id, `None` for negative links such as [LEAF_NODE].
+    pub fn from_link(link: isize) -> Option<Self> {
+        usize::try_from(link).ok().map(Self)
+    }
+
+    /// Converts an optional id back into a raw link, [LEAF_NODE] for `None`.
+    pub fn to_link(id: Option<Self>) -> isize {
+        id.map_or(LEAF_NODE, |id| id.0 as isize)
+    }
+}
+
+impl From<usize> for NodeId {
+    fn from(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+impl From<NodeId> for usize {
+    fn from(id: NodeId) -> Self {
+        id.0
+    }
+}
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Debug;
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize};
+
+/// Integer type of child links stored by a [Tree](crate::tree::Tree). The default `isize` marks missing children
+/// with `-1`, unsigned types with their maximum value. Narrower types save memory and cache, e.g. `u32` links
+/// take half the space of `isize` ones on 64-bit targets, for trees of fewer than `u32::MAX` nodes.
+pub trait TreeIndex: Copy + Eq + Debug {
+    /// Link marking a missing child.
+    const NONE: Self;
+
+    /// Link to `node`, `None` if the type can't represent it.
+    fn from_node(node: usize) -> Option<Self>;
+
+    /// Node the link points to, `None` for [TreeIndex::NONE] and links not representing any node.
+    fn node(self) -> Option<usize>;
+
+    /// The link as reported in [TreeError](crate::tree::TreeError)s, saturated to `isize::MAX`.
+    fn to_isize(self) -> isize;
+}
+
+impl TreeIndex for isize {
+    const NONE: Self = -1;
+
+    fn from_node(node: usize) -> Option<Self> {
+        isize::try_from(node).ok()
+    }
+
+    fn node(self) -> Option<usize> {
+        usize::try_from(self).ok()
+    }
+
+    fn to_isize(self) -> isize {
+        self
+    }
+}
+
+macro_rules! unsigned_tree_index {
+    ($($index:ty),*) => {
+        $(
+            impl TreeIndex for $index {
+                const NONE: Self = <$index>::MAX;
+
+                fn from_node(node: usize) -> Option<Self> {
+                    <$index>::try_from(node).ok().filter(|&link| link != Self::NONE)
+                }
+
+                fn node(self) -> Option<usize> {
+                    usize::try_from(self).ok().filter(|_| self != Self::NONE)
+                }
+
+                fn to_isize(self) -> isize {
+                    isize::try_from(self).unwrap_or(isize::MAX)
+                }
+            }
+        )*
+    };
+}
+
+unsigned_tree_index!(u8, u16, u32, u64, usize);
+
+macro_rules! non_zero_tree_index {
+    ($($index:ty => $raw:ty),*) => {
+        $(
+            /// Absent children are `None`, so the sentinel never shows up in the links. The root can't be anybody's
+            /// child, every other node id is nonzero. Niche-optimized to the size of the raw integer.
+            impl TreeIndex for Option<$index> {
+                const NONE: Self = None;
+
+                fn from_node(node: usize) -> Option<Self> {
+                    <$raw>::try_from(node).ok().and_then(<$index>::new).map(Some)
+                }
+
+                fn node(self) -> Option<usize> {
+                    usize::try_from(self?.get()).ok()
+                }
+
+                fn to_isize(self) -> isize {
+                    self.map_or(-1, |link| isize::try_from(link.get()).unwrap_or(isize::MAX))
+                }
+            }
+        )*
+    };
+}
+
+non_zero_tree_index!(NonZeroU16 => u16, NonZeroU32 => u32, NonZeroU64 => u64, NonZeroUsize => usize);
+
+/// Child link that can't be confused with a sentinel, see [TreeIndex] for `Option<NonZeroU32>`.
+///
+/// # Examples
+///
+/// ```
+///         use std::num::NonZeroU32;
+///         use treesome::tree::{Link, NodeId, Tree};
+///         let tree: Tree<&str, Link> = Tree::from_links(vec![vec![NonZeroU32::new(1), None]], vec!["a", "b"])
+///             .expect("Tree has a valid structure");
+///         assert_eq!(tree.try_children(NodeId::ROOT), Ok(vec![Some(NodeId::new(1))]));
+///         assert_eq!(std::mem::size_of::<Link>(), std::mem::size_of::<u32>());
+/// ```
+pub type Link = Option<NonZeroU32>;
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Link, Tree, TreeIndex};
+
+    #[test]
+    fn links() {
+        assert_eq!(isize::from_node(3), Some(3));
+        assert_eq!(isize::NONE.node(), None);
+        assert_eq!((-2_isize).node(), None);
+        assert_eq!(u8::from_node(254), Some(254));
+        assert_eq!(u8::from_node(255), None);
+        assert_eq!(u8::NONE.node(), None);
+        assert_eq!(u32::from_node(7).and_then(TreeIndex::node), Some(7));
+        assert_eq!(u64::MAX.to_isize(), isize::MAX);
+    }
+
+    #[test]
+    fn non_zero_links() {
+        assert_eq!(Link::from_node(0), None);
+        assert_eq!(Link::from_node(1).and_then(TreeIndex::node), Some(1));
+        assert_eq!(Link::from_node(u32::MAX as usize + 1), None);
+        assert_eq!(Link::NONE.node(), None);
+        assert_eq!(Link::NONE.to_isize(), -1);
+    }
+
+    #[test]
+    fn non_zero_tree() {
+        let tree = Tree::new(vec![vec![1, 2, -1], vec![-1, -1, -1]], vec![1, 2, 3])
+            .expect("Tree has a valid structure");
+        let linked = tree.reindex::<Link>().expect("Links fit into NonZeroU32");
+        assert!(linked.validate().is_ok());
+        assert_eq!(linked.detect_cycle(), None);
+        assert_eq!(linked.reindex::<isize>(), Ok(tree));
+
+        // Links to the root aren't representable, the tree would have a cycle
+        let cyclic = Tree::new(vec![vec![1, 0]], vec![1, 2]).expect("Tree has a valid structure");
+        assert!(cyclic.reindex::<Link>().is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::tree::{NodeId, Tree, TreeIndex};
+
+impl<T, Idx: TreeIndex> Tree<T, Idx> {
+    /// Finds a cycle of child links, if there's any. Nodes of the cycle are returned in link order, each node
+    /// links to the next one and the last one back to the first. Links out of bounds are ignored, links to the
+    /// root are followed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let tree = Tree::new(vec![vec![1, 2, 3, 1]], vec!["a", "b", "c", "d"]).expect("Tree has a valid structure");
+    ///         let cycle = vec![NodeId::new(1), NodeId::new(2), NodeId::new(3)];
+    ///         assert_eq!(tree.detect_cycle(), Some(cycle));
+    ///
+    ///         let tree = Tree::new(vec![vec![1, -1], vec![-1, -1]], vec!["a", "b"]).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.detect_cycle(), None);
+    /// ```
+    pub fn detect_cycle(&self) -> Option<Vec<NodeId>> {
+        let len = self.values.len();
+        let mut on_path = vec![false; len];
+        let mut finished = vec![false; len];
+        for start in 0..len {
+            if finished[start] {
+                continue;
+            }
+
+            // Depth-first search keeping the current path with the next dimension to follow from each node
+            on_path[start] = true;
+            let mut path = vec![(start, 0_usize)];
+            while let Some((node, dimension)) = path.last_mut() {
+                let node = *node;
+                let Some(links) = self.nodes.get(*dimension) else {
+                    on_path[node] = false;
+                    finished[node] = true;
+                    path.pop();
+                    continue;
+                };
+                *dimension += 1;
+
+                let Some(child) = links[node].node() else {
+                    continue;
+                };
+                if child >= len || finished[child] {
+                    continue;
+                }
+                if on_path[child] {
+                    let cycle_start = path.iter().position(|&(node, _)| node == child)?;
+                    return Some(
+                        path[cycle_start..]
+                            .iter()
+                            .map(|&(node, _)| NodeId::new(node))
+                            .collect(),
+                    );
+                }
+                on_path[child] = true;
+                path.push((child, 0));
+            }
+        }
+        None
+    }
+
+    /// Ids of nodes that have a value, but aren't reachable from the root by following child links, in ascending
+    /// order. Orphans as well as whole detached subtrees are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let tree = Tree::new(vec![vec![1, -1, 3, -1]], vec!["a", "b", "c", "d"]).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.unreachable_nodes(), vec![NodeId::new(2), NodeId::new(3)]);
+    /// ```
+    pub fn unreachable_nodes(&self) -> Vec<NodeId> {
+        let len = self.values.len();
+        let mut reachable = vec![false; len];
+        let mut stack: Vec<usize> = (0..len.min(1)).collect();
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut reachable[node], true) {
+                continue;
+            }
+            stack.extend(self.child_ids(node));
+        }
+        (0..len)
+            .filter(|&node| !reachable[node])
+            .map(NodeId::new)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, Tree};
+
+    #[test]
+    fn detect_cycle() {
+        let ids = |ids: &[usize]| ids.iter().map(|&id| NodeId::new(id)).collect::<Vec<_>>();
+
+        let self_loop =
+            Tree::new(vec![vec![1, 1]], vec![0; 2]).expect("Tree has a valid structure");
+        assert_eq!(self_loop.detect_cycle(), Some(ids(&[1])));
+
+        let through_root = Tree::new(vec![vec![1, -1, -1], vec![2, 0, -1]], vec![0; 3])
+            .expect("Tree has a valid structure");
+        assert_eq!(through_root.detect_cycle(), Some(ids(&[0, 1])));
+
+        // Orphaned cycle, not reachable from the root
+        let orphaned =
+            Tree::new(vec![vec![1, -1, 3, 2]], vec![0; 4]).expect("Tree has a valid structure");
+        assert_eq!(orphaned.detect_cycle(), Some(ids(&[2, 3])));
+
+        // Shared children and out of bounds links aren't cycles
+        let shared = Tree::new(vec![vec![1, 2, -1], vec![2, 7, -1]], vec![0; 3])
+            .expect("Tree has a valid structure");
+        assert_eq!(shared.detect_cycle(), None);
+
+        let chain: Vec<isize> = (1..=100_000).chain([-1]).collect();
+        let deep = Tree::new(vec![chain], vec![0; 100_001]).expect("Tree has a valid structure");
+        assert_eq!(deep.detect_cycle(), None);
+    }
+
+    #[test]
+    fn unreachable_nodes() {
+        let valid = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec![0; 4])
+            .expect("Tree has a valid structure");
+        assert!(valid.unreachable_nodes().is_empty());
+
+        // Node 2 is an orphan, 3 and 4 form a detached cycle, the root link and out of bounds links are ignored
+        let broken = Tree::new(
+            vec![vec![1, 0, -1, 4, 3], vec![9, -1, -1, -1, -1]],
+            vec![0; 5],
+        )
+        .expect("Tree has a valid structure");
+        let unreachable: Vec<usize> = broken
+            .unreachable_nodes()
+            .into_iter()
+            .map(usize::from)
+            .collect();
+        assert_eq!(unreachable, vec![2, 3, 4]);
+
+        let empty: Tree<u8> = Tree::new(vec![], vec![]).expect("Tree has a valid structure");
+        assert!(empty.unreachable_nodes().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::tree::{NodeId, Tree, TreeError, TreeIndex};
+
+impl<T: Clone, Idx: TreeIndex> Tree<T, Idx> {
+    /// Renumbers nodes in breadth-first order from the root, so a level-order traversal reads nodes and values
+    /// sequentially. Nodes unreachable from the root keep their relative order after the reachable ones.
+    /// Returns the new id of every old node id. Fails with [TreeError::IndexOutOfBounds] without changing
+    /// the tree if a link points out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let mut tree = Tree::new(vec![vec![3, -1, -1, 1]], vec!["root", "grandchild", "orphan", "child"])
+    ///             .expect("Tree has a valid structure");
+    ///
+    ///         let remapping = tree.relayout_bfs().expect("All links are in bounds");
+    ///         assert_eq!(remapping, vec![0, 2, 3, 1].into_iter().map(NodeId::new).collect::<Vec<_>>());
+    ///         assert_eq!(tree.children(0), vec![1]);
+    ///         assert_eq!(tree.path_fold(2, Vec::new(), |mut path, value| { path.push(*value); path }),
+    ///             Some(vec!["root", "child", "grandchild"]));
+    /// ```
+    pub fn relayout_bfs(&mut self) -> Result<Vec<NodeId>, TreeError> {
+        self.check_links()?;
+        let mut order = Vec::with_capacity(self.values.len());
+        let mut visited = vec![false; self.values.len()];
+        let mut queue: VecDeque<usize> = (0..self.values.len().min(1)).collect();
+        while let Some(node) = queue.pop_front() {
+            if std::mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            order.push(node);
+            queue.extend(self.child_ids(node));
+        }
+        self.relayout(order, visited)
+    }
+
+    /// Renumbers nodes in depth-first pre-order from the root, so every subtree occupies a contiguous range of
+    /// ids starting with its root. Otherwise same as [Tree::relayout_bfs].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let left = vec![1, 3, -1, -1, -1];
+    ///         let right = vec![2, 4, -1, -1, -1];
+    ///         let mut tree = Tree::new(vec![left, right], vec![0, 1, 2, 3, 4]).expect("Tree has a valid structure");
+    ///
+    ///         let remapping = tree.relayout_dfs().expect("All links are in bounds");
+    ///         assert_eq!(remapping, vec![0, 1, 4, 2, 3].into_iter().map(NodeId::new).collect::<Vec<_>>());
+    ///         assert_eq!(tree.children(1), vec![2, 3]);
+    /// ```
+    pub fn relayout_dfs(&mut self) -> Result<Vec<NodeId>, TreeError> {
+        self.check_links()?;
+        let mut order = Vec::with_capacity(self.values.len());
+        let mut visited = vec![false; self.values.len()];
+        let mut stack: Vec<usize> = (0..self.values.len().min(1)).collect();
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            order.push(node);
+            let children: Vec<usize> = self.child_ids(node).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        self.relayout(order, visited)
+    }
+
+    /// Moves every node listed in `order` to its position in the list, appending nodes not `visited`.
+    fn relayout(
+        &mut self,
+        mut order: Vec<usize>,
+        visited: Vec<bool>,
+    ) -> Result<Vec<NodeId>, TreeError> {
+        order.extend((0..self.values.len()).filter(|&node| !visited[node]));
+        let mut remapping = vec![NodeId::ROOT; self.values.len()];
+        for (new, &old) in order.iter().enumerate() {
+            remapping[old] = NodeId::new(new);
+        }
+
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|dimension| {
+                order
+                    .iter()
+                    .map(|&old| {
+                        match dimension[old].node() {
+                            None => Some(Idx::NONE),
+                            Some(child) => Idx::from_node(remapping[child].index()),
+                        }
+                        .ok_or(TreeError::IndexOutOfBounds {
+                            node: old,
+                            index: dimension[old].to_isize(),
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<Idx>>, TreeError>>()?;
+
+        let mut values: Vec<Option<T>> = Arc::unwrap_or_clone(std::mem::take(&mut self.values))
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.values = Arc::new(order.iter().filter_map(|&old| values[old].take()).collect());
+        self.nodes = Arc::new(nodes);
+        Ok(remapping)
+    }
+}
+
+impl<T, Idx: TreeIndex> Tree<T, Idx> {
+    /// Fails with [TreeError::IndexOutOfBounds] if any link points out of bounds.
+    fn check_links(&self) -> Result<(), TreeError> {
+        let len = self.values.len();
+        for dimension in self.nodes.iter() {
+            for (node, &link) in dimension.iter().enumerate() {
+                if link != Idx::NONE && link.node().is_none_or(|child| child >= len) {
+                    return Err(TreeError::IndexOutOfBounds {
+                        node,
+                        index: link.to_isize(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, Tree, TreeError};
+
+    #[test]
+    fn relayout() {
+        // Root with children 4 and 2, node 4 with child 1, node 3 unreachable
+        let links = vec![vec![4, -1, -1, -1, 1], vec![2, -1, -1, -1, -1]];
+        let values = vec!["root", "grandchild", "second", "orphan", "first"];
+        let tree = Tree::new(links, values).expect("Tree has a valid structure");
+
+        let mut bfs = tree.clone();
+        let remapping = bfs.relayout_bfs().expect("All links are in bounds");
+        let ids: Vec<usize> = remapping.into_iter().map(usize::from).collect();
+        assert_eq!(ids, vec![0, 3, 2, 4, 1]);
+        assert_eq!(
+            bfs.values(),
+            &["root", "first", "second", "grandchild", "orphan"]
+        );
+        assert!(bfs.validate().is_err()); // The orphan stays an orphan
+        assert_eq!(bfs.unreachable_nodes(), vec![NodeId::new(4)]);
+
+        let mut dfs = tree.clone();
+        dfs.relayout_dfs().expect("All links are in bounds");
+        assert_eq!(
+            dfs.values(),
+            &["root", "first", "grandchild", "second", "orphan"]
+        );
+        assert_eq!(dfs.children(1), vec![2, -1]);
+
+        let mut compact = tree.reindex::<u32>().expect("Links fit into u32");
+        compact.relayout_bfs().expect("All links are in bounds");
+        assert_eq!(compact.reindex::<isize>(), Ok(bfs));
+
+        let mut broken =
+            Tree::new(vec![vec![1, 5]], vec![0, 1]).expect("Tree has a valid structure");
+        assert_eq!(
+            broken.relayout_dfs(),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: 5 })
+        );
+        assert_eq!(broken.values(), &[0, 1]);
+    }
+}
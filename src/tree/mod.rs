@@ -1,60 +1,353 @@
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::sized::{LEAF_NODE, ROOT_NODE};
-use crate::tree::TreeError::CorruptedTree;
+use crate::algo;
+use crate::consts::ROOT_NODE;
+use crate::footprint::MemoryFootprint;
+use crate::treelike::find_parent;
 
-#[derive(Debug, Clone)]
-pub enum TreeError {
-    CorruptedTree(String),
-}
+#[cfg(any(feature = "indextree", feature = "ego-tree"))]
+mod adapters;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod branded;
+mod canonical;
+mod center;
+mod columns;
+mod compressed;
+mod cow;
+mod edges;
+mod error;
+#[cfg(feature = "petgraph")]
+mod graph;
+mod id;
+mod index;
+mod integrity;
+mod layout;
+mod node_map;
+mod outline;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod repr;
+mod snapshot;
+mod tracked;
+mod view;
+mod weighted;
+#[cfg(feature = "xml")]
+mod xml;
+
+#[cfg(feature = "arrow")]
+pub use arrow::*;
+pub use branded::*;
+pub use columns::*;
+pub use compressed::*;
+pub use cow::*;
+pub use error::*;
+pub use id::*;
+pub use index::*;
+pub use node_map::*;
+#[cfg(feature = "serde")]
+pub use repr::*;
+pub use snapshot::*;
+pub use tracked::*;
+pub use view::*;
+pub use weighted::*;
+#[cfg(feature = "xml")]
+pub use xml::*;
 
-/// Dynamically sized dense tree.
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// Dynamically sized dense tree. `Send` and `Sync` whenever `T` is.
+///
+/// ## Snapshots
+/// Nodes and values are kept in separate reference-counted buffers, shared by clones and [Snapshot]s of the tree.
+/// Mutation copies a buffer only if it's shared, so a writer can keep editing the tree while readers traverse
+/// a stable snapshot.
+///
+/// ## Index type
+/// Child links are `isize` by default, with [LEAF_NODE](crate::consts::LEAF_NODE) marking missing children. Any other
+/// [TreeIndex] can be used to save memory, see [Tree::from_links] and [Tree::reindex]. Accessors taking or
+/// returning raw links are offered for the default index type only, [NodeId] based ones for all of them.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Tree<T> {
-    nodes: Vec<Vec<isize>>,
-    values: Vec<T>,
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "TreeData<T, Idx>",
+        bound(deserialize = "T: Deserialize<'de>, Idx: TreeIndex + Deserialize<'de>")
+    )
+)]
+pub struct Tree<T, Idx = isize> {
+    nodes: Arc<Vec<Vec<Idx>>>,
+    values: Arc<Vec<T>>,
 }
 
 impl<T> Tree<T> {
     pub fn new(nodes: Vec<Vec<isize>>, values: Vec<T>) -> Result<Self, TreeError> {
-        if nodes
+        Self::from_links(nodes, values)
+    }
+
+    /// Same as [Tree::new], followed by [Tree::validate].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{Tree, TreeError};
+    ///         let tree = Tree::new_validated(vec![vec![1, 2, -1], vec![2, -1, -1]], vec![1, 2, 3]);
+    ///         assert!(matches!(tree, Err(TreeError::MultipleParents { node: 2 })));
+    /// ```
+    pub fn new_validated(nodes: Vec<Vec<isize>>, values: Vec<T>) -> Result<Self, TreeError> {
+        let tree = Self::new(nodes, values)?;
+        tree.validate()?;
+        Ok(tree)
+    }
+
+    /// Nodes and values of the tree. Buffers shared with snapshots or clones are copied.
+    pub(crate) fn into_parts(self) -> (Vec<Vec<isize>>, Vec<T>)
+    where
+        T: Clone,
+    {
+        (
+            Arc::unwrap_or_clone(self.nodes),
+            Arc::unwrap_or_clone(self.values),
+        )
+    }
+
+    /// Links `child` as the child of `node_id` in given `dimension`, [LEAF_NODE](crate::consts::LEAF_NODE)
+    /// removing the link. Nodes shared with a [Snapshot] are copied first.
+    ///
+    /// With the `debug-validate` feature, panics if `child` is out of bounds or the root. Other invariants may
+    /// hold only after a series of edits, e.g. a moved subtree has two parents until the old link is removed.
+    pub fn set_child(&mut self, node_id: usize, dimension: usize, child: isize) {
+        Arc::make_mut(&mut self.nodes)[dimension][node_id] = child;
+        debug_validate!(self.check_link(node_id, child));
+    }
+
+    /// Adds `value` as a new node, the child of `parent` in the dimension after its last child, and returns its id.
+    /// A dimension is added if the last one is taken. Buffers shared with a [Snapshot] are copied first.
+    ///
+    /// With the `debug-validate` feature, panics if the new link is invalid, like [Tree::set_child].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let mut tree = Tree::new(vec![vec![1, -1]], vec!["root", "a"]).expect("Tree has a valid structure");
+    ///
+    ///         assert_eq!(tree.add_child(0, "b"), Ok(2));
+    ///         assert_eq!(tree.children(0), vec![1, 2]);
+    ///         assert_eq!(tree.children(1), vec![-1, -1]);
+    /// ```
+    pub fn add_child(&mut self, parent: usize, value: T) -> Result<usize, TreeError>
+    where
+        T: Clone,
+    {
+        self.check_node(NodeId::new(parent))?;
+        let child = self.values.len();
+        let link = isize::from_node(child).ok_or(TreeError::NodeOutOfBounds { node: child })?;
+        let nodes = Arc::make_mut(&mut self.nodes);
+        let dimension = nodes
             .iter()
-            .any(|nodes_vec| nodes_vec.len() != values.len())
-        {
-            Err(CorruptedTree(format!(
-                "Tree nodes and values length do not match. Expected length: {}",
-                values.len()
-            )))
-        } else {
-            Ok(Self { nodes, values })
+            .rposition(|dimension| dimension[parent] != isize::NONE)
+            .map_or(0, |last| last + 1);
+        if dimension == nodes.len() {
+            nodes.push(vec![isize::NONE; child]);
+        }
+        for links in nodes.iter_mut() {
+            links.push(isize::NONE);
         }
+        nodes[dimension][parent] = link;
+        Arc::make_mut(&mut self.values).push(value);
+        debug_validate!(self.check_link(parent, link));
+        Ok(child)
     }
-    /// True if given `node_id` is a leaf node (no children), false otherwise.
+
+    /// Removes `node` with all nodes reachable from it. Remaining nodes keep their order, ids following a removed
+    /// node shift down so ids stay dense. Buffers shared with a [Snapshot] are copied first.
+    ///
+    /// The root can't be removed, [TreeError::RootRemoval] is returned instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let mut tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec!['a', 'b', 'c', 'd']).expect("Tree has a valid structure");
+    ///
+    ///         tree.remove_subtree(1).expect("Node exists");
+    ///         assert_eq!(tree, Tree::new(vec![vec![-1, -1], vec![1, -1]], vec!['a', 'c']).expect("Tree has a valid structure"));
+    /// ```
+    pub fn remove_subtree(&mut self, node: usize) -> Result<(), TreeError>
+    where
+        T: Clone,
+    {
+        self.remove_subtree_remapped(node).map(|_| ())
+    }
+
+    /// Same as [Tree::remove_subtree], returning the new id of every old node id, `None` for removed nodes. The
+    /// remapping keeps a [NodeMap] in sync with the tree, see [NodeMap::remap].
     ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let mut tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec!['a', 'b', 'c', 'd']).expect("Tree has a valid structure");
+    ///
+    ///         let remapping = tree.remove_subtree_remapped(1).expect("Node exists");
+    ///         assert_eq!(remapping, vec![Some(NodeId::new(0)), None, Some(NodeId::new(1)), None]);
+    /// ```
+    pub fn remove_subtree_remapped(&mut self, node: usize) -> Result<Vec<Option<NodeId>>, TreeError>
+    where
+        T: Clone,
+    {
+        self.check_node(NodeId::new(node))?;
+        if node == ROOT_NODE as usize {
+            return Err(TreeError::RootRemoval);
+        }
+        let len = self.values.len();
+        let mut removed = vec![false; len];
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            if !std::mem::replace(&mut removed[current], true) {
+                stack.extend(self.child_ids(current));
+            }
+        }
+        // New id of every kept node, the number of kept nodes before it
+        let mut kept = 0;
+        let ids: Vec<isize> = removed
+            .iter()
+            .map(|&gone| {
+                let id = kept;
+                kept += isize::from(!gone);
+                id
+            })
+            .collect();
+
+        let relink = |link: isize| match link.node() {
+            Some(child) if child < len && removed[child] => isize::NONE,
+            Some(child) if child < len => ids[child],
+            _ => link,
+        };
+        for links in Arc::make_mut(&mut self.nodes).iter_mut() {
+            let mut kept = removed.iter();
+            links.retain(|_| kept.next() == Some(&false));
+            for link in links.iter_mut() {
+                *link = relink(*link);
+            }
+        }
+        let mut kept = removed.iter();
+        Arc::make_mut(&mut self.values).retain(|_| kept.next() == Some(&false));
+        Ok(removed
+            .iter()
+            .zip(ids)
+            .map(|(&gone, id)| (!gone).then(|| NodeId::new(id as usize)))
+            .collect())
+    }
+
+    /// Rotates the subtree of `node_id` to the left in a binary tree, the right child becoming the subtree's root.
+    /// In-order of the nodes is kept, so rotations preserve the order of a binary search tree. Returns `Ok(false)`
+    /// without any change if the node has no right child.
     ///
+    /// The subtree stays at `node_id`, so the link from its parent remains valid and even the root can be
+    /// rotated: the values of `node_id` and of its right child swap places, the child's id now holding the former
+    /// root of the subtree. A second dimension is added to trees with a single one. Trees with more than two
+    /// dimensions aren't binary, [TreeError::ArityExceeded] is returned for them. Buffers shared with a [Snapshot]
+    /// are copied first.
     ///
     /// # Examples
     ///
     /// ```
     ///         use treesome::tree::Tree;
-    ///         let left = vec![1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
-    ///         let mid = vec![2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
-    ///         let right = vec![3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1];
-    ///         let values = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
-    ///         let tree = Tree::new(vec![left, mid, right], values).expect("Tree has a valid structure");
-    ///         assert!(!tree.is_leaf_node(0));
-    ///         assert!(tree.is_leaf_node(4));
+    ///         let left = vec![1, -1, 3, -1, -1];
+    ///         let right = vec![2, -1, 4, -1, -1];
+    ///         let mut tree = Tree::new(vec![left, right], vec!['x', 'a', 'y', 'b', 'c']).expect("Tree has a valid structure");
+    ///
+    ///         assert_eq!(tree.rotate_left(0), Ok(true));
+    ///         let left = vec![2, -1, 1, -1, -1];
+    ///         let right = vec![4, -1, 3, -1, -1];
+    ///         assert_eq!(tree, Tree::new(vec![left, right], vec!['y', 'a', 'x', 'b', 'c']).expect("Tree has a valid structure"));
+    ///
+    ///         assert_eq!(tree.rotate_right(0), Ok(true));
+    ///         assert_eq!(tree.values(), &['x', 'a', 'y', 'b', 'c']);
+    ///         assert_eq!(tree.rotate_right(1), Ok(false));
     /// ```
-    pub fn is_leaf_node(&self, node_id: usize) -> bool {
+    pub fn rotate_left(&mut self, node_id: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        self.rotate(node_id, 1)
+    }
+
+    /// Rotates the subtree of `node_id` to the right in a binary tree, the left child becoming the subtree's root.
+    /// Mirrors [Tree::rotate_left], returning `Ok(false)` if the node has no left child.
+    pub fn rotate_right(&mut self, node_id: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        self.rotate(node_id, 0)
+    }
+
+    /// Lifts the child of `node_id` in the `raised` dimension, swapping values so the subtree keeps its position.
+    fn rotate(&mut self, node_id: usize, raised: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        self.check_node(NodeId::new(node_id))?;
+        if self.nodes.len() > 2 {
+            return Err(TreeError::ArityExceeded {
+                node: node_id,
+                children: self.nodes.len(),
+                max: 2,
+            });
+        }
+        let Some(child) = self.child_in(node_id, raised) else {
+            return Ok(false);
+        };
+
+        let len = self.values.len();
+        let nodes = Arc::make_mut(&mut self.nodes);
+        nodes.resize(2, vec![isize::NONE; len]);
+        let lowered = 1 - raised;
+        // The child's slot takes the node with the inner grandchild, the node's slot the child with the outer one
+        let outer = nodes[lowered][node_id];
+        let inner = nodes[lowered][child];
+        nodes[raised][node_id] = nodes[raised][child];
+        nodes[lowered][node_id] = child as isize;
+        nodes[raised][child] = inner;
+        nodes[lowered][child] = outer;
+        Arc::make_mut(&mut self.values).swap(node_id, child);
+        debug_validate!(self.validate());
+        Ok(true)
+    }
+
+    /// Valid child of `node_id` in `dimension`, `None` for missing dimensions, placeholders and links out of bounds.
+    fn child_in(&self, node_id: usize, dimension: usize) -> Option<usize> {
         self.nodes
-            .iter()
-            .enumerate()
-            .all(|(m, _)| self.nodes[m][node_id] == LEAF_NODE)
+            .get(dimension)?
+            .get(node_id)?
+            .node()
+            .filter(|&child| child > ROOT_NODE as usize && child < self.values.len())
+    }
+
+    #[cfg(feature = "debug-validate")]
+    fn check_link(&self, node_id: usize, child: isize) -> Result<(), TreeError> {
+        if child == isize::NONE {
+            Ok(())
+        } else if child < 0 || child as usize >= self.values.len() {
+            Err(TreeError::IndexOutOfBounds {
+                node: node_id,
+                index: child,
+            })
+        } else if child == ROOT_NODE {
+            Err(TreeError::CycleDetected { node: node_id })
+        } else {
+            Ok(())
+        }
     }
 
-    /// Returns a [Vec] of size `n` with node's children indices, or [LEAF_NODE] as a placeholder for every missing child.
+    /// Returns a [Vec] of size `n` with node's children indices, or [LEAF_NODE](crate::consts::LEAF_NODE) as
+    /// a placeholder for every missing child.
     ///
     /// # Examples
     ///
@@ -69,6 +362,8 @@ impl<T> Tree<T> {
     ///         assert_eq!(tree.children(2), vec![7, 8, 9]);
     ///         assert_eq!(tree.children(6), vec![-1, -1, -1]); // Leaf node, no children
     /// ```
+    ///
+    /// Panics if `node_id` is out of bounds, see [Tree::try_children] for a checked variant.
     pub fn children(&self, node_id: usize) -> Vec<isize> {
         self.nodes
             .iter()
@@ -76,14 +371,47 @@ impl<T> Tree<T> {
             .collect()
     }
 
+    /// Same as [Tree::children], without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than the number of values. Children returned for nodes of a tree that passed
+    /// [Tree::validate] satisfy this.
+    pub unsafe fn children_unchecked(&self, node_id: usize) -> Vec<isize> {
+        debug_assert!(
+            node_id < self.values.len(),
+            "Node {node_id} is out of bounds"
+        );
+        self.nodes
+            .iter()
+            // SAFETY: All dimensions are as long as values, the caller guarantees `node_id` is in bounds
+            .map(|dimension| unsafe { *dimension.get_unchecked(node_id) })
+            .collect()
+    }
+
+    /// Value of `node_id`, without bounds checks.
+    ///
+    /// # Safety
+    /// `node_id` must be lower than the number of values.
+    pub unsafe fn value_unchecked(&self, node_id: usize) -> &T {
+        debug_assert!(
+            node_id < self.values.len(),
+            "Node {node_id} is out of bounds"
+        );
+        // SAFETY: The caller guarantees `node_id` is in bounds
+        unsafe { self.values.get_unchecked(node_id) }
+    }
+
     /// Returns index of a node's parent, if the node has a parent. `None` otherwise.
     /// E.g. root nodes don't have a parent.
     ///
+    /// The node linking to `node_id` is looked up, so the answer holds whatever the layout, e.g. after
+    /// [Tree::add_child]. The parent's position in the complete layout is checked first in O(1), other layouts
+    /// fall back to a linear scan.
     ///
     /// # Examples
     ///
     /// ```
-    ///         use treesome::sized::ROOT_NODE;
+    ///         use treesome::consts::ROOT_NODE;
     ///         use treesome::tree::Tree;
     ///         let left = vec![1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
     ///         let mid = vec![2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
@@ -107,18 +435,697 @@ impl<T> Tree<T> {
     ///         assert_eq!(tree.parent(10).unwrap(), 3);
     /// ```
     pub fn parent(&self, node_id: isize) -> Option<isize> {
-        if node_id <= ROOT_NODE || node_id as usize >= self.values.len() {
-            return None; // Root node doesn't have a parent.
-        };
+        let parent = self
+            .try_parent(NodeId::from_link(node_id)?)
+            .ok()
+            .flatten()?;
+        isize::try_from(parent.index()).ok()
+    }
+}
+
+impl<T, Idx: TreeIndex> Tree<T, Idx> {
+    /// Constructs a tree with links of any [TreeIndex] type, e.g. `u32` to halve the memory of links.
+    /// Like [Tree::new], only the lengths of dimensions are checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let tree: Tree<&str, u32> =
+    ///             Tree::from_links(vec![vec![1, u32::MAX]], vec!["a", "b"]).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.try_children(NodeId::ROOT), Ok(vec![Some(NodeId::new(1))]));
+    ///         assert!(tree.validate().is_ok());
+    /// ```
+    pub fn from_links(nodes: Vec<Vec<Idx>>, values: Vec<T>) -> Result<Self, TreeError> {
+        if let Some(dimension) = nodes
+            .iter()
+            .find(|nodes_vec| nodes_vec.len() != values.len())
+        {
+            Err(TreeError::LengthMismatch {
+                expected: values.len(),
+                found: dimension.len(),
+            })
+        } else {
+            Ok(Self {
+                nodes: Arc::new(nodes),
+                values: Arc::new(values),
+            })
+        }
+    }
+
+    /// Copies links of the tree into links of another [TreeIndex] type, sharing the values. Fails with
+    /// [TreeError::IndexOutOfBounds] at the first link `J` can't represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 2, -1]], vec![1, 2, 3]).expect("Tree has a valid structure");
+    ///         let compact = tree.reindex::<u32>().expect("Links fit into u32");
+    ///         assert_eq!(compact.reindex::<isize>(), Ok(tree));
+    /// ```
+    pub fn reindex<J: TreeIndex>(&self) -> Result<Tree<T, J>, TreeError> {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|dimension| {
+                dimension
+                    .iter()
+                    .enumerate()
+                    .map(|(node, &link)| {
+                        match link.node() {
+                            _ if link == Idx::NONE => Some(J::NONE),
+                            Some(child) => J::from_node(child),
+                            None => None,
+                        }
+                        .ok_or(TreeError::IndexOutOfBounds {
+                            node,
+                            index: link.to_isize(),
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<J>>, TreeError>>()?;
+        Ok(Tree {
+            nodes: Arc::new(nodes),
+            values: Arc::clone(&self.values),
+        })
+    }
+
+    /// Checks the tree is well-formed: all child indices are in bounds, node `0` is the only root, every other node
+    /// is a child of exactly one node, and there are no cycles. Returns an error with the first offending node.
+    pub fn validate(&self) -> Result<(), TreeError> {
+        let len = self.values.len();
+        let mut parents: Vec<Option<usize>> = vec![None; len];
+        for node in 0..len {
+            for dimension in self.nodes.iter() {
+                let link = dimension[node];
+                if link == Idx::NONE {
+                    continue;
+                }
+                let Some(child) = link.node().filter(|&child| child < len) else {
+                    return Err(TreeError::IndexOutOfBounds {
+                        node,
+                        index: link.to_isize(),
+                    });
+                };
+                if child == ROOT_NODE as usize {
+                    return Err(TreeError::CycleDetected { node });
+                }
+                if parents[child].replace(node).is_some() {
+                    return Err(TreeError::MultipleParents { node: child });
+                }
+            }
+        }
+        if let Some(node) = (1..len).find(|&node| parents[node].is_none()) {
+            return Err(TreeError::MultipleRoots { node });
+        }
+
+        // Every node but the root has a single parent, so nodes unreachable from the root form cycles
+        let mut reachable = vec![false; len];
+        let mut stack: Vec<usize> = (0..len.min(1)).collect();
+        while let Some(node) = stack.pop() {
+            reachable[node] = true;
+            stack.extend(self.child_ids(node));
+        }
+        match reachable.iter().position(|&reachable| !reachable) {
+            Some(node) => Err(TreeError::CycleDetected { node }),
+            None => Ok(()),
+        }
+    }
+
+    /// Bytes taken by the tree, see [MemoryFootprint]. The two reference counted buffers add their counters to
+    /// the overhead.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let counters = 2 * 2 * size_of::<usize>();
+        MemoryFootprint {
+            structure: size_of::<Self>(),
+            overhead: counters,
+            ..MemoryFootprint::default()
+        } + MemoryFootprint::vec(&self.nodes, false)
+            + self
+                .nodes
+                .iter()
+                .map(|dimension| MemoryFootprint::vec(dimension, false))
+                .sum()
+            + MemoryFootprint::vec(&self.values, true)
+    }
+
+    /// Values of all nodes, in order of node ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let mut tree = Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+    ///
+    ///         tree[NodeId::new(1)] += 10;
+    ///         assert_eq!(tree.values(), &[1, 12]);
+    ///         assert_eq!(tree[0], 1);
+    /// ```
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Links of the tree, a vector per dimension.
+    pub(crate) fn dimensions(&self) -> &[Vec<Idx>] {
+        &self.nodes
+    }
+
+    /// Mutable access to value of a node. Values shared with a [Snapshot] are copied first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let mut tree = Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+    ///         let snapshot = tree.snapshot();
+    ///
+    ///         *tree.value_mut(1) += 10;
+    ///         assert_eq!(tree.path_fold(1, 0, |sum, value| sum + value), Some(13));
+    ///         assert_eq!(snapshot.path_fold(1, 0, |sum, value| sum + value), Some(3));
+    /// ```
+    pub fn value_mut(&mut self, node_id: usize) -> &mut T
+    where
+        T: Clone,
+    {
+        &mut Arc::make_mut(&mut self.values)[node_id]
+    }
+
+    /// True if given `node_id` is a leaf node (no children), false otherwise.
+    ///
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let left = vec![1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
+    ///         let mid = vec![2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
+    ///         let right = vec![3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1];
+    ///         let values = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    ///         let tree = Tree::new(vec![left, mid, right], values).expect("Tree has a valid structure");
+    ///         assert!(!tree.is_leaf_node(0));
+    ///         assert!(tree.is_leaf_node(4));
+    /// ```
+    ///
+    /// Panics if `node_id` is out of bounds, see [Tree::try_is_leaf] for a checked variant.
+    pub fn is_leaf_node(&self, node_id: usize) -> bool {
+        self.nodes
+            .iter()
+            .enumerate()
+            .all(|(m, _)| self.nodes[m][node_id] == Idx::NONE)
+    }
+
+    /// Number of children of `node_id`, not counting [LEAF_NODE](crate::consts::LEAF_NODE) placeholders and links
+    /// out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec![1, 2, 3, 4]).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.child_count(0), 2);
+    ///         assert_eq!(tree.child_count(1), 1);
+    ///         assert_eq!(tree.max_degree(), 2);
+    ///         assert_eq!(tree.degree_histogram(), vec![2, 1, 1]);
+    /// ```
+    ///
+    /// Panics if `node_id` is out of bounds.
+    pub fn child_count(&self, node_id: usize) -> usize {
+        assert!(
+            node_id < self.values.len(),
+            "Node {node_id} is out of bounds"
+        );
+        self.child_ids(node_id).count()
+    }
+
+    /// Highest number of children of a node, zero for an empty tree. See [algo::max_degree].
+    pub fn max_degree(&self) -> usize {
+        algo::max_degree(self)
+    }
+
+    /// Number of nodes by their number of children, see [algo::degree_histogram].
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        algo::degree_histogram(self)
+    }
+
+    /// Returns the value of `node`, or `None` if the node doesn't exist.
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.values.get(node.index())
+    }
+
+    /// Checked variant of [Tree::children]. Missing children are `None` instead of
+    /// [LEAF_NODE](crate::consts::LEAF_NODE), and [TreeError::NodeOutOfBounds] is returned instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree, TreeError};
+    ///         let tree = Tree::new(vec![vec![1, -1], vec![-1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+    ///         assert_eq!(tree.try_children(NodeId::ROOT), Ok(vec![Some(NodeId::new(1)), None]));
+    ///         assert_eq!(tree.try_children(NodeId::new(2)), Err(TreeError::NodeOutOfBounds { node: 2 }));
+    /// ```
+    pub fn try_children(&self, node: NodeId) -> Result<Vec<Option<NodeId>>, TreeError> {
+        let node = self.check_node(node)?;
+        Ok(self
+            .nodes
+            .iter()
+            .map(|dimension| dimension[node].node().map(NodeId::new))
+            .collect())
+    }
+
+    /// Checked variant of [Tree::is_leaf_node]. Returns [TreeError::NodeOutOfBounds] instead of panicking.
+    pub fn try_is_leaf(&self, node: NodeId) -> Result<bool, TreeError> {
+        let node = self.check_node(node)?;
+        Ok(self.is_leaf_node(node))
+    }
+
+    /// Checked variant of [Tree::parent]. Returns `Ok(None)` for the root and unlinked nodes, and
+    /// [TreeError::NodeOutOfBounds] for nodes that don't exist, instead of conflating both as `None`.
+    pub fn try_parent(&self, node: NodeId) -> Result<Option<NodeId>, TreeError> {
+        let node = self.check_node(node)?;
+        let parent = find_parent(node, self.values.len(), self.nodes.len(), |parent| {
+            self.nodes
+                .iter()
+                .any(|dimension| dimension[parent].node() == Some(node))
+        });
+        Ok(parent.map(NodeId::new))
+    }
+
+    /// First existing child of `node`, skipping [LEAF_NODE](crate::consts::LEAF_NODE) placeholders. `None` for
+    /// leaves and nodes that don't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, Tree};
+    ///         let tree = Tree::new(vec![vec![-1, -1, -1], vec![1, -1, -1], vec![2, -1, -1]], vec![0, 1, 2])
+    ///             .expect("Tree has a valid structure");
+    ///         assert_eq!(tree.first_child(NodeId::ROOT), Some(NodeId::new(1)));
+    ///         assert_eq!(tree.last_child(NodeId::ROOT), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 1), Some(NodeId::new(2)));
+    ///         assert_eq!(tree.nth_child(NodeId::ROOT, 2), None);
+    /// ```
+    pub fn first_child(&self, node: NodeId) -> Option<NodeId> {
+        self.nth_child(node, 0)
+    }
+
+    /// Last existing child of `node`, skipping [LEAF_NODE](crate::consts::LEAF_NODE) placeholders. `None` for
+    /// leaves and nodes that don't exist.
+    pub fn last_child(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).last().map(NodeId::new)
+    }
+
+    /// Existing child of `node` at position `k`, counting from zero and skipping
+    /// [LEAF_NODE](crate::consts::LEAF_NODE) placeholders. `None` if the node has at most `k` children or doesn't
+    /// exist.
+    pub fn nth_child(&self, node: NodeId, k: usize) -> Option<NodeId> {
+        let node = self.check_node(node).ok()?;
+        self.child_ids(node).nth(k).map(NodeId::new)
+    }
+
+    fn check_node(&self, node: NodeId) -> Result<usize, TreeError> {
+        if node.index() < self.values.len() {
+            Ok(node.index())
+        } else {
+            Err(TreeError::NodeOutOfBounds { node: node.index() })
+        }
+    }
+
+    /// Folds values on the path from the root to `node_id`, root first. Useful for weighted depths,
+    /// cumulative costs or probability products. Returns `None` if `node_id` isn't reachable from the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let left = vec![1, 3, -1, -1, -1];
+    ///         let right = vec![2, 4, -1, -1, -1];
+    ///         let values = vec![1, 2, 3, 4, 5];
+    ///         let tree = Tree::new(vec![left, right], values).expect("Tree has a valid structure");
+    ///
+    ///         assert_eq!(tree.path_fold(4, 0, |acc, value| acc + value), Some(1 + 2 + 5));
+    ///         assert_eq!(tree.path_fold(2, 1, |acc, value| acc * value), Some(3));
+    ///         assert_eq!(tree.path_fold(42, 0, |acc, value| acc + value), None);
+    /// ```
+    pub fn path_fold<A, F>(&self, node_id: usize, init: A, mut f: F) -> Option<A>
+    where
+        F: FnMut(A, &T) -> A,
+    {
+        let path = self.path_to(node_id)?;
+        Some(
+            path.into_iter()
+                .fold(init, |acc, node| f(acc, &self.values[node])),
+        )
+    }
+
+    /// Ids of nodes on the path from the root to `node_id`, both ends included. The path is found by descending
+    /// from the root, invalid child indices are skipped.
+    fn path_to(&self, node_id: usize) -> Option<Vec<usize>> {
+        algo::path_to(self.values.len(), node_id, |node| self.child_ids(node))
+    }
+
+    /// Valid children of `node_id`, skipping [LEAF_NODE](crate::consts::LEAF_NODE) placeholders and indices out
+    /// of bounds.
+    fn child_ids(&self, node_id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes.iter().filter_map(move |dimension| {
+            dimension[node_id]
+                .node()
+                .filter(|&child| child > ROOT_NODE as usize && child < self.values.len())
+        })
+    }
+}
+
+/// Empty tree, without dimensions and nodes.
+impl<T, Idx> Default for Tree<T, Idx> {
+    fn default() -> Self {
+        Self {
+            nodes: Arc::new(Vec::new()),
+            values: Arc::new(Vec::new()),
+        }
+    }
+}
+
+impl<T, Idx> Index<usize> for Tree<T, Idx> {
+    type Output = T;
+
+    fn index(&self, node_id: usize) -> &Self::Output {
+        &self.values[node_id]
+    }
+}
+
+/// Values shared with a [Snapshot] are copied first, see [Tree::value_mut].
+impl<T: Clone, Idx> IndexMut<usize> for Tree<T, Idx> {
+    fn index_mut(&mut self, node_id: usize) -> &mut Self::Output {
+        &mut Arc::make_mut(&mut self.values)[node_id]
+    }
+}
 
-        let tree_dimension = self.nodes.len();
-        Some((node_id - 1) / tree_dimension as isize)
+impl<T, Idx> Index<NodeId> for Tree<T, Idx> {
+    type Output = T;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        &self[node.index()]
+    }
+}
+
+impl<T: Clone, Idx> IndexMut<NodeId> for Tree<T, Idx> {
+    fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+        &mut self[node.index()]
+    }
+}
+
+/// Serialized form of a [Tree], validated by [Tree::validate] before it's turned into the tree.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct TreeData<T, Idx> {
+    nodes: Vec<Vec<Idx>>,
+    values: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T, Idx: TreeIndex> TryFrom<TreeData<T, Idx>> for Tree<T, Idx> {
+    type Error = TreeError;
+
+    fn try_from(data: TreeData<T, Idx>) -> Result<Self, Self::Error> {
+        let tree = Tree::from_links(data.nodes, data.values)?;
+        tree.validate()?;
+        Ok(tree)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tree::Tree;
+    use crate::tree::{NodeId, Tree, TreeError};
+    use crate::TreeLike;
+
+    #[cfg(feature = "debug-validate")]
+    #[test]
+    #[should_panic(expected = "Tree invariant violated")]
+    fn debug_validate_set_child() {
+        let mut tree =
+            Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+        tree.set_child(1, 0, 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_malformed() {
+        for malformed in [
+            r#"{"nodes":[[1,0]],"values":[1,2]}"#,
+            r#"{"nodes":[[1,5]],"values":[1,2]}"#,
+            r#"{"nodes":[[1]],"values":[1,2]}"#,
+            r#"{"nodes":[[-1,-1,-1]],"values":[1,2,3]}"#,
+        ] {
+            assert!(serde_json::from_str::<Tree<u8>>(malformed).is_err());
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        // Search tree of 1..=7, skewed to the right
+        let left = vec![-1, -1, 3, -1, -1, -1, -1];
+        let right = vec![1, 2, 4, -1, 5, 6, -1];
+        let mut tree = Tree::new(vec![left, right], vec![1, 2, 4, 3, 5, 6, 7])
+            .expect("Tree has a valid structure");
+        let in_order = |tree: &Tree<u8>| {
+            let (mut values, mut stack, mut node) = (Vec::new(), Vec::new(), Some(0));
+            while node.is_some() || !stack.is_empty() {
+                while let Some(current) = node {
+                    stack.push(current);
+                    node = NodeId::from_link(tree.children(current)[0]).map(NodeId::index);
+                }
+                let current = stack.pop().unwrap();
+                values.push(tree[current]);
+                node = NodeId::from_link(tree.children(current)[1]).map(NodeId::index);
+            }
+            values
+        };
+
+        assert_eq!(tree.rotate_left(0), Ok(true));
+        assert_eq!(tree.rotate_left(0), Ok(true));
+        assert_eq!(tree.rotate_left(4), Ok(true));
+        assert_eq!((tree[0], tree[2], tree[4]), (4, 2, 6));
+        assert_eq!(in_order(&tree), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert!(tree.validate().is_ok());
+        assert_eq!(tree.rotate_left(1), Ok(false));
+        assert_eq!(
+            tree.rotate_left(7),
+            Err(TreeError::NodeOutOfBounds { node: 7 })
+        );
+
+        // A second dimension is added for a right rotation of a left chain
+        let mut chain =
+            Tree::new(vec![vec![1, -1]], vec![2, 1]).expect("Tree has a valid structure");
+        assert_eq!(chain.rotate_right(0), Ok(true));
+        assert_eq!(chain.children(0), vec![-1, 1]);
+        assert_eq!(chain.values(), &[1, 2]);
+
+        let mut ternary =
+            Tree::new(vec![vec![1, -1]; 3], vec![0, 1]).expect("Tree has a valid structure");
+        assert!(matches!(
+            ternary.rotate_left(0),
+            Err(TreeError::ArityExceeded { max: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn index_types() {
+        let left = vec![1, 3, -1, -1, -1];
+        let right = vec![2, 4, -1, -1, -1];
+        let tree =
+            Tree::new(vec![left, right], vec![0, 1, 2, 3, 4]).expect("Tree has a valid structure");
+
+        let compact = tree.reindex::<u8>().expect("Links fit into u8");
+        assert_eq!(
+            compact.try_children(NodeId::new(1)),
+            tree.try_children(NodeId::new(1))
+        );
+        assert_eq!(compact.try_is_leaf(NodeId::new(2)), Ok(true));
+        assert_eq!(compact.path_fold(4, 0, |sum, value| sum + value), Some(5));
+        assert!(compact.validate().is_ok());
+        assert_eq!(compact.reindex::<isize>(), Ok(tree));
+
+        let links: Vec<isize> = (1..=300).chain([-1]).collect();
+        let deep = Tree::new(vec![links], vec![0; 301]).expect("Tree has a valid structure");
+        assert_eq!(
+            deep.reindex::<u8>(),
+            Err(TreeError::IndexOutOfBounds {
+                node: 254,
+                index: 255
+            })
+        );
+
+        let broken: Tree<u8, u32> =
+            Tree::from_links(vec![vec![1, 7]], vec![0, 1]).expect("Tree has a valid structure");
+        assert_eq!(
+            broken.validate(),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: 7 })
+        );
+    }
+
+    #[test]
+    fn index_values() {
+        let mut tree =
+            Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+        let snapshot = tree.snapshot();
+        tree[0] = 10;
+        tree[NodeId::new(1)] *= 10;
+        assert_eq!(tree.values(), &[10, 20]);
+        assert_eq!(tree[NodeId::ROOT], 10);
+        assert_eq!(snapshot.values(), &[1, 2]);
+
+        let mut sized = crate::sized::Tree::new([[1, -1]], ['a', 'b']);
+        sized[NodeId::new(1)] = 'c';
+        assert_eq!(sized.values(), &['a', 'c']);
+        let mut btree = crate::sized::BTree::new([1, -1], [-1, -1], ['a', 'b']);
+        btree[0] = 'z';
+        assert_eq!(btree[NodeId::ROOT], 'z');
+        assert_eq!(btree.values(), &['z', 'b']);
+    }
+
+    #[test]
+    fn parent_boundaries() {
+        let tree = Tree::new(
+            vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]],
+            vec![0; 5],
+        )
+        .expect("Tree has a valid structure");
+        for node_id in [isize::MIN, -2, -1, 0, 5, isize::MAX] {
+            assert_eq!(tree.parent(node_id), None);
+        }
+        assert_eq!(tree.parent(4), Some(1));
+
+        let no_dimensions = Tree::new(vec![], vec![0; 3]).expect("Tree has a valid structure");
+        assert_eq!(no_dimensions.parent(2), None);
+        assert_eq!(no_dimensions.try_parent(NodeId::new(2)), Ok(None));
+    }
+
+    #[test]
+    fn parent_after_add_child() {
+        let mut tree = Tree::new(vec![], vec!["root"]).expect("Tree has a valid structure");
+        let a = tree.add_child(0, "a").expect("Root exists");
+        let b = tree.add_child(0, "b").expect("Root exists");
+        let c = tree.add_child(b, "c").expect("Node b exists");
+
+        // Node 3 sits at the complete-layout position of a child of node 1, but is linked from node 2
+        assert_eq!(tree.parent(c as isize), Some(b as isize));
+        assert_eq!(tree.try_parent(NodeId::new(c)), Ok(Some(NodeId::new(b))));
+        assert_eq!(tree.parent(a as isize), Some(0));
+        assert_eq!(TreeLike::parent(&tree, c), Some(b));
+    }
+
+    #[test]
+    fn checked_accessors() {
+        let tree = Tree::new(
+            vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]],
+            vec![0, 1, 2, 3, 4],
+        )
+        .expect("Tree has a valid structure");
+        assert_eq!(tree.get(NodeId::new(4)), Some(&4));
+        assert_eq!(tree.get(NodeId::new(5)), None);
+        assert_eq!(tree.try_is_leaf(NodeId::new(2)), Ok(true));
+        assert_eq!(tree.try_is_leaf(NodeId::new(1)), Ok(false));
+        assert_eq!(tree.try_parent(NodeId::ROOT), Ok(None));
+        assert_eq!(tree.try_parent(NodeId::new(3)), Ok(Some(NodeId::new(1))));
+        for node in [5, usize::MAX] {
+            assert_eq!(
+                tree.try_children(NodeId::new(node)),
+                Err(TreeError::NodeOutOfBounds { node })
+            );
+            assert_eq!(
+                tree.try_is_leaf(NodeId::new(node)),
+                Err(TreeError::NodeOutOfBounds { node })
+            );
+            assert_eq!(
+                tree.try_parent(NodeId::new(node)),
+                Err(TreeError::NodeOutOfBounds { node })
+            );
+        }
+
+        let shallow = Tree::new(vec![], vec![0]).expect("Tree has a valid structure");
+        assert_eq!(shallow.try_parent(NodeId::ROOT), Ok(None));
+    }
+
+    #[test]
+    fn validate() {
+        let valid = Tree::new_validated(
+            vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]],
+            vec![0; 5],
+        );
+        assert!(valid.is_ok());
+        let empty = Tree::<i32>::new_validated(vec![vec![]], vec![]);
+        assert!(empty.is_ok());
+
+        let check = |nodes: Vec<Vec<isize>>| {
+            let len = nodes[0].len();
+            Tree::new(nodes, vec![0; len]).unwrap().validate()
+        };
+        assert!(matches!(
+            check(vec![vec![1, 5, -1]]),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: 5 })
+        ));
+        assert!(matches!(
+            check(vec![vec![1, -3, -1]]),
+            Err(TreeError::IndexOutOfBounds { node: 1, index: -3 })
+        ));
+        assert!(matches!(
+            check(vec![vec![1, 0]]),
+            Err(TreeError::CycleDetected { node: 1 })
+        ));
+        assert!(matches!(
+            check(vec![vec![1, 2, -1], vec![2, -1, -1]]),
+            Err(TreeError::MultipleParents { node: 2 })
+        ));
+        assert!(matches!(
+            check(vec![vec![1, -1, -1]]),
+            Err(TreeError::MultipleRoots { node: 2 })
+        ));
+        assert!(matches!(
+            check(vec![vec![1, -1, 3, 2]]),
+            Err(TreeError::CycleDetected { node: 2 })
+        ));
+    }
+
+    #[test]
+    fn standard_traits() {
+        use std::collections::HashSet;
+        fn assert_standard<S: Clone + std::fmt::Debug + Eq + std::hash::Hash>() {}
+        assert_standard::<Tree<u8>>();
+        assert_standard::<Tree<u8, u16>>();
+        assert_standard::<crate::sized::Tree<u8, 2, 4>>();
+        assert_standard::<crate::sized::BTree<u8, 4>>();
+        assert_standard::<crate::sized::Children>();
+        assert_standard::<crate::tree::CompressedTree<u8>>();
+        assert_standard::<crate::sparse::ArenaTree<u8>>();
+
+        let tree = Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Valid structure");
+        let trees: HashSet<Tree<i32>> = [tree.clone(), tree, Tree::default()].into();
+        assert_eq!(trees.len(), 2);
+        assert!(trees.contains(&Tree::new(vec![], vec![]).expect("Valid structure")));
+    }
+
+    #[test]
+    fn send_sync() {
+        let left = vec![1, 3, -1, -1, -1];
+        let right = vec![2, 4, -1, -1, -1];
+        let tree = Tree::new(vec![left, right], vec![1, 2, 3, 4, 5]).expect("Valid structure");
+        let sums: Vec<Option<i32>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..5)
+                .map(|node| {
+                    let tree = &tree;
+                    scope.spawn(move || tree.path_fold(node, 0, |acc, value| acc + value))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        assert_eq!(sums, vec![Some(1), Some(3), Some(4), Some(7), Some(8)]);
+    }
 
     #[test]
     fn new_validation() {
@@ -133,13 +1140,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn path_fold() {
+        let left = vec![1, 3, 5, -1, -1, -1, -1];
+        let right = vec![2, 4, 6, -1, -1, -1, -1];
+        let values = vec![0.5, 0.5, 0.5, 0.2, 0.8, 0.1, 0.9];
+        let tree = Tree::new(vec![left, right], values).expect("Tree has a valid structure");
+
+        assert_eq!(tree.path_fold(0, 1.0, |acc, p| acc * p), Some(0.5));
+        assert_eq!(
+            tree.path_fold(6, 1.0, |acc, p| acc * p),
+            Some(0.5 * 0.5 * 0.9)
+        );
+        assert_eq!(tree.path_fold(3, 0, |depth, _| depth + 1), Some(3));
+        assert_eq!(tree.path_fold(7, 0, |depth, _| depth + 1), None);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn serde() {
-        let left = vec![1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1];
-        let mid = vec![2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1];
-        let right = vec![3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1];
-        let values = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let left = vec![1, 4, 7, 10, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let mid = vec![2, 5, 8, 11, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let right = vec![3, 6, 9, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1];
+        let values = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
         let tree = Tree::new(vec![left, mid, right], values).expect("Tree has a valid structure");
         let string_repr = serde_json::to_string(&tree).unwrap();
         let deserialized_tree: Tree<i32> = serde_json::from_str(&string_repr).unwrap();
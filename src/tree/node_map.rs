@@ -0,0 +1,180 @@
+use std::ops::Index;
+
+use crate::tree::NodeId;
+
+/// Dense side table attaching values of another type to node ids of a [Tree](crate::tree::Tree), e.g. layout
+/// coordinates or dirty flags, without changing the values of the tree. Entries are optional, missing ones take
+/// no space beyond their slot.
+///
+/// Ids stay valid while nodes are added or relinked. Operations renumbering nodes return the new id of every old
+/// id, [NodeMap::remap] moves the entries along: [Tree::relayout_bfs](crate::tree::Tree::relayout_bfs),
+/// [Tree::relayout_dfs](crate::tree::Tree::relayout_dfs) and
+/// [Tree::remove_subtree_remapped](crate::tree::Tree::remove_subtree_remapped), the latter dropping entries of
+/// removed nodes. Rotations swap values of nodes, entries stay with the ids.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{NodeId, NodeMap, Tree};
+///         let mut tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec!['a', 'b', 'c', 'd']).expect("Tree has a valid structure");
+///         let mut depths = NodeMap::new();
+///         depths.insert(NodeId::new(2), 1);
+///         depths.insert(NodeId::new(3), 2);
+///
+///         let remapping = tree.remove_subtree_remapped(1).expect("Node exists");
+///         depths.remap(&remapping);
+///         assert_eq!(tree[1], 'c');
+///         assert_eq!(depths[NodeId::new(1)], 1);
+///         assert_eq!(depths.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NodeMap<V> {
+    entries: Vec<Option<V>>,
+}
+
+impl<V> NodeMap<V> {
+    /// Creates an empty table, slots are allocated by [NodeMap::insert].
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Entry of `node`, if there's one.
+    pub fn get(&self, node: NodeId) -> Option<&V> {
+        self.entries.get(node.index())?.as_ref()
+    }
+
+    /// Mutable entry of `node`, if there's one.
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut V> {
+        self.entries.get_mut(node.index())?.as_mut()
+    }
+
+    /// Sets the entry of `node`, returning the previous one. The table grows up to `node` if needed.
+    pub fn insert(&mut self, node: NodeId, value: V) -> Option<V> {
+        if node.index() >= self.entries.len() {
+            self.entries.resize_with(node.index() + 1, || None);
+        }
+        self.entries[node.index()].replace(value)
+    }
+
+    /// Removes and returns the entry of `node`, if there's one.
+    pub fn remove(&mut self, node: NodeId) -> Option<V> {
+        self.entries.get_mut(node.index())?.take()
+    }
+
+    /// True if `node` has an entry.
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.get(node).is_some()
+    }
+
+    /// Number of entries, not of slots.
+    pub fn len(&self) -> usize {
+        self.entries.iter().flatten().count()
+    }
+
+    /// True if there are no entries, even if slots are allocated.
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    /// Entries with their node ids, in the order of ids.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &V)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(node, value)| Some((NodeId::new(node), value.as_ref()?)))
+    }
+
+    /// Moves every entry to the new id of its node, given by the remapping returned from the tree operation
+    /// that renumbered nodes. Entries of nodes mapped to `None` and of nodes past the remapping are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{NodeId, NodeMap, Tree};
+    ///         let mut tree = Tree::new(vec![vec![2, -1, 1]], vec!["root", "grandchild", "child"]).expect("Tree has a valid structure");
+    ///         let mut labels = NodeMap::new();
+    ///         labels.insert(NodeId::new(1), "leaf");
+    ///
+    ///         labels.remap(&tree.relayout_bfs().expect("All links are in bounds"));
+    ///         assert_eq!(labels.get(NodeId::new(2)), Some(&"leaf"));
+    /// ```
+    pub fn remap<R>(&mut self, remapping: &[R])
+    where
+        R: Copy + Into<Option<NodeId>>,
+    {
+        let mut entries = std::mem::take(&mut self.entries);
+        entries.truncate(remapping.len());
+        for (node, value) in entries.into_iter().enumerate() {
+            let (Some(value), Some(new)) = (value, remapping[node].into()) else {
+                continue;
+            };
+            self.insert(new, value);
+        }
+    }
+}
+
+impl<V> Default for NodeMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maps are equal if they have the same entries, regardless of empty slots.
+impl<V: PartialEq> PartialEq for NodeMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<V: Eq> Eq for NodeMap<V> {}
+
+/// Panics if `node` has no entry, see [NodeMap::get] for a checked variant.
+impl<V> Index<NodeId> for NodeMap<V> {
+    type Output = V;
+
+    fn index(&self, node: NodeId) -> &Self::Output {
+        self.get(node)
+            .unwrap_or_else(|| panic!("Node {} has no entry", node.index()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, NodeMap, Tree};
+
+    #[test]
+    fn follows_renumbering() {
+        let left = vec![1, 3, -1, -1, -1, -1];
+        let right = vec![2, 4, 5, -1, -1, -1];
+        let mut tree = Tree::new(vec![left, right], vec![0, 1, 2, 3, 4, 5])
+            .expect("Tree has a valid structure");
+        let mut map = NodeMap::new();
+        for node in [1, 2, 4, 5] {
+            assert_eq!(map.insert(NodeId::new(node), tree[node] * 10), None);
+        }
+        assert_eq!(map.insert(NodeId::new(5), 55), Some(50));
+        assert_eq!(map.remove(NodeId::new(9)), None);
+
+        // Pre-order 0, 1, 3, 4, 2, 5
+        map.remap(&tree.relayout_dfs().expect("All links are in bounds"));
+        let entries = [(1, 10), (3, 40), (4, 20), (5, 55)];
+        assert!(map.iter().eq(entries
+            .iter()
+            .map(|(node, entry)| (NodeId::new(*node), entry))));
+        assert_eq!(tree[3], 4);
+
+        map.remap(&tree.remove_subtree_remapped(1).expect("Node exists"));
+        assert_eq!(tree.values(), &[0, 2, 5]);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(NodeId::new(1), &20), (NodeId::new(2), &55)]
+        );
+        assert!(!map.contains(NodeId::new(0)));
+
+        map.remap::<NodeId>(&[]);
+        assert!(map.is_empty());
+        assert_eq!(map, NodeMap::default());
+    }
+}
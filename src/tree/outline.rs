@@ -0,0 +1,145 @@
+use crate::consts::LEAF_NODE;
+use crate::tree::{Tree, TreeError, TreeIndex};
+
+impl<T, Idx: TreeIndex> Tree<T, Idx> {
+    /// Values of nodes reachable from the root in depth-first pre-order, each with its depth, the root being at
+    /// depth zero. This outline is how editors and tree widgets list a tree, indenting values by their depth.
+    /// [Tree::from_flattened] builds the tree back.
+    ///
+    /// Child slots aren't part of the outline, so missing children between existing ones don't survive the round
+    /// trip: children of a rebuilt node take its first dimensions and nodes are numbered in pre-order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 3, -1, -1], vec![2, -1, -1, -1]], vec!["src", "tree", "lib.rs", "mod.rs"])
+    ///             .expect("Tree has a valid structure");
+    ///
+    ///         let outline = tree.flatten();
+    ///         assert_eq!(outline, vec![(0, &"src"), (1, &"tree"), (2, &"mod.rs"), (1, &"lib.rs")]);
+    ///
+    ///         let rebuilt = Tree::from_flattened(outline.iter().map(|(depth, value)| (*depth, **value))).expect("Outline is valid");
+    ///         assert_eq!(rebuilt.flatten(), outline);
+    /// ```
+    pub fn flatten(&self) -> Vec<(usize, &T)> {
+        let mut outline = Vec::with_capacity(self.values.len());
+        let mut visited = vec![false; self.values.len()];
+        let mut stack: Vec<(usize, usize)> = (0..self.values.len().min(1))
+            .map(|root| (root, 0))
+            .collect();
+        while let Some((node, depth)) = stack.pop() {
+            if std::mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            outline.push((depth, &self.values[node]));
+            let children: Vec<usize> = self.child_ids(node).collect();
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        outline
+    }
+}
+
+impl<T> Tree<T> {
+    /// Builds a tree from an outline of `(depth, value)` pairs in pre-order, see [Tree::flatten]. Children of a
+    /// node are the pairs one level deeper following it, up to the next pair at its depth or above. Node ids
+    /// follow the order of the pairs and children take consecutive dimensions. An empty outline gives an empty tree.
+    ///
+    /// The first pair has to be the root at depth zero and each following pair at most one level deeper than the
+    /// previous one. [TreeError::MultipleRoots] is returned for another pair at depth zero,
+    /// [TreeError::CorruptedTree] for a skipped level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{Tree, TreeError};
+    ///         let tree = Tree::from_flattened([(0, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]).expect("Outline is valid");
+    ///         assert_eq!(tree.children(0), vec![1, 3]);
+    ///         assert_eq!(tree.children(1), vec![2, -1]);
+    ///
+    ///         assert_eq!(Tree::from_flattened([(0, 'a'), (0, 'b')]), Err(TreeError::MultipleRoots { node: 1 }));
+    ///         assert!(Tree::from_flattened([(0, 'a'), (2, 'b')]).is_err());
+    /// ```
+    pub fn from_flattened(pairs: impl IntoIterator<Item = (usize, T)>) -> Result<Self, TreeError> {
+        let mut nodes: Vec<Vec<isize>> = Vec::new();
+        let mut values = Vec::new();
+        // Nodes on the path from the root to the last node, with the number of children each has so far
+        let mut path: Vec<(usize, usize)> = Vec::new();
+        for (depth, value) in pairs {
+            let node = values.len();
+            if depth == 0 && node > 0 {
+                return Err(TreeError::MultipleRoots { node });
+            }
+            if depth > path.len() || (depth == 0) != (node == 0) {
+                return Err(TreeError::CorruptedTree(format!(
+                    "node {node} at depth {depth} skips a level below depth {}",
+                    path.len().saturating_sub(1)
+                )));
+            }
+            path.truncate(depth);
+
+            for dimension in nodes.iter_mut() {
+                dimension.push(LEAF_NODE);
+            }
+            if let Some((parent, children)) = path.last_mut() {
+                if *children == nodes.len() {
+                    nodes.push(vec![LEAF_NODE; node + 1]);
+                }
+                nodes[*children][*parent] = node as isize;
+                *children += 1;
+            }
+            values.push(value);
+            path.push((node, 0));
+        }
+        Tree::new(nodes, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Tree, TreeError};
+
+    #[test]
+    fn outline_roundtrip() {
+        let left = vec![1, 3, -1, 5, -1, -1, -1];
+        let right = vec![2, 4, -1, -1, -1, -1, -1];
+        let middle = vec![-1, -1, -1, 6, -1, -1, -1];
+        let tree = Tree::new(vec![left, middle, right], (0..7).collect::<Vec<u8>>())
+            .expect("Tree has a valid structure");
+
+        let outline = tree.flatten();
+        let depths: Vec<usize> = outline.iter().map(|(depth, _)| *depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 3, 3, 2, 1]);
+
+        let rebuilt =
+            Tree::from_flattened(outline.into_iter().map(|(depth, value)| (depth, *value)))
+                .expect("Outline is valid");
+        assert_eq!(rebuilt.values(), &[0, 1, 3, 5, 6, 4, 2]);
+        // The gap before the right child of the root is closed
+        assert_eq!(rebuilt.children(0), vec![1, 6]);
+        assert_eq!(rebuilt.flatten(), tree.flatten());
+    }
+
+    #[test]
+    fn malformed_outlines() {
+        assert_eq!(Tree::<u8>::from_flattened([]), Ok(Tree::default()));
+        assert!(matches!(
+            Tree::from_flattened([(1, 'a')]),
+            Err(TreeError::CorruptedTree(_))
+        ));
+        assert!(matches!(
+            Tree::from_flattened([(0, 'a'), (1, 'b'), (3, 'c')]),
+            Err(TreeError::CorruptedTree(_))
+        ));
+        assert_eq!(
+            Tree::from_flattened([(0, 'a'), (1, 'b'), (0, 'c')]),
+            Err(TreeError::MultipleRoots { node: 2 })
+        );
+
+        // Cycles aren't followed
+        let mut cyclic =
+            Tree::new(vec![vec![1, -1]], vec!['a', 'b']).expect("Tree has a valid structure");
+        cyclic.set_child(1, 0, 1);
+        assert_eq!(cyclic.flatten(), vec![(0, &'a'), (1, &'b')]);
+    }
+}
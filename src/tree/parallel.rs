@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use crate::consts::ROOT_NODE;
+use crate::parallel;
+use crate::tree::Tree;
+
+impl<T: Sync> Tree<T> {
+    /// Parallel iterator over node values, in node id order. Use `enumerate()` to get node ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use rayon::prelude::*;
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]], vec![1, 2, 3, 4, 5]).unwrap();
+    ///
+    ///         assert_eq!(tree.par_iter().sum::<i32>(), 15);
+    /// ```
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, T> {
+        self.values.par_iter()
+    }
+
+    /// Maps every node value in parallel, keeping the tree structure.
+    pub fn par_map<U, F>(&self, f: F) -> Tree<U>
+    where
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        Tree {
+            nodes: Arc::clone(&self.nodes),
+            values: Arc::new(self.values.par_iter().map(f).collect()),
+        }
+    }
+
+    /// Folds the tree bottom-up, `f` being given a node's value and results of its children in order. Sibling
+    /// subtrees are folded in parallel. Returns `None` for an empty tree. Nodes unreachable from the root are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]], vec![1, 2, 3, 4, 5]).unwrap();
+    ///
+    ///         // Subtree sums
+    ///         let sum = tree.par_fold_subtrees(|value, children: Vec<i32>| value + children.iter().sum::<i32>());
+    ///         assert_eq!(sum, Some(15));
+    ///
+    ///         // Height
+    ///         let height = tree.par_fold_subtrees(|_, children: Vec<usize>| 1 + children.into_iter().max().unwrap_or(0));
+    ///         assert_eq!(height, Some(3));
+    /// ```
+    pub fn par_fold_subtrees<A, F>(&self, f: F) -> Option<A>
+    where
+        A: Send,
+        F: Fn(&T, Vec<A>) -> A + Sync,
+    {
+        parallel::fold_subtrees(&self.values, self.child_lists(), f)
+    }
+
+    /// Folds the tree bottom-up in parallel: a node's result is `leaf` of its value, combined with results of its
+    /// children in order. As `combine` has to be associative, results of siblings are combined in parallel too.
+    /// Returns `None` for an empty tree. Nodes unreachable from the root are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::Tree;
+    ///         let tree = Tree::new(vec![vec![1, 3, -1, -1, -1], vec![2, 4, -1, -1, -1]], vec![1, 2, 3, 4, 5]).unwrap();
+    ///
+    ///         assert_eq!(tree.par_fold(|_| 1, |a, b| a + b), Some(5)); // Size
+    ///         assert_eq!(tree.par_fold(|value| *value, i32::max), Some(5));
+    ///         // Pre-order concatenation, combine is associative but not commutative
+    ///         let pre_order = tree.par_fold(|value| value.to_string(), |a, b| a + &b);
+    ///         assert_eq!(pre_order.as_deref(), Some("12453"));
+    /// ```
+    pub fn par_fold<A, L, C>(&self, leaf: L, combine: C) -> Option<A>
+    where
+        A: Send,
+        L: Fn(&T) -> A + Sync,
+        C: Fn(A, A) -> A + Sync,
+    {
+        parallel::fold_reduce(&self.values, self.child_lists(), leaf, combine)
+    }
+}
+
+impl<T> Tree<T> {
+    /// Child ids of a node, without borrowing values, which might not be `Send`.
+    fn child_lists(&self) -> impl Fn(usize) -> Vec<usize> + Sync + '_ {
+        let (nodes, len) = (&self.nodes, self.values.len());
+        move |node| {
+            nodes
+                .iter()
+                .map(|dimension| dimension[node])
+                .filter(|&child| child > ROOT_NODE && (child as usize) < len)
+                .map(|child| child as usize)
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use crate::tree::Tree;
+
+    fn complete(levels: u32, arity: usize) -> Tree<u64> {
+        let len = (0..levels).map(|level| arity.pow(level)).sum::<usize>();
+        let nodes = (0..arity)
+            .map(|dimension| {
+                (0..len)
+                    .map(|node| {
+                        let child = node * arity + dimension + 1;
+                        if child < len {
+                            child as isize
+                        } else {
+                            -1
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Tree::new(nodes, (0..len as u64).collect()).expect("Valid structure")
+    }
+
+    #[test]
+    fn par_iter_and_map() {
+        let tree = complete(8, 3);
+        let expected: u64 = (0..tree.values.len() as u64).sum();
+        assert_eq!(tree.par_iter().sum::<u64>(), expected);
+
+        let doubled = tree.par_map(|value| value * 2);
+        assert_eq!(doubled.children(0), tree.children(0));
+        assert_eq!(doubled.par_iter().sum::<u64>(), expected * 2);
+    }
+
+    #[test]
+    fn par_fold_subtrees() {
+        let tree = complete(10, 2);
+        let expected: u64 = (0..tree.values.len() as u64).sum();
+        let sum = tree.par_fold_subtrees(|value, children: Vec<u64>| {
+            value + children.into_iter().sum::<u64>()
+        });
+        assert_eq!(sum, Some(expected));
+
+        // Children results come in order
+        let order = tree.par_fold_subtrees(|value, children: Vec<Vec<u64>>| {
+            let mut values = vec![*value];
+            values.extend(children.into_iter().flatten());
+            values
+        });
+        let mut pre_order = Vec::new();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            pre_order.push(node as u64);
+            stack.extend(tree.child_ids(node).collect::<Vec<_>>().into_iter().rev());
+        }
+        assert_eq!(order, Some(pre_order));
+
+        // Deep trees don't overflow the stack
+        let len = 100_000;
+        let chain: Vec<isize> = (1..len as isize).chain([-1]).collect();
+        let chain = Tree::new(vec![chain], vec![1_u64; len]).expect("Valid structure");
+        let depth = chain.par_fold_subtrees(|_, children: Vec<usize>| {
+            1 + children.into_iter().max().unwrap_or(0)
+        });
+        assert_eq!(depth, Some(len));
+
+        let empty: Tree<u64> = Tree::new(vec![vec![]], vec![]).expect("Valid structure");
+        assert_eq!(empty.par_fold_subtrees(|_, _: Vec<()>| ()), None);
+    }
+
+    #[test]
+    fn par_fold() {
+        let tree = complete(10, 3);
+        let len = tree.values.len() as u64;
+        assert_eq!(
+            tree.par_fold(|value| *value, |a, b| a + b),
+            Some(len * (len - 1) / 2)
+        );
+        assert_eq!(tree.par_fold(|value| *value, u64::max), Some(len - 1));
+
+        let pre_order = tree.par_fold(
+            |value| vec![*value],
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+        );
+        let expected = tree.par_fold_subtrees(|value, children: Vec<Vec<u64>>| {
+            let mut values = vec![*value];
+            values.extend(children.into_iter().flatten());
+            values
+        });
+        assert_eq!(pre_order, expected);
+
+        let len = 100_000;
+        let chain: Vec<isize> = (1..len as isize).chain([-1]).collect();
+        let chain = Tree::new(vec![chain], vec![1_u64; len]).expect("Valid structure");
+        assert_eq!(
+            chain.par_fold(|value| *value, |a, b| a + b),
+            Some(len as u64)
+        );
+    }
+}
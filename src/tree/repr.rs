@@ -0,0 +1,316 @@
+use serde::de::Error as _;
+use serde::ser::{Error as _, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::{Tree, TreeError};
+
+/// Shape of a serialized [Tree], picked at serialization time by [Tree::with_repr] and [Tree::deserialize_repr], or
+/// fixed by the [Nested] and [Flat] wrappers.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum SerdeRepr {
+    /// Links and values as arrays, e.g. `{"nodes": [[1, -1]], "values": [1, 2]}`. Same as serializing the tree
+    /// itself.
+    #[default]
+    Arrays,
+    /// Nested objects from the root, e.g. `{"value": 1, "children": [{"value": 2, "children": []}]}`.
+    Nested,
+    /// Parent array, e.g. `{"parents": [null, 0], "values": [1, 2]}`.
+    Flat,
+}
+
+/// Tree serialized as nested objects from the root, a `value` and `children` per node. Missing children before the
+/// last present one are `null`, keeping the dimensions of present ones. Deserialized trees get node ids in
+/// pre-order and as many dimensions as the node with most children needs.
+///
+/// Nesting follows the depth of the tree, deep trees may hit recursion limits of serializers.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{Nested, Tree};
+///         let tree = Tree::new(vec![vec![-1, -1, -1], vec![2, -1, -1], vec![1, -1, -1]], vec![1, 3, 2]).expect("Tree has a valid structure");
+///
+///         let json = serde_json::to_string(&Nested(&tree)).unwrap();
+///         assert_eq!(json, r#"{"value":1,"children":[null,{"value":2,"children":[]},{"value":3,"children":[]}]}"#);
+///         let Nested(parsed): Nested<Tree<i32>> = serde_json::from_str(&json).unwrap();
+///         assert_eq!(parsed.children(0), vec![-1, 1, 2]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Nested<T>(pub T);
+
+/// Tree serialized as a parent array, `parents` holding the parent id of every node (`null` for the root) next to
+/// `values`. Dimensions aren't kept: deserialized nodes take their children in the order of ids.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{Flat, Tree};
+///         let tree = Tree::new(vec![vec![1, 2, -1], vec![-1, -1, -1]], vec!['a', 'b', 'c']).expect("Tree has a valid structure");
+///
+///         let json = serde_json::to_string(&Flat(&tree)).unwrap();
+///         assert_eq!(json, r#"{"parents":[null,0,1],"values":["a","b","c"]}"#);
+///         let Flat(parsed): Flat<Tree<char>> = serde_json::from_str(&json).unwrap();
+///         assert_eq!(parsed.children(1), vec![2]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Flat<T>(pub T);
+
+/// Borrowed tree serialized in a [SerdeRepr] chosen at runtime, see [Tree::with_repr].
+#[derive(Debug, Clone, Copy)]
+pub struct Repr<'a, T> {
+    tree: &'a Tree<T>,
+    repr: SerdeRepr,
+}
+
+impl<T> Tree<T> {
+    /// View of the tree serialized in given `repr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{SerdeRepr, Tree};
+    ///         let tree = Tree::new(vec![vec![1, -1]], vec![1, 2]).expect("Tree has a valid structure");
+    ///
+    ///         let flat = serde_json::to_string(&tree.with_repr(SerdeRepr::Flat)).unwrap();
+    ///         assert_eq!(flat, r#"{"parents":[null,0],"values":[1,2]}"#);
+    ///         let mut json = serde_json::Deserializer::from_str(&flat);
+    ///         assert_eq!(Tree::deserialize_repr(SerdeRepr::Flat, &mut json).unwrap(), tree);
+    /// ```
+    pub fn with_repr(&self, repr: SerdeRepr) -> Repr<'_, T> {
+        Repr { tree: self, repr }
+    }
+
+    /// Deserializes a tree serialized in given `repr`.
+    pub fn deserialize_repr<'de, D>(repr: SerdeRepr, deserializer: D) -> Result<Self, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        match repr {
+            SerdeRepr::Arrays => Tree::deserialize(deserializer),
+            SerdeRepr::Nested => Nested::deserialize(deserializer).map(|Nested(tree)| tree),
+            SerdeRepr::Flat => Flat::deserialize(deserializer).map(|Flat(tree)| tree),
+        }
+    }
+
+    /// Parent of every node, checked by [Tree::validate] first.
+    fn parents(&self) -> Result<Vec<Option<usize>>, TreeError> {
+        self.validate()?;
+        let mut parents = vec![None; self.values.len()];
+        for node in 0..self.values.len() {
+            for child in self.child_ids(node) {
+                parents[child] = Some(node);
+            }
+        }
+        Ok(parents)
+    }
+}
+
+impl<T: Serialize> Serialize for Repr<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.repr {
+            SerdeRepr::Arrays => self.tree.serialize(serializer),
+            SerdeRepr::Nested => Nested(self.tree).serialize(serializer),
+            SerdeRepr::Flat => Flat(self.tree).serialize(serializer),
+        }
+    }
+}
+
+/// Node of a tree serialized with its subtree.
+struct NestedNode<'a, T> {
+    tree: &'a Tree<T>,
+    node: usize,
+}
+
+impl<T: Serialize> Serialize for NestedNode<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut children: Vec<Option<NestedNode<T>>> = self
+            .tree
+            .nodes
+            .iter()
+            .map(|dimension| {
+                let child = dimension[self.node];
+                (child != LEAF_NODE).then_some(NestedNode {
+                    tree: self.tree,
+                    node: child as usize,
+                })
+            })
+            .collect();
+        while children.last().is_some_and(Option::is_none) {
+            children.pop();
+        }
+        let mut state = serializer.serialize_struct("Node", 2)?;
+        state.serialize_field("value", &self.tree.values[self.node])?;
+        state.serialize_field("children", &children)?;
+        state.end()
+    }
+}
+
+impl<T: Serialize> Serialize for Nested<&Tree<T>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.validate().map_err(S::Error::custom)?;
+        if self.0.values.is_empty() {
+            return serializer.serialize_none();
+        }
+        NestedNode {
+            tree: self.0,
+            node: ROOT_NODE as usize,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<T: Serialize> Serialize for Nested<Tree<T>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Nested(&self.0).serialize(serializer)
+    }
+}
+
+/// Deserialized form of [Nested], a node with its subtree.
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct NestedData<T> {
+    value: T,
+    #[serde(default)]
+    children: Vec<Option<NestedData<T>>>,
+}
+
+impl<T> From<Option<NestedData<T>>> for Tree<T> {
+    fn from(root: Option<NestedData<T>>) -> Self {
+        // Children of a node are numbered when it's visited, in pre-order
+        let mut nodes: Vec<Vec<isize>> = vec![Vec::new()];
+        let mut values = Vec::new();
+        let mut stack = Vec::from_iter(root.map(|root| (root, None::<(usize, usize)>)));
+        while let Some((data, parent)) = stack.pop() {
+            let node = values.len();
+            values.push(data.value);
+            while nodes.len() < data.children.len() {
+                nodes.push(vec![LEAF_NODE; node]);
+            }
+            for dimension in nodes.iter_mut() {
+                dimension.push(LEAF_NODE);
+            }
+            if let Some((parent, dimension)) = parent {
+                nodes[dimension][parent] = node as isize;
+            }
+            for (dimension, child) in data.children.into_iter().enumerate().rev() {
+                if let Some(child) = child {
+                    stack.push((child, Some((node, dimension))));
+                }
+            }
+        }
+        Tree::new(nodes, values)
+            .unwrap_or_else(|_| unreachable!("Every dimension has a link per node"))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Nested<Tree<T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<NestedData<T>>::deserialize(deserializer).map(|root| Nested(Tree::from(root)))
+    }
+}
+
+impl<T: Serialize> Serialize for Flat<&Tree<T>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let parents = self.0.parents().map_err(S::Error::custom)?;
+        let mut state = serializer.serialize_struct("Tree", 2)?;
+        state.serialize_field("parents", &parents)?;
+        state.serialize_field("values", self.0.values.as_slice())?;
+        state.end()
+    }
+}
+
+impl<T: Serialize> Serialize for Flat<Tree<T>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Flat(&self.0).serialize(serializer)
+    }
+}
+
+/// Deserialized form of [Flat].
+#[derive(Deserialize)]
+struct FlatData<T> {
+    parents: Vec<Option<usize>>,
+    values: Vec<T>,
+}
+
+impl<T> TryFrom<FlatData<T>> for Tree<T> {
+    type Error = TreeError;
+
+    fn try_from(data: FlatData<T>) -> Result<Self, Self::Error> {
+        let len = data.values.len();
+        if data.parents.len() != len {
+            return Err(TreeError::LengthMismatch {
+                expected: len,
+                found: data.parents.len(),
+            });
+        }
+        let mut nodes: Vec<Vec<isize>> = vec![vec![LEAF_NODE; len]];
+        let mut degrees = vec![0; len];
+        for (node, parent) in data.parents.into_iter().enumerate() {
+            let Some(parent) = parent else {
+                continue;
+            };
+            if parent >= len {
+                return Err(TreeError::NodeOutOfBounds { node: parent });
+            }
+            if degrees[parent] == nodes.len() {
+                nodes.push(vec![LEAF_NODE; len]);
+            }
+            nodes[degrees[parent]][parent] = node as isize;
+            degrees[parent] += 1;
+        }
+        Tree::new_validated(nodes, data.values)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Flat<Tree<T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = FlatData::deserialize(deserializer)?;
+        Tree::try_from(data).map(Flat).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Flat, Nested, SerdeRepr, Tree};
+
+    #[test]
+    fn serde_repr() {
+        let tree = Tree::new(
+            vec![
+                vec![1, 3, -1, -1, -1],
+                vec![2, -1, -1, -1, -1],
+                vec![-1, 4, -1, -1, -1],
+            ],
+            vec![0, 1, 2, 3, 4],
+        )
+        .expect("Tree has a valid structure");
+
+        let arrays = serde_json::to_string(&tree.with_repr(SerdeRepr::Arrays)).unwrap();
+        assert_eq!(arrays, serde_json::to_string(&tree).unwrap());
+
+        let nested = serde_json::to_string(&Nested(&tree)).unwrap();
+        // Node ids are assigned in pre-order, the shape and dimensions are kept
+        let Nested(parsed): Nested<Tree<u8>> = serde_json::from_str(&nested).unwrap();
+        assert_eq!(parsed.values(), &vec![0, 1, 3, 4, 2]);
+        assert_eq!(serde_json::to_string(&Nested(&parsed)).unwrap(), nested);
+
+        let flat = serde_json::to_string(&Flat(tree.clone())).unwrap();
+        assert_eq!(flat, r#"{"parents":[null,0,0,1,1],"values":[0,1,2,3,4]}"#);
+        let Flat(parsed): Flat<Tree<u8>> = serde_json::from_str(&flat).unwrap();
+        assert_eq!(parsed.children(1), vec![3, 4]);
+
+        let empty: Tree<u8> = Tree::new(vec![vec![]], vec![]).expect("Tree has a valid structure");
+        assert_eq!(serde_json::to_string(&Nested(&empty)).unwrap(), "null");
+        let Nested(parsed): Nested<Tree<u8>> = serde_json::from_str("null").unwrap();
+        assert_eq!(parsed, empty);
+
+        let cyclic = Tree::new(vec![vec![1, 1]], vec![0, 1]).expect("Links are in bounds");
+        assert!(serde_json::to_string(&Nested(&cyclic)).is_err());
+        let two_roots = r#"{"parents":[null,null],"values":[0,1]}"#;
+        assert!(serde_json::from_str::<Flat<Tree<u8>>>(two_roots).is_err());
+        let uneven = r#"{"parents":[null],"values":[0,1]}"#;
+        assert!(serde_json::from_str::<Flat<Tree<u8>>>(uneven).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::tree::Tree;
+
+/// Immutable view of a [Tree] as it was when [Tree::snapshot] was called. Shares nodes and values with the tree,
+/// so taking a snapshot is O(1). Later mutations of the tree copy the shared buffers and aren't visible in
+/// the snapshot. Dereferences to [Tree] for reading.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::Tree;
+///         let mut tree = Tree::new(vec![vec![1, -1, -1]], vec!["a", "b", "c"]).expect("Tree has a valid structure");
+///
+///         let snapshot = tree.snapshot();
+///         std::thread::scope(|scope| {
+///             let reader = scope.spawn(|| snapshot.children(0));
+///             tree.set_child(0, 0, 2);
+///             *tree.value_mut(2) = "d";
+///             assert_eq!(reader.join().unwrap(), vec![1]);
+///         });
+///
+///         assert_eq!(snapshot.children(0), vec![1]);
+///         assert_eq!(tree.children(0), vec![2]);
+/// ```
+#[derive(Debug)]
+pub struct Snapshot<T, Idx = isize> {
+    tree: Tree<T, Idx>,
+}
+
+impl<T, Idx> Tree<T, Idx> {
+    /// Takes an O(1) immutable [Snapshot] of the tree.
+    pub fn snapshot(&self) -> Snapshot<T, Idx> {
+        Snapshot {
+            tree: Tree {
+                nodes: Arc::clone(&self.nodes),
+                values: Arc::clone(&self.values),
+            },
+        }
+    }
+}
+
+impl<T, Idx> Snapshot<T, Idx> {
+    /// True if the snapshot still shares both nodes and values with `tree`, i.e. neither was mutated since.
+    pub fn is_current(&self, tree: &Tree<T, Idx>) -> bool {
+        Arc::ptr_eq(&self.tree.nodes, &tree.nodes) && Arc::ptr_eq(&self.tree.values, &tree.values)
+    }
+}
+
+impl<T, Idx> Clone for Snapshot<T, Idx> {
+    fn clone(&self) -> Self {
+        self.tree.snapshot()
+    }
+}
+
+impl<T, Idx> Deref for Snapshot<T, Idx> {
+    type Target = Tree<T, Idx>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    #[test]
+    fn snapshot() {
+        let mut tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec![1, 2, 3])
+            .expect("Tree has a valid structure");
+        let snapshot = tree.snapshot();
+        assert!(snapshot.is_current(&tree));
+
+        // Values are copied on the first mutation only, nodes stay shared
+        *tree.value_mut(0) = 10;
+        assert!(!snapshot.is_current(&tree));
+        assert!(std::sync::Arc::ptr_eq(&snapshot.nodes, &tree.nodes));
+        let values = tree.values.as_ptr();
+        *tree.value_mut(1) = 20;
+        assert_eq!(tree.values.as_ptr(), values);
+
+        tree.set_child(0, 1, -1);
+        assert_eq!(tree.children(0), vec![1, -1]);
+        assert_eq!(snapshot.children(0), vec![1, 2]);
+        assert_eq!(snapshot.values(), &[1, 2, 3]);
+        assert_eq!(tree.values(), &[10, 20, 3]);
+
+        let older = snapshot.clone();
+        drop(snapshot);
+        assert_eq!(older.path_fold(2, 0, |acc, value| acc + value), Some(4));
+    }
+}
@@ -0,0 +1,265 @@
+use std::ops::Deref;
+
+use crate::tree::{NodeId, NodeMap, Tree, TreeError};
+use crate::TreeLike;
+
+/// Kinds of change of a node tracked by a [TrackedTree].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Change {
+    /// Links of the node changed, the node was added or it was lifted by a rotation.
+    pub structure: bool,
+    /// The value was replaced or mutably borrowed.
+    pub value: bool,
+}
+
+/// Tree recording which nodes changed since the last [TrackedTree::clear], so incremental rendering or
+/// serialization can process the changed nodes only instead of diffing whole trees. Opt-in, mutations of a plain
+/// [Tree] aren't tracked. Dereferences to [Tree] for reading.
+///
+/// Changes are kept by node id and follow renumbering of nodes. Removing a subtree changes its parent, changes of
+/// removed nodes are dropped.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{Change, NodeId, Tree, TrackedTree};
+///         let tree = Tree::new(vec![vec![1, -1, -1]], vec!["a", "b", "c"]).expect("Tree has a valid structure");
+///         let mut tracked = TrackedTree::from(tree);
+///
+///         *tracked.value_mut(2) = "d";
+///         tracked.set_child(1, 0, 2);
+///         assert_eq!(tracked.change(NodeId::new(1)), Change { structure: true, value: false });
+///         assert_eq!(tracked.changed().collect::<Vec<_>>(), vec![NodeId::new(1), NodeId::new(2)]);
+///
+///         tracked.clear();
+///         assert_eq!(tracked.changed().count(), 0);
+///         assert_eq!(tracked.children(1), vec![2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackedTree<T> {
+    tree: Tree<T>,
+    changes: NodeMap<Change>,
+}
+
+impl<T> TrackedTree<T> {
+    /// Change of `node` since the last clear, all `false` if it hasn't changed.
+    pub fn change(&self, node: NodeId) -> Change {
+        self.changes.get(node).copied().unwrap_or_default()
+    }
+
+    /// Ids of changed nodes in ascending order.
+    pub fn changed(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.changes.iter().map(|(node, _)| node)
+    }
+
+    /// Ids of nodes with changed links in ascending order.
+    pub fn changed_structure(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.changes
+            .iter()
+            .filter(|(_, change)| change.structure)
+            .map(|(node, _)| node)
+    }
+
+    /// Ids of nodes with replaced values in ascending order.
+    pub fn changed_values(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.changes
+            .iter()
+            .filter(|(_, change)| change.value)
+            .map(|(node, _)| node)
+    }
+
+    /// Forgets all changes, e.g. once they've been processed.
+    pub fn clear(&mut self) {
+        self.changes = NodeMap::new();
+    }
+
+    /// Unwraps the tree, dropping the changes.
+    pub fn into_inner(self) -> Tree<T> {
+        self.tree
+    }
+
+    /// See [Tree::set_child], changes `node_id`.
+    pub fn set_child(&mut self, node_id: usize, dimension: usize, child: isize) {
+        self.tree.set_child(node_id, dimension, child);
+        self.mark(node_id, true, false);
+    }
+
+    /// See [Tree::add_child], changes `parent` and the added node.
+    pub fn add_child(&mut self, parent: usize, value: T) -> Result<usize, TreeError>
+    where
+        T: Clone,
+    {
+        let child = self.tree.add_child(parent, value)?;
+        self.mark(parent, true, false);
+        self.mark(child, true, true);
+        Ok(child)
+    }
+
+    /// See [Tree::remove_subtree], changes the parent of `node`.
+    pub fn remove_subtree(&mut self, node: usize) -> Result<(), TreeError>
+    where
+        T: Clone,
+    {
+        // The parent linking to the node, not the one of the implicit layout
+        let parent = TreeLike::parent(&self.tree, node);
+        let remapping = self.tree.remove_subtree_remapped(node)?;
+        self.changes.remap(&remapping);
+        if let Some(parent) = parent.and_then(|parent| remapping[parent]) {
+            self.mark(parent.index(), true, false);
+        }
+        Ok(())
+    }
+
+    /// See [Tree::rotate_left], changes `node_id` and its former right child, their values swap.
+    pub fn rotate_left(&mut self, node_id: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        self.rotate(node_id, 1)
+    }
+
+    /// See [Tree::rotate_right], changes `node_id` and its former left child, their values swap.
+    pub fn rotate_right(&mut self, node_id: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        self.rotate(node_id, 0)
+    }
+
+    /// See [Tree::value_mut], changes the value of `node_id` whether or not it's written to.
+    pub fn value_mut(&mut self, node_id: usize) -> &mut T
+    where
+        T: Clone,
+    {
+        self.mark(node_id, false, true);
+        self.tree.value_mut(node_id)
+    }
+
+    /// See [Tree::relayout_bfs], changes keep following their nodes.
+    pub fn relayout_bfs(&mut self) -> Result<Vec<NodeId>, TreeError>
+    where
+        T: Clone,
+    {
+        let remapping = self.tree.relayout_bfs()?;
+        self.changes.remap(&remapping);
+        Ok(remapping)
+    }
+
+    /// See [Tree::relayout_dfs], changes keep following their nodes.
+    pub fn relayout_dfs(&mut self) -> Result<Vec<NodeId>, TreeError>
+    where
+        T: Clone,
+    {
+        let remapping = self.tree.relayout_dfs()?;
+        self.changes.remap(&remapping);
+        Ok(remapping)
+    }
+
+    fn rotate(&mut self, node_id: usize, raised: usize) -> Result<bool, TreeError>
+    where
+        T: Clone,
+    {
+        let child = self.tree.child_in(node_id, raised);
+        let rotated = self.tree.rotate(node_id, raised)?;
+        if let Some(child) = child.filter(|_| rotated) {
+            self.mark(node_id, true, true);
+            self.mark(child, true, true);
+        }
+        Ok(rotated)
+    }
+
+    fn mark(&mut self, node: usize, structure: bool, value: bool) {
+        let node = NodeId::new(node);
+        let change = self.change(node);
+        self.changes.insert(
+            node,
+            Change {
+                structure: change.structure || structure,
+                value: change.value || value,
+            },
+        );
+    }
+}
+
+impl<T> From<Tree<T>> for TrackedTree<T> {
+    fn from(tree: Tree<T>) -> Self {
+        Self {
+            tree,
+            changes: NodeMap::new(),
+        }
+    }
+}
+
+impl<T> Deref for TrackedTree<T> {
+    type Target = Tree<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, TrackedTree, Tree};
+
+    #[test]
+    fn tracks_changes() {
+        let left = vec![1, 3, -1, -1, -1];
+        let right = vec![2, 4, -1, -1, -1];
+        let tree =
+            Tree::new(vec![left, right], vec![5, 3, 8, 1, 4]).expect("Tree has a valid structure");
+        let mut tracked = TrackedTree::from(tree);
+        assert_eq!(tracked.rotate_left(2), Ok(false));
+        assert_eq!(tracked.changed().count(), 0);
+
+        assert_eq!(tracked.rotate_right(0), Ok(true));
+        assert_eq!(tracked.values(), &[3, 5, 8, 1, 4]);
+        let ids = |ids: &[usize]| ids.iter().copied().map(NodeId::new).collect::<Vec<_>>();
+        assert_eq!(tracked.changed_values().collect::<Vec<_>>(), ids(&[0, 1]));
+
+        let added = tracked.add_child(3, 0).expect("Node 3 exists");
+        assert_eq!(
+            tracked.changed_structure().collect::<Vec<_>>(),
+            ids(&[0, 1, 3, 5])
+        );
+        tracked.clear();
+
+        // Node 4 is removed with its subtree, nodes after it shift down
+        *tracked.value_mut(added) = 2;
+        tracked.remove_subtree(4).expect("Node 4 exists");
+        assert_eq!(tracked.changed_values().collect::<Vec<_>>(), ids(&[4]));
+        assert_eq!(tracked.changed_structure().collect::<Vec<_>>(), ids(&[1]));
+        assert_eq!(tracked[4], 2);
+
+        tracked.relayout_bfs().expect("All links are in bounds");
+        assert_eq!(
+            tracked
+                .changed()
+                .map(|node| tracked[node.index()])
+                .collect::<Vec<_>>(),
+            vec![5, 2]
+        );
+        assert_eq!(tracked.into_inner().values().len(), 5);
+    }
+
+    #[test]
+    fn removal_shifts_parent() {
+        let tree = Tree::new(
+            vec![vec![1, -1, -1, 2], vec![3, -1, -1, -1]],
+            vec!['r', 'x', 'c', 'p'],
+        )
+        .expect("Tree has a valid structure");
+        let mut tracked = TrackedTree::from(tree);
+        *tracked.value_mut(2) = 'd';
+        *tracked.value_mut(3) = 'q';
+        tracked.clear();
+        *tracked.value_mut(1) = 'y';
+
+        // The parent moves from id 3 to id 2, the change of node 1 stays where it was
+        tracked.remove_subtree(2).expect("Node 2 exists");
+        assert_eq!(tracked.values(), &['r', 'y', 'q']);
+        let ids = |ids: &[usize]| ids.iter().copied().map(NodeId::new).collect::<Vec<_>>();
+        assert_eq!(tracked.changed_structure().collect::<Vec<_>>(), ids(&[2]));
+        assert_eq!(tracked.changed_values().collect::<Vec<_>>(), ids(&[1]));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::tree::{NodeId, Tree, TreeError, TreeIndex};
+use crate::TreeLike;
+
+/// Read-only view of the subtree of one node, borrowing the tree. Nodes of the subtree are numbered in pre-order,
+/// the node the view was made from being `0`, so a branch can be passed around as a tree of its own without
+/// copying it out. [SubtreeView::tree_id] translates ids back to the tree.
+///
+/// Implements [TreeLike], links out of bounds or back to visited nodes are left out.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{NodeId, Tree};
+///         let left = vec![1, 3, -1, -1, -1];
+///         let right = vec![2, 4, -1, -1, -1];
+///         let tree = Tree::new(vec![left, right], vec!['a', 'b', 'c', 'd', 'e']).expect("Tree has a valid structure");
+///
+///         let branch = tree.subtree_view(NodeId::new(1)).expect("Node 1 exists");
+///         assert_eq!(branch.len(), 3);
+///         assert_eq!(branch.children(0), vec![1, 2]);
+///         assert_eq!(branch.parent(2), Some(0));
+///         assert_eq!(*branch.value(2), 'e');
+///         assert_eq!(branch.tree_id(2), Some(NodeId::new(4)));
+///         assert_eq!(branch.iter().collect::<String>(), "bde");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubtreeView<'a, T, Idx = isize> {
+    tree: &'a Tree<T, Idx>,
+    nodes: Vec<usize>,
+    /// Id in the view of every node of the tree, `None` for nodes outside the subtree.
+    view_ids: Vec<Option<usize>>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+}
+
+impl<T, Idx: TreeIndex> Tree<T, Idx> {
+    /// Borrows the subtree of `node` as a [SubtreeView]. Fails with [TreeError::NodeOutOfBounds] if `node` doesn't
+    /// exist.
+    pub fn subtree_view(&self, node: NodeId) -> Result<SubtreeView<'_, T, Idx>, TreeError> {
+        let root = self.check_node(node)?;
+        let mut view = SubtreeView {
+            tree: self,
+            nodes: Vec::new(),
+            view_ids: vec![None; self.values.len()],
+            parents: Vec::new(),
+            children: Vec::new(),
+        };
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(root, None)];
+        while let Some((node, parent)) = stack.pop() {
+            if view.view_ids[node].is_some() {
+                continue;
+            }
+            let id = view.nodes.len();
+            view.view_ids[node] = Some(id);
+            if let Some(parent) = parent {
+                view.children[parent].push(id);
+            }
+            let children = TreeLike::children(self, node);
+            stack.extend(children.into_iter().rev().map(|child| (child, Some(id))));
+            view.nodes.push(node);
+            view.parents.push(parent);
+            view.children.push(Vec::new());
+        }
+        Ok(view)
+    }
+}
+
+impl<'a, T, Idx> SubtreeView<'a, T, Idx> {
+    /// Number of nodes in the subtree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Never true, the view contains at least the node it was made from.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Existing children of `node`, in the order of their dimensions. Panics if `node` is out of bounds.
+    pub fn children(&self, node: usize) -> Vec<usize> {
+        self.children[node].clone()
+    }
+
+    /// Parent of `node`, `None` for the root of the view and nodes out of bounds.
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        self.parents.get(node).copied().flatten()
+    }
+
+    /// Value of `node`. Panics if `node` is out of bounds.
+    pub fn value(&self, node: usize) -> &'a T {
+        &self.tree.values[self.nodes[node]]
+    }
+
+    /// Id of `node` in the viewed tree, `None` if it's out of bounds.
+    pub fn tree_id(&self, node: usize) -> Option<NodeId> {
+        self.nodes.get(node).copied().map(NodeId::new)
+    }
+
+    /// Id of the node with `tree_id` in the view, `None` if the node isn't in the subtree.
+    pub fn view_id(&self, tree_id: NodeId) -> Option<usize> {
+        self.view_ids.get(tree_id.index()).copied().flatten()
+    }
+
+    /// Values of the subtree in pre-order, i.e. by their ids in the view.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.nodes.iter().map(|&node| &self.tree.values[node])
+    }
+
+    /// The viewed tree, for operations taking ids of the tree.
+    pub fn tree(&self) -> &'a Tree<T, Idx> {
+        self.tree
+    }
+}
+
+impl<T, Idx> TreeLike for SubtreeView<'_, T, Idx> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        SubtreeView::children(self, node)
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        SubtreeView::parent(self, node)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        SubtreeView::value(self, node)
+    }
+
+    fn len(&self) -> usize {
+        SubtreeView::len(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{NodeId, Tree, TreeError};
+    use crate::TreeLike;
+
+    #[test]
+    fn relative_ids() {
+        let tree = Tree::new(
+            vec![vec![1, 3, -1, 5, -1, -1], vec![2, 4, 0, -1, -1, -1]],
+            vec![0, 1, 2, 3, 4, 5],
+        )
+        .expect("Tree has a valid structure");
+        let view = tree.subtree_view(NodeId::new(1)).expect("Node 1 exists");
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5, 4]);
+        assert_eq!(view.children(1), vec![2]);
+        assert_eq!(view.parent(2), Some(1));
+        assert_eq!(view.parent(0), None);
+        assert_eq!(view.view_id(NodeId::new(4)), Some(3));
+        assert_eq!(view.view_id(NodeId::new(2)), None);
+        assert_eq!(view.view_id(NodeId::new(6)), None);
+        assert_eq!(view.tree_id(4), None);
+        assert_eq!(view.at_path(&[0, 0]), Some(2));
+
+        // Links back to the root are dropped like in traversals of the tree
+        let leaf = tree.subtree_view(NodeId::new(2)).expect("Node 2 exists");
+        assert_eq!(leaf.len(), 1);
+        assert!(TreeLike::is_leaf(&leaf, 0));
+        assert_eq!(
+            tree.subtree_view(NodeId::new(6)).map(|view| view.len()),
+            Err(TreeError::NodeOutOfBounds { node: 6 })
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::iter::Sum;
+use std::ops::Deref;
+
+use crate::tree::{Tree, TreeError};
+
+/// Tree with a weight on every parent to child edge, e.g. a cost or a distance. Weights are kept in dimensions
+/// mirroring the links of the tree, the weight in slot `dimension` of a node belonging to the edge to its child in
+/// the same dimension. Weights of slots without a child are kept, but never read. Dereferences to [Tree] for
+/// reading.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::{Tree, WeightedTree};
+///         let left = vec![1, 3, -1, -1, -1];
+///         let right = vec![2, 4, -1, -1, -1];
+///         let tree = Tree::new(vec![left, right], vec!["hub", "a", "b", "a0", "a1"]).expect("Tree has a valid structure");
+///         let weights = vec![vec![2.0, 0.5, 0.0, 0.0, 0.0], vec![1.0, 1.5, 0.0, 0.0, 0.0]];
+///         let network = WeightedTree::new(tree, weights).expect("Weights mirror links");
+///
+///         assert_eq!(network.edge_weight(1, 1), Some(&1.5));
+///         assert_eq!(network.edge_weight(2, 0), None);
+///         assert_eq!(network.path_cost(4, 2), Some(1.5 + 2.0 + 1.0));
+///         assert_eq!(network.path_cost(1, 3), Some(0.5));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WeightedTree<T, W> {
+    tree: Tree<T>,
+    weights: Vec<Vec<W>>,
+}
+
+impl<T, W> WeightedTree<T, W> {
+    /// Attaches `weights` to edges of `tree`, one dimension of weights per dimension of links. Fails with
+    /// [TreeError::LengthMismatch] if the number of dimensions or the length of any of them differs.
+    pub fn new(tree: Tree<T>, weights: Vec<Vec<W>>) -> Result<Self, TreeError> {
+        let dimensions = tree.dimensions();
+        if weights.len() != dimensions.len() {
+            return Err(TreeError::LengthMismatch {
+                expected: dimensions.len(),
+                found: weights.len(),
+            });
+        }
+        if let Some(dimension) = weights
+            .iter()
+            .find(|dimension| dimension.len() != tree.values.len())
+        {
+            return Err(TreeError::LengthMismatch {
+                expected: tree.values.len(),
+                found: dimension.len(),
+            });
+        }
+        Ok(Self { tree, weights })
+    }
+
+    /// Attaches `weight` to every edge of `tree`.
+    pub fn uniform(tree: Tree<T>, weight: W) -> Self
+    where
+        W: Clone,
+    {
+        let weights = vec![vec![weight; tree.values.len()]; tree.dimensions().len()];
+        Self { tree, weights }
+    }
+
+    /// Weight of the edge from `parent` to its child in `child_slot`, `None` if there's no child in the slot or the
+    /// slot doesn't exist.
+    pub fn edge_weight(&self, parent: usize, child_slot: usize) -> Option<&W> {
+        self.tree.child_in(parent, child_slot)?;
+        Some(&self.weights[child_slot][parent])
+    }
+
+    /// Mutable access to the weight of the edge from `parent` to its child in `child_slot`, `None` if there's no
+    /// child in the slot.
+    pub fn edge_weight_mut(&mut self, parent: usize, child_slot: usize) -> Option<&mut W> {
+        self.tree.child_in(parent, child_slot)?;
+        Some(&mut self.weights[child_slot][parent])
+    }
+
+    /// Sum of weights of edges on the path between nodes `a` and `b`, up from `a` to their lowest common ancestor
+    /// and down to `b`. Edges are undirected for the cost, so it's symmetric and zero from a node to itself.
+    /// Returns `None` if a node isn't reachable from the root.
+    pub fn path_cost(&self, a: usize, b: usize) -> Option<W>
+    where
+        W: Copy + Sum<W>,
+    {
+        let to_a = self.tree.path_to(a)?;
+        let to_b = self.tree.path_to(b)?;
+        let common = to_a.iter().zip(&to_b).take_while(|(a, b)| a == b).count();
+        let cost = |path: &[usize]| {
+            path.windows(2)
+                .map(|edge| self.weight_between(edge[0], edge[1]))
+                .collect::<Vec<W>>()
+        };
+        // Both paths share the ancestor at `common - 1`, edges below it differ
+        let up = cost(&to_a[common - 1..]);
+        let down = cost(&to_b[common - 1..]);
+        Some(up.into_iter().chain(down).sum())
+    }
+
+    /// The weighted tree, without weights.
+    pub fn tree(&self) -> &Tree<T> {
+        &self.tree
+    }
+
+    /// Weights by dimension and node, mirroring links of the tree.
+    pub fn weights(&self) -> &[Vec<W>] {
+        &self.weights
+    }
+
+    /// Splits the weighted tree into the tree and its weights.
+    pub fn into_parts(self) -> (Tree<T>, Vec<Vec<W>>) {
+        (self.tree, self.weights)
+    }
+
+    /// Weight of the edge from `parent` to `child`, which is a child of `parent` on a path from the root.
+    fn weight_between(&self, parent: usize, child: usize) -> W
+    where
+        W: Copy,
+    {
+        let slot = (0..self.weights.len())
+            .find(|&slot| self.tree.child_in(parent, slot) == Some(child))
+            .unwrap_or_else(|| unreachable!("Node {child} is a child of {parent}"));
+        self.weights[slot][parent]
+    }
+}
+
+impl<T, W> Deref for WeightedTree<T, W> {
+    type Target = Tree<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Tree, TreeError, WeightedTree};
+
+    #[test]
+    fn path_costs() {
+        let tree =
+            Tree::new(vec![vec![1, 2, 3, -1, -1]], vec![0; 5]).expect("Tree has a valid structure");
+        assert_eq!(
+            WeightedTree::new(tree.clone(), vec![vec![1_u32; 4]]),
+            Err(TreeError::LengthMismatch {
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(
+            WeightedTree::new(tree.clone(), Vec::<Vec<u32>>::new()),
+            Err(TreeError::LengthMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+
+        let mut chain = WeightedTree::uniform(tree, 1_u32);
+        *chain.edge_weight_mut(2, 0).expect("Node 2 has a child") = 10;
+        assert_eq!(chain.edge_weight_mut(4, 0), None);
+        assert_eq!(chain.path_cost(0, 3), Some(12));
+        assert_eq!(chain.path_cost(3, 1), Some(11));
+        assert_eq!(chain.path_cost(2, 2), Some(0));
+        assert_eq!(chain.path_cost(0, 4), None);
+        assert_eq!(chain.path_cost(7, 0), None);
+        assert_eq!(chain.weights()[0][2], 10);
+    }
+}
@@ -0,0 +1,255 @@
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::tree::{Tree, TreeError};
+
+/// Node of an XML document: an element with its attributes, or a run of text inside an element. Children of an
+/// element are its content in document order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Element {
+    Tag {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    Text(String),
+}
+
+impl Element {
+    fn tag(start: &BytesStart) -> Result<Self, TreeError> {
+        let attributes = start
+            .attributes()
+            .map(|attribute| {
+                let attribute = attribute.map_err(malformed)?;
+                let value = attribute
+                    .normalized_value(XmlVersion::Implicit1_0)
+                    .map_err(malformed)?;
+                Ok((attribute.key.as_ref().to_owned(), value.into_owned()))
+            })
+            .collect::<Result<_, TreeError>>()?;
+        Ok(Element::Tag {
+            name: start.name().as_ref().to_owned(),
+            attributes,
+        })
+    }
+}
+
+fn malformed(error: impl std::fmt::Display) -> TreeError {
+    TreeError::CorruptedTree(format!("Malformed XML: {error}"))
+}
+
+impl Tree<Element> {
+    /// Parses an XML document into a tree rooted at its document element, node ids in document order. Text is
+    /// trimmed, with entity and character references resolved; whitespace-only text, comments, processing
+    /// instructions, the XML declaration and the doctype are left out. CDATA sections become text.
+    ///
+    /// Malformed documents are reported as [TreeError::CorruptedTree], a second document element as
+    /// [TreeError::MultipleRoots] and a document without any element as [TreeError::EmptyTree].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::tree::{Element, NodeId, Tree};
+    ///         let tree = Tree::from_xml(r#"<p class="note">Fish &amp; chips<br/></p>"#).expect("Document is well-formed");
+    ///
+    ///         assert_eq!(tree.children(0), vec![1, 2]);
+    ///         assert_eq!(tree.get(NodeId::new(1)), Some(&Element::Text("Fish & chips".to_string())));
+    ///         assert_eq!(tree.to_xml(), Ok(r#"<p class="note">Fish &amp; chips<br/></p>"#.to_string()));
+    /// ```
+    pub fn from_xml(xml: &str) -> Result<Self, TreeError> {
+        let mut reader = Reader::from_str(xml);
+        let mut values: Vec<Element> = Vec::new();
+        let mut children: Vec<Vec<usize>> = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            let event = reader.read_event().map_err(malformed)?;
+            match &event {
+                Event::Text(content) => text.push_str(&content.xml10_content()),
+                Event::CData(content) => text.push_str(&content.xml10_content()),
+                Event::GeneralRef(reference) => {
+                    match reference.resolve_char_ref().map_err(malformed)? {
+                        Some(character) => text.push(character),
+                        None => text.push_str(resolve_predefined_entity(reference).ok_or_else(
+                            || malformed(format!("unknown entity &{};", &**reference)),
+                        )?),
+                    }
+                }
+                _ => {
+                    let trimmed = text.trim();
+                    if !trimmed.is_empty() {
+                        let Some(&parent) = open.last() else {
+                            return Err(malformed("text outside of the document element"));
+                        };
+                        children[parent].push(values.len());
+                        values.push(Element::Text(trimmed.to_owned()));
+                        children.push(Vec::new());
+                    }
+                    text.clear();
+                }
+            }
+
+            match event {
+                Event::Start(ref start) | Event::Empty(ref start) => {
+                    let node = values.len();
+                    match open.last() {
+                        Some(&parent) => children[parent].push(node),
+                        None if node > ROOT_NODE as usize => {
+                            return Err(TreeError::MultipleRoots { node })
+                        }
+                        None => {}
+                    }
+                    values.push(Element::tag(start)?);
+                    children.push(Vec::new());
+                    if matches!(event, Event::Start(_)) {
+                        open.push(node);
+                    }
+                }
+                Event::End(_) => {
+                    open.pop();
+                }
+                Event::Eof if !open.is_empty() => {
+                    return Err(malformed("unclosed element"));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if values.is_empty() {
+            return Err(TreeError::EmptyTree);
+        }
+        let dimensions = children.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let mut nodes = vec![vec![LEAF_NODE; values.len()]; dimensions];
+        for (parent, children) in children.into_iter().enumerate() {
+            for (dimension, child) in children.into_iter().enumerate() {
+                nodes[dimension][parent] = child as isize;
+            }
+        }
+        Tree::new(nodes, values)
+    }
+
+    /// Serializes the tree as an XML document without a declaration or indentation, text and attribute values
+    /// escaped. The tree is checked by [Tree::validate] first; a text root or a text node with children is reported
+    /// as [TreeError::CorruptedTree].
+    pub fn to_xml(&self) -> Result<String, TreeError> {
+        self.validate()?;
+        if self.values.is_empty() {
+            return Err(TreeError::EmptyTree);
+        }
+        if let Element::Text(_) = self.values[ROOT_NODE as usize] {
+            return Err(TreeError::CorruptedTree(
+                "Document root must be an element".to_string(),
+            ));
+        }
+
+        let mut xml = String::new();
+        // Nodes to write, `true` marking a node whose content has been written and only needs its end tag
+        let mut stack = vec![(ROOT_NODE as usize, false)];
+        while let Some((node, closing)) = stack.pop() {
+            let children: Vec<usize> = self.child_ids(node).collect();
+            match &self.values[node] {
+                Element::Tag { name, .. } if closing => {
+                    xml.push_str("</");
+                    xml.push_str(name);
+                    xml.push('>');
+                }
+                Element::Tag { name, attributes } => {
+                    xml.push('<');
+                    xml.push_str(name);
+                    for (key, value) in attributes {
+                        xml.push(' ');
+                        xml.push_str(key);
+                        xml.push_str("=\"");
+                        xml.push_str(&escape(value.as_str()));
+                        xml.push('"');
+                    }
+                    if children.is_empty() {
+                        xml.push_str("/>");
+                        continue;
+                    }
+                    xml.push('>');
+                    stack.push((node, true));
+                    stack.extend(children.into_iter().rev().map(|child| (child, false)));
+                }
+                Element::Text(_) if !children.is_empty() => {
+                    return Err(TreeError::CorruptedTree(format!(
+                        "Text node {node} has children"
+                    )));
+                }
+                Element::Text(text) => xml.push_str(&escape(text.as_str())),
+            }
+        }
+        Ok(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Element, Tree, TreeError};
+
+    #[test]
+    fn xml_roundtrip() {
+        let document = r#"<?xml version="1.0"?>
+            <!-- catalogue -->
+            <catalogue lang="en">
+                <book id="1" title="&quot;Trees&quot; &amp; more">
+                    First line &#x41;&#66; <![CDATA[<raw>]]>
+                    <chapter/>
+                    tail
+                </book>
+                <book id="2"/>
+            </catalogue>"#;
+        let tree = Tree::from_xml(document).expect("Document is well-formed");
+        assert_eq!(tree.values().len(), 6);
+        assert_eq!(tree.child_ids(0).collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(tree.child_ids(1).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(
+            tree.values()[1],
+            Element::Tag {
+                name: "book".to_string(),
+                attributes: vec![
+                    ("id".to_string(), "1".to_string()),
+                    ("title".to_string(), "\"Trees\" & more".to_string())
+                ]
+            }
+        );
+        assert_eq!(
+            tree.values()[2],
+            Element::Text("First line AB <raw>".to_string())
+        );
+        assert_eq!(tree.values()[4], Element::Text("tail".to_string()));
+
+        let xml = tree.to_xml().expect("Tree is a document");
+        assert_eq!(
+            xml,
+            "<catalogue lang=\"en\"><book id=\"1\" title=\"&quot;Trees&quot; &amp; more\">First line AB &lt;raw&gt;\
+             <chapter/>tail</book><book id=\"2\"/></catalogue>"
+        );
+        assert_eq!(Tree::from_xml(&xml), Ok(tree));
+
+        assert_eq!(
+            Tree::from_xml("<!-- nothing -->"),
+            Err(TreeError::EmptyTree)
+        );
+        assert_eq!(
+            Tree::from_xml("<a/><b/>"),
+            Err(TreeError::MultipleRoots { node: 1 })
+        );
+        for malformed in ["<a><b></a>", "<a>", "<a>&unknown;</a>", "text<a/>"] {
+            assert!(
+                matches!(Tree::from_xml(malformed), Err(TreeError::CorruptedTree(_))),
+                "{malformed}"
+            );
+        }
+
+        let text_root = Tree::new(vec![vec![-1]], vec![Element::Text("a".to_string())])
+            .expect("Tree has a valid structure");
+        assert!(matches!(
+            text_root.to_xml(),
+            Err(TreeError::CorruptedTree(_))
+        ));
+    }
+}
@@ -0,0 +1,711 @@
+use crate::arena::{self, ArenaTree};
+use crate::consts::ROOT_NODE;
+use crate::implicit::ImplicitBTree;
+use crate::sized::{self, BTree};
+use crate::sparse::{self, Indexed};
+use crate::storage::{StoredBTree, TreeStorage};
+use crate::tree::{self, NodeId, TreeError, TreeIndex};
+
+/// Read-only access shared by all tree types, so an algorithm written against it works on any of them. Nodes are
+/// identified by ids `0..len()`; links out of bounds or to the root are left out, like in traversals.
+///
+/// Sparse trees are accessed through [Indexed], numbering their nodes.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::implicit::ImplicitBTree;
+///         use treesome::sized::BTree;
+///         use treesome::TreeLike;
+///
+///         fn sum(tree: &impl TreeLike<Value = i32>) -> i32 {
+///             (0..tree.len()).map(|node| tree.value(node)).sum()
+///         }
+///
+///         assert_eq!(sum(&BTree::new([1, -1, -1], [2, -1, -1], [1, 2, 3])), 6);
+///         assert_eq!(sum(&ImplicitBTree::from(vec![1, 2, 3, 4])), 10);
+/// ```
+pub trait TreeLike {
+    type Value;
+
+    /// The root node, `None` for an empty tree.
+    fn root(&self) -> Option<usize>;
+
+    /// Existing children of `node`, in the order of their child slots. Panics if `node` is out of bounds.
+    fn children(&self, node: usize) -> Vec<usize>;
+
+    /// Parent of `node`, `None` for the root, unlinked nodes and nodes out of bounds.
+    fn parent(&self, node: usize) -> Option<usize>;
+
+    /// Value of `node`. Panics if `node` is out of bounds.
+    fn value(&self, node: usize) -> &Self::Value;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// True if `node` has no existing children, whatever the tree marks missing children with. Panics if `node` is
+    /// out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sized::BTree;
+    ///         use treesome::TreeLike;
+    ///         let tree = BTree::new([-1, -1, -1], [1, -1, 7], [1, 2, 3]);
+    ///
+    ///         assert!(!TreeLike::is_leaf(&tree, 0));
+    ///         assert!(TreeLike::is_leaf(&tree, 2));
+    /// ```
+    fn is_leaf(&self, node: usize) -> bool {
+        self.children(node).is_empty()
+    }
+
+    /// Number of existing children of `node`. Panics if `node` is out of bounds.
+    fn degree(&self, node: usize) -> usize {
+        self.children(node).len()
+    }
+
+    /// Node reached from the root by taking the child at each position of `path`, the root for an empty path.
+    /// `None` for an empty tree or if a node has no child at the position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::implicit::ImplicitBTree;
+    ///         use treesome::TreeLike;
+    ///         let tree = ImplicitBTree::from(vec!['a', 'b', 'c', 'd']);
+    ///
+    ///         assert_eq!(tree.at_path(&[0, 0]), Some(3));
+    ///         assert_eq!(tree.at_path(&[]), Some(0));
+    ///         assert_eq!(tree.at_path(&[1, 0]), None);
+    /// ```
+    fn at_path(&self, path: &[usize]) -> Option<usize> {
+        path.iter().try_fold(self.root()?, |node, &position| {
+            self.children(node).get(position).copied()
+        })
+    }
+}
+
+/// Mutation shared by the growable tree types, so builders and generators can target any of them. Nodes are
+/// identified by the ids of the tree type; ids of other nodes may change when a subtree is removed, see the
+/// implementations.
+///
+/// Implemented by [tree::Tree], [ArenaTree](crate::arena::ArenaTree) and the arena of sparse trees,
+/// [sparse::ArenaTree]. The reference counted [Node](crate::sparse::Node) shares its nodes, so it can't be mutated
+/// through `&mut`.
+///
+/// # Examples
+///
+/// ```
+///         use treesome::arena::ArenaTree;
+///         use treesome::tree::Tree;
+///         use treesome::MutableTreeLike;
+///
+///         /// Chain of `depth` nodes below the root
+///         fn chain<M: MutableTreeLike<Value = usize>>(tree: &mut M, depth: usize) {
+///             let mut node = tree.root_id().expect("Tree has a root");
+///             for value in 1..=depth {
+///                 node = tree.add_child(node, value).expect("Node has just been added");
+///             }
+///         }
+///
+///         let mut dynamic = Tree::new(vec![vec![-1]], vec![0]).expect("Tree has a valid structure");
+///         chain(&mut dynamic, 3);
+///         assert_eq!(dynamic.children(2), vec![3]);
+///
+///         let mut arena = ArenaTree::new(0);
+///         chain(&mut arena, 3);
+///         assert_eq!(arena.len(), 4);
+/// ```
+pub trait MutableTreeLike {
+    type Value;
+    type Id: Copy;
+
+    /// The root node, `None` for an empty tree.
+    fn root_id(&self) -> Option<Self::Id>;
+
+    /// Adds `value` as the last child of `parent`, returning the id of the new node.
+    fn add_child(&mut self, parent: Self::Id, value: Self::Value) -> Result<Self::Id, TreeError>;
+
+    /// Removes `node` with its subtree. The root can't be removed, [TreeError::RootRemoval] is returned instead.
+    fn remove_subtree(&mut self, node: Self::Id) -> Result<(), TreeError>;
+
+    /// Replaces the value of `node`, returning the previous one.
+    fn set_value(&mut self, node: Self::Id, value: Self::Value) -> Result<Self::Value, TreeError>;
+
+    /// Children of `node` in order, none for nodes that don't exist.
+    fn children_of(&self, node: Self::Id) -> Vec<Self::Id>;
+
+    /// Value of `node`, `None` for nodes that don't exist.
+    fn value_of(&self, node: Self::Id) -> Option<&Self::Value>;
+
+    /// Entry of the node reached from the root by taking the child at each position of `path`, which can create
+    /// the nodes missing on the way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///         use treesome::sparse::ArenaTree;
+    ///         use treesome::MutableTreeLike;
+    ///         let mut tree = ArenaTree::root(String::from("root"));
+    ///
+    ///         assert_eq!(tree.at_path_mut(&[1, 0]).get(), None);
+    ///         let node = tree.at_path_mut(&[1, 0]).or_default().expect("Tree has a root");
+    ///         tree.set_value(node, String::from("leaf")).expect("Node exists");
+    ///
+    ///         assert_eq!(tree.len(), 4); // Root, two children and the leaf
+    ///         assert_eq!(tree.at_path_mut(&[1, 0]).get().map(String::as_str), Some("leaf"));
+    /// ```
+    fn at_path_mut(&mut self, path: &[usize]) -> Entry<'_, Self> {
+        let mut node = self.root_id();
+        let mut depth = 0;
+        while let (Some(current), Some(&position)) = (node, path.get(depth)) {
+            match self.children_of(current).get(position) {
+                Some(&child) => node = Some(child),
+                None => break,
+            }
+            depth += 1;
+        }
+        Entry {
+            tree: self,
+            node,
+            missing: path[depth..].to_vec(),
+        }
+    }
+}
+
+/// Node of a [MutableTreeLike] addressed by a path of child positions, see [MutableTreeLike::at_path_mut]. Either
+/// exists, or can be created together with the missing nodes leading to it.
+pub struct Entry<'a, M: MutableTreeLike + ?Sized> {
+    tree: &'a mut M,
+    /// Deepest existing node on the path, `None` for an empty tree
+    node: Option<M::Id>,
+    /// Positions on the rest of the path, below `node`
+    missing: Vec<usize>,
+}
+
+impl<M: MutableTreeLike + ?Sized> Entry<'_, M> {
+    /// The node, if it exists.
+    pub fn node(&self) -> Option<M::Id> {
+        self.node.filter(|_| self.missing.is_empty())
+    }
+
+    /// Value of the node, if it exists.
+    pub fn get(&self) -> Option<&M::Value> {
+        self.tree.value_of(self.node()?)
+    }
+
+    /// Returns the node, creating it first if it doesn't exist. Every missing node on the path gets a value from
+    /// `factory`, including siblings created to fill the positions before it. An empty tree has no root to start
+    /// from, [TreeError::EmptyTree] is returned instead.
+    pub fn or_insert_with(self, mut factory: impl FnMut() -> M::Value) -> Result<M::Id, TreeError> {
+        let mut node = self.node.ok_or(TreeError::EmptyTree)?;
+        for position in self.missing {
+            let existing = self.tree.children_of(node).len();
+            for _ in existing..position {
+                self.tree.add_child(node, factory())?;
+            }
+            node = self.tree.add_child(node, factory())?;
+        }
+        Ok(node)
+    }
+
+    /// Returns the node, creating it and the missing nodes on the way with default values if it doesn't exist.
+    pub fn or_default(self) -> Result<M::Id, TreeError>
+    where
+        M::Value: Default,
+    {
+        self.or_insert_with(Default::default)
+    }
+}
+
+/// Parent of `node` among `0..len` nodes, trying the position of the parent in the complete `arity`-ary layout
+/// before a linear scan.
+pub(crate) fn find_parent(
+    node: usize,
+    len: usize,
+    arity: usize,
+    links_to: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    if node == ROOT_NODE as usize || node >= len {
+        return None;
+    }
+    NodeId::new(node)
+        .implicit_parent(arity)
+        .map(NodeId::index)
+        .filter(|&candidate| links_to(candidate))
+        .or_else(|| (0..len).find(|&parent| links_to(parent)))
+}
+
+/// Children given by raw `links`, skipping missing children and links out of bounds or to the root.
+fn existing(links: impl IntoIterator<Item = isize>, len: usize) -> Vec<usize> {
+    links
+        .into_iter()
+        .filter(|&child| child > ROOT_NODE && (child as usize) < len)
+        .map(|child| child as usize)
+        .collect()
+}
+
+impl<T, Idx: TreeIndex> TreeLike for tree::Tree<T, Idx> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        (!self.values().is_empty()).then_some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        assert!(node < self.len(), "Node {node} is out of bounds");
+        self.dimensions()
+            .iter()
+            .filter_map(|dimension| dimension[node].node())
+            .filter(|&child| child > ROOT_NODE as usize && child < self.len())
+            .collect()
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        self.try_parent(NodeId::new(node))
+            .ok()
+            .flatten()
+            .map(NodeId::index)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self.values()[node]
+    }
+
+    fn len(&self) -> usize {
+        self.values().len()
+    }
+}
+
+impl<T, const M: usize, const N: usize> TreeLike for sized::Tree<T, M, N> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        (N > 0).then_some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        existing(sized::Tree::children(self, node), N)
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        self.try_parent(NodeId::new(node))
+            .ok()
+            .flatten()
+            .map(NodeId::index)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self[node]
+    }
+
+    fn len(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> TreeLike for BTree<T, N> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        (N > 0).then_some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        let children = BTree::children(self, node);
+        existing([children.left, children.right], N)
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        self.try_parent(NodeId::new(node))
+            .ok()
+            .flatten()
+            .map(NodeId::index)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self[node]
+    }
+
+    fn len(&self) -> usize {
+        N
+    }
+}
+
+impl<T, L: TreeStorage<isize>, V: TreeStorage<T>> TreeLike for StoredBTree<T, L, V> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        (!self.is_empty()).then_some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        let node = NodeId::new(node);
+        [self.left(node), self.right(node)]
+            .into_iter()
+            .flatten()
+            .map(NodeId::index)
+            .collect()
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        StoredBTree::parent(self, NodeId::new(node)).map(NodeId::index)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self.values()[node]
+    }
+
+    fn len(&self) -> usize {
+        StoredBTree::len(self)
+    }
+}
+
+impl<T> TreeLike for ImplicitBTree<T> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        (!ImplicitBTree::is_empty(self)).then_some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        assert!(
+            node < ImplicitBTree::len(self),
+            "Node {node} is out of bounds"
+        );
+        let (left, right) = ImplicitBTree::children(self, NodeId::new(node));
+        left.into_iter().chain(right).map(NodeId::index).collect()
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        ImplicitBTree::parent(self, NodeId::new(node)).map(NodeId::index)
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self[NodeId::new(node)]
+    }
+
+    fn len(&self) -> usize {
+        ImplicitBTree::len(self)
+    }
+}
+
+impl<T, const C: usize> TreeLike for Indexed<T, C> {
+    type Value = T;
+
+    fn root(&self) -> Option<usize> {
+        Some(ROOT_NODE as usize)
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        self.children[node].clone()
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        self.parents.get(node).copied().flatten()
+    }
+
+    fn value(&self, node: usize) -> &T {
+        &self.nodes[node].value
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+/// Ids are dense, removing a subtree shifts down ids of the nodes following its nodes, see
+/// [tree::Tree::remove_subtree].
+impl<T: Clone> MutableTreeLike for tree::Tree<T> {
+    type Value = T;
+    type Id = usize;
+
+    fn root_id(&self) -> Option<usize> {
+        TreeLike::root(self)
+    }
+
+    fn add_child(&mut self, parent: usize, value: T) -> Result<usize, TreeError> {
+        tree::Tree::add_child(self, parent, value)
+    }
+
+    fn remove_subtree(&mut self, node: usize) -> Result<(), TreeError> {
+        tree::Tree::remove_subtree(self, node)
+    }
+
+    fn set_value(&mut self, node: usize, value: T) -> Result<T, TreeError> {
+        if node >= TreeLike::len(self) {
+            return Err(TreeError::NodeOutOfBounds { node });
+        }
+        Ok(std::mem::replace(self.value_mut(node), value))
+    }
+
+    fn children_of(&self, node: usize) -> Vec<usize> {
+        if node >= TreeLike::len(self) {
+            return Vec::new();
+        }
+        TreeLike::children(self, node)
+    }
+
+    fn value_of(&self, node: usize) -> Option<&T> {
+        self.values().get(node)
+    }
+}
+
+/// Ids of removed nodes become stale, ids of other nodes don't change.
+impl<T> MutableTreeLike for ArenaTree<T> {
+    type Value = T;
+    type Id = arena::ArenaId;
+
+    fn root_id(&self) -> Option<arena::ArenaId> {
+        Some(self.root())
+    }
+
+    fn add_child(&mut self, parent: arena::ArenaId, value: T) -> Result<arena::ArenaId, TreeError> {
+        self.append(parent, value)
+    }
+
+    fn remove_subtree(&mut self, node: arena::ArenaId) -> Result<(), TreeError> {
+        self.remove(node).map(drop)
+    }
+
+    fn set_value(&mut self, node: arena::ArenaId, value: T) -> Result<T, TreeError> {
+        self.replace(node, value)
+    }
+
+    fn children_of(&self, node: arena::ArenaId) -> Vec<arena::ArenaId> {
+        self.children(node).collect()
+    }
+
+    fn value_of(&self, node: arena::ArenaId) -> Option<&T> {
+        self.get(node)
+    }
+}
+
+/// The arena doesn't shrink, a removed subtree is just detached from its parent by [sparse::ArenaTree::detach].
+/// Ids don't change.
+impl<T> MutableTreeLike for sparse::ArenaTree<T> {
+    type Value = T;
+    type Id = NodeId;
+
+    fn root_id(&self) -> Option<NodeId> {
+        Some(NodeId::ROOT)
+    }
+
+    fn add_child(&mut self, parent: NodeId, value: T) -> Result<NodeId, TreeError> {
+        if parent.index() >= self.len() {
+            return Err(TreeError::NodeOutOfBounds {
+                node: parent.index(),
+            });
+        }
+        Ok(self.create_child(parent, value))
+    }
+
+    fn remove_subtree(&mut self, node: NodeId) -> Result<(), TreeError> {
+        if node == NodeId::ROOT {
+            return Err(TreeError::RootRemoval);
+        }
+        if node.index() >= self.len() {
+            return Err(TreeError::NodeOutOfBounds { node: node.index() });
+        }
+        self.detach(node);
+        Ok(())
+    }
+
+    fn set_value(&mut self, node: NodeId, value: T) -> Result<T, TreeError> {
+        self.get_mut(node)
+            .map(|current| std::mem::replace(current, value))
+            .ok_or(TreeError::NodeOutOfBounds { node: node.index() })
+    }
+
+    fn children_of(&self, node: NodeId) -> Vec<NodeId> {
+        self.children(node).collect()
+    }
+
+    fn value_of(&self, node: NodeId) -> Option<&T> {
+        self.get(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::ArenaTree;
+    use crate::implicit::ImplicitBTree;
+    use crate::sized::{self, BTree};
+    use crate::sparse::{self, Node};
+    use crate::tree::{NodeId, Tree, TreeError};
+    use crate::{MutableTreeLike, TreeLike};
+
+    /// Values in pre-order with the parent of every node, read through the trait only.
+    fn walk<T: Copy>(tree: &impl TreeLike<Value = T>) -> Vec<(T, Option<T>)> {
+        let mut walked = Vec::new();
+        let mut stack: Vec<usize> = tree.root().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let parent = tree.parent(node).map(|parent| *tree.value(parent));
+            walked.push((*tree.value(node), parent));
+            stack.extend(tree.children(node).into_iter().rev());
+        }
+        walked
+    }
+
+    #[test]
+    fn same_tree_in_every_type() {
+        // 'a' with children 'b' and 'c', 'b' with the right child 'd'
+        let expected = vec![
+            ('a', None),
+            ('b', Some('a')),
+            ('d', Some('b')),
+            ('c', Some('a')),
+        ];
+
+        let dynamic = Tree::new(
+            vec![vec![1, -1, -1, -1], vec![2, 3, -1, -1]],
+            vec!['a', 'b', 'c', 'd'],
+        )
+        .expect("Tree has a valid structure");
+        assert_eq!(walk(&dynamic), expected);
+
+        // Not in the complete layout, parents are found by a scan
+        let sized = sized::Tree::new([[2, -1, 3, -1], [1, -1, -1, -1]], ['a', 'c', 'b', 'd']);
+        assert_eq!(walk(&sized), expected);
+        assert_eq!(TreeLike::parent(&sized, 3), Some(2));
+
+        let btree = BTree::new([1, -1, -1, -1], [2, 3, -1, -1], ['a', 'b', 'c', 'd']);
+        assert_eq!(walk(&btree), expected);
+
+        let implicit = ImplicitBTree::from(vec!['a', 'b', 'c', 'd']);
+        assert_eq!(walk(&implicit), expected);
+        assert!(TreeLike::parent(&implicit, 4).is_none());
+
+        let root = Node::root('a');
+        root.create_child('b').create_child('d');
+        root.create_child('c');
+        let indexed = root.indexed();
+        assert_eq!(walk(&indexed), expected);
+        assert_eq!(indexed.len(), 4);
+
+        let empty = ImplicitBTree::<char>::new();
+        assert!(TreeLike::is_empty(&empty));
+        assert_eq!(walk(&empty), vec![]);
+    }
+
+    /// Builds the root with children 1, 2 and 3, the second one having children 4 and 5, then sets the value of the
+    /// last one to 30 and removes the second one. Returns the ids of the nodes.
+    fn build<M: MutableTreeLike<Value = u8>>(tree: &mut M) -> Vec<M::Id> {
+        let root = tree.root_id().expect("Tree has a root");
+        let mut ids = vec![root];
+        for value in 1..=3 {
+            ids.push(tree.add_child(root, value).expect("Root exists"));
+        }
+        for value in 4..=5 {
+            ids.push(tree.add_child(ids[2], value).expect("Node exists"));
+        }
+        assert_eq!(tree.remove_subtree(root), Err(TreeError::RootRemoval));
+        assert_eq!(tree.set_value(ids[3], 30), Ok(3));
+        tree.remove_subtree(ids[2]).expect("Node exists");
+        ids
+    }
+
+    #[test]
+    fn same_mutations_in_every_type() {
+        let mut dynamic = Tree::new(vec![vec![-1]], vec![0]).expect("Tree has a valid structure");
+        build(&mut dynamic);
+        assert_eq!(
+            dynamic,
+            // The child slot of the removed node stays empty
+            Tree::new(
+                vec![vec![1, -1, -1], vec![-1, -1, -1], vec![2, -1, -1]],
+                vec![0, 1, 30]
+            )
+            .expect("Tree has a valid structure")
+        );
+        assert_eq!(
+            MutableTreeLike::set_value(&mut dynamic, 3, 0),
+            Err(TreeError::NodeOutOfBounds { node: 3 })
+        );
+
+        let mut arena = ArenaTree::new(0);
+        let ids = build(&mut arena);
+        assert_eq!(
+            arena.children(ids[0]).collect::<Vec<_>>(),
+            vec![ids[1], ids[3]]
+        );
+        assert_eq!(arena[ids[3]], 30);
+        assert_eq!(arena.len(), 3);
+        assert_eq!(
+            arena.set_value(ids[4], 0),
+            Err(TreeError::StaleNode {
+                node: ids[4].index()
+            })
+        );
+
+        let mut sparse = sparse::ArenaTree::root(0);
+        let ids = build(&mut sparse);
+        assert_eq!(
+            sparse.children(NodeId::ROOT).collect::<Vec<_>>(),
+            vec![ids[1], ids[3]]
+        );
+        assert_eq!(sparse.parent(ids[2]), None);
+        assert_eq!(
+            sparse.children(ids[2]).collect::<Vec<_>>(),
+            vec![ids[4], ids[5]]
+        );
+        assert_eq!(
+            sparse.add_child(NodeId::new(9), 9),
+            Err(TreeError::NodeOutOfBounds { node: 9 })
+        );
+
+        // Detaching the last child keeps appending working
+        sparse.detach(ids[3]);
+        let last = sparse.create_child(NodeId::ROOT, 7);
+        assert_eq!(
+            sparse.children(NodeId::ROOT).collect::<Vec<_>>(),
+            vec![ids[1], last]
+        );
+    }
+
+    /// Values of all nodes in pre-order once `paths` are created with values counting up from one.
+    fn entries<M: MutableTreeLike<Value = u8>>(tree: &mut M, paths: &[&[usize]]) -> Vec<u8> {
+        let mut next = 0;
+        for path in paths {
+            tree.at_path_mut(path)
+                .or_insert_with(|| {
+                    next += 1;
+                    next
+                })
+                .expect("Tree has a root");
+        }
+        let mut values = Vec::new();
+        let mut stack: Vec<M::Id> = tree.root_id().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            values.push(*tree.value_of(node).expect("Node exists"));
+            stack.extend(tree.children_of(node).into_iter().rev());
+        }
+        values
+    }
+
+    #[test]
+    fn path_entries() {
+        // [0, 1] creates a filler at [0, 0]; [0] already exists by then
+        let paths: &[&[usize]] = &[&[0, 1], &[0], &[1], &[0, 1, 0]];
+        let expected = vec![0, 1, 2, 3, 5, 4];
+
+        let mut dynamic = Tree::new(vec![vec![-1]], vec![0]).expect("Tree has a valid structure");
+        assert_eq!(entries(&mut dynamic, paths), expected);
+        assert_eq!(dynamic.at_path(&[0, 1, 0]), Some(5));
+        assert_eq!(dynamic.at_path_mut(&[0, 1, 0]).get(), Some(&5));
+        assert_eq!(dynamic.at_path_mut(&[2]).node(), None);
+        assert_eq!(entries(&mut sparse::ArenaTree::root(0), paths), expected);
+        assert_eq!(entries(&mut ArenaTree::new(0), paths), expected);
+
+        let mut empty = Tree::<u8>::new(vec![], vec![]).expect("Tree has a valid structure");
+        assert_eq!(empty.at_path(&[]), None);
+        assert_eq!(
+            empty.at_path_mut(&[]).or_default(),
+            Err(TreeError::EmptyTree)
+        );
+    }
+}
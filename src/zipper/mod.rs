@@ -0,0 +1,391 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::consts::{LEAF_NODE, ROOT_NODE};
+use crate::sparse::Node;
+use crate::tree::Tree;
+
+/// Zipper over an owned n-ary tree, for structural editing. The focused subtree is held directly, while the path
+/// to the root is kept as a stack of breadcrumbs, so focus movement and edits at the focus are O(1) in the depth
+/// of the tree. Edited trees are materialized with [Zipper::commit] or [Zipper::into_sparse].
+///
+/// # Examples
+///
+/// ```
+///         use treesome::tree::Tree;
+///         use treesome::zipper::Zipper;
+///         let tree = Tree::new(vec![vec![1, -1, -1], vec![2, -1, -1]], vec!["+", "1", "2"]).unwrap();
+///
+///         let mut zipper = Zipper::from_tree(tree).expect("Tree isn't empty");
+///         zipper.down(1);
+///         zipper.replace("*");
+///         zipper.push_child("3");
+///         zipper.push_child("4");
+///
+///         let edited = zipper.commit();
+///         assert_eq!(edited.children(0), vec![1, 2]);
+///         assert_eq!(edited.children(2), vec![3, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Zipper<T> {
+    focus: ZipNode<T>,
+    crumbs: Vec<Crumb<T>>,
+}
+
+#[derive(Debug, Clone)]
+struct ZipNode<T> {
+    value: T,
+    children: Vec<ZipNode<T>>,
+}
+
+/// Parent of a focused node, with siblings to the left in order and siblings to the right in reverse order.
+#[derive(Debug, Clone)]
+struct Crumb<T> {
+    value: T,
+    left: Vec<ZipNode<T>>,
+    right: Vec<ZipNode<T>>,
+}
+
+impl<T> Zipper<T> {
+    /// Creates a zipper focused on the root of `tree`, or `None` for an empty tree. Nodes unreachable from the root
+    /// are dropped. Values are moved unless shared with a tree [Snapshot](crate::tree::Snapshot), in which case
+    /// they're cloned.
+    pub fn from_tree(tree: Tree<T>) -> Option<Self>
+    where
+        T: Clone,
+    {
+        let (nodes, values) = tree.into_parts();
+        if values.is_empty() {
+            return None;
+        }
+
+        let len = values.len();
+        let mut visited = vec![false; len];
+        visited[ROOT_NODE as usize] = true;
+        let mut order = vec![ROOT_NODE as usize];
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); len];
+        let mut next = 0;
+        while next < order.len() {
+            let node = order[next];
+            for dimension in &nodes {
+                let child = dimension[node];
+                if child > ROOT_NODE && (child as usize) < len && !visited[child as usize] {
+                    visited[child as usize] = true;
+                    children[node].push(child as usize);
+                    order.push(child as usize);
+                }
+            }
+            next += 1;
+        }
+
+        let mut values: Vec<Option<T>> = values.into_iter().map(Some).collect();
+        let mut built: Vec<Option<ZipNode<T>>> = (0..len).map(|_| None).collect();
+        for &node in order.iter().rev() {
+            built[node] = Some(ZipNode {
+                value: values[node].take().expect("Every node is built once"),
+                children: children[node]
+                    .iter()
+                    .map(|&child| built[child].take().expect("Children are built first"))
+                    .collect(),
+            });
+        }
+
+        Some(Self::focused(
+            built[ROOT_NODE as usize].take().expect("Root is built"),
+        ))
+    }
+
+    /// Creates a zipper focused on a copy of the tree rooted at `root`.
+    pub fn from_sparse<const C: usize>(root: &Node<T, C>) -> Self
+    where
+        T: Clone,
+    {
+        // Pre-order, with parents' positions
+        let mut order: Vec<(Rc<Node<T, C>>, usize)> = Vec::new();
+        let mut stack: Vec<(Rc<Node<T, C>>, usize)> = root
+            .children()
+            .into_iter()
+            .rev()
+            .map(|child| (child, 0))
+            .collect();
+        while let Some((node, parent)) = stack.pop() {
+            let position = order.len() + 1;
+            stack.extend(
+                node.children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, position)),
+            );
+            order.push((node, parent));
+        }
+
+        // Children are completed before their parents, siblings arrive in reverse order
+        let mut children: Vec<Vec<ZipNode<T>>> = (0..=order.len()).map(|_| Vec::new()).collect();
+        for (position, (node, parent)) in order.iter().enumerate().rev() {
+            let mut node_children = std::mem::take(&mut children[position + 1]);
+            node_children.reverse();
+            children[*parent].push(ZipNode {
+                value: node.value.clone(),
+                children: node_children,
+            });
+        }
+        let mut root_children = std::mem::take(&mut children[0]);
+        root_children.reverse();
+
+        Self::focused(ZipNode {
+            value: root.value.clone(),
+            children: root_children,
+        })
+    }
+
+    fn focused(focus: ZipNode<T>) -> Self {
+        Self {
+            focus,
+            crumbs: Vec::new(),
+        }
+    }
+
+    /// Value of the focused node.
+    pub fn value(&self) -> &T {
+        &self.focus.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.focus.value
+    }
+
+    /// Replaces value of the focused node, returning the previous one.
+    pub fn replace(&mut self, value: T) -> T {
+        std::mem::replace(&mut self.focus.value, value)
+    }
+
+    /// Number of children of the focused node.
+    pub fn child_count(&self) -> usize {
+        self.focus.children.len()
+    }
+
+    /// Depth of the focused node, zero for the root.
+    pub fn depth(&self) -> usize {
+        self.crumbs.len()
+    }
+
+    /// Moves the focus to the child at `index`. If there's no such child, the focus stays in place and `None`
+    /// is returned.
+    pub fn down(&mut self, index: usize) -> Option<&T> {
+        if index >= self.focus.children.len() {
+            return None;
+        }
+
+        let mut right = self.focus.children.split_off(index + 1);
+        right.reverse();
+        let child = self.focus.children.pop().expect("Index is in bounds");
+        let left = std::mem::take(&mut self.focus.children);
+        let parent = std::mem::replace(&mut self.focus, child);
+        self.crumbs.push(Crumb {
+            value: parent.value,
+            left,
+            right,
+        });
+        Some(&self.focus.value)
+    }
+
+    /// Moves the focus to the parent. If there's none, the focus stays in place and `None` is returned.
+    pub fn up(&mut self) -> Option<&T> {
+        let crumb = self.crumbs.pop()?;
+        let mut children = crumb.left;
+        let child = std::mem::replace(
+            &mut self.focus,
+            ZipNode {
+                value: crumb.value,
+                children: Vec::new(),
+            },
+        );
+        children.push(child);
+        children.extend(crumb.right.into_iter().rev());
+        self.focus.children = children;
+        Some(&self.focus.value)
+    }
+
+    /// Moves the focus to the previous sibling. If there's none, the focus stays in place and `None` is returned.
+    pub fn left(&mut self) -> Option<&T> {
+        let crumb = self.crumbs.last_mut()?;
+        let sibling = crumb.left.pop()?;
+        crumb
+            .right
+            .push(std::mem::replace(&mut self.focus, sibling));
+        Some(&self.focus.value)
+    }
+
+    /// Moves the focus to the next sibling. If there's none, the focus stays in place and `None` is returned.
+    pub fn right(&mut self) -> Option<&T> {
+        let crumb = self.crumbs.last_mut()?;
+        let sibling = crumb.right.pop()?;
+        crumb.left.push(std::mem::replace(&mut self.focus, sibling));
+        Some(&self.focus.value)
+    }
+
+    /// Moves the focus to the root.
+    pub fn root(&mut self) -> &T {
+        while self.up().is_some() {}
+        &self.focus.value
+    }
+
+    /// Inserts a new leaf child at `index` of the focused node. Panics if `index` is greater than the number
+    /// of children.
+    pub fn insert_child(&mut self, index: usize, value: T) {
+        self.focus.children.insert(
+            index,
+            ZipNode {
+                value,
+                children: Vec::new(),
+            },
+        );
+    }
+
+    /// Appends a new leaf child to the focused node.
+    pub fn push_child(&mut self, value: T) {
+        self.insert_child(self.focus.children.len(), value);
+    }
+
+    /// Removes the child at `index` of the focused node together with its subtree, returning the child's value.
+    pub fn remove_child(&mut self, index: usize) -> Option<T> {
+        if index >= self.focus.children.len() {
+            return None;
+        }
+        Some(self.focus.children.remove(index).value)
+    }
+
+    /// Materializes the edited tree. Nodes are numbered in BFS order, the number of dimensions being the highest
+    /// number of children of any node.
+    pub fn commit(mut self) -> Tree<T> {
+        self.root();
+
+        let mut values = Vec::new();
+        let mut links: Vec<Vec<isize>> = Vec::new();
+        let mut next_id = 1;
+        let mut queue = VecDeque::from([self.focus]);
+        while let Some(node) = queue.pop_front() {
+            links.push((next_id..next_id + node.children.len() as isize).collect());
+            next_id += node.children.len() as isize;
+            values.push(node.value);
+            queue.extend(node.children);
+        }
+
+        let arity = links.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let nodes = (0..arity)
+            .map(|dimension| {
+                links
+                    .iter()
+                    .map(|children| children.get(dimension).copied().unwrap_or(LEAF_NODE))
+                    .collect()
+            })
+            .collect();
+        Tree::new(nodes, values)
+            .unwrap_or_else(|_| unreachable!("Every dimension has a link per node"))
+    }
+
+    /// Materializes the edited tree as a [Node] tree, returning its root.
+    pub fn into_sparse(mut self) -> Rc<Node<T>> {
+        self.root();
+
+        let ZipNode { value, children } = self.focus;
+        let root = Node::root(value);
+        let mut stack = vec![(root.clone(), children)];
+        while let Some((parent, children)) = stack.pop() {
+            for child in children {
+                stack.push((parent.create_child(child.value), child.children));
+            }
+        }
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sparse::Node;
+    use crate::tree::Tree;
+    use crate::zipper::Zipper;
+
+    fn tree() -> Tree<i32> {
+        // 0 -> (1 -> (4, 5), 2, 3 -> 6)
+        let first = vec![1, 4, -1, 6, -1, -1, -1];
+        let second = vec![2, 5, -1, -1, -1, -1, -1];
+        let third = vec![3, -1, -1, -1, -1, -1, -1];
+        Tree::new(vec![first, second, third], vec![0, 1, 2, 3, 4, 5, 6]).unwrap()
+    }
+
+    #[test]
+    fn navigation() {
+        let mut zipper = Zipper::from_tree(tree()).unwrap();
+        assert_eq!(zipper.up(), None);
+        assert_eq!(zipper.down(3), None);
+        assert_eq!(zipper.down(1), Some(&2));
+        assert_eq!(zipper.right(), Some(&3));
+        assert_eq!(zipper.right(), None);
+        assert_eq!(zipper.down(0), Some(&6));
+        assert_eq!(zipper.depth(), 2);
+        assert_eq!(zipper.up(), Some(&3));
+        assert_eq!(zipper.left(), Some(&2));
+        assert_eq!(zipper.left(), Some(&1));
+        assert_eq!(zipper.left(), None);
+        assert_eq!(zipper.root(), &0);
+
+        // Navigation alone doesn't change the tree
+        assert_eq!(zipper.commit(), tree());
+    }
+
+    #[test]
+    fn edits() {
+        let mut zipper = Zipper::from_tree(tree()).unwrap();
+        zipper.down(0);
+        assert_eq!(zipper.remove_child(0), Some(4));
+        assert_eq!(zipper.remove_child(5), None);
+        *zipper.value_mut() += 10;
+        zipper.right();
+        zipper.insert_child(0, 7);
+        zipper.push_child(8);
+        zipper.right();
+        assert_eq!(zipper.replace(30), 3);
+        zipper.up();
+        assert_eq!(zipper.remove_child(0), Some(11));
+
+        // 0 -> (2 -> (7, 8), 30 -> 6)
+        let edited = zipper.commit();
+        let expected = Tree::new(
+            vec![vec![1, 3, 5, -1, -1, -1], vec![2, 4, -1, -1, -1, -1]],
+            vec![0, 2, 30, 7, 8, 6],
+        )
+        .unwrap();
+        assert_eq!(edited, expected);
+    }
+
+    #[test]
+    fn sparse_round_trip() {
+        let root = Node::root("root");
+        let a = root.create_child("a");
+        root.create_child("b");
+        a.create_child("a0");
+        a.create_child("a1");
+
+        let mut zipper = Zipper::from_sparse(&root);
+        zipper.down(0);
+        zipper.down(1);
+        zipper.push_child("a10");
+
+        let root = zipper.into_sparse();
+        let values = |node: &Node<&'static str>| -> Vec<&str> {
+            node.children().iter().map(|child| child.value).collect()
+        };
+        assert_eq!(values(&root), vec!["a", "b"]);
+        let a = &root.children()[0];
+        assert_eq!(values(a), vec!["a0", "a1"]);
+        assert_eq!(values(&a.children()[1]), vec!["a10"]);
+        assert!(root.children()[1].is_leaf());
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree: Tree<i32> = Tree::new(vec![vec![]], vec![]).unwrap();
+        assert!(Zipper::from_tree(tree).is_none());
+    }
+}